@@ -0,0 +1,20 @@
+//! Cologne address ids, which reach the provider from saved configs and API callers.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tonneli_core::AddressId;
+use tonneli_provider_cologne::AwbAddressId;
+
+fuzz_target!(|input: &str| {
+    let id = AddressId(input.to_owned());
+    if let Ok(parts) = AwbAddressId::parse(&id) {
+        assert!(!parts.street_code.trim().is_empty(), "street code must be set");
+        assert!(!parts.building_number.trim().is_empty(), "house number must be set");
+        assert_eq!(
+            AwbAddressId::parse(&AddressId(parts.to_string())).ok(),
+            Some(parts),
+            "formatting a parsed id must round-trip"
+        );
+    }
+});
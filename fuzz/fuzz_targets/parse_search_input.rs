@@ -0,0 +1,30 @@
+//! Free-form search input as typed into the TUI search box.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tonneli_core::AddressSearch;
+
+fuzz_target!(|input: &str| {
+    let search = AddressSearch::parse(input);
+    assert_eq!(
+        search.street.trim(),
+        search.street,
+        "street must not keep surrounding whitespace"
+    );
+    assert!(
+        search
+            .house_number
+            .as_deref()
+            .is_none_or(|number| !number.is_empty() && !number.contains(char::is_whitespace)),
+        "house number must be a single word"
+    );
+    if let Some((number, addition)) = search.house_number_parts() {
+        assert!(!number.is_empty(), "house number part must not be empty");
+        assert!(
+            addition.is_none_or(|addition| !addition.is_empty()),
+            "addition must be absent rather than empty"
+        );
+    }
+    let _ = search.is_empty();
+});
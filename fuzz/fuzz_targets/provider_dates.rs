@@ -0,0 +1,17 @@
+//! Date strings as found in provider responses.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tonneli_core::dates::{MalformedDates, parse_provider_date};
+
+fuzz_target!(|input: &str| {
+    let parsed = parse_provider_date(input);
+    let mut malformed = MalformedDates::default();
+    assert_eq!(malformed.parse(input), parsed, "both entry points must agree");
+    assert_eq!(
+        malformed.into_inner().is_empty(),
+        parsed.is_some(),
+        "exactly the rejected inputs are recorded"
+    );
+});
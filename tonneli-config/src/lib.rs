@@ -0,0 +1,626 @@
+//! Configuration shared by the Tonneli frontends.
+//!
+//! Settings are layered: `$XDG_CONFIG_HOME/tonneli/config.toml` first, then
+//! `TONNELI_<SECTION>__<KEY>` environment variables (e.g. `TONNELI_HTTP__PROXY`),
+//! then `--set section.key=value` command-line flags. The merged settings are
+//! checked section by section, so errors name the offending key.
+//!
+//! Corrections of provider schedules are kept apart from the settings, in an
+//! `overrides.toml` next to the config file, see [`ScheduleOverrides`].
+
+/// Typed configuration sections.
+pub mod sections;
+
+pub use sections::*;
+
+use std::fs;
+use std::io::{Error as IoError, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use toml::de::Error as TomlDeError;
+use toml::ser::Error as TomlSerError;
+use tonneli_core::household::HouseholdProfile;
+use tonneli_core::overrides::ScheduleOverrides;
+use tonneli_core::reminder::ReminderSettings;
+
+/// Prefix of environment variables overriding settings.
+pub const ENV_PREFIX: &str = "TONNELI_";
+/// Separates the section from the key in environment variable names.
+const ENV_SEPARATOR: &str = "__";
+
+const HTTP_SECTION: &str = "http";
+const PROVIDERS_SECTION: &str = "providers";
+const REGIOIT_TOWNS_SECTION: &str = "regioit_towns";
+const ABFALLIO_TOWNS_SECTION: &str = "abfallio_towns";
+const JUMOMIND_TOWNS_SECTION: &str = "jumomind_towns";
+const RECYCLE_TOWNS_SECTION: &str = "recycle_towns";
+const FILE_TOWNS_SECTION: &str = "file_towns";
+const CACHE_SECTION: &str = "cache";
+const REMINDERS_SECTION: &str = "reminders";
+const HOUSEHOLD_SECTION: &str = "household";
+const PRIVACY_SECTION: &str = "privacy";
+const TUI_SECTION: &str = "tui";
+
+/// File with schedule overrides, in the directory of the config file.
+const OVERRIDES_FILE: &str = "overrides.toml";
+
+#[derive(thiserror::Error, Debug)]
+/// Errors while loading, checking or saving the configuration.
+pub enum ConfigError {
+    /// The config file exists but cannot be read.
+    #[error("Failed to read {}", path.display())]
+    Read {
+        /// Config file path.
+        path: PathBuf,
+        /// Underlying I/O error.
+        source: IoError,
+    },
+    /// The config file is not valid TOML.
+    #[error("Invalid config file {}", path.display())]
+    Parse {
+        /// Config file path.
+        path: PathBuf,
+        /// Parser error with line and column.
+        source: TomlDeError,
+    },
+    /// An environment variable or command-line override is malformed.
+    #[error("Invalid {origin} {entry:?}: {message}")]
+    Override {
+        /// Where the override came from, e.g. `environment variable`.
+        origin: &'static str,
+        /// The override as given.
+        entry: String,
+        /// What is wrong with it.
+        message: &'static str,
+    },
+    /// A command-line flag lacks its value.
+    #[error("Missing value after {0}")]
+    MissingArgument(String),
+    /// A section has unknown keys or values of the wrong type.
+    #[error("Invalid [{section}] section")]
+    Section {
+        /// Section name.
+        section: &'static str,
+        /// Deserializer error naming the key.
+        source: TomlDeError,
+    },
+    /// A value has the right type but is out of range.
+    #[error("Invalid value for {key}: {message}")]
+    Invalid {
+        /// Dotted path of the setting.
+        key: String,
+        /// What is wrong with the value.
+        message: String,
+    },
+    /// The config file cannot be written.
+    #[error("Failed to write {}", path.display())]
+    Write {
+        /// Config file path.
+        path: PathBuf,
+        /// Underlying I/O error.
+        source: IoError,
+    },
+    /// Settings cannot be encoded as TOML.
+    #[error("Cannot encode settings")]
+    Encode(#[from] TomlSerError),
+}
+
+impl ConfigError {
+    pub(crate) fn invalid(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Invalid {
+            key: key.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// One `section.key = value` override from the environment or the command line.
+#[derive(Debug, Clone)]
+struct Override {
+    origin: &'static str,
+    entry: String,
+    key: String,
+    value: String,
+}
+
+impl Override {
+    fn error(&self, message: &'static str) -> ConfigError {
+        ConfigError::Override {
+            origin: self.origin,
+            entry: self.entry.clone(),
+            message,
+        }
+    }
+}
+
+/// Collects the configuration layers and merges them into a [`Config`].
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLoader {
+    path: Option<PathBuf>,
+    env: Vec<Override>,
+    cli: Vec<Override>,
+}
+
+impl ConfigLoader {
+    /// Loader for the default config file and no overrides.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            path: dirs::config_dir().map(|dir| dir.join("tonneli").join("config.toml")),
+            ..Self::default()
+        }
+    }
+
+    /// Read the config file at `path` instead of the default location.
+    #[must_use]
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Apply `TONNELI_<SECTION>__<KEY>` variables, e.g. from [`std::env::vars`].
+    ///
+    /// Variables without the `__` separator, like `TONNELI_CHAOS`, are not settings
+    /// and are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::Override`] for a variable with an empty key segment.
+    pub fn env_vars<I>(mut self, vars: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        for (name, value) in vars {
+            let Some(path) = name
+                .strip_prefix(ENV_PREFIX)
+                .filter(|path| path.contains(ENV_SEPARATOR))
+            else {
+                continue;
+            };
+            let segments = path
+                .split(ENV_SEPARATOR)
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>();
+            let entry = Override {
+                origin: "environment variable",
+                entry: name.clone(),
+                key: segments.join("."),
+                value,
+            };
+            if segments.iter().any(String::is_empty) {
+                return Err(entry.error("expected TONNELI_<SECTION>__<KEY>"));
+            }
+            self.env.push(entry);
+        }
+        Ok(self)
+    }
+
+    /// Take `--config <path>` and `--set section.key=value` from `args`.
+    ///
+    /// Returns the loader and the arguments it did not consume, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigError`] for a flag without value or a malformed `--set`.
+    pub fn args<I>(mut self, args: I) -> Result<(Self, Vec<String>), ConfigError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut rest = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_owned(), Some(value)),
+                _ => (arg.clone(), None),
+            };
+            if flag != "--config" && flag != "--set" {
+                rest.push(arg);
+                continue;
+            }
+            let value = match inline {
+                Some(value) => value.to_owned(),
+                None => args
+                    .next()
+                    .ok_or_else(|| ConfigError::MissingArgument(flag.clone()))?,
+            };
+            if flag == "--config" {
+                self.path = Some(PathBuf::from(value));
+            } else {
+                let Some((key, setting)) = value.split_once('=') else {
+                    return Err(ConfigError::Override {
+                        origin: "--set",
+                        entry: value,
+                        message: "expected section.key=value",
+                    });
+                };
+                self.cli.push(Override {
+                    origin: "--set",
+                    key: key.trim().to_owned(),
+                    value: setting.to_owned(),
+                    entry: value.clone(),
+                });
+            }
+        }
+        Ok((self, rest))
+    }
+
+    /// Read the file, apply the overrides and check every section.
+    ///
+    /// A missing file counts as empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigError`] naming the file, override or key that is invalid.
+    pub fn load(self) -> Result<Config, ConfigError> {
+        let file = match &self.path {
+            Some(path) => read_table(path)?,
+            None => toml::Table::new(),
+        };
+        let mut merged = file.clone();
+        for entry in self.env.iter().chain(&self.cli) {
+            apply(&mut merged, entry)?;
+        }
+        let overrides = match self.path.as_deref().and_then(Path::parent) {
+            Some(dir) => read_overrides(&dir.join(OVERRIDES_FILE))?,
+            None => ScheduleOverrides::default(),
+        };
+        Ok(Config {
+            overrides,
+            ..Config::from_tables(self.path, file, &merged)?
+        })
+    }
+}
+
+/// Validated settings, plus the config file they were read from for saving.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    path: Option<PathBuf>,
+    /// The file's own content, without overrides; unknown sections survive saving.
+    file: toml::Table,
+    http: HttpSettings,
+    providers: ProviderSettings,
+    cache: CacheSettings,
+    reminders: ReminderSettings,
+    household: HouseholdProfile,
+    overrides: ScheduleOverrides,
+    privacy: PrivacySettings,
+    frontends: FrontendSettings,
+}
+
+impl Config {
+    fn from_tables(
+        path: Option<PathBuf>,
+        file: toml::Table,
+        merged: &toml::Table,
+    ) -> Result<Self, ConfigError> {
+        let http = section::<HttpSettings>(merged, HTTP_SECTION)?;
+        http.validate()?;
+        let providers = ProviderSettings {
+            enabled: section::<ProvidersSection>(merged, PROVIDERS_SECTION)?.enabled,
+            regioit_towns: section(merged, REGIOIT_TOWNS_SECTION)?,
+            abfallio_towns: section(merged, ABFALLIO_TOWNS_SECTION)?,
+            jumomind_towns: section(merged, JUMOMIND_TOWNS_SECTION)?,
+            recycle_towns: section(merged, RECYCLE_TOWNS_SECTION)?,
+            file_towns: section(merged, FILE_TOWNS_SECTION)?,
+        };
+        providers.validate()?;
+        let cache = section::<CacheSettings>(merged, CACHE_SECTION)?;
+        cache.validate()?;
+        let reminders = section(merged, REMINDERS_SECTION)?;
+        validate_reminders(&reminders)?;
+        let household = section(merged, HOUSEHOLD_SECTION)?;
+        let privacy = section(merged, PRIVACY_SECTION)?;
+        let frontends = FrontendSettings {
+            tui: section(merged, TUI_SECTION)?,
+        };
+        frontends.tui.validate()?;
+
+        Ok(Self {
+            path,
+            file,
+            http,
+            providers,
+            cache,
+            reminders,
+            household,
+            overrides: ScheduleOverrides::default(),
+            privacy,
+            frontends,
+        })
+    }
+
+    /// File the settings were read from and are saved to.
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// HTTP settings from `[http]`.
+    #[must_use]
+    pub fn http(&self) -> &HttpSettings {
+        &self.http
+    }
+
+    /// Additional providers.
+    #[must_use]
+    pub fn providers(&self) -> &ProviderSettings {
+        &self.providers
+    }
+
+    /// Schedule cache settings from `[cache]`.
+    #[must_use]
+    pub fn cache(&self) -> &CacheSettings {
+        &self.cache
+    }
+
+    /// Reminder settings from `[reminders]`.
+    #[must_use]
+    pub fn reminders(&self) -> &ReminderSettings {
+        &self.reminders
+    }
+
+    /// Bins of the household from `[household]`.
+    #[must_use]
+    pub fn household(&self) -> &HouseholdProfile {
+        &self.household
+    }
+
+    /// Schedule corrections from `overrides.toml`.
+    #[must_use]
+    pub fn overrides(&self) -> &ScheduleOverrides {
+        &self.overrides
+    }
+
+    /// Audit log and data minimization from `[privacy]`.
+    #[must_use]
+    pub fn privacy(&self) -> &PrivacySettings {
+        &self.privacy
+    }
+
+    /// Frontend settings such as `[tui]`.
+    #[must_use]
+    pub fn frontends(&self) -> &FrontendSettings {
+        &self.frontends
+    }
+
+    /// Replace the `[reminders]` section and write the file back.
+    ///
+    /// Only the file's own content is written, never values from overrides.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigError`] when the settings are invalid or the file cannot be written.
+    pub fn save_reminders(&mut self, settings: &ReminderSettings) -> Result<(), ConfigError> {
+        validate_reminders(settings)?;
+        let section = toml::Value::try_from(settings)?;
+        self.file.insert(REMINDERS_SECTION.to_owned(), section);
+        self.reminders = settings.clone();
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), ConfigError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let write_error = |source| ConfigError::Write {
+            path: path.clone(),
+            source,
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(write_error)?;
+        }
+        let raw = toml::to_string_pretty(&self.file)?;
+        fs::write(path, raw).map_err(write_error)
+    }
+}
+
+fn read_table(path: &Path) -> Result<toml::Table, ConfigError> {
+    match fs::read_to_string(path) {
+        Ok(raw) => raw.parse().map_err(|source| ConfigError::Parse {
+            path: path.to_owned(),
+            source,
+        }),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(toml::Table::new()),
+        Err(source) => Err(ConfigError::Read {
+            path: path.to_owned(),
+            source,
+        }),
+    }
+}
+
+fn read_overrides(path: &Path) -> Result<ScheduleOverrides, ConfigError> {
+    toml::Value::Table(read_table(path)?)
+        .try_into()
+        .map_err(|source| ConfigError::Parse {
+            path: path.to_owned(),
+            source,
+        })
+}
+
+/// Deserialize one top-level entry, or its defaults when it is missing.
+fn section<T>(table: &toml::Table, name: &'static str) -> Result<T, ConfigError>
+where
+    T: DeserializeOwned + Default,
+{
+    table
+        .get(name)
+        .cloned()
+        .map(toml::Value::try_into)
+        .transpose()
+        .map_err(|source| ConfigError::Section {
+            section: name,
+            source,
+        })
+        .map(Option::unwrap_or_default)
+}
+
+/// Set `entry.key` in `table`, creating missing sections.
+fn apply(table: &mut toml::Table, entry: &Override) -> Result<(), ConfigError> {
+    let mut segments = entry.key.split('.').collect::<Vec<_>>();
+    let Some(last) = segments.pop().filter(|last| !last.is_empty()) else {
+        return Err(entry.error("expected section.key=value"));
+    };
+    let mut current = table;
+    for segment in segments {
+        if segment.is_empty() {
+            return Err(entry.error("expected section.key=value"));
+        }
+        current = current
+            .entry(segment)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| entry.error("the key is not inside a section"))?;
+    }
+    current.insert(last.to_owned(), parse_value(&entry.value));
+    Ok(())
+}
+
+/// Read an override as a TOML value (`5`, `true`, `["a"]`), or as a plain string.
+fn parse_value(raw: &str) -> toml::Value {
+    format!("value = {raw}")
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use tonneli_core::model::Fraction;
+    use tonneli_core::service::AuditDetail;
+
+    use super::*;
+
+    fn temp_config(name: &str, content: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("tonneli-config-{}-{name}.toml", process::id()));
+        fs::write(&path, content).expect("temp dir is writable");
+        path
+    }
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|&item| item.to_owned()).collect()
+    }
+
+    #[test]
+    fn later_layers_win() {
+        let path = temp_config(
+            "layers",
+            "[http]\nread_timeout_secs = 10\nuser_agent = \"file\"\n\n[custom]\nkept = true\n",
+        );
+        let env = [
+            ("TONNELI_HTTP__USER_AGENT", "env"),
+            ("TONNELI_TUI__RANGE_DAYS", "30"),
+            ("TONNELI_PROVIDERS__ENABLED", r#"["aachen", "cologne"]"#),
+            ("TONNELI_HOUSEHOLD__ORGANIC", "none"),
+            ("TONNELI_PRIVACY__AUDIT_VERBOSE", "true"),
+            ("TONNELI_PRIVACY__MINIMIZE_DATA", "true"),
+            ("TONNELI_CHAOS", "slow=1"),
+        ]
+        .map(|(name, value)| (name.to_owned(), value.to_owned()));
+        let args = strings(&["-v", "--config", "ignored", "--set", "http.user_agent=cli"]);
+
+        let (loader, rest) = ConfigLoader::new()
+            .env_vars(env)
+            .and_then(|loader| loader.args(args))
+            .expect("overrides are well-formed");
+        let config = loader.path(&path).load().expect("config is valid");
+
+        assert_eq!(rest, ["-v"], "unknown flags are left alone");
+        assert_eq!(config.http().read_timeout_secs, Some(10), "file value");
+        assert_eq!(
+            config.http().user_agent.as_deref(),
+            Some("cli"),
+            "the command line beats the environment and the file"
+        );
+        assert_eq!(config.frontends().tui.range_days, 30, "environment value");
+        assert_eq!(
+            config.providers().enabled.as_deref(),
+            Some(strings(&["aachen", "cologne"]).as_slice()),
+            "enabled providers are a list of city ids"
+        );
+        assert!(
+            !config.household().collects(&Fraction::Organic),
+            "household bins are configurable"
+        );
+        assert_eq!(
+            config.privacy().audit_detail(),
+            AuditDetail::Endpoint,
+            "minimizing data keeps queries out of the audit log"
+        );
+        assert!(
+            config.file.contains_key("custom"),
+            "unknown sections are kept"
+        );
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn errors_name_the_offending_key() {
+        let load = |args: &[&str]| {
+            ConfigLoader::default()
+                .args(strings(args))
+                .and_then(|(loader, _)| loader.load())
+                .err()
+                .map(|err| match &err {
+                    ConfigError::Section { source, .. } => format!("{err}: {source}"),
+                    _ => err.to_string(),
+                })
+        };
+
+        assert_eq!(
+            load(&["--set", "http.connect_timeout_secs=0"]).as_deref(),
+            Some("Invalid value for http.connect_timeout_secs: must be greater than 0"),
+            "out of range values name their key"
+        );
+        assert!(
+            load(&["--set", "http.proxi=http://proxy"])
+                .is_some_and(|message| message.contains("[http]") && message.contains("proxi")),
+            "typos in known sections are reported"
+        );
+        assert_eq!(
+            load(&["--set", "tui.default_address=Hauptstraße 12"]).as_deref(),
+            Some("Invalid value for tui.default_address: needs tui.default_city to search in"),
+            "a default address belongs to a default city"
+        );
+        assert_eq!(
+            load(&["--set", "tui"]).as_deref(),
+            Some("Invalid --set \"tui\": expected section.key=value"),
+            "malformed overrides are rejected"
+        );
+        assert_eq!(
+            load(&["--set"]).as_deref(),
+            Some("Missing value after --set"),
+            "flags need a value"
+        );
+    }
+
+    #[test]
+    fn reads_overrides_next_to_the_config_file() {
+        let dir = env::temp_dir().join(format!("tonneli-config-{}-overrides", process::id()));
+        fs::create_dir_all(&dir).expect("temp dir is writable");
+        let path = dir.join("config.toml");
+        fs::write(&path, "").expect("temp dir is writable");
+        fs::write(
+            dir.join(OVERRIDES_FILE),
+            "[[event]]\ncity = \"cologne\"\naddress = \"4711\"\naction = \"move\"\n\
+             fraction = \"Paper\"\ndate = 2025-03-10\nto = 2025-03-12\n",
+        )
+        .expect("temp dir is writable");
+
+        let config = ConfigLoader::new().path(&path).load();
+        fs::remove_dir_all(&dir).ok();
+        let overrides = config.expect("overrides are valid").overrides().clone();
+        assert_eq!(overrides.events.len(), 1, "the override is read");
+        assert!(
+            overrides
+                .events
+                .first()
+                .is_some_and(|entry| entry.address.0 == "4711"),
+            "entries keep their address"
+        );
+    }
+}
@@ -0,0 +1,513 @@
+//! Typed configuration sections and their validation.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tonneli_core::model::CityId;
+use tonneli_core::reminder::ReminderSettings;
+use tonneli_core::service::{AuditDetail, ServiceBuilder};
+
+use crate::ConfigError;
+
+/// Longest accepted reminder lead time.
+const MAX_LEAD_TIME_HOURS: u32 = 7 * 24;
+/// Longest schedule range a frontend may request by default.
+const MAX_RANGE_DAYS: u32 = 366;
+/// How long schedules are reused when `http.schedule_cache_secs` is unset.
+const DEFAULT_SCHEDULE_CACHE_SECS: u64 = 5 * 60;
+/// Languages the Recycle! app publishes names in.
+const RECYCLE_LANGUAGES: &[&str] = &["nl", "fr", "de", "en"];
+
+/// Connection settings from the `[http]` section; unset keys keep the service defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpSettings {
+    /// `User-Agent` sent to provider backends.
+    pub user_agent: Option<String>,
+    /// Proxy URL all provider requests are routed through.
+    pub proxy: Option<String>,
+    /// Additional PEM files with trusted root certificates.
+    pub root_certificates: Vec<PathBuf>,
+    /// Trust only `root_certificates`.
+    pub only_custom_roots: bool,
+    /// Idle connections kept per provider host.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Maximum time to establish a connection.
+    pub connect_timeout_secs: Option<u64>,
+    /// Maximum time to wait for data while reading a response.
+    pub read_timeout_secs: Option<u64>,
+    /// Longest a single fetch may take, including retries.
+    pub max_request_duration_secs: Option<u64>,
+    /// Largest accepted response body.
+    pub max_response_bytes: Option<usize>,
+    /// How long a fetched schedule is reused before asking the provider again; `0` disables it.
+    pub schedule_cache_secs: Option<u64>,
+    /// Most calls per minute sent to a city's backend, by city id, e.g. `cologne = 30`.
+    pub rate_limits: BTreeMap<String, u32>,
+}
+
+impl HttpSettings {
+    /// Apply the configured values to a service builder.
+    #[must_use]
+    pub fn apply(&self, mut builder: ServiceBuilder) -> ServiceBuilder {
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent.clone());
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        for path in &self.root_certificates {
+            builder = builder.root_certificate(path.clone());
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(secs) = self.connect_timeout_secs {
+            builder = builder.connect_timeout(Duration::from_secs(secs));
+        }
+        if let Some(secs) = self.read_timeout_secs {
+            builder = builder.read_timeout(Duration::from_secs(secs));
+        }
+        if let Some(secs) = self.max_request_duration_secs {
+            builder = builder.max_request_duration(Duration::from_secs(secs));
+        }
+        if let Some(limit) = self.max_response_bytes {
+            builder = builder.max_response_bytes(limit);
+        }
+        match self
+            .schedule_cache_secs
+            .unwrap_or(DEFAULT_SCHEDULE_CACHE_SECS)
+        {
+            0 => {}
+            secs => builder = builder.schedule_cache(Duration::from_secs(secs)),
+        }
+        for (city, per_minute) in &self.rate_limits {
+            builder = builder.rate_limit(CityId(city.clone()), *per_minute);
+        }
+        builder.only_custom_roots(self.only_custom_roots)
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(proxy) = &self.proxy {
+            check_url("http.proxy", proxy, &["http://", "https://", "socks5://"])?;
+        }
+        let limits = [
+            ("http.connect_timeout_secs", self.connect_timeout_secs),
+            ("http.read_timeout_secs", self.read_timeout_secs),
+            (
+                "http.max_request_duration_secs",
+                self.max_request_duration_secs,
+            ),
+            (
+                "http.max_response_bytes",
+                self.max_response_bytes.map(|bytes| bytes as u64),
+            ),
+        ];
+        for (key, value) in limits {
+            if value == Some(0) {
+                return Err(ConfigError::invalid(key, "must be greater than 0"));
+            }
+        }
+        if let Some((city, _)) = self.rate_limits.iter().find(|(_, limit)| **limit == 0) {
+            return Err(ConfigError::invalid(
+                format!("http.rate_limits.{city}"),
+                "must be greater than 0",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Additional town hosted on a `RegioIT` instance, from `[[regioit_towns]]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegioItTownSettings {
+    /// City id used by the service, e.g. `"fuerth"`.
+    pub id: String,
+    /// Display name; defaults to `town`.
+    pub name: Option<String>,
+    /// Town name as listed by the instance's `/orte` endpoint.
+    pub town: String,
+    /// Abfallnavi service hosting the town, e.g. `"aachen"`; alternative to `base_url`.
+    pub service: Option<String>,
+    /// REST base URL of the instance, for instances outside Abfallnavi.
+    pub base_url: Option<String>,
+    /// Ort id of the town, which saves looking it up by name.
+    pub ort_id: Option<i64>,
+}
+
+/// Municipality on the Abfall.IO backend, from `[[abfallio_towns]]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AbfallIoTownSettings {
+    /// City id used by the service, e.g. `"musterstadt"`.
+    pub id: String,
+    /// Display name.
+    pub name: String,
+    /// Client key of the operator, as embedded in the town's calendar widget.
+    pub key: String,
+    /// Municipality id (`f_id_kommune`) within the operator's calendar.
+    pub kommune: String,
+    /// API base URL; defaults to the public Abfall.IO API.
+    pub base_url: Option<String>,
+}
+
+/// City served by a Jumomind (`MyMüll`) operator, from `[[jumomind_towns]]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JumomindTownSettings {
+    /// City id used by the service, e.g. `"musterstadt"`.
+    pub id: String,
+    /// Display name; defaults to `city`.
+    pub name: Option<String>,
+    /// Service id of the operator, e.g. `"mymuell"` or `"zaw"`.
+    pub service_id: String,
+    /// City name as listed by the operator's city list.
+    pub city: String,
+    /// API base URL; defaults to the operator's endpoint on jumomind.com.
+    pub base_url: Option<String>,
+}
+
+/// Belgian municipality in the Recycle! app, from `[[recycle_towns]]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecycleTownSettings {
+    /// City id used by the service, e.g. `"leuven"`.
+    pub id: String,
+    /// Display name.
+    pub name: String,
+    /// Four-digit postal code of the municipality.
+    pub zip_code: String,
+    /// Secret the recycleapp.be web app sends to obtain access tokens (`x-secret`).
+    pub secret: String,
+    /// Language of street and fraction names: `nl` (default), `fr`, `de` or `en`.
+    pub language: Option<String>,
+    /// API base URL; defaults to the public Recycle! API.
+    pub base_url: Option<String>,
+}
+
+/// Town whose schedule is kept in a local JSON or CSV file, from `[[file_towns]]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileTownSettings {
+    /// City id used by the service, e.g. `"musterdorf"`.
+    pub id: String,
+    /// Display name.
+    pub name: String,
+    /// Schedule file; relative paths are resolved against the working directory.
+    pub path: PathBuf,
+}
+
+/// The `[providers]` section.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ProvidersSection {
+    pub(crate) enabled: Option<Vec<String>>,
+}
+
+/// Which providers are registered, and the ones beyond the built-in ones.
+#[derive(Debug, Clone, Default)]
+pub struct ProviderSettings {
+    /// City ids to register, from `enabled` in `[providers]`; every provider when unset.
+    ///
+    /// Configured towns count as well, so their ids have to be listed too.
+    pub enabled: Option<Vec<String>>,
+    /// Extra `RegioIT` towns, from the top-level `[[regioit_towns]]` array.
+    pub regioit_towns: Vec<RegioItTownSettings>,
+    /// Abfall.IO towns, from the top-level `[[abfallio_towns]]` array.
+    pub abfallio_towns: Vec<AbfallIoTownSettings>,
+    /// Jumomind towns, from the top-level `[[jumomind_towns]]` array.
+    pub jumomind_towns: Vec<JumomindTownSettings>,
+    /// Recycle! municipalities, from the top-level `[[recycle_towns]]` array.
+    pub recycle_towns: Vec<RecycleTownSettings>,
+    /// Towns kept in schedule files, from the top-level `[[file_towns]]` array.
+    pub file_towns: Vec<FileTownSettings>,
+}
+
+impl ProviderSettings {
+    /// Restrict the service to the enabled cities, if any are configured.
+    #[must_use]
+    pub fn apply(&self, builder: ServiceBuilder) -> ServiceBuilder {
+        match &self.enabled {
+            Some(enabled) => builder.enabled_cities(enabled.iter().cloned().map(CityId)),
+            None => builder,
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), ConfigError> {
+        for (index, id) in self.enabled.iter().flatten().enumerate() {
+            if id.trim().is_empty() {
+                return Err(ConfigError::invalid(
+                    format!("providers.enabled[{index}]"),
+                    "must not be empty",
+                ));
+            }
+        }
+        for (index, town) in self.regioit_towns.iter().enumerate() {
+            let key = |field: &str| format!("regioit_towns[{index}].{field}");
+            if town.id.trim().is_empty() {
+                return Err(ConfigError::invalid(key("id"), "must not be empty"));
+            }
+            if town.town.trim().is_empty() {
+                return Err(ConfigError::invalid(key("town"), "must not be empty"));
+            }
+            match (&town.service, &town.base_url) {
+                (Some(service), None) => check_host_label(&key("service"), service)?,
+                (None, Some(base_url)) => {
+                    check_url(&key("base_url"), base_url, &["http://", "https://"])?;
+                }
+                _ => {
+                    return Err(ConfigError::invalid(
+                        key("service"),
+                        "set either service or base_url",
+                    ));
+                }
+            }
+        }
+        for (index, town) in self.abfallio_towns.iter().enumerate() {
+            let key = |field: &str| format!("abfallio_towns[{index}].{field}");
+            for (field, value) in [
+                ("id", &town.id),
+                ("name", &town.name),
+                ("key", &town.key),
+                ("kommune", &town.kommune),
+            ] {
+                if value.trim().is_empty() {
+                    return Err(ConfigError::invalid(key(field), "must not be empty"));
+                }
+            }
+            if let Some(base_url) = &town.base_url {
+                check_url(&key("base_url"), base_url, &["http://", "https://"])?;
+            }
+        }
+        for (index, town) in self.jumomind_towns.iter().enumerate() {
+            let key = |field: &str| format!("jumomind_towns[{index}].{field}");
+            for (field, value) in [("id", &town.id), ("city", &town.city)] {
+                if value.trim().is_empty() {
+                    return Err(ConfigError::invalid(key(field), "must not be empty"));
+                }
+            }
+            check_host_label(&key("service_id"), &town.service_id)?;
+            if let Some(base_url) = &town.base_url {
+                check_url(&key("base_url"), base_url, &["http://", "https://"])?;
+            }
+        }
+        for (index, town) in self.recycle_towns.iter().enumerate() {
+            let key = |field: &str| format!("recycle_towns[{index}].{field}");
+            for (field, value) in [
+                ("id", &town.id),
+                ("name", &town.name),
+                ("secret", &town.secret),
+            ] {
+                if value.trim().is_empty() {
+                    return Err(ConfigError::invalid(key(field), "must not be empty"));
+                }
+            }
+            if town.zip_code.len() != 4 || !town.zip_code.bytes().all(|byte| byte.is_ascii_digit())
+            {
+                return Err(ConfigError::invalid(key("zip_code"), "must be four digits"));
+            }
+            if let Some(language) = &town.language
+                && !RECYCLE_LANGUAGES.contains(&language.as_str())
+            {
+                return Err(ConfigError::invalid(
+                    key("language"),
+                    format!("must be one of {}", RECYCLE_LANGUAGES.join(", ")),
+                ));
+            }
+            if let Some(base_url) = &town.base_url {
+                check_url(&key("base_url"), base_url, &["http://", "https://"])?;
+            }
+        }
+        for (index, town) in self.file_towns.iter().enumerate() {
+            let key = |field: &str| format!("file_towns[{index}].{field}");
+            for (field, value) in [("id", &town.id), ("name", &town.name)] {
+                if value.trim().is_empty() {
+                    return Err(ConfigError::invalid(key(field), "must not be empty"));
+                }
+            }
+            if town.path.as_os_str().is_empty() {
+                return Err(ConfigError::invalid(key("path"), "must not be empty"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where frontends keep fetched schedules, from the `[cache]` section.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheSettings {
+    /// Cache directory; defaults to the platform cache directory.
+    pub dir: Option<PathBuf>,
+    /// Age after which a cached schedule is fetched again.
+    pub max_age_hours: u64,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            max_age_hours: 24,
+        }
+    }
+}
+
+impl CacheSettings {
+    /// Configured directory, or `tonneli` in the platform cache directory.
+    #[must_use]
+    pub fn dir(&self) -> Option<PathBuf> {
+        self.dir
+            .clone()
+            .or_else(|| dirs::cache_dir().map(|dir| dir.join("tonneli")))
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), ConfigError> {
+        if self.max_age_hours == 0 {
+            return Err(ConfigError::invalid(
+                "cache.max_age_hours",
+                "must be greater than 0",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What frontends record about the user, from the `[privacy]` section.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrivacySettings {
+    /// Write every request sent to a provider to an audit log.
+    pub audit_log: bool,
+    /// Include query strings, which usually contain the address, in the audit log.
+    pub audit_verbose: bool,
+    /// Keep house numbers out of logs, error reports and the audit log.
+    pub minimize_data: bool,
+    /// Key file encrypting stored data such as the checklist; created with a random key if missing.
+    pub key_file: Option<PathBuf>,
+}
+
+impl PrivacySettings {
+    /// Detail of audit log entries; minimizing data overrules `audit_verbose`.
+    #[must_use]
+    pub fn audit_detail(&self) -> AuditDetail {
+        if self.audit_verbose && !self.minimize_data {
+            AuditDetail::Verbose
+        } else {
+            AuditDetail::Endpoint
+        }
+    }
+
+    /// Apply data minimization to a service builder; the audit log itself is up to the frontend.
+    #[must_use]
+    pub fn apply(&self, builder: ServiceBuilder) -> ServiceBuilder {
+        builder.minimize_data(self.minimize_data)
+    }
+}
+
+/// Terminal UI settings, from the `[tui]` section.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TuiSettings {
+    /// City id preselected on the city screen.
+    pub default_city: Option<String>,
+    /// Address searched in `default_city` on start, e.g. `"Hauptstraße 12"`; its schedule opens.
+    pub default_address: Option<String>,
+    /// Days of pickups shown from today on.
+    pub range_days: u32,
+}
+
+impl Default for TuiSettings {
+    fn default() -> Self {
+        Self {
+            default_city: None,
+            default_address: None,
+            range_days: 60,
+        }
+    }
+}
+
+impl TuiSettings {
+    pub(crate) fn validate(&self) -> Result<(), ConfigError> {
+        if !(1..=MAX_RANGE_DAYS).contains(&self.range_days) {
+            return Err(ConfigError::invalid(
+                "tui.range_days",
+                format!("must be between 1 and {MAX_RANGE_DAYS}"),
+            ));
+        }
+        if self
+            .default_city
+            .as_deref()
+            .is_some_and(|city| city.trim().is_empty())
+        {
+            return Err(ConfigError::invalid(
+                "tui.default_city",
+                "must not be empty",
+            ));
+        }
+        if let Some(address) = &self.default_address {
+            if address.trim().is_empty() {
+                return Err(ConfigError::invalid(
+                    "tui.default_address",
+                    "must not be empty",
+                ));
+            }
+            if self.default_city.is_none() {
+                return Err(ConfigError::invalid(
+                    "tui.default_address",
+                    "needs tui.default_city to search in",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Settings of the individual frontends.
+#[derive(Debug, Clone, Default)]
+pub struct FrontendSettings {
+    /// Terminal UI.
+    pub tui: TuiSettings,
+}
+
+pub(crate) fn validate_reminders(reminders: &ReminderSettings) -> Result<(), ConfigError> {
+    if reminders.lead_time_hours > MAX_LEAD_TIME_HOURS {
+        return Err(ConfigError::invalid(
+            "reminders.lead_time_hours",
+            format!("must be at most {MAX_LEAD_TIME_HOURS}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Service names become part of a host name.
+fn check_host_label(key: &str, label: &str) -> Result<(), ConfigError> {
+    if label.is_empty()
+        || !label
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-')
+    {
+        return Err(ConfigError::invalid(
+            key,
+            "must consist of letters, digits and dashes",
+        ));
+    }
+    Ok(())
+}
+
+fn check_url(key: &str, url: &str, schemes: &[&str]) -> Result<(), ConfigError> {
+    let has_host = schemes
+        .iter()
+        .find_map(|scheme| url.strip_prefix(scheme))
+        .is_some_and(|rest| !rest.trim().is_empty());
+    if has_host {
+        Ok(())
+    } else {
+        Err(ConfigError::invalid(
+            key,
+            format!("{url:?} is not a URL starting with {}", schemes.join(", ")),
+        ))
+    }
+}
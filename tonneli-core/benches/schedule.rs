@@ -0,0 +1,91 @@
+//! Sorting, grouping and merging of the events of one address.
+#![expect(
+    missing_docs,
+    reason = "criterion_group! generates an undocumented pub fn"
+)]
+#![expect(
+    unused_crate_dependencies,
+    reason = "benchmarks only exercise part of the library's dependencies"
+)]
+
+use std::hint::black_box;
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate};
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use tonneli_core::merge::merge_events;
+use tonneli_core::model::{Fraction, PickupEvent};
+
+const YEARS: i64 = 5;
+
+/// Five years of bi-weekly and four-weekly pickups, ordered by fraction like provider responses.
+fn events() -> Vec<PickupEvent> {
+    let start = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap_or_default();
+    let rotation = [
+        (Fraction::Residual, 14, 0, "Restabfall"),
+        (Fraction::Organic, 7, 1, "Bioabfall"),
+        (Fraction::Paper, 28, 2, "Papier / Pappe"),
+        (Fraction::Plastic, 14, 3, "Leichtverpackungen"),
+        (Fraction::Glass, 28, 4, "Altglas"),
+        (
+            Fraction::Other("Sperrmüll".into()),
+            91,
+            0,
+            "Sperrmüll auf Abruf",
+        ),
+    ];
+
+    rotation
+        .into_iter()
+        .flat_map(|(fraction, interval, offset, note)| {
+            let note = Arc::<str>::from(note);
+            (0..YEARS * 365 / interval).map(move |period| PickupEvent {
+                date: start + Duration::days(offset + period * interval),
+                fraction: fraction.clone(),
+                note: Some(Arc::clone(&note)),
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            })
+        })
+        .collect()
+}
+
+fn bench_schedule(criterion: &mut Criterion) {
+    let events = events();
+
+    criterion.bench_function("sort_by_date", |bencher| {
+        bencher.iter_batched(
+            || events.clone(),
+            |mut events| {
+                events.sort_by_key(|event| event.date);
+                events
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    let mut sorted = events.clone();
+    sorted.sort_by_key(|event| event.date);
+    criterion.bench_function("group_by_date", |bencher| {
+        bencher.iter(|| {
+            black_box(&sorted)
+                .chunk_by(|left, right| left.date == right.date)
+                .map(<[PickupEvent]>::len)
+                .max()
+        });
+    });
+
+    // A cached snapshot refreshed with a fresh copy: every event is a duplicate.
+    criterion.bench_function("merge_overlapping_sources", |bencher| {
+        bencher.iter_batched(
+            || (events.clone(), events.clone()),
+            |(cached, fresh)| merge_events([cached, fresh]),
+            BatchSize::SmallInput,
+        );
+    });
+}
+
+criterion_group!(benches, bench_schedule);
+criterion_main!(benches);
@@ -0,0 +1,114 @@
+//! Client-side street filtering as done by the `RegioIT` providers over a whole town.
+#![expect(
+    missing_docs,
+    reason = "criterion_group! generates an undocumented pub fn"
+)]
+#![expect(
+    unused_crate_dependencies,
+    reason = "benchmarks only exercise part of the library's dependencies"
+)]
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use tonneli_core::matching::{StreetMatcher, fold};
+
+const PREFIXES: &[&str] = &[
+    "", "Ober", "Unter", "Alt", "Neu", "Groß", "Klein", "Hinter", "Vorder", "Äußere", "Innere",
+    "Nord", "Süd", "Ost", "West", "Hohe", "Lange", "Kurze", "Sankt-", "Am ",
+];
+const NAMES: &[&str] = &[
+    "haupt",
+    "bahnhof",
+    "kirch",
+    "schul",
+    "garten",
+    "linden",
+    "berg",
+    "wald",
+    "mühl",
+    "rosen",
+    "eichen",
+    "birken",
+    "nürnberger",
+    "fürther",
+    "schwabacher",
+    "königs",
+    "markt",
+    "bucher",
+    "johannis",
+    "löffelholz",
+    "tafelhof",
+    "wöhrder",
+    "gleißhammer",
+    "maxfeld",
+    "sündersbühl",
+];
+const SUFFIXES: &[&str] = &[
+    "straße", "weg", "gasse", "platz", "allee", "ring", "steig", "anger", "graben", "hof", "damm",
+    "ufer", "markt", "pfad", "tor", "brücke", "feld", "wiese", "höhe", "winkel",
+];
+
+/// 10 000 distinct street names in German spelling.
+fn streets() -> Vec<String> {
+    PREFIXES
+        .iter()
+        .flat_map(|prefix| {
+            NAMES.iter().flat_map(move |name| {
+                SUFFIXES.iter().map(move |suffix| {
+                    let name = if prefix.is_empty() || prefix.ends_with([' ', '-']) {
+                        let mut chars = name.chars();
+                        chars.next().map_or_else(String::new, |first| {
+                            first.to_uppercase().chain(chars).collect()
+                        })
+                    } else {
+                        (*name).to_owned()
+                    };
+                    format!("{prefix}{name}{suffix}")
+                })
+            })
+        })
+        .collect()
+}
+
+/// Rank and order candidates the way the Nuremberg address port does.
+fn filter<'street>(streets: &'street [String], query: &str, fuzzy: bool) -> Vec<&'street str> {
+    let matcher = StreetMatcher::new(query, fuzzy);
+    let mut candidates = streets
+        .iter()
+        .filter_map(|street| matcher.rank(street).map(|rank| (rank, street.as_str())))
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|(rank, _)| *rank);
+    candidates.into_iter().map(|(_, street)| street).collect()
+}
+
+fn bench_street_filter(criterion: &mut Criterion) {
+    let streets = streets();
+    assert_eq!(streets.len(), 10_000, "benchmark expects a 10k street town");
+
+    let mut group = criterion.benchmark_group("street_filter_10k");
+    for (query, fuzzy) in [
+        ("Hauptmarkt", false),
+        ("Fürther Straße", false),
+        ("fuerther", true),
+        ("Gleishammerweg", true),
+        ("xyz", true),
+    ] {
+        let label = if fuzzy { "fuzzy" } else { "exact" };
+        group.bench_with_input(BenchmarkId::new(label, query), &query, |bencher, query| {
+            bencher.iter(|| filter(black_box(&streets), query, fuzzy));
+        });
+    }
+    group.finish();
+
+    criterion.bench_function("fold_10k", |bencher| {
+        bencher.iter(|| {
+            for street in &streets {
+                black_box(fold(street));
+            }
+        });
+    });
+}
+
+criterion_group!(benches, bench_street_filter);
+criterion_main!(benches);
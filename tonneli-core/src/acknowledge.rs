@@ -0,0 +1,260 @@
+//! Acknowledged and snoozed reminders, shared by every channel.
+//!
+//! A user marks a pickup as handled ("bin is out") or snoozes its reminder on one
+//! device; the state is stored per pickup in a [`StoragePort`], so other frontends
+//! and notification channels reading the same storage stop nagging as well.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Address, Fraction, PickupEvent};
+use crate::storage::{StorageError, StoragePort};
+
+/// Storage namespace of the reminder states.
+pub const REMINDER_STATE_NAMESPACE: &str = "reminder-state";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "lowercase")]
+/// What the user did about the reminder of one pickup.
+pub enum ReminderState {
+    /// The bin is out; no further reminders for this pickup.
+    Acknowledged,
+    /// Remind again once `until` has passed.
+    Snoozed {
+        /// End of the snooze, local time.
+        until: NaiveDateTime,
+    },
+}
+
+impl ReminderState {
+    /// Whether reminders are held back at `now`.
+    #[must_use]
+    pub fn silences(self, now: NaiveDateTime) -> bool {
+        match self {
+            Self::Acknowledged => true,
+            Self::Snoozed { until } => now < until,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Stored value; keeps the date so stale entries can be dropped without parsing keys.
+struct Entry {
+    date: NaiveDate,
+    #[serde(flatten)]
+    state: ReminderState,
+}
+
+#[derive(Debug, Clone, Default)]
+/// Reminder states of all pickups, as loaded from storage.
+pub struct ReminderStates {
+    entries: HashMap<String, Entry>,
+}
+
+impl ReminderStates {
+    /// Read all states from `storage`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] when the storage cannot be read or holds undecodable values.
+    pub async fn load(storage: &dyn StoragePort) -> Result<Self, StorageError> {
+        let mut entries = HashMap::new();
+        for key in storage.list(REMINDER_STATE_NAMESPACE).await? {
+            let Some(raw) = storage.get(REMINDER_STATE_NAMESPACE, &key).await? else {
+                continue;
+            };
+            let entry =
+                serde_json::from_slice(&raw).map_err(|err| StorageError::Backend(Box::new(err)))?;
+            entries.insert(key, entry);
+        }
+        Ok(Self { entries })
+    }
+
+    /// State of the pickup at `address`, if the user did anything about it.
+    #[must_use]
+    pub fn get(&self, address: &Address, pickup: &PickupEvent) -> Option<ReminderState> {
+        self.entries
+            .get(&pickup_key(address, pickup))
+            .map(|entry| entry.state)
+    }
+
+    /// Whether the pickup has been acknowledged.
+    #[must_use]
+    pub fn is_acknowledged(&self, address: &Address, pickup: &PickupEvent) -> bool {
+        self.get(address, pickup) == Some(ReminderState::Acknowledged)
+    }
+
+    /// Whether reminders of the pickup are held back at `now`.
+    #[must_use]
+    pub fn silences(&self, address: &Address, pickup: &PickupEvent, now: NaiveDateTime) -> bool {
+        self.get(address, pickup)
+            .is_some_and(|state| state.silences(now))
+    }
+
+    /// Set or, with `None`, clear the state of a pickup and write it to `storage`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] when the storage cannot be written; the in-memory
+    /// state is only changed after a successful write.
+    pub async fn set(
+        &mut self,
+        storage: &dyn StoragePort,
+        address: &Address,
+        pickup: &PickupEvent,
+        state: Option<ReminderState>,
+    ) -> Result<(), StorageError> {
+        let key = pickup_key(address, pickup);
+        let Some(state) = state else {
+            storage.delete(REMINDER_STATE_NAMESPACE, &key).await?;
+            self.entries.remove(&key);
+            return Ok(());
+        };
+        let entry = Entry {
+            date: pickup.date,
+            state,
+        };
+        let raw = serde_json::to_vec(&entry).map_err(|err| StorageError::Backend(Box::new(err)))?;
+        storage.put(REMINDER_STATE_NAMESPACE, &key, &raw).await?;
+        self.entries.insert(key, entry);
+        Ok(())
+    }
+
+    /// Drop the states of pickups before `today` from memory and `storage`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] when the storage cannot be written.
+    pub async fn prune(
+        &mut self,
+        storage: &dyn StoragePort,
+        today: NaiveDate,
+    ) -> Result<(), StorageError> {
+        let stale = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.date < today)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in stale {
+            storage.delete(REMINDER_STATE_NAMESPACE, &key).await?;
+            self.entries.remove(&key);
+        }
+        Ok(())
+    }
+}
+
+/// Storage key of one pickup: city, address id, date and fraction.
+fn pickup_key(address: &Address, pickup: &PickupEvent) -> String {
+    let fraction = match &pickup.fraction {
+        Fraction::Residual => Cow::Borrowed("residual"),
+        Fraction::Organic => Cow::Borrowed("organic"),
+        Fraction::Paper => Cow::Borrowed("paper"),
+        Fraction::Plastic => Cow::Borrowed("plastic"),
+        Fraction::Glass => Cow::Borrowed("glass"),
+        Fraction::Metal => Cow::Borrowed("metal"),
+        Fraction::StreetCleaning => Cow::Borrowed("street-cleaning"),
+        Fraction::BulkyWaste => Cow::Borrowed("bulky-waste"),
+        Fraction::GardenWaste => Cow::Borrowed("garden-waste"),
+        Fraction::Hazardous => Cow::Borrowed("hazardous"),
+        Fraction::ChristmasTree => Cow::Borrowed("christmas-tree"),
+        Fraction::Textiles => Cow::Borrowed("textiles"),
+        Fraction::Electronics => Cow::Borrowed("electronics"),
+        Fraction::Other(name) => Cow::Owned(format!("other:{name}")),
+    };
+    format!(
+        "{}:{}:{}:{fraction}",
+        address.city.0, address.id.0, pickup.date
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::model::{AddressId, CityId};
+    use crate::storage::MemoryStorage;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).expect("valid test date")
+    }
+
+    fn pickup(date: NaiveDate, fraction: Fraction) -> PickupEvent {
+        PickupEvent {
+            date,
+            fraction,
+            note: None,
+            container: None,
+            correction: None,
+            holiday: None,
+            predicted: false,
+        }
+    }
+
+    #[test]
+    fn states_survive_reloading_and_expire() {
+        let storage = MemoryStorage::default();
+        let address = Address {
+            id: AddressId("1".to_owned()),
+            city: CityId("cologne".to_owned()),
+            label: "Domkloster 4".into(),
+            street: "Domkloster".into(),
+            house_number: "4".into(),
+        };
+        let paper = pickup(day(11), Fraction::Paper);
+        let glass = pickup(day(12), Fraction::Glass);
+        let evening = day(10).and_hms_opt(20, 0, 0).expect("valid test time");
+
+        let mut states = ReminderStates::default();
+        block_on(async {
+            states
+                .set(
+                    &storage,
+                    &address,
+                    &paper,
+                    Some(ReminderState::Acknowledged),
+                )
+                .await?;
+            states
+                .set(
+                    &storage,
+                    &address,
+                    &glass,
+                    Some(ReminderState::Snoozed {
+                        until: evening + Duration::hours(1),
+                    }),
+                )
+                .await
+        })
+        .expect("memory storage accepts states");
+
+        let reloaded = block_on(ReminderStates::load(&storage)).expect("states decode");
+        assert!(
+            reloaded.is_acknowledged(&address, &paper),
+            "acknowledgments are read back"
+        );
+        assert!(
+            reloaded.silences(&address, &glass, evening),
+            "snoozed reminders are held back"
+        );
+        assert!(
+            !reloaded.silences(&address, &glass, evening + Duration::hours(2)),
+            "snoozes end"
+        );
+
+        let mut pruned = reloaded;
+        block_on(pruned.prune(&storage, day(12))).expect("memory storage deletes states");
+        assert_eq!(
+            block_on(storage.list(REMINDER_STATE_NAMESPACE))
+                .expect("memory storage lists keys")
+                .len(),
+            1,
+            "only the state of the past pickup is dropped"
+        );
+    }
+}
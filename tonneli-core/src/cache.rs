@@ -0,0 +1,229 @@
+//! Memoizing provider schedules for a while, so browsing between addresses and
+//! ranges does not ask the city's API again each time.
+//!
+//! [`CachedSchedulePort`] wraps any [`SchedulePort`] and keeps successful answers
+//! per address and range until their time to live runs out; errors are not cached.
+//! [`cached`] wraps the schedule port of a whole plugin.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use web_time::Instant;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::model::{AddressId, CityMeta, DateRange, PickupEvent};
+use crate::plugin::CityPlugin;
+use crate::ports::{PortError, SchedulePort, ScheduleReport};
+
+/// Address and range a schedule was fetched for.
+type CacheKey = (AddressId, NaiveDate, NaiveDate);
+
+/// Schedule port decorator answering repeated requests from memory.
+pub struct CachedSchedulePort {
+    inner: Arc<dyn SchedulePort>,
+    ttl: Duration,
+    entries: Mutex<HashMap<CacheKey, (Instant, ScheduleReport)>>,
+}
+
+impl CachedSchedulePort {
+    /// Cache the schedules of `inner` for `ttl` after they were fetched.
+    #[must_use]
+    pub fn new(inner: Arc<dyn SchedulePort>, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Forget every cached schedule, e.g. when the user asks for fresh data.
+    pub fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    fn lookup(&self, key: &CacheKey) -> Option<ScheduleReport> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries
+            .get(key)
+            .filter(|(fetched, _)| fetched.elapsed() < self.ttl)
+            .map(|(_, report)| report.clone())
+    }
+
+    fn store(&self, key: CacheKey, report: ScheduleReport) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.retain(|_, (fetched, _)| fetched.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), report));
+    }
+}
+
+/// Wrap the schedule port of `plugin` so its answers are reused for `ttl`.
+#[must_use]
+pub fn cached(plugin: CityPlugin, ttl: Duration) -> CityPlugin {
+    CityPlugin {
+        schedule_port: Arc::new(CachedSchedulePort::new(plugin.schedule_port, ttl)),
+        ..plugin
+    }
+}
+
+#[async_trait]
+impl SchedulePort for CachedSchedulePort {
+    fn city(&self) -> &CityMeta {
+        self.inner.city()
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let key = (address_id.clone(), range.start, range.end);
+        if let Some(report) = self.lookup(&key) {
+            return Ok(report);
+        }
+        let report = self.inner.schedule_report(address_id, range).await?;
+        self.store(key, report.clone());
+        Ok(report)
+    }
+
+    fn supports_partial_fetch(&self) -> bool {
+        self.inner.supports_partial_fetch()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::model::Fraction;
+
+    /// Provider counting its calls; fails for the address `"down"`.
+    struct Counting {
+        meta: CityMeta,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SchedulePort for Counting {
+        fn city(&self) -> &CityMeta {
+            &self.meta
+        }
+
+        async fn schedule(
+            &self,
+            address_id: &AddressId,
+            range: DateRange,
+        ) -> Result<Vec<PickupEvent>, PortError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if address_id.0 == "down" {
+                return Err(PortError::Timeout);
+            }
+            Ok(vec![PickupEvent {
+                date: range.start,
+                fraction: Fraction::Paper,
+                note: None,
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            }])
+        }
+    }
+
+    fn counting() -> Arc<Counting> {
+        Arc::new(Counting {
+            meta: CityMeta::new("bremen", "Bremen"),
+            calls: AtomicUsize::new(0),
+        })
+    }
+
+    fn cache(inner: &Arc<Counting>, ttl: Duration) -> CachedSchedulePort {
+        let shared: Arc<dyn SchedulePort> = Arc::<Counting>::clone(inner);
+        CachedSchedulePort::new(shared, ttl)
+    }
+
+    fn march(start: u32) -> DateRange {
+        DateRange {
+            start: NaiveDate::from_ymd_opt(2025, 3, start).expect("valid test date"),
+            end: NaiveDate::from_ymd_opt(2025, 3, 31).expect("valid test date"),
+        }
+    }
+
+    #[test]
+    fn reuses_answers_per_address_and_range() {
+        let inner = counting();
+        let port = cache(&inner, Duration::from_mins(1));
+        let home = AddressId("home".to_owned());
+
+        for _ in 0..3 {
+            let events = block_on(port.schedule(&home, march(1))).expect("provider answers");
+            assert_eq!(events.len(), 1, "the cached answer is complete");
+        }
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1, "fetched once");
+
+        block_on(port.schedule(&home, march(15))).expect("provider answers");
+        block_on(port.schedule(&AddressId("work".to_owned()), march(1))).expect("provider answers");
+        assert_eq!(
+            inner.calls.load(Ordering::SeqCst),
+            3,
+            "other ranges and addresses are fetched"
+        );
+
+        port.clear();
+        block_on(port.schedule(&home, march(1))).expect("provider answers");
+        assert_eq!(
+            inner.calls.load(Ordering::SeqCst),
+            4,
+            "cleared entries are fetched again"
+        );
+    }
+
+    #[test]
+    fn does_not_cache_errors() {
+        let inner = counting();
+        let port = cache(&inner, Duration::from_mins(1));
+        let down = AddressId("down".to_owned());
+        for _ in 0..2 {
+            assert!(
+                block_on(port.schedule(&down, march(1))).is_err(),
+                "errors are passed on"
+            );
+        }
+        assert_eq!(
+            inner.calls.load(Ordering::SeqCst),
+            2,
+            "errors are not cached"
+        );
+    }
+
+    #[test]
+    fn fetches_expired_answers_again() {
+        let inner = counting();
+        let port = cache(&inner, Duration::ZERO);
+        let home = AddressId("home".to_owned());
+        for _ in 0..2 {
+            block_on(port.schedule(&home, march(1))).expect("provider answers");
+        }
+        assert_eq!(
+            inner.calls.load(Ordering::SeqCst),
+            2,
+            "expired answers are fetched again"
+        );
+    }
+}
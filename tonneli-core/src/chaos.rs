@@ -0,0 +1,366 @@
+//! Failure injection for exercising retry and partial-result paths during development.
+//!
+//! [`chaotic`] wraps the ports of a plugin so that a share of their calls time out,
+//! get throttled, receive a response that does not decode or answer late. Enable it
+//! with the `TONNELI_CHAOS` environment variable, see [`ChaosConfig::from_env`].
+
+use std::env;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tonneli_http::sleep;
+
+use crate::model::{
+    Address, AddressId, CityMeta, DateRange, IdMigration, PickupEvent, VersionedAddressId,
+};
+use crate::plugin::CityPlugin;
+use crate::ports::{
+    AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport, SearchResults,
+};
+
+/// Environment variable holding a [`ChaosConfig`], e.g.
+/// `timeout=0.1,rate_limit=0.1,malformed=0.05,slow=0.2,delay_ms=2000`.
+pub const CHAOS_ENV: &str = "TONNELI_CHAOS";
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+/// Reasons a [`ChaosConfig`] cannot be parsed.
+pub enum ChaosConfigError {
+    /// An entry is not of the form `key=value`.
+    #[error("Expected key=value, got {0:?}")]
+    Entry(String),
+    /// The key is not a known setting.
+    #[error("Unknown chaos setting {0:?}")]
+    UnknownKey(String),
+    /// The value is not a number in the allowed range.
+    #[error("Invalid value for {key}: {value:?}")]
+    Value {
+        /// Setting the value was given for.
+        key: String,
+        /// Value as written.
+        value: String,
+    },
+    /// The failure rates add up to more than one.
+    #[error("Chaos rates add up to more than 1")]
+    RatesTooHigh,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Share of port calls that fail or are slowed down, each between 0 and 1.
+pub struct ChaosConfig {
+    /// Calls that wait for `delay` and then fail with [`PortError::Timeout`].
+    pub timeout: f64,
+    /// Calls that fail with [`PortError::RateLimited`], as if the provider answered 429.
+    pub rate_limit: f64,
+    /// Calls that fail with [`PortError::Decode`], as if the provider sent broken JSON.
+    pub malformed: f64,
+    /// Calls that succeed, but only after waiting for `delay`.
+    pub slow: f64,
+    /// How long slow and timed out calls take.
+    pub delay: Duration,
+    /// Seed for reproducible runs; random when unset.
+    pub seed: Option<u64>,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            timeout: 0.0,
+            rate_limit: 0.0,
+            malformed: 0.0,
+            slow: 0.0,
+            delay: Duration::from_secs(2),
+            seed: None,
+        }
+    }
+}
+
+impl ChaosConfig {
+    /// Read the configuration from [`CHAOS_ENV`]; `None` when it is unset or empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ChaosConfigError`] when the variable is set but invalid.
+    pub fn from_env() -> Result<Option<Self>, ChaosConfigError> {
+        match env::var(CHAOS_ENV) {
+            Ok(value) if !value.trim().is_empty() => value.parse().map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl FromStr for ChaosConfig {
+    type Err = ChaosConfigError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut config = Self::default();
+        for entry in input
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| ChaosConfigError::Entry(entry.to_owned()))?;
+            let (key, value) = (key.trim(), value.trim());
+            let invalid = || ChaosConfigError::Value {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            };
+            let rate = || {
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|rate| (0.0..=1.0).contains(rate))
+                    .ok_or_else(invalid)
+            };
+            match key {
+                "timeout" => config.timeout = rate()?,
+                "rate_limit" | "429" => config.rate_limit = rate()?,
+                "malformed" => config.malformed = rate()?,
+                "slow" => config.slow = rate()?,
+                "delay_ms" => {
+                    config.delay = Duration::from_millis(value.parse().ok().ok_or_else(invalid)?);
+                }
+                "seed" => config.seed = Some(value.parse().ok().ok_or_else(invalid)?),
+                _ => return Err(ChaosConfigError::UnknownKey(key.to_owned())),
+            }
+        }
+        if config.timeout + config.rate_limit + config.malformed + config.slow > 1.0 {
+            return Err(ChaosConfigError::RatesTooHigh);
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fault {
+    Timeout,
+    RateLimit,
+    Malformed,
+    Slow,
+}
+
+/// Shared dice for all ports of one plugin.
+#[derive(Debug)]
+struct Chaos {
+    config: ChaosConfig,
+    rng: Mutex<fastrand::Rng>,
+}
+
+impl Chaos {
+    fn new(config: ChaosConfig) -> Self {
+        let rng = config
+            .seed
+            .map_or_else(fastrand::Rng::new, fastrand::Rng::with_seed);
+        Self {
+            config,
+            rng: Mutex::new(rng),
+        }
+    }
+
+    fn roll(&self) -> Option<Fault> {
+        let roll = self
+            .rng
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .f64();
+        let faults = [
+            (Fault::Timeout, self.config.timeout),
+            (Fault::RateLimit, self.config.rate_limit),
+            (Fault::Malformed, self.config.malformed),
+            (Fault::Slow, self.config.slow),
+        ];
+        let mut threshold = 0.0;
+        faults.into_iter().find_map(|(fault, rate)| {
+            threshold += rate;
+            (roll < threshold).then_some(fault)
+        })
+    }
+
+    /// Fail or delay the call about to be made, or let it through unchanged.
+    async fn strike(&self) -> Result<(), PortError> {
+        match self.roll() {
+            None => Ok(()),
+            Some(Fault::Timeout) => {
+                sleep(self.config.delay).await;
+                Err(PortError::Timeout)
+            }
+            Some(Fault::RateLimit) => Err(PortError::RateLimited {
+                retry_after: Some(Duration::from_secs(1)),
+                status: None,
+            }),
+            Some(Fault::Malformed) => Err(PortError::Decode(
+                "expected value at line 1 column 1 (injected)".to_owned(),
+            )),
+            Some(Fault::Slow) => {
+                sleep(self.config.delay).await;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Port decorator injecting the faults configured in a [`ChaosConfig`].
+pub struct ChaosPort<P: ?Sized> {
+    inner: Arc<P>,
+    chaos: Arc<Chaos>,
+}
+
+/// Wrap both ports of `plugin` so their calls fail or slow down as configured.
+#[must_use]
+pub fn chaotic(plugin: CityPlugin, config: ChaosConfig) -> CityPlugin {
+    let chaos = Arc::new(Chaos::new(config));
+    CityPlugin {
+        meta: plugin.meta,
+        address_port: Arc::new(ChaosPort {
+            inner: plugin.address_port,
+            chaos: Arc::clone(&chaos),
+        }),
+        schedule_port: Arc::new(ChaosPort {
+            inner: plugin.schedule_port,
+            chaos,
+        }),
+    }
+}
+
+#[async_trait]
+impl AddressPort for ChaosPort<dyn AddressPort> {
+    fn city(&self) -> &CityMeta {
+        self.inner.city()
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        self.chaos.strike().await?;
+        self.inner.search(query, limit).await
+    }
+
+    async fn search_results(
+        &self,
+        query: &AddressSearch,
+        limit: usize,
+    ) -> Result<SearchResults, PortError> {
+        self.chaos.strike().await?;
+        self.inner.search_results(query, limit).await
+    }
+
+    async fn towns(&self) -> Result<Vec<String>, PortError> {
+        self.chaos.strike().await?;
+        self.inner.towns().await
+    }
+
+    async fn resolve(&self, address_id: &AddressId) -> Result<Address, PortError> {
+        self.chaos.strike().await?;
+        self.inner.resolve(address_id).await
+    }
+
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        let probe = self.inner.health_check().await?;
+        Some(self.chaos.strike().await.and(probe))
+    }
+
+    fn id_version(&self) -> u32 {
+        self.inner.id_version()
+    }
+
+    async fn migrate_id(&self, stored: &VersionedAddressId) -> Result<IdMigration, PortError> {
+        self.chaos.strike().await?;
+        self.inner.migrate_id(stored).await
+    }
+}
+
+#[async_trait]
+impl SchedulePort for ChaosPort<dyn SchedulePort> {
+    fn city(&self) -> &CityMeta {
+        self.inner.city()
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        self.chaos.strike().await?;
+        self.inner.schedule(address_id, range).await
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        self.chaos.strike().await?;
+        self.inner.schedule_report(address_id, range).await
+    }
+
+    fn supports_partial_fetch(&self) -> bool {
+        self.inner.supports_partial_fetch()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rates_and_delay() {
+        let config = "timeout=0.1, 429=0.2,malformed=0.05,slow=0.3,delay_ms=500,seed=7"
+            .parse::<ChaosConfig>()
+            .expect("valid chaos config");
+        assert_eq!(
+            config,
+            ChaosConfig {
+                timeout: 0.1,
+                rate_limit: 0.2,
+                malformed: 0.05,
+                slow: 0.3,
+                delay: Duration::from_millis(500),
+                seed: Some(7),
+            },
+            "every setting is read"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        let error = |input: &str| input.parse::<ChaosConfig>().err();
+        assert_eq!(
+            error("timeout"),
+            Some(ChaosConfigError::Entry("timeout".to_owned())),
+            "missing value"
+        );
+        assert_eq!(
+            error("typo=0.1"),
+            Some(ChaosConfigError::UnknownKey("typo".to_owned())),
+            "unknown key"
+        );
+        assert!(error("slow=1.5").is_some(), "rates are at most 1");
+        assert_eq!(
+            error("timeout=0.6,slow=0.6"),
+            Some(ChaosConfigError::RatesTooHigh),
+            "at most every call can fail"
+        );
+    }
+
+    #[test]
+    fn seeded_rolls_follow_the_rates() {
+        let chaos = Chaos::new(ChaosConfig {
+            rate_limit: 0.25,
+            malformed: 0.25,
+            seed: Some(42),
+            ..ChaosConfig::default()
+        });
+        let faults = (0..1000).filter_map(|_| chaos.roll()).collect::<Vec<_>>();
+
+        assert!(
+            (400..600).contains(&faults.len()),
+            "about half of the calls fail, got {}",
+            faults.len()
+        );
+        assert!(
+            !faults.contains(&Fault::Timeout) && !faults.contains(&Fault::Slow),
+            "disabled faults never happen"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! Source of the current date, so "today"-dependent logic can run against a frozen clock.
+
+use std::sync::{Mutex, PoisonError};
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::model::CityMeta;
+
+/// Tells the current local date and time.
+///
+/// The service and the TUI ask their clock instead of calling [`Local::now`], so
+/// tests can pin "today", e.g. to the last evening of a year.
+pub trait Clock: Send + Sync {
+    /// Current local date and time.
+    fn now(&self) -> NaiveDateTime;
+
+    /// Current local date.
+    fn today(&self) -> NaiveDate {
+        self.now().date()
+    }
+
+    /// Current instant; by default [`Clock::now`] read as UTC.
+    fn now_utc(&self) -> DateTime<Utc> {
+        self.now().and_utc()
+    }
+
+    /// Current date and time in the time zone of `city`, e.g. for quiet hours and
+    /// reminder lead times, which the household means in its own local time.
+    ///
+    /// Falls back to [`Clock::now`] for cities without a known time zone.
+    fn now_in(&self, city: &CityMeta) -> NaiveDateTime {
+        city.timezone
+            .as_deref()
+            .and_then(|name| name.parse::<Tz>().ok())
+            .map_or_else(
+                || self.now(),
+                |zone| self.now_utc().with_timezone(&zone).naive_local(),
+            )
+    }
+
+    /// Current date in the time zone of `city`, the day its pickup dates refer to.
+    ///
+    /// Falls back to [`Clock::today`] for cities without a known time zone, so a
+    /// server running in UTC still shows Cologne's pickups for today until midnight
+    /// in Cologne.
+    fn today_in(&self, city: &CityMeta) -> NaiveDate {
+        self.now_in(city).date()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// The system's local time; the service default.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> NaiveDateTime {
+        Local::now().naive_local()
+    }
+
+    fn now_utc(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+#[derive(Debug)]
+/// Clock that stands still until it is moved explicitly.
+///
+/// Its time counts as UTC when converted to a city's time zone.
+pub struct FixedClock(Mutex<NaiveDateTime>);
+
+impl FixedClock {
+    /// Clock frozen at `now`.
+    #[must_use]
+    pub const fn new(now: NaiveDateTime) -> Self {
+        Self(Mutex::new(now))
+    }
+
+    /// Move the clock to `now`.
+    pub fn set(&self, now: NaiveDateTime) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = now;
+    }
+
+    /// Move the clock forward by `step`.
+    pub fn advance(&self, step: Duration) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) += step;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> NaiveDateTime {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::model::{Address, AddressId, CityMeta, DateRange, PickupEvent};
+    use crate::plugin::{CityPlugin, PluginRegistry};
+    use crate::ports::{AddressPort, AddressSearch, PortError, SchedulePort};
+    use crate::service::TonneliService;
+
+    struct EmptyPort(CityMeta);
+
+    #[async_trait]
+    impl AddressPort for EmptyPort {
+        fn city(&self) -> &CityMeta {
+            &self.0
+        }
+
+        async fn search(&self, _: &AddressSearch, _: usize) -> Result<Vec<Address>, PortError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[async_trait]
+    impl SchedulePort for EmptyPort {
+        fn city(&self) -> &CityMeta {
+            &self.0
+        }
+
+        async fn schedule(
+            &self,
+            _: &AddressId,
+            _: DateRange,
+        ) -> Result<Vec<PickupEvent>, PortError> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn new_years_eve() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 12, 31)
+            .and_then(|date| date.and_hms_opt(23, 59, 0))
+            .expect("valid test time")
+    }
+
+    #[test]
+    fn fixed_clock_crosses_the_year_boundary() {
+        let clock = FixedClock::new(new_years_eve());
+        assert_eq!(clock.today().to_string(), "2025-12-31", "frozen on the eve");
+
+        clock.advance(Duration::minutes(1));
+        assert_eq!(
+            clock.today().to_string(),
+            "2026-01-01",
+            "midnight starts the new year"
+        );
+    }
+
+    #[test]
+    fn today_follows_the_city_time_zone() {
+        let clock = FixedClock::new(new_years_eve());
+        let cologne = CityMeta::new("cologne", "Köln").with_locale("DE", "Europe/Berlin");
+        let unknown = CityMeta::new("demo", "Demo");
+
+        assert_eq!(
+            clock.today_in(&cologne).to_string(),
+            "2026-01-01",
+            "23:59 UTC is already New Year in Cologne"
+        );
+        assert_eq!(
+            clock.now_in(&cologne).to_string(),
+            "2026-01-01 00:59:00",
+            "Cologne's wall clock runs an hour ahead in winter"
+        );
+        assert_eq!(
+            clock.today_in(&unknown),
+            clock.today(),
+            "no time zone, the clock's own date"
+        );
+    }
+
+    #[test]
+    fn service_dates_snapshots_by_its_clock() {
+        let meta = CityMeta::new("demo", "Demo");
+        let plugin = CityPlugin {
+            meta: meta.clone(),
+            address_port: Arc::new(EmptyPort(meta.clone())),
+            schedule_port: Arc::new(EmptyPort(meta.clone())),
+        };
+        let clock = Arc::new(FixedClock::new(new_years_eve()));
+        let service = TonneliService::new(Arc::new(PluginRegistry::new(vec![plugin])))
+            .with_clock(Arc::<FixedClock>::clone(&clock));
+        let range = DateRange {
+            start: clock.today(),
+            end: clock.today() + Duration::days(14),
+        };
+        let address = AddressId("1".to_owned());
+
+        let before = block_on(service.refresh_schedule(meta.id.clone(), &address, None, range))
+            .expect("empty schedules refresh");
+        clock.advance(Duration::minutes(1));
+        let after = block_on(service.refresh_schedule(meta.id, &address, Some(&before), range))
+            .expect("empty schedules refresh");
+
+        assert_eq!(
+            before.fetched_on.to_string(),
+            "2025-12-31",
+            "taken on the eve"
+        );
+        assert_eq!(
+            after.fetched_on.to_string(),
+            "2026-01-01",
+            "retaken in the new year"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! Tolerant parsing of the date formats found in provider responses.
+
+use chrono::{DateTime, NaiveDate};
+
+/// Epoch timestamps shorter than this are not treated as milliseconds.
+const MIN_EPOCH_MILLIS_DIGITS: usize = 11;
+const HALF_DAY_MILLIS: i64 = 12 * 60 * 60 * 1000;
+
+/// Parse a pickup date in any of the formats municipal APIs use.
+///
+/// Accepts ISO dates (`2025-03-14`, optionally followed by a time such as
+/// `2025-03-14T00:00:00+01:00`), dotted German dates (`14.03.2025`) and epoch
+/// milliseconds (`1741906800000`). Epoch values are rounded to the nearest UTC day,
+/// since providers encode local midnight. Returns `None` for anything else.
+#[must_use]
+pub fn parse_provider_date(raw: &str) -> Option<NaiveDate> {
+    let raw = raw.trim();
+
+    if raw.len() >= MIN_EPOCH_MILLIS_DIGITS && raw.bytes().all(|byte| byte.is_ascii_digit()) {
+        let millis = raw.parse::<i64>().ok()?;
+        return DateTime::from_timestamp_millis(millis.checked_add(HALF_DAY_MILLIS)?)
+            .map(|timestamp| timestamp.date_naive());
+    }
+
+    let date_part = raw.split(['T', ' ']).next().unwrap_or(raw);
+    NaiveDate::parse_from_str(date_part, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(date_part, "%d.%m.%Y"))
+        .ok()
+}
+
+#[derive(Debug, Clone, Default)]
+/// Raw values that could not be parsed while building a schedule.
+pub struct MalformedDates {
+    raw: Vec<String>,
+}
+
+impl MalformedDates {
+    /// Parse `raw`, remembering it if it is not a valid date.
+    pub fn parse(&mut self, raw: &str) -> Option<NaiveDate> {
+        let parsed = parse_provider_date(raw);
+        if parsed.is_none() {
+            self.raw.push(raw.to_owned());
+        }
+        parsed
+    }
+
+    /// Record a value that was rejected by other means (e.g. an impossible day).
+    pub fn push(&mut self, raw: String) {
+        self.raw.push(raw);
+    }
+
+    /// The rejected raw values, in encounter order.
+    #[must_use]
+    pub fn into_inner(self) -> Vec<String> {
+        self.raw
+    }
+}
@@ -0,0 +1,189 @@
+//! Changes between two fetches of the same schedule.
+
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::model::{EventKey, PickupEvent};
+
+/// Largest shift in days still treated as the same pickup moved, e.g. around holidays.
+pub const MAX_MOVE_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+/// One difference between an old and a new schedule of an address.
+pub enum ScheduleChange {
+    /// A pickup only the new schedule has.
+    Added {
+        /// The new pickup.
+        event: PickupEvent,
+    },
+    /// A pickup only the old schedule had.
+    Removed {
+        /// The dropped pickup.
+        event: PickupEvent,
+    },
+    /// A pickup of the same bin now happens on another day.
+    Moved {
+        /// The pickup as the old schedule had it.
+        from: PickupEvent,
+        /// The pickup as the new schedule has it.
+        to: PickupEvent,
+    },
+}
+
+impl ScheduleChange {
+    /// Earliest day the change affects, which is what users need to know first.
+    #[must_use]
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            Self::Added { event } | Self::Removed { event } => event.date,
+            Self::Moved { from, to } => from.date.min(to.date),
+        }
+    }
+}
+
+/// Changes that turn `old` into `new`, sorted by [`ScheduleChange::date`].
+///
+/// Pickups are compared by [`PickupEvent::key`], so note changes are ignored and an
+/// unchanged schedule yields no changes. A removed pickup and an added one of the
+/// same fraction and container at most [`MAX_MOVE_DAYS`] apart are reported as
+/// [`ScheduleChange::Moved`]; each removed pickup pairs with the nearest such one.
+#[must_use]
+pub fn diff_schedules(old: &[PickupEvent], new: &[PickupEvent]) -> Vec<ScheduleChange> {
+    let old_keys = old.iter().map(PickupEvent::key).collect::<HashSet<_>>();
+    let new_keys = new.iter().map(PickupEvent::key).collect::<HashSet<_>>();
+    let mut removed = old
+        .iter()
+        .filter(|event| !new_keys.contains(&event.key()))
+        .collect::<Vec<_>>();
+    let mut added = new
+        .iter()
+        .filter(|event| !old_keys.contains(&event.key()))
+        .map(Some)
+        .collect::<Vec<_>>();
+    removed.sort_by_key(|event| event.date);
+
+    let mut changes = Vec::new();
+    for from in removed {
+        let nearest = added
+            .iter()
+            .enumerate()
+            .filter_map(|(index, candidate)| {
+                let to = (*candidate)?;
+                let shift = (to.date - from.date).num_days().abs();
+                (same_bin(&from.key(), &to.key()) && shift <= MAX_MOVE_DAYS)
+                    .then_some((index, shift))
+            })
+            .min_by_key(|(_, shift)| *shift)
+            .and_then(|(index, _)| added.get_mut(index)?.take());
+        changes.push(match nearest {
+            Some(to) => ScheduleChange::Moved {
+                from: from.clone(),
+                to: to.clone(),
+            },
+            None => ScheduleChange::Removed {
+                event: from.clone(),
+            },
+        });
+    }
+    changes.extend(
+        added
+            .into_iter()
+            .flatten()
+            .map(|event| ScheduleChange::Added {
+                event: event.clone(),
+            }),
+    );
+    changes.sort_by_key(ScheduleChange::date);
+    changes
+}
+
+fn same_bin(first: &EventKey, second: &EventKey) -> bool {
+    first.fraction == second.fraction && first.container == second.container
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Fraction;
+
+    fn pickup(day: u32, fraction: Fraction) -> PickupEvent {
+        PickupEvent {
+            date: NaiveDate::from_ymd_opt(2025, 12, day).expect("valid test date"),
+            fraction,
+            note: None,
+            container: None,
+            correction: None,
+            holiday: None,
+            predicted: false,
+        }
+    }
+
+    #[test]
+    fn unchanged_schedules_have_no_changes() {
+        let old = [pickup(22, Fraction::Paper), pickup(29, Fraction::Residual)];
+        let mut new = old.to_vec();
+        new.reverse();
+        if let Some(first) = new.first_mut() {
+            first.note = Some("Tonnen bis 6 Uhr bereitstellen".into());
+        }
+        assert!(
+            diff_schedules(&old, &new).is_empty(),
+            "order and notes do not count as changes"
+        );
+    }
+
+    #[test]
+    fn holiday_shifts_are_moves() {
+        let old = [
+            pickup(1, Fraction::Glass),
+            pickup(25, Fraction::Residual),
+            pickup(26, Fraction::Paper),
+        ];
+        let new = [
+            pickup(27, Fraction::Residual),
+            pickup(27, Fraction::Paper),
+            pickup(30, Fraction::Organic),
+        ];
+        assert_eq!(
+            diff_schedules(&old, &new),
+            [
+                ScheduleChange::Removed {
+                    event: pickup(1, Fraction::Glass),
+                },
+                ScheduleChange::Moved {
+                    from: pickup(25, Fraction::Residual),
+                    to: pickup(27, Fraction::Residual),
+                },
+                ScheduleChange::Moved {
+                    from: pickup(26, Fraction::Paper),
+                    to: pickup(27, Fraction::Paper),
+                },
+                ScheduleChange::Added {
+                    event: pickup(30, Fraction::Organic),
+                },
+            ],
+            "same fraction within a week is a move, everything else is added or removed"
+        );
+    }
+
+    #[test]
+    fn far_shifts_are_not_moves() {
+        let old = [pickup(1, Fraction::Paper)];
+        let new = [pickup(15, Fraction::Paper)];
+        assert_eq!(
+            diff_schedules(&old, &new),
+            [
+                ScheduleChange::Removed {
+                    event: pickup(1, Fraction::Paper),
+                },
+                ScheduleChange::Added {
+                    event: pickup(15, Fraction::Paper),
+                },
+            ],
+            "two weeks apart is a different pickup"
+        );
+    }
+}
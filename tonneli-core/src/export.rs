@@ -0,0 +1,4 @@
+//! Exporting schedules in formats other applications read.
+
+/// iCalendar (RFC 5545) files for calendar apps.
+pub mod ics;
@@ -0,0 +1,354 @@
+//! Writing pickups as an iCalendar (RFC 5545) file that calendar apps can import.
+//!
+//! Every pickup becomes an all-day `VEVENT`. Summaries default to the German
+//! name of the fraction and can be replaced per fraction; alarms fire a number
+//! of hours before the pickup day starts, like the reminders of
+//! [`crate::reminder`], and can be set for all fractions or per fraction.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Days, Utc};
+
+use crate::matching::fold;
+use crate::model::{Address, CityMeta, Fraction, PickupEvent};
+
+/// Product identifier written into every calendar.
+const PRODID: &str = "-//tonneli//Abfuhrkalender//DE";
+/// Longest content line in octets before it is folded.
+const MAX_LINE_OCTETS: usize = 75;
+
+#[derive(Debug, Clone)]
+/// Settings for turning pickups into an iCalendar file.
+pub struct IcsExport {
+    summaries: HashMap<Fraction, String>,
+    alarm_hours: Option<u32>,
+    fraction_alarms: HashMap<Fraction, Option<u32>>,
+    stamp: DateTime<Utc>,
+}
+
+impl Default for IcsExport {
+    fn default() -> Self {
+        Self {
+            summaries: HashMap::new(),
+            alarm_hours: None,
+            fraction_alarms: HashMap::new(),
+            stamp: Utc::now(),
+        }
+    }
+}
+
+impl IcsExport {
+    /// Export with default summaries and no alarms.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `summary` as the event title of `fraction` instead of its default name.
+    #[must_use]
+    pub fn with_summary(mut self, fraction: Fraction, summary: impl Into<String>) -> Self {
+        self.summaries.insert(fraction, summary.into());
+        self
+    }
+
+    /// Add an alarm `hours_before` the pickup day starts to every event; `None` removes it.
+    #[must_use]
+    pub fn with_alarm(mut self, hours_before: Option<u32>) -> Self {
+        self.alarm_hours = hours_before;
+        self
+    }
+
+    /// Alarm for the events of `fraction` only, overriding [`IcsExport::with_alarm`];
+    /// `None` turns its alarm off.
+    #[must_use]
+    pub fn with_fraction_alarm(mut self, fraction: Fraction, hours_before: Option<u32>) -> Self {
+        self.fraction_alarms.insert(fraction, hours_before);
+        self
+    }
+
+    /// Write `stamp` as creation time (`DTSTAMP`) instead of the current time.
+    #[must_use]
+    pub fn with_stamp(mut self, stamp: DateTime<Utc>) -> Self {
+        self.stamp = stamp;
+        self
+    }
+
+    /// Title of the events of `fraction`.
+    #[must_use]
+    pub fn summary<'fraction>(&'fraction self, fraction: &'fraction Fraction) -> &'fraction str {
+        self.summaries
+            .get(fraction)
+            .map_or_else(|| default_summary(fraction), String::as_str)
+    }
+
+    /// Hours before the pickup day the alarm of `fraction` fires, if it has one.
+    #[must_use]
+    pub fn alarm_hours(&self, fraction: &Fraction) -> Option<u32> {
+        self.fraction_alarms
+            .get(fraction)
+            .copied()
+            .unwrap_or(self.alarm_hours)
+    }
+
+    /// Calendar with one all-day event per pickup of `address` in `city`.
+    ///
+    /// Lines end in CRLF and are folded at 75 octets, as RFC 5545 requires. Event
+    /// UIDs only depend on the address, day, fraction and container, so importing
+    /// an updated export replaces the events of an earlier one.
+    #[must_use]
+    pub fn render(&self, events: &[PickupEvent], address: &Address, city: &CityMeta) -> String {
+        let location = format!("{}, {}", address.label, city.name);
+        let stamp = self.stamp.format("%Y%m%dT%H%M%SZ").to_string();
+
+        let mut ics = String::new();
+        push_line(&mut ics, "BEGIN:VCALENDAR");
+        push_line(&mut ics, "VERSION:2.0");
+        push_line(&mut ics, &format!("PRODID:{PRODID}"));
+        push_line(&mut ics, "CALSCALE:GREGORIAN");
+        push_line(&mut ics, "METHOD:PUBLISH");
+        push_line(&mut ics, &format!("X-WR-CALNAME:{}", escape(&location)));
+        for event in events {
+            let mut summary = self.summary(&event.fraction).to_owned();
+            if let Some(container) = &event.container {
+                let _infallible = write!(summary, " ({container})");
+            }
+            let end = event
+                .date
+                .checked_add_days(Days::new(1))
+                .unwrap_or(event.date);
+
+            push_line(&mut ics, "BEGIN:VEVENT");
+            push_line(
+                &mut ics,
+                &format!("UID:{}", escape(&uid(event, address, city))),
+            );
+            push_line(&mut ics, &format!("DTSTAMP:{stamp}"));
+            push_line(
+                &mut ics,
+                &format!("DTSTART;VALUE=DATE:{}", event.date.format("%Y%m%d")),
+            );
+            push_line(
+                &mut ics,
+                &format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+            );
+            push_line(&mut ics, &format!("SUMMARY:{}", escape(&summary)));
+            if let Some(note) = event.note.as_deref().filter(|note| *note != summary) {
+                push_line(&mut ics, &format!("DESCRIPTION:{}", escape(note)));
+            }
+            push_line(&mut ics, &format!("LOCATION:{}", escape(&location)));
+            push_line(&mut ics, "TRANSP:TRANSPARENT");
+            if let Some(hours) = self.alarm_hours(&event.fraction) {
+                push_line(&mut ics, "BEGIN:VALARM");
+                push_line(&mut ics, "ACTION:DISPLAY");
+                push_line(&mut ics, &format!("DESCRIPTION:{}", escape(&summary)));
+                push_line(&mut ics, &format!("TRIGGER:-PT{hours}H"));
+                push_line(&mut ics, "END:VALARM");
+            }
+            push_line(&mut ics, "END:VEVENT");
+        }
+        push_line(&mut ics, "END:VCALENDAR");
+        ics
+    }
+}
+
+/// Calendar of `events` with default summaries and no alarms, see [`IcsExport::render`].
+#[must_use]
+pub fn to_ics(events: &[PickupEvent], address: &Address, city: &CityMeta) -> String {
+    IcsExport::new().render(events, address, city)
+}
+
+/// German name of a fraction, as municipal calendars title their entries.
+fn default_summary(fraction: &Fraction) -> &str {
+    match fraction {
+        Fraction::Residual => "Restabfall",
+        Fraction::Organic => "Bioabfall",
+        Fraction::Paper => "Papier",
+        Fraction::Plastic => "Gelbe Tonne / Gelber Sack",
+        Fraction::Glass => "Glas",
+        Fraction::Metal => "Metall",
+        Fraction::StreetCleaning => "Straßenreinigung",
+        Fraction::BulkyWaste => "Sperrmüll",
+        Fraction::GardenWaste => "Grünschnitt",
+        Fraction::Hazardous => "Schadstoffe",
+        Fraction::ChristmasTree => "Weihnachtsbäume",
+        Fraction::Textiles => "Alttextilien",
+        Fraction::Electronics => "Elektroschrott",
+        Fraction::Other(name) => name,
+    }
+}
+
+/// Stable identifier of a pickup: day, fraction and container at the address.
+fn uid(event: &PickupEvent, address: &Address, city: &CityMeta) -> String {
+    let fraction = match &event.fraction {
+        Fraction::Other(name) => fold(name),
+        known => format!("{known:?}").to_lowercase(),
+    };
+    let container = event
+        .container
+        .as_deref()
+        .map(|container| format!("-{}", fold(container)))
+        .unwrap_or_default();
+    let local = format!(
+        "{}-{fraction}{container}.{}.{}",
+        event.date.format("%Y%m%d"),
+        city.id.0,
+        address.id.0
+    );
+    let local = local
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '.' || ch == '-' {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    format!("{local}@tonneli")
+}
+
+/// Escape backslashes, separators and line breaks of an iCalendar text value.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Append `line` with CRLF, folding it into continuation lines of at most 75 octets.
+fn push_line(ics: &mut String, line: &str) {
+    let mut octets = 0;
+    for ch in line.chars() {
+        if octets + ch.len_utf8() > MAX_LINE_OCTETS {
+            ics.push_str("\r\n ");
+            // The leading space counts towards the continuation line.
+            octets = 1;
+        }
+        ics.push(ch);
+        octets += ch.len_utf8();
+    }
+    ics.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{NaiveDate, TimeZone};
+
+    use super::*;
+    use crate::ics_import::CalendarImport;
+    use crate::model::{AddressId, CityId};
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).expect("valid test date")
+    }
+
+    fn pickup(date: NaiveDate, fraction: Fraction, container: Option<&str>) -> PickupEvent {
+        PickupEvent {
+            date,
+            fraction,
+            note: None,
+            container: container.map(Arc::from),
+            correction: None,
+            holiday: None,
+            predicted: false,
+        }
+    }
+
+    fn address() -> (Address, CityMeta) {
+        let address = Address {
+            id: AddressId("10010:4:".to_owned()),
+            city: CityId("cologne".to_owned()),
+            label: "Domkloster 4".into(),
+            street: "Domkloster".into(),
+            house_number: "4".into(),
+        };
+        let city = CityMeta::new("cologne", "Köln");
+        (address, city)
+    }
+
+    #[test]
+    fn writes_all_day_events_with_alarms() {
+        let (address, city) = address();
+        let events = [
+            pickup(day(4), Fraction::Residual, Some("120 l")),
+            pickup(day(31), Fraction::Paper, None),
+        ];
+        let export = IcsExport::new()
+            .with_summary(Fraction::Paper, "Blaue Tonne")
+            .with_alarm(Some(6))
+            .with_fraction_alarm(Fraction::Residual, None)
+            .with_stamp(Utc.with_ymd_and_hms(2025, 3, 1, 8, 0, 0).unwrap());
+        let ics = export.render(&events, &address, &city);
+
+        assert!(
+            ics.lines().all(|line| line.len() <= MAX_LINE_OCTETS + 1),
+            "lines are folded: {ics}"
+        );
+        assert!(ics.ends_with("END:VCALENDAR\r\n"), "lines end in CRLF");
+        for expected in [
+            "X-WR-CALNAME:Domkloster 4\\, Köln",
+            "UID:20250304-residual-120-l.cologne.10010-4-@tonneli",
+            "DTSTAMP:20250301T080000Z",
+            "DTSTART;VALUE=DATE:20250331",
+            "DTEND;VALUE=DATE:20250401",
+            "SUMMARY:Restabfall (120 l)",
+            "SUMMARY:Blaue Tonne",
+            "TRIGGER:-PT6H",
+        ] {
+            assert!(ics.contains(expected), "missing {expected:?} in {ics}");
+        }
+        assert_eq!(
+            ics.matches("BEGIN:VALARM").count(),
+            1,
+            "residual waste has its alarm turned off"
+        );
+    }
+
+    #[test]
+    fn round_trips_through_the_import() {
+        let (address, city) = address();
+        let mut moved = pickup(day(20), Fraction::BulkyWaste, None);
+        moved.note = Some(
+            "Bitte bis 6 Uhr bereitstellen; Zufahrt über den Hof, nicht über die Einfahrt".into(),
+        );
+        let events = [pickup(day(11), Fraction::Organic, None), moved];
+
+        let ics = to_ics(&events, &address, &city);
+        let import =
+            CalendarImport::parse(&ics, &CalendarImport::mapper()).expect("export is a calendar");
+        let imported = import
+            .events
+            .iter()
+            .map(|event| (event.date, event.fraction.clone(), event.note.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            imported,
+            [
+                (day(11), Fraction::Organic, None),
+                (
+                    day(20),
+                    Fraction::BulkyWaste,
+                    Some("Bitte bis 6 Uhr bereitstellen; Zufahrt über den Hof, nicht über die Einfahrt".into())
+                ),
+            ],
+            "dates, fractions and notes survive escaping and folding"
+        );
+        assert_eq!(
+            import.location.as_deref(),
+            Some("Domkloster 4, Köln"),
+            "the address is the location"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Concurrent calls across several providers with per-provider error isolation.
+
+use std::future::Future;
+
+use futures::stream::{self, StreamExt};
+
+use crate::model::CityId;
+use crate::ports::PortError;
+
+/// Provider calls running at the same time during a fan-out.
+pub const MAX_CONCURRENT_PROVIDER_CALLS: usize = 8;
+
+#[derive(Debug)]
+/// Outcome of one provider call within a fan-out.
+pub struct ProviderStatus {
+    /// City whose provider was called.
+    pub city: CityId,
+    /// Number of results contributed, or why the call failed.
+    pub outcome: Result<usize, PortError>,
+}
+
+impl ProviderStatus {
+    /// Whether the provider call succeeded.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.outcome.is_ok()
+    }
+}
+
+#[derive(Debug)]
+/// Results merged from all providers that answered, plus one status per call.
+pub struct FanOut<T> {
+    /// Merged results of all successful calls, in call order.
+    pub results: Vec<T>,
+    /// One entry per call, in call order.
+    pub statuses: Vec<ProviderStatus>,
+}
+
+impl<T> Default for FanOut<T> {
+    fn default() -> Self {
+        Self {
+            results: Vec::new(),
+            statuses: Vec::new(),
+        }
+    }
+}
+
+impl<T> FanOut<T> {
+    /// Statuses of the calls that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &ProviderStatus> {
+        self.statuses.iter().filter(|status| !status.is_ok())
+    }
+
+    /// Whether every call failed (`false` when there were no calls).
+    #[must_use]
+    pub fn all_failed(&self) -> bool {
+        !self.statuses.is_empty() && self.statuses.iter().all(|status| !status.is_ok())
+    }
+}
+
+/// Run provider calls concurrently; a failing call is recorded and never aborts the others.
+pub(crate) async fn fan_out<T, I, Fut>(calls: I) -> FanOut<T>
+where
+    I: IntoIterator<Item = (CityId, Fut)>,
+    Fut: Future<Output = Result<Vec<T>, PortError>>,
+{
+    let outcomes = stream::iter(calls)
+        .map(|(city, call)| async move { (city, call.await) })
+        .buffered(MAX_CONCURRENT_PROVIDER_CALLS)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut merged = FanOut::default();
+    for (city, outcome) in outcomes {
+        let outcome = outcome.map(|mut results| {
+            let count = results.len();
+            merged.results.append(&mut results);
+            count
+        });
+        merged.statuses.push(ProviderStatus { city, outcome });
+    }
+    merged
+}
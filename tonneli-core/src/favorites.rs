@@ -0,0 +1,452 @@
+//! Addresses the user saved under a label, e.g. "Home".
+//!
+//! Favorites are kept in a [`StoragePort`], next to the reminder states, so
+//! every frontend can open the saved addresses without searching them again and
+//! an `EncryptedStorage` encrypts them like everything else. Each favorite
+//! remembers the id encoding version of its provider, see [`VersionedAddressId`].
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use futures::lock::Mutex as WriteLock;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Address, AddressId, CityId, INITIAL_ADDRESS_ID_VERSION, VersionedAddressId};
+use crate::storage::{MemoryStorage, StorageError, StoragePort};
+
+/// Storage namespace of the favorites.
+const NAMESPACE: &str = "favorites";
+/// Key of the list inside [`NAMESPACE`]; one value keeps the order they were added in.
+const KEY: &str = "list";
+/// File name of the favorites of earlier versions inside the data directory.
+const LEGACY_FILE_NAME: &str = "favorites.json";
+
+/// Failure reading or writing the favorites.
+#[derive(thiserror::Error, Debug)]
+pub enum FavoritesError {
+    /// The storage cannot be read or written.
+    #[error("Cannot access favorites: {0}")]
+    Storage(#[from] StorageError),
+    /// A favorites file of an earlier version cannot be read or removed.
+    #[error("Cannot access {path}: {source}")]
+    Io {
+        /// File that was accessed.
+        path: PathBuf,
+        /// Underlying error.
+        source: io::Error,
+    },
+    /// The stored value or file is not a favorites list.
+    #[error("Invalid favorites: {0}")]
+    Format(#[from] serde_json::Error),
+    /// A favorite needs a label to be found again.
+    #[error("Favorite label must not be empty")]
+    EmptyLabel,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Address saved under a user-defined label.
+pub struct Favorite {
+    /// Name the user gave the address, e.g. "Home".
+    pub label: String,
+    /// City whose provider the address belongs to.
+    pub city: CityId,
+    /// Address as the provider returned it.
+    pub address: Address,
+    /// Id encoding version of the provider when the favorite was saved.
+    #[serde(default = "initial_address_id_version")]
+    pub id_version: u32,
+}
+
+fn initial_address_id_version() -> u32 {
+    INITIAL_ADDRESS_ID_VERSION
+}
+
+impl Favorite {
+    /// Favorite for `address` in `city`, assuming the initial id encoding.
+    #[must_use]
+    pub fn new(label: impl Into<String>, city: CityId, address: Address) -> Self {
+        Self {
+            label: label.into(),
+            city,
+            address,
+            id_version: INITIAL_ADDRESS_ID_VERSION,
+        }
+    }
+
+    /// Id of the address tagged with its encoding version, for
+    /// [`TonneliService::migrate_address_id`](crate::service::TonneliService::migrate_address_id).
+    #[must_use]
+    pub fn versioned_id(&self) -> VersionedAddressId {
+        VersionedAddressId {
+            version: self.id_version,
+            id: self.address.id.clone(),
+        }
+    }
+
+    /// Same favorite with the address id replaced, after a migration.
+    #[must_use]
+    pub fn with_id(mut self, id: AddressId, version: u32) -> Self {
+        self.address.id = id;
+        self.id_version = version;
+        self
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+/// JSON form of the stored list, and of the favorites file of earlier versions.
+struct FavoritesFile {
+    favorites: Vec<Favorite>,
+}
+
+/// Saved addresses, written back to their storage on every change.
+pub struct FavoritesStore {
+    storage: Arc<dyn StoragePort>,
+    favorites: Mutex<Vec<Favorite>>,
+    /// Held from changing the list until it is stored, so writes land in order.
+    writing: WriteLock<()>,
+}
+
+impl fmt::Debug for FavoritesStore {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("FavoritesStore")
+            .field("favorites", &*self.lock())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for FavoritesStore {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+impl FavoritesStore {
+    /// Favorites that are forgotten on exit.
+    #[must_use]
+    pub fn in_memory() -> Self {
+        Self::with_favorites(Arc::new(MemoryStorage::default()), Vec::new())
+    }
+
+    fn with_favorites(storage: Arc<dyn StoragePort>, favorites: Vec<Favorite>) -> Self {
+        Self {
+            storage,
+            favorites: Mutex::new(favorites),
+            writing: WriteLock::new(()),
+        }
+    }
+
+    /// Read the favorites kept in `storage`; none stored yet is an empty list.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FavoritesError`] when the storage cannot be read or holds no
+    /// favorites list.
+    pub async fn open(storage: Arc<dyn StoragePort>) -> Result<Self, FavoritesError> {
+        let favorites = match storage.get(NAMESPACE, KEY).await? {
+            Some(value) => serde_json::from_slice::<FavoritesFile>(&value)?.favorites,
+            None => Vec::new(),
+        };
+        Ok(Self::with_favorites(storage, favorites))
+    }
+
+    /// Where earlier versions kept the favorites in plain text,
+    /// `$XDG_DATA_HOME/tonneli/favorites.json`; `None` without a data directory.
+    #[must_use]
+    pub fn legacy_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("tonneli").join(LEGACY_FILE_NAME))
+    }
+
+    /// Move the favorites of an earlier version's file at `path` into the storage
+    /// and delete the file, returning how many were added.
+    ///
+    /// Labels that are saved already keep their address. A missing file adds none.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FavoritesError`] when the file cannot be read or removed, is not
+    /// a favorites list, or the storage cannot be written. The file is kept then.
+    pub async fn import_file(&self, path: &Path) -> Result<usize, FavoritesError> {
+        let io_error = |source| FavoritesError::Io {
+            path: path.to_owned(),
+            source,
+        };
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(source) => return Err(io_error(source)),
+        };
+        let file = serde_json::from_str::<FavoritesFile>(&text)?;
+        let added = self
+            .update(|favorites| {
+                let before = favorites.len();
+                for favorite in file.favorites {
+                    if !favorites
+                        .iter()
+                        .any(|saved| saved.label.eq_ignore_ascii_case(&favorite.label))
+                    {
+                        favorites.push(favorite);
+                    }
+                }
+                favorites.len() - before
+            })
+            .await?;
+        fs::remove_file(path).map_err(io_error)?;
+        Ok(added)
+    }
+
+    /// All favorites in the order they were added.
+    #[must_use]
+    pub fn list(&self) -> Vec<Favorite> {
+        self.lock().clone()
+    }
+
+    /// Favorite with `label`, ignoring case.
+    #[must_use]
+    pub fn get(&self, label: &str) -> Option<Favorite> {
+        let label = label.trim();
+        self.lock()
+            .iter()
+            .find(|favorite| favorite.label.eq_ignore_ascii_case(label))
+            .cloned()
+    }
+
+    /// Save `favorite`, replacing one with the same label, and store the list.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FavoritesError::EmptyLabel`] for a blank label, or another
+    /// [`FavoritesError`] when the storage cannot be written.
+    pub async fn add(&self, favorite: Favorite) -> Result<(), FavoritesError> {
+        let label = favorite.label.trim().to_owned();
+        if label.is_empty() {
+            return Err(FavoritesError::EmptyLabel);
+        }
+        let favorite = Favorite { label, ..favorite };
+        self.update(|favorites| {
+            match favorites
+                .iter_mut()
+                .find(|saved| saved.label.eq_ignore_ascii_case(&favorite.label))
+            {
+                Some(saved) => *saved = favorite,
+                None => favorites.push(favorite),
+            }
+        })
+        .await
+    }
+
+    /// Drop the favorite with `label`, ignoring case; `false` when there was none.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FavoritesError`] when the storage cannot be written.
+    pub async fn remove(&self, label: &str) -> Result<bool, FavoritesError> {
+        let label = label.trim();
+        self.update(|favorites| {
+            let before = favorites.len();
+            favorites.retain(|favorite| !favorite.label.eq_ignore_ascii_case(label));
+            favorites.len() != before
+        })
+        .await
+    }
+
+    /// Apply `change` to the list and store the result.
+    async fn update<R>(
+        &self,
+        change: impl FnOnce(&mut Vec<Favorite>) -> R,
+    ) -> Result<R, FavoritesError> {
+        let _writing = self.writing.lock().await;
+        let (result, value) = {
+            let mut favorites = self.lock();
+            let result = change(&mut favorites);
+            let file = FavoritesFile {
+                favorites: favorites.clone(),
+            };
+            (result, serde_json::to_vec(&file)?)
+        };
+        self.storage.put(NAMESPACE, KEY, &value).await?;
+        Ok(result)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Favorite>> {
+        self.favorites
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use futures::executor::block_on;
+
+    use super::*;
+
+    fn address(id: &str, label: &str) -> Address {
+        Address {
+            id: AddressId(id.to_owned()),
+            city: CityId("aachen".to_owned()),
+            label: label.into(),
+            street: label.into(),
+            house_number: "".into(),
+        }
+    }
+
+    fn home(id: &str) -> Favorite {
+        Favorite::new("Home", CityId("aachen".to_owned()), address(id, "Markt 1"))
+    }
+
+    fn saved(store: &FavoritesStore) -> Vec<(String, String)> {
+        store
+            .list()
+            .into_iter()
+            .map(|favorite| (favorite.label, favorite.address.id.0))
+            .collect()
+    }
+
+    #[test]
+    fn favorites_survive_reopening() {
+        block_on(async {
+            let storage: Arc<dyn StoragePort> = Arc::new(MemoryStorage::default());
+            let store = FavoritesStore::open(Arc::clone(&storage))
+                .await
+                .expect("no list yet is an empty list");
+            assert!(store.list().is_empty(), "nothing saved yet");
+
+            store.add(home("1")).await.expect("favorite is stored");
+            store
+                .add(Favorite::new(
+                    "Office",
+                    CityId("cologne".to_owned()),
+                    address("2", "Domplatz 1"),
+                ))
+                .await
+                .expect("favorite is stored");
+            store.add(home("3")).await.expect("favorite is stored");
+
+            let reopened = FavoritesStore::open(Arc::clone(&storage))
+                .await
+                .expect("favorites are readable");
+            assert_eq!(
+                saved(&reopened),
+                [
+                    ("Home".to_owned(), "3".to_owned()),
+                    ("Office".to_owned(), "2".to_owned())
+                ],
+                "a label is saved once, with the latest address"
+            );
+
+            assert!(
+                reopened.remove("office").await.expect("removal is stored"),
+                "labels match ignoring case"
+            );
+            assert!(
+                !reopened.remove("Office").await.expect("nothing to store"),
+                "removing twice finds nothing"
+            );
+            let remaining = FavoritesStore::open(storage)
+                .await
+                .expect("favorites are readable");
+            assert_eq!(remaining.list().len(), 1, "the removal is persisted");
+        });
+    }
+
+    #[test]
+    fn legacy_file_is_moved_into_the_storage() {
+        block_on(async {
+            let dir = env::temp_dir().join(format!("tonneli-favorites-{}", process::id()));
+            fs::create_dir_all(&dir).expect("test directory is creatable");
+            let path = dir.join(LEGACY_FILE_NAME);
+            let file = FavoritesFile {
+                favorites: vec![
+                    home("1"),
+                    Favorite::new(
+                        "Office",
+                        CityId("cologne".to_owned()),
+                        address("2", "Domplatz 1"),
+                    ),
+                ],
+            };
+            fs::write(
+                &path,
+                serde_json::to_vec(&file).expect("favorites serialize"),
+            )
+            .expect("legacy file is writable");
+            let store = FavoritesStore::in_memory();
+            store.add(home("9")).await.expect("favorite is stored");
+
+            let added = store.import_file(&path).await.expect("legacy file imports");
+
+            assert_eq!(added, 1, "only the office is new");
+            assert_eq!(
+                saved(&store),
+                [
+                    ("Home".to_owned(), "9".to_owned()),
+                    ("Office".to_owned(), "2".to_owned())
+                ],
+                "saved labels keep their address"
+            );
+            assert!(!path.exists(), "the plain-text file is gone");
+            assert_eq!(
+                store
+                    .import_file(&path)
+                    .await
+                    .expect("a missing file is fine"),
+                0,
+                "importing twice adds nothing"
+            );
+            fs::remove_dir_all(&dir).expect("test directory is removable");
+        });
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn favorites_are_encrypted_with_the_storage() {
+        use crate::storage::{EncryptedStorage, StorageKey};
+
+        block_on(async {
+            let storage = Arc::new(EncryptedStorage::new(
+                MemoryStorage::default(),
+                &StorageKey::generate(),
+            ));
+            let shared: Arc<dyn StoragePort> =
+                Arc::<EncryptedStorage<MemoryStorage>>::clone(&storage);
+            let store = FavoritesStore::open(shared)
+                .await
+                .expect("no list yet is an empty list");
+            store.add(home("1")).await.expect("favorite is stored");
+
+            let raw = storage
+                .inner()
+                .get(NAMESPACE, KEY)
+                .await
+                .expect("memory storage is readable")
+                .expect("the list is stored");
+            assert!(
+                !raw.windows(b"Markt 1".len())
+                    .any(|window| window == b"Markt 1"),
+                "the address is not stored in plain text"
+            );
+        });
+    }
+
+    #[test]
+    fn blank_labels_are_rejected() {
+        let store = FavoritesStore::in_memory();
+        let result = block_on(store.add(Favorite::new(
+            " ",
+            CityId("aachen".to_owned()),
+            address("1", "Markt 1"),
+        )));
+        assert!(
+            matches!(result, Err(FavoritesError::EmptyLabel)),
+            "a blank label cannot be found again, got {result:?}"
+        );
+        assert!(store.get("Home").is_none(), "nothing was saved");
+    }
+}
@@ -0,0 +1,188 @@
+//! Keyword table mapping provider fraction labels to [`Fraction`].
+
+use crate::matching::fold;
+use crate::model::Fraction;
+
+/// German keywords shared by most municipal APIs, in priority order.
+const GERMAN_RULES: &[(&str, Fraction)] = &[
+    ("rest", Fraction::Residual),
+    ("bio", Fraction::Organic),
+    ("papier", Fraction::Paper),
+    ("pappe", Fraction::Paper),
+    ("gelb", Fraction::Plastic),
+    ("leichtverpackung", Fraction::Plastic),
+    ("lvp", Fraction::Plastic),
+    ("wertstoff", Fraction::Plastic),
+    ("glas", Fraction::Glass),
+    ("elektro", Fraction::Electronics),
+    ("e-schrott", Fraction::Electronics),
+    ("metall", Fraction::Metal),
+    ("schrott", Fraction::Metal),
+    ("strassenreinigung", Fraction::StreetCleaning),
+    ("kehrtermin", Fraction::StreetCleaning),
+    ("sperr", Fraction::BulkyWaste),
+    ("grünschnitt", Fraction::GardenWaste),
+    ("grüngut", Fraction::GardenWaste),
+    ("gartenabfall", Fraction::GardenWaste),
+    ("strauchschnitt", Fraction::GardenWaste),
+    ("laub", Fraction::GardenWaste),
+    ("schadstoff", Fraction::Hazardous),
+    ("problemabfall", Fraction::Hazardous),
+    ("weihnachtsbaum", Fraction::ChristmasTree),
+    ("tannenbaum", Fraction::ChristmasTree),
+    ("christbaum", Fraction::ChristmasTree),
+    ("textil", Fraction::Textiles),
+    ("altkleider", Fraction::Textiles),
+];
+
+#[derive(Debug, Clone)]
+/// Ordered keyword rules; the first keyword contained in a label decides its fraction.
+///
+/// Labels and keywords are compared after [`fold`], so case, umlauts and `ß` do not matter.
+pub struct FractionMapper {
+    rules: Vec<(String, Fraction)>,
+}
+
+impl Default for FractionMapper {
+    fn default() -> Self {
+        Self::german()
+    }
+}
+
+impl FractionMapper {
+    /// Mapper without any rules; everything maps to [`Fraction::Other`].
+    #[must_use]
+    pub fn empty() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Mapper with the shared German vocabulary.
+    #[must_use]
+    pub fn german() -> Self {
+        Self::empty().with_rules(GERMAN_RULES)
+    }
+
+    /// Add a rule that takes precedence over all existing rules.
+    #[must_use]
+    pub fn with_rule(mut self, keyword: &str, fraction: Fraction) -> Self {
+        self.rules.insert(0, (fold(keyword), fraction));
+        self
+    }
+
+    /// Append rules after the existing ones, keeping their order.
+    #[must_use]
+    pub fn with_rules(mut self, rules: &[(&str, Fraction)]) -> Self {
+        self.rules.extend(
+            rules
+                .iter()
+                .map(|(keyword, fraction)| (fold(keyword), fraction.clone())),
+        );
+        self
+    }
+
+    /// Fraction for a label, if any rule matches.
+    #[must_use]
+    pub fn try_map(&self, label: &str) -> Option<Fraction> {
+        let folded = fold(label);
+        self.rules
+            .iter()
+            .find(|(keyword, _)| folded.contains(keyword.as_str()))
+            .map(|(_, fraction)| fraction.clone())
+    }
+
+    /// Fraction for a label, falling back to [`Fraction::Other`] with the original label.
+    #[must_use]
+    pub fn map(&self, label: &str) -> Fraction {
+        self.try_map(label)
+            .unwrap_or_else(|| Fraction::Other(label.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_regioit_vocabulary() {
+        let mapper = FractionMapper::german();
+        let cases = [
+            ("Restabfall", Fraction::Residual),
+            ("Restmüll 14-täglich", Fraction::Residual),
+            ("Bioabfall", Fraction::Organic),
+            ("Papier / Pappe", Fraction::Paper),
+            ("Altpapier", Fraction::Paper),
+            ("Gelber Sack", Fraction::Plastic),
+            ("Gelbe Tonne", Fraction::Plastic),
+            ("Leichtverpackungen", Fraction::Plastic),
+            ("LVP", Fraction::Plastic),
+            ("Glas", Fraction::Glass),
+            ("Altmetall", Fraction::Metal),
+            ("Schrott", Fraction::Metal),
+            ("Straßenreinigung", Fraction::StreetCleaning),
+            ("Kehrtermin Fahrbahn", Fraction::StreetCleaning),
+        ];
+        for (label, expected) in cases {
+            assert_eq!(mapper.map(label), expected, "label {label:?}");
+        }
+    }
+
+    #[test]
+    fn maps_special_collections() {
+        let mapper = FractionMapper::german();
+        let cases = [
+            ("Sperrmüll", Fraction::BulkyWaste),
+            ("Sperrgut auf Abruf", Fraction::BulkyWaste),
+            ("Grünschnitt", Fraction::GardenWaste),
+            ("Laubsäcke", Fraction::GardenWaste),
+            ("Bioabfall und Grünschnitt", Fraction::Organic),
+            ("Schadstoffmobil", Fraction::Hazardous),
+            ("Weihnachtsbäume", Fraction::ChristmasTree),
+            ("Tannenbaumabfuhr", Fraction::ChristmasTree),
+            ("Altkleider", Fraction::Textiles),
+            ("Elektroschrott", Fraction::Electronics),
+            ("E-Schrott", Fraction::Electronics),
+        ];
+        for (label, expected) in cases {
+            assert_eq!(mapper.map(label), expected, "label {label:?}");
+        }
+    }
+
+    #[test]
+    fn maps_awb_vocabulary_with_provider_rules() {
+        let mapper = FractionMapper::german().with_rules(&[
+            ("grey", Fraction::Residual),
+            ("blue", Fraction::Paper),
+            ("brown", Fraction::Organic),
+        ]);
+        let cases = [
+            ("grey", Fraction::Residual),
+            ("blue", Fraction::Paper),
+            ("brown", Fraction::Organic),
+            ("wertstoff", Fraction::Plastic),
+        ];
+        for (label, expected) in cases {
+            assert_eq!(mapper.map(label), expected, "label {label:?}");
+        }
+    }
+
+    #[test]
+    fn unknown_labels_keep_their_name() {
+        let mapper = FractionMapper::german();
+        assert_eq!(
+            mapper.map("Windeltonne"),
+            Fraction::Other("Windeltonne".into()),
+            "unknown labels must not be guessed"
+        );
+        assert_eq!(mapper.try_map("Windeltonne"), None, "no rule should match");
+    }
+
+    #[test]
+    fn provider_rules_take_precedence() {
+        let mapper = FractionMapper::german().with_rule("restmüll gelb", Fraction::Metal);
+        assert_eq!(
+            mapper.map("Restmüll gelb"),
+            Fraction::Metal,
+            "with_rule must be checked before the shared table"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Detecting the registered city for a position or postal code.
+
+use async_trait::async_trait;
+
+use crate::model::{CityArea, CityId, CityMeta, Coordinates};
+use crate::ports::PortError;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Where the user is, as reported by a device or typed in.
+pub enum GeoQuery {
+    /// Position, e.g. from the device's location service.
+    Position(Coordinates),
+    /// Postal code such as `50667`; spaces and case are ignored.
+    PostalCode(String),
+}
+
+#[async_trait]
+/// Trait for looking up the city serving a location.
+pub trait GeoPort: Send + Sync {
+    /// City whose provider covers the position or postal code.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PortError::UnsupportedCity`] when no known city covers the location,
+    /// or another [`PortError`] when a geocoding backend fails.
+    async fn locate(&self, query: &GeoQuery) -> Result<CityId, PortError>;
+}
+
+/// [`GeoPort`] matching locations against the [`CityArea`] of each city, offline.
+///
+/// Positions go to the nearest city center whose radius contains them; postal codes
+/// go to the city with the longest matching prefix.
+#[derive(Debug, Clone, Default)]
+pub struct AreaLocator {
+    areas: Vec<(CityId, CityArea)>,
+}
+
+impl AreaLocator {
+    /// Locator for the given city areas.
+    #[must_use]
+    pub fn new(areas: Vec<(CityId, CityArea)>) -> Self {
+        Self { areas }
+    }
+
+    /// Locator for every city in `cities` that declares an area.
+    #[must_use]
+    pub fn from_cities(cities: &[CityMeta]) -> Self {
+        Self::new(
+            cities
+                .iter()
+                .filter_map(|meta| Some((meta.id.clone(), meta.area.clone()?)))
+                .collect(),
+        )
+    }
+
+    /// City covering the location, if any.
+    #[must_use]
+    pub fn find(&self, query: &GeoQuery) -> Option<&CityId> {
+        match query {
+            GeoQuery::Position(position) => self
+                .areas
+                .iter()
+                .filter(|(_, area)| area.contains(*position))
+                .min_by(|(_, first), (_, second)| {
+                    first
+                        .center
+                        .distance_km(*position)
+                        .total_cmp(&second.center.distance_km(*position))
+                })
+                .map(|(city, _)| city),
+            GeoQuery::PostalCode(code) => {
+                let code = compact(code);
+                self.areas
+                    .iter()
+                    .filter_map(|(city, area)| {
+                        area.postal_codes
+                            .iter()
+                            .map(|prefix| compact(prefix))
+                            .filter(|prefix| {
+                                !prefix.is_empty() && code.starts_with(prefix.as_str())
+                            })
+                            .map(|prefix| prefix.len())
+                            .max()
+                            .map(|len| (city, len))
+                    })
+                    .max_by_key(|(_, len)| *len)
+                    .map(|(city, _)| city)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl GeoPort for AreaLocator {
+    async fn locate(&self, query: &GeoQuery) -> Result<CityId, PortError> {
+        self.find(query).cloned().ok_or(PortError::UnsupportedCity)
+    }
+}
+
+/// Postal code without whitespace, in upper case.
+fn compact(code: &str) -> String {
+    code.chars()
+        .filter(|ch| !ch.is_whitespace())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locator() -> AreaLocator {
+        let city = |id: &str| CityId(id.to_owned());
+        AreaLocator::new(vec![
+            (
+                city("cologne"),
+                CityArea::new(Coordinates::new(50.9375, 6.9603), 15.0)
+                    .with_postal_codes(["506", "507", "508", "509", "510", "511"]),
+            ),
+            (
+                city("rhein-sieg"),
+                CityArea::new(Coordinates::new(50.7928, 7.2070), 30.0)
+                    .with_postal_codes(["533", "536", "537", "538"]),
+            ),
+            (
+                city("cambridge"),
+                CityArea::new(Coordinates::new(52.2053, 0.1218), 8.0)
+                    .with_postal_codes(["CB1", "CB2"]),
+            ),
+        ])
+    }
+
+    fn found(query: &GeoQuery) -> Option<String> {
+        locator().find(query).map(|city| city.0.clone())
+    }
+
+    #[test]
+    fn distances_are_great_circle_kilometers() {
+        let cologne = Coordinates::new(50.9375, 6.9603);
+        let nuremberg = Coordinates::new(49.4521, 11.0767);
+        let distance = cologne.distance_km(nuremberg);
+        assert!(
+            (330.0..345.0).contains(&distance),
+            "Köln to Nürnberg is about 337 km, got {distance}"
+        );
+        assert!(
+            cologne.distance_km(cologne).abs() < 1e-9,
+            "a point has no distance to itself"
+        );
+    }
+
+    #[test]
+    fn positions_go_to_the_nearest_covering_city() {
+        let cases = [
+            (Coordinates::new(50.94, 6.95), Some("cologne")),
+            // Within both radii, but closer to Siegburg.
+            (Coordinates::new(50.82, 7.15), Some("rhein-sieg")),
+            (Coordinates::new(52.20, 0.13), Some("cambridge")),
+            (Coordinates::new(48.14, 11.58), None),
+        ];
+        for (position, expected) in cases {
+            assert_eq!(
+                found(&GeoQuery::Position(position)).as_deref(),
+                expected,
+                "city at {position:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn postal_codes_use_the_longest_prefix() {
+        let cases = [
+            ("50667", Some("cologne")),
+            (" 53721 ", Some("rhein-sieg")),
+            ("cb2 1tn", Some("cambridge")),
+            ("80331", None),
+            ("", None),
+        ];
+        for (code, expected) in cases {
+            assert_eq!(
+                found(&GeoQuery::PostalCode(code.to_owned())).as_deref(),
+                expected,
+                "city for postal code {code:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn cities_without_an_area_are_skipped() {
+        let cities = [
+            CityMeta::new("demo", "Demo"),
+            CityMeta::new("cologne", "Köln")
+                .with_area(CityArea::new(Coordinates::new(50.9375, 6.9603), 15.0)),
+        ];
+        let locator = AreaLocator::from_cities(&cities);
+        assert_eq!(
+            locator
+                .find(&GeoQuery::Position(Coordinates::new(50.94, 6.95)))
+                .map(|city| city.0.as_str()),
+            Some("cologne"),
+            "the declared area is used"
+        );
+        assert_eq!(
+            locator.areas.len(),
+            1,
+            "only cities with an area are listed"
+        );
+    }
+}
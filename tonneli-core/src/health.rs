@@ -0,0 +1,154 @@
+//! Probing provider backends for monitoring long-running deployments.
+
+use std::time::Duration;
+
+use web_time::Instant;
+
+use crate::model::CityId;
+use crate::plugin::CityPlugin;
+use crate::ports::PortError;
+
+#[derive(Debug)]
+/// Result of a provider's health probe, see [`AddressPort::health_check`](crate::ports::AddressPort::health_check).
+pub enum HealthStatus {
+    /// The backend answered the probe.
+    Up,
+    /// The probe failed.
+    Down(PortError),
+    /// The provider has no cheap probe, so its state is not known.
+    Unchecked,
+}
+
+#[derive(Debug)]
+/// Health of one registered provider.
+pub struct ProviderHealth {
+    /// City the provider serves.
+    pub city: CityId,
+    /// Outcome of the probe.
+    pub status: HealthStatus,
+    /// Time the probe took; zero when nothing was probed.
+    pub latency: Duration,
+}
+
+impl ProviderHealth {
+    /// Whether the probe failed; unchecked providers do not count as down.
+    #[must_use]
+    pub fn is_down(&self) -> bool {
+        matches!(self.status, HealthStatus::Down(_))
+    }
+}
+
+/// Run the health probe of `plugin` and time it.
+pub(crate) async fn probe(plugin: &CityPlugin) -> ProviderHealth {
+    let started = Instant::now();
+    let status = match plugin.address_port.health_check().await {
+        None => HealthStatus::Unchecked,
+        Some(Ok(())) => HealthStatus::Up,
+        Some(Err(err)) => HealthStatus::Down(err),
+    };
+    let latency = match status {
+        HealthStatus::Unchecked => Duration::ZERO,
+        HealthStatus::Up | HealthStatus::Down(_) => started.elapsed(),
+    };
+    ProviderHealth {
+        city: plugin.meta.id.clone(),
+        status,
+        latency,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::model::{Address, AddressId, CityMeta, DateRange, PickupEvent};
+    use crate::plugin::PluginRegistry;
+    use crate::ports::{AddressPort, AddressSearch, SchedulePort};
+    use crate::service::TonneliService;
+
+    struct Probed {
+        meta: CityMeta,
+        answers: Option<bool>,
+    }
+
+    #[async_trait]
+    impl AddressPort for Probed {
+        fn city(&self) -> &CityMeta {
+            &self.meta
+        }
+
+        async fn search(&self, _: &AddressSearch, _: usize) -> Result<Vec<Address>, PortError> {
+            Ok(Vec::new())
+        }
+
+        async fn health_check(&self) -> Option<Result<(), PortError>> {
+            self.answers
+                .map(|answers| answers.then_some(()).ok_or(PortError::HttpStatus(503)))
+        }
+    }
+
+    #[async_trait]
+    impl SchedulePort for Probed {
+        fn city(&self) -> &CityMeta {
+            &self.meta
+        }
+
+        async fn schedule(
+            &self,
+            _: &AddressId,
+            _: DateRange,
+        ) -> Result<Vec<PickupEvent>, PortError> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn plugin(id: &str, answers: Option<bool>) -> CityPlugin {
+        let port = Arc::new(Probed {
+            meta: CityMeta::new(id, id),
+            answers,
+        });
+        CityPlugin {
+            meta: port.meta.clone(),
+            address_port: Arc::<Probed>::clone(&port),
+            schedule_port: port,
+        }
+    }
+
+    #[test]
+    fn every_provider_reports_its_status() {
+        let service = TonneliService::new(Arc::new(PluginRegistry::new(vec![
+            plugin("cologne", Some(true)),
+            plugin("bremen", Some(false)),
+            plugin("demo", None),
+        ])));
+
+        let health = block_on(service.provider_health());
+        let statuses = health
+            .iter()
+            .map(|provider| {
+                let status = match &provider.status {
+                    HealthStatus::Up => "up",
+                    HealthStatus::Down(_) => "down",
+                    HealthStatus::Unchecked => "unchecked",
+                };
+                (provider.city.0.as_str(), status)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [("bremen", "down"), ("cologne", "up"), ("demo", "unchecked")],
+            "one entry per city, sorted by id"
+        );
+        assert!(
+            health
+                .iter()
+                .filter(|provider| matches!(provider.status, HealthStatus::Unchecked))
+                .all(|provider| provider.latency.is_zero()),
+            "nothing is timed without a probe"
+        );
+    }
+}
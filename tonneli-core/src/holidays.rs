@@ -0,0 +1,362 @@
+//! German public holidays per state, and the pickups they probably moved.
+//!
+//! Collection usually pauses on a public holiday and every pickup of the rest of
+//! that week moves one day later. [`annotate_holidays`] marks the pickups that fall
+//! on a holiday or later in its week with a [`HolidayShift`], so clients can tell a
+//! shifted date from the regular one.
+
+use chrono::{Datelike, Days, NaiveDate, TimeDelta, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::model::{CityMeta, PickupEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// German federal state, which decides the regional holidays.
+pub enum State {
+    /// Baden-Württemberg (BW).
+    BadenWuerttemberg,
+    /// Bayern (BY).
+    Bayern,
+    /// Berlin (BE).
+    Berlin,
+    /// Brandenburg (BB).
+    Brandenburg,
+    /// Bremen (HB).
+    Bremen,
+    /// Hamburg (HH).
+    Hamburg,
+    /// Hessen (HE).
+    Hessen,
+    /// Mecklenburg-Vorpommern (MV).
+    MecklenburgVorpommern,
+    /// Niedersachsen (NI).
+    Niedersachsen,
+    /// Nordrhein-Westfalen (NW).
+    NordrheinWestfalen,
+    /// Rheinland-Pfalz (RP).
+    RheinlandPfalz,
+    /// Saarland (SL).
+    Saarland,
+    /// Sachsen (SN).
+    Sachsen,
+    /// Sachsen-Anhalt (ST).
+    SachsenAnhalt,
+    /// Schleswig-Holstein (SH).
+    SchleswigHolstein,
+    /// Thüringen (TH).
+    Thueringen,
+}
+
+impl State {
+    const ALL: [(Self, &'static str, &'static str); 16] = [
+        (Self::BadenWuerttemberg, "BW", "Baden-Württemberg"),
+        (Self::Bayern, "BY", "Bayern"),
+        (Self::Berlin, "BE", "Berlin"),
+        (Self::Brandenburg, "BB", "Brandenburg"),
+        (Self::Bremen, "HB", "Bremen"),
+        (Self::Hamburg, "HH", "Hamburg"),
+        (Self::Hessen, "HE", "Hessen"),
+        (Self::MecklenburgVorpommern, "MV", "Mecklenburg-Vorpommern"),
+        (Self::Niedersachsen, "NI", "Niedersachsen"),
+        (Self::NordrheinWestfalen, "NW", "Nordrhein-Westfalen"),
+        (Self::RheinlandPfalz, "RP", "Rheinland-Pfalz"),
+        (Self::Saarland, "SL", "Saarland"),
+        (Self::Sachsen, "SN", "Sachsen"),
+        (Self::SachsenAnhalt, "ST", "Sachsen-Anhalt"),
+        (Self::SchleswigHolstein, "SH", "Schleswig-Holstein"),
+        (Self::Thueringen, "TH", "Thüringen"),
+    ];
+
+    /// State of a German city, from its region; `None` for cities elsewhere.
+    #[must_use]
+    pub fn of_city(meta: &CityMeta) -> Option<Self> {
+        if meta
+            .country
+            .as_deref()
+            .is_some_and(|country| !country.eq_ignore_ascii_case("DE"))
+        {
+            return None;
+        }
+        meta.region.as_deref().and_then(Self::from_region)
+    }
+
+    /// State named by a [`CityMeta::region`], either
+    /// its German name (`Nordrhein-Westfalen`) or its code (`NW`, `DE-NW`).
+    #[must_use]
+    pub fn from_region(region: &str) -> Option<Self> {
+        let region = region.trim();
+        let code = region.strip_prefix("DE-").unwrap_or(region);
+        Self::ALL
+            .iter()
+            .find(|(_, short, name)| {
+                short.eq_ignore_ascii_case(code) || name.eq_ignore_ascii_case(region)
+            })
+            .map(|(state, _, _)| *state)
+    }
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Public holiday that pauses waste collection.
+pub enum Holiday {
+    /// Neujahr, 1 January.
+    NewYear,
+    /// Heilige Drei Könige, 6 January.
+    Epiphany,
+    /// Internationaler Frauentag, 8 March.
+    WomensDay,
+    /// Karfreitag.
+    GoodFriday,
+    /// Ostermontag.
+    EasterMonday,
+    /// Tag der Arbeit, 1 May.
+    LabourDay,
+    /// Christi Himmelfahrt.
+    Ascension,
+    /// Pfingstmontag.
+    WhitMonday,
+    /// Fronleichnam.
+    CorpusChristi,
+    /// Mariä Himmelfahrt, 15 August.
+    Assumption,
+    /// Weltkindertag, 20 September.
+    ChildrensDay,
+    /// Tag der Deutschen Einheit, 3 October.
+    GermanUnity,
+    /// Reformationstag, 31 October.
+    Reformation,
+    /// Allerheiligen, 1 November.
+    AllSaints,
+    /// Buß- und Bettag, the Wednesday before 23 November.
+    RepentanceDay,
+    /// 1. Weihnachtstag, 25 December.
+    ChristmasDay,
+    /// 2. Weihnachtstag, 26 December.
+    BoxingDay,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// A pickup on a public holiday or later in the same week.
+pub struct HolidayShift {
+    /// Latest holiday on or before the pickup in its week.
+    pub holiday: Holiday,
+    /// Date of that holiday.
+    pub holiday_date: NaiveDate,
+    /// Likely regular date: one day earlier per holiday earlier in the week. `None`
+    /// for a pickup on the holiday itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shifted_from: Option<NaiveDate>,
+}
+
+/// Easter Sunday of `year` (anonymous Gregorian algorithm).
+fn easter(year: i32) -> Option<NaiveDate> {
+    let golden = year.rem_euclid(19);
+    let century = year.div_euclid(100);
+    let in_century = year.rem_euclid(100);
+    let epact = (19 * golden + century - century / 4 - (8 * century + 13) / 25 + 15).rem_euclid(30);
+    let weekday =
+        (32 + 2 * century.rem_euclid(4) + 2 * (in_century / 4) - epact - in_century.rem_euclid(4))
+            .rem_euclid(7);
+    let correction = (golden + 11 * epact + 22 * weekday) / 451;
+    let days = epact + weekday - 7 * correction + 114;
+    NaiveDate::from_ymd_opt(
+        year,
+        u32::try_from(days / 31).ok()?,
+        u32::try_from(days.rem_euclid(31) + 1).ok()?,
+    )
+}
+
+/// Public holidays of `state` in `year`, in date order.
+#[must_use]
+pub fn holidays(state: State, year: i32) -> Vec<(NaiveDate, Holiday)> {
+    use State::{
+        BadenWuerttemberg, Bayern, Berlin, Brandenburg, Bremen, Hamburg, Hessen,
+        MecklenburgVorpommern, Niedersachsen, NordrheinWestfalen, RheinlandPfalz, Saarland,
+        Sachsen, SachsenAnhalt, SchleswigHolstein, Thueringen,
+    };
+
+    let fixed =
+        |month, day, holiday| NaiveDate::from_ymd_opt(year, month, day).map(|date| (date, holiday));
+    let easter = easter(year);
+    let from_easter = |days: i64, holiday| {
+        easter
+            .and_then(|sunday| sunday.checked_add_signed(TimeDelta::days(days)))
+            .map(|date| (date, holiday))
+    };
+    let repentance = NaiveDate::from_ymd_opt(year, 11, 22).and_then(|latest| {
+        let back =
+            (latest.weekday().num_days_from_monday() + 7 - Weekday::Wed.num_days_from_monday()) % 7;
+        latest.checked_sub_days(Days::new(back.into()))
+    });
+
+    let mut days = vec![
+        fixed(1, 1, Holiday::NewYear),
+        from_easter(-2, Holiday::GoodFriday),
+        from_easter(1, Holiday::EasterMonday),
+        fixed(5, 1, Holiday::LabourDay),
+        from_easter(39, Holiday::Ascension),
+        from_easter(50, Holiday::WhitMonday),
+        fixed(10, 3, Holiday::GermanUnity),
+        fixed(12, 25, Holiday::ChristmasDay),
+        fixed(12, 26, Holiday::BoxingDay),
+    ];
+    if matches!(state, BadenWuerttemberg | Bayern | SachsenAnhalt) {
+        days.push(fixed(1, 6, Holiday::Epiphany));
+    }
+    if (state == Berlin && year >= 2019) || (state == MecklenburgVorpommern && year >= 2023) {
+        days.push(fixed(3, 8, Holiday::WomensDay));
+    }
+    if matches!(
+        state,
+        BadenWuerttemberg | Bayern | Hessen | NordrheinWestfalen | RheinlandPfalz | Saarland
+    ) {
+        days.push(from_easter(60, Holiday::CorpusChristi));
+    }
+    if state == Saarland {
+        days.push(fixed(8, 15, Holiday::Assumption));
+    }
+    if state == Thueringen && year >= 2019 {
+        days.push(fixed(9, 20, Holiday::ChildrensDay));
+    }
+    let reformation = match state {
+        Brandenburg | MecklenburgVorpommern | Sachsen | SachsenAnhalt | Thueringen => true,
+        Bremen | Hamburg | Niedersachsen | SchleswigHolstein => year >= 2018,
+        _ => year == 2017,
+    };
+    if reformation {
+        days.push(fixed(10, 31, Holiday::Reformation));
+    }
+    if matches!(
+        state,
+        BadenWuerttemberg | Bayern | NordrheinWestfalen | RheinlandPfalz | Saarland
+    ) {
+        days.push(fixed(11, 1, Holiday::AllSaints));
+    }
+    if state == Sachsen {
+        days.push(repentance.map(|date| (date, Holiday::RepentanceDay)));
+    }
+
+    let mut days = days.into_iter().flatten().collect::<Vec<_>>();
+    days.sort_unstable_by_key(|(date, _)| *date);
+    days
+}
+
+/// Set [`PickupEvent::holiday`] for the pickups on a holiday of `state` or later in
+/// its week, and clear it everywhere else.
+///
+/// Weeks run from Monday to Saturday; holidays on a Sunday move nothing.
+pub fn annotate_holidays(events: &mut [PickupEvent], state: State) {
+    let mut years = events
+        .iter()
+        .map(|event| event.date.year())
+        .collect::<Vec<_>>();
+    years.sort_unstable();
+    years.dedup();
+    let calendar = years
+        .into_iter()
+        .flat_map(|year| holidays(state, year))
+        .filter(|(date, _)| date.weekday() != Weekday::Sun)
+        .collect::<Vec<_>>();
+
+    for event in events {
+        let week = event.date.iso_week();
+        let earlier = calendar
+            .iter()
+            .filter(|(date, _)| *date <= event.date && date.iso_week() == week)
+            .collect::<Vec<_>>();
+        event.holiday = earlier.last().map(|&&(holiday_date, holiday)| {
+            let before = earlier
+                .iter()
+                .filter(|(date, _)| *date < event.date)
+                .count();
+            HolidayShift {
+                holiday,
+                holiday_date,
+                shifted_from: u64::try_from(before)
+                    .ok()
+                    .filter(|&days| days > 0 && event.date.weekday() != Weekday::Sun)
+                    .and_then(|days| event.date.checked_sub_days(Days::new(days))),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Fraction;
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap_or_default()
+    }
+
+    #[test]
+    fn movable_and_regional_holidays() {
+        let nrw = holidays(State::NordrheinWestfalen, 2025);
+        assert!(
+            nrw.contains(&(day(2025, 4, 21), Holiday::EasterMonday)),
+            "Easter 2025 is on 20 April"
+        );
+        assert!(
+            nrw.contains(&(day(2025, 6, 19), Holiday::CorpusChristi)),
+            "Corpus Christi in NRW"
+        );
+        assert!(
+            !holidays(State::Bremen, 2025)
+                .iter()
+                .any(|(_, holiday)| *holiday == Holiday::CorpusChristi),
+            "not in Bremen"
+        );
+        assert!(
+            holidays(State::Sachsen, 2025).contains(&(day(2025, 11, 19), Holiday::RepentanceDay)),
+            "Wednesday before 23 November"
+        );
+        assert_eq!(
+            State::from_region("Nordrhein-Westfalen"),
+            Some(State::NordrheinWestfalen),
+            "German name"
+        );
+        assert_eq!(State::from_region("DE-BY"), Some(State::Bayern), "ISO code");
+    }
+
+    #[test]
+    fn pickups_after_a_holiday_are_shifted() {
+        let pickup = |date| PickupEvent {
+            date,
+            fraction: Fraction::Residual,
+            note: None,
+            container: None,
+            correction: None,
+            holiday: None,
+            predicted: false,
+        };
+        // Christmas 2024 is Wednesday and Thursday.
+        let mut events = [
+            pickup(day(2024, 12, 23)),
+            pickup(day(2024, 12, 25)),
+            pickup(day(2024, 12, 27)),
+            pickup(day(2025, 1, 2)),
+        ];
+        annotate_holidays(&mut events, State::NordrheinWestfalen);
+
+        let shifts = events
+            .iter()
+            .map(|event| {
+                event
+                    .holiday
+                    .map(|shift| (shift.holiday, shift.shifted_from))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            shifts,
+            [
+                None,
+                Some((Holiday::ChristmasDay, None)),
+                Some((Holiday::BoxingDay, Some(day(2024, 12, 25)))),
+                Some((Holiday::NewYear, Some(day(2025, 1, 1)))),
+            ],
+            "two days back after both Christmas days, one after New Year"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Which bins a household actually has.
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Fraction, PickupEvent};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// How a household uses the collection of one fraction.
+pub enum BinOwnership {
+    /// The household has its own bin and puts it out.
+    #[default]
+    Own,
+    /// The container is shared, e.g. with the whole building; pickups are shown
+    /// but someone else takes care of putting it out.
+    Shared,
+    /// The household has no bin of this fraction; its pickups are hidden.
+    None,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+/// Bins of a household, per fraction; every fraction is owned unless configured otherwise.
+pub struct HouseholdProfile {
+    /// Residual waste.
+    pub residual: BinOwnership,
+    /// Organic waste.
+    pub organic: BinOwnership,
+    /// Paper and cardboard.
+    pub paper: BinOwnership,
+    /// Light packaging or plastics.
+    pub plastic: BinOwnership,
+    /// Glass collection.
+    pub glass: BinOwnership,
+    /// Metal scrap.
+    pub metal: BinOwnership,
+    /// Street cleaning; `none` hides it.
+    pub street_cleaning: BinOwnership,
+    /// Bulky waste.
+    pub bulky_waste: BinOwnership,
+    /// Garden and green waste.
+    pub garden_waste: BinOwnership,
+    /// Hazardous waste.
+    pub hazardous: BinOwnership,
+    /// Christmas trees.
+    pub christmas_tree: BinOwnership,
+    /// Used clothes and textiles.
+    pub textiles: BinOwnership,
+    /// Electrical and electronic equipment.
+    pub electronics: BinOwnership,
+    /// Any provider-specific fraction.
+    pub other: BinOwnership,
+}
+
+impl HouseholdProfile {
+    /// Ownership of the given fraction.
+    #[must_use]
+    pub fn ownership(&self, fraction: &Fraction) -> BinOwnership {
+        match fraction {
+            Fraction::Residual => self.residual,
+            Fraction::Organic => self.organic,
+            Fraction::Paper => self.paper,
+            Fraction::Plastic => self.plastic,
+            Fraction::Glass => self.glass,
+            Fraction::Metal => self.metal,
+            Fraction::StreetCleaning => self.street_cleaning,
+            Fraction::BulkyWaste => self.bulky_waste,
+            Fraction::GardenWaste => self.garden_waste,
+            Fraction::Hazardous => self.hazardous,
+            Fraction::ChristmasTree => self.christmas_tree,
+            Fraction::Textiles => self.textiles,
+            Fraction::Electronics => self.electronics,
+            Fraction::Other(_) => self.other,
+        }
+    }
+
+    /// Whether pickups of the fraction concern the household at all.
+    #[must_use]
+    pub fn collects(&self, fraction: &Fraction) -> bool {
+        self.ownership(fraction) != BinOwnership::None
+    }
+
+    /// Whether the household puts the bin out itself and so wants to be reminded.
+    ///
+    /// Reminder engines combine this with
+    /// [`ReminderSettings::is_enabled_for`](crate::reminder::ReminderSettings::is_enabled_for).
+    #[must_use]
+    pub fn puts_out(&self, fraction: &Fraction) -> bool {
+        self.ownership(fraction) == BinOwnership::Own
+    }
+
+    /// Drop the pickups of fractions the household has no bin for.
+    pub fn retain_collected(&self, events: &mut Vec<PickupEvent>) {
+        events.retain(|event| self.collects(&event.fraction));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn hides_missing_bins_and_keeps_shared_ones() {
+        let profile = HouseholdProfile {
+            organic: BinOwnership::None,
+            paper: BinOwnership::Shared,
+            ..HouseholdProfile::default()
+        };
+        let date = NaiveDate::from_ymd_opt(2025, 3, 10).expect("valid test date");
+        let mut events = [Fraction::Residual, Fraction::Organic, Fraction::Paper]
+            .into_iter()
+            .map(|fraction| PickupEvent {
+                date,
+                fraction,
+                note: None,
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            })
+            .collect::<Vec<_>>();
+
+        profile.retain_collected(&mut events);
+        let fractions = events
+            .iter()
+            .map(|event| event.fraction.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fractions,
+            [Fraction::Residual, Fraction::Paper],
+            "organic pickups are hidden"
+        );
+        assert!(
+            !profile.puts_out(&Fraction::Paper),
+            "shared containers are not reminded of"
+        );
+        assert!(
+            profile.puts_out(&Fraction::Other("Sperrmüll".into())),
+            "unconfigured fractions are owned"
+        );
+    }
+}
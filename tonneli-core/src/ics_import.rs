@@ -0,0 +1,331 @@
+//! Importing pickups from the calendar (ICS) exports of municipal apps.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+
+use crate::dates::MalformedDates;
+use crate::fraction_map::FractionMapper;
+use crate::household::{BinOwnership, HouseholdProfile};
+use crate::merge::merge_events;
+use crate::model::{Fraction, PickupEvent};
+
+/// Bin colors municipal apps name their calendar entries after.
+const COLOR_RULES: &[(&str, Fraction)] = &[
+    ("blaue", Fraction::Paper),
+    ("braune", Fraction::Organic),
+    ("graue", Fraction::Residual),
+    ("schwarze", Fraction::Residual),
+];
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+/// Errors while reading a calendar export.
+pub enum ImportError {
+    /// The input has no `BEGIN:VCALENDAR`.
+    #[error("Not an iCalendar file")]
+    NotACalendar,
+    /// The calendar has no events at all.
+    #[error("The calendar contains no events")]
+    NoEvents,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Pickups and hints recovered from a calendar export.
+pub struct CalendarImport {
+    /// Calendar name (`X-WR-CALNAME`), often the address or the app's name.
+    pub name: Option<String>,
+    /// Most frequent event location, usually the address the export was made for.
+    pub location: Option<String>,
+    /// Pickups in date order, past ones included; duplicates are merged.
+    pub events: Vec<PickupEvent>,
+    /// Summaries no rule mapped, imported as [`Fraction::Other`].
+    pub unmapped: Vec<String>,
+    /// `DTSTART` values that are not dates.
+    pub malformed: Vec<String>,
+}
+
+impl CalendarImport {
+    /// Mapper for calendar summaries: the shared German vocabulary plus bin colors.
+    #[must_use]
+    pub fn mapper() -> FractionMapper {
+        FractionMapper::german().with_rules(COLOR_RULES)
+    }
+
+    /// Read the `VEVENT`s of an ICS file, naming fractions with `mapper`.
+    ///
+    /// Only the start date of each event is used; recurrence rules are not
+    /// expanded, as the exports of municipal apps list every pickup on its own.
+    /// The summary decides the fraction and the description becomes the note.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ImportError`] when `ics` is not a calendar or has no events.
+    pub fn parse(ics: &str, mapper: &FractionMapper) -> Result<Self, ImportError> {
+        let lines = unfold(ics);
+        if !lines
+            .iter()
+            .any(|line| line.eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+        {
+            return Err(ImportError::NotACalendar);
+        }
+
+        let mut import = Self::default();
+        let mut malformed = MalformedDates::default();
+        let mut locations = BTreeMap::<String, usize>::new();
+        let mut unmapped = BTreeSet::new();
+        let mut event: Option<RawEvent> = None;
+        let mut found = false;
+        for line in &lines {
+            let Some((name, value)) = property(line) else {
+                continue;
+            };
+            match (name.as_str(), event.as_mut()) {
+                ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                    event = Some(RawEvent::default());
+                }
+                ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                    found = true;
+                    let Some(raw) = event.take() else { continue };
+                    if let Some(location) = raw.location {
+                        *locations.entry(location).or_default() += 1;
+                    }
+                    let Some(date) = raw.start.and_then(|start| {
+                        let date = parse_date(&start);
+                        if date.is_none() {
+                            malformed.push(start);
+                        }
+                        date
+                    }) else {
+                        continue;
+                    };
+                    let summary = raw.summary.unwrap_or_default();
+                    let fraction = mapper.try_map(&summary).unwrap_or_else(|| {
+                        unmapped.insert(summary.clone());
+                        Fraction::Other(summary.into())
+                    });
+                    import.events.push(PickupEvent {
+                        date,
+                        fraction,
+                        note: raw.description.map(Arc::from),
+                        container: None,
+                        correction: None,
+                        holiday: None,
+                        predicted: false,
+                    });
+                }
+                ("X-WR-CALNAME", None) => import.name = Some(unescape(value)),
+                ("DTSTART", Some(raw)) => raw.start = Some(value.to_owned()),
+                ("SUMMARY", Some(raw)) => raw.summary = Some(unescape(value)),
+                ("LOCATION", Some(raw)) => raw.location = Some(unescape(value)),
+                ("DESCRIPTION", Some(raw)) => raw.description = Some(unescape(value)),
+                _ => {}
+            }
+        }
+        if !found {
+            return Err(ImportError::NoEvents);
+        }
+
+        import.events = merge_events([import.events]);
+        import.location = locations
+            .into_iter()
+            .filter(|(location, _)| !location.trim().is_empty())
+            .max_by_key(|&(_, count)| count)
+            .map(|(location, _)| location);
+        import.unmapped = unmapped.into_iter().collect();
+        import.malformed = malformed.into_inner();
+        Ok(import)
+    }
+
+    /// Household profile owning exactly the fractions the export has pickups of.
+    ///
+    /// Municipal apps usually export only the bins a user subscribed to, so
+    /// fractions missing from the calendar are set to [`BinOwnership::None`].
+    #[must_use]
+    pub fn household(&self) -> HouseholdProfile {
+        let seen = |wanted: &Fraction| {
+            if self.events.iter().any(|event| &event.fraction == wanted) {
+                BinOwnership::Own
+            } else {
+                BinOwnership::None
+            }
+        };
+        HouseholdProfile {
+            residual: seen(&Fraction::Residual),
+            organic: seen(&Fraction::Organic),
+            paper: seen(&Fraction::Paper),
+            plastic: seen(&Fraction::Plastic),
+            glass: seen(&Fraction::Glass),
+            metal: seen(&Fraction::Metal),
+            street_cleaning: seen(&Fraction::StreetCleaning),
+            bulky_waste: seen(&Fraction::BulkyWaste),
+            garden_waste: seen(&Fraction::GardenWaste),
+            hazardous: seen(&Fraction::Hazardous),
+            christmas_tree: seen(&Fraction::ChristmasTree),
+            textiles: seen(&Fraction::Textiles),
+            electronics: seen(&Fraction::Electronics),
+            other: if self.unmapped.is_empty() {
+                BinOwnership::None
+            } else {
+                BinOwnership::Own
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct RawEvent {
+    start: Option<String>,
+    summary: Option<String>,
+    location: Option<String>,
+    description: Option<String>,
+}
+
+/// Join folded lines, which continue with a leading space or tab.
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continued), Some(last)) => last.push_str(continued),
+            _ => lines.push(line.to_owned()),
+        }
+    }
+    lines
+}
+
+/// Upper-cased property name and raw value; parameters such as `;VALUE=DATE` are dropped.
+fn property(line: &str) -> Option<(String, &str)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(index, ch)| {
+        if ch == '"' {
+            quoted = !quoted;
+        }
+        (ch == ':' && !quoted).then_some(index)
+    })?;
+    let (head, value) = (line.get(..colon)?, line.get(colon + 1..)?);
+    let name = head.split(';').next().unwrap_or(head);
+    Some((name.to_ascii_uppercase(), value))
+}
+
+/// Date of a `DTSTART` value, either a date (`20250314`) or a date-time (`20250314T060000Z`).
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+/// Resolve the backslash escapes of iCalendar text values.
+fn unescape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            text.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(escaped) => text.push(escaped),
+            None => text.push('\\'),
+        }
+    }
+    text.trim().to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = "BEGIN:VCALENDAR\r\n\
+        VERSION:2.0\r\n\
+        X-WR-CALNAME:Abfallkalender\r\n\
+        BEGIN:VEVENT\r\n\
+        DTSTART;VALUE=DATE:20250314\r\n\
+        SUMMARY:Blaue Tonne\r\n\
+        LOCATION:Domkloster 4\\, 50667 Köln\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        DTSTART:20250311T060000Z\r\n\
+        SUMMARY:Restmüll\r\n\
+        DESCRIPTION:Bitte bis 6 Uhr\r\n \x20bereitstellen\r\n\
+        LOCATION:Domkloster 4\\, 50667 Köln\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        DTSTART;VALUE=DATE:2025031\r\n\
+        SUMMARY:Gelbe Tonne\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        DTSTART;VALUE=DATE:20250320\r\n\
+        SUMMARY:Windeltonne\r\n\
+        END:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).expect("valid test date")
+    }
+
+    #[test]
+    fn imports_municipal_export() {
+        let import =
+            CalendarImport::parse(EXPORT, &CalendarImport::mapper()).expect("export is valid");
+
+        assert_eq!(
+            import.name.as_deref(),
+            Some("Abfallkalender"),
+            "calendar name"
+        );
+        assert_eq!(
+            import.location.as_deref(),
+            Some("Domkloster 4, 50667 Köln"),
+            "the location is unescaped"
+        );
+        let events = import
+            .events
+            .iter()
+            .map(|event| (event.date, event.fraction.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            [
+                (day(11), Fraction::Residual),
+                (day(14), Fraction::Paper),
+                (day(20), Fraction::Other("Windeltonne".into())),
+            ],
+            "events are sorted and mapped by summary and bin color"
+        );
+        assert_eq!(
+            import
+                .events
+                .first()
+                .and_then(|event| event.note.as_deref()),
+            Some("Bitte bis 6 Uhr bereitstellen"),
+            "folded descriptions become notes"
+        );
+        assert_eq!(
+            import.unmapped,
+            ["Windeltonne"],
+            "unknown summaries are listed"
+        );
+        assert_eq!(import.malformed, ["2025031"], "broken dates are reported");
+
+        let household = import.household();
+        assert_eq!(
+            (household.paper, household.organic, household.other),
+            (BinOwnership::Own, BinOwnership::None, BinOwnership::Own),
+            "only exported fractions are owned"
+        );
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let mapper = CalendarImport::mapper();
+        assert_eq!(
+            CalendarImport::parse("Name,Date\nPapier,2025-03-14\n", &mapper),
+            Err(ImportError::NotACalendar),
+            "CSV is not a calendar"
+        );
+        assert_eq!(
+            CalendarImport::parse("BEGIN:VCALENDAR\nEND:VCALENDAR\n", &mapper),
+            Err(ImportError::NoEvents),
+            "empty calendars are rejected"
+        );
+    }
+}
@@ -1,15 +1,116 @@
 //! Core types and service wiring for the tonneli waste schedule aggregator.
 
+/// Acknowledged and snoozed reminders.
+pub mod acknowledge;
+/// Memoizing provider schedules for a configurable time.
+pub mod cache;
+/// Failure injection for resilience testing.
+pub mod chaos;
+/// Injectable source of the current date.
+pub mod clock;
+/// Tolerant parsing of provider date formats.
+pub mod dates;
+/// Added, removed and moved pickups between two fetches of a schedule.
+pub mod diff;
+/// Exporting schedules for calendar apps and other tools.
+pub mod export;
+/// Concurrent multi-provider calls with per-provider error isolation.
+pub mod fanout;
+/// Addresses saved under user-defined labels.
+pub mod favorites;
+/// Keyword table mapping provider fraction labels to fractions.
+pub mod fraction_map;
+/// Detecting the city for a position or postal code.
+pub mod geo;
+/// Probing provider backends for monitoring.
+pub mod health;
+/// German public holidays and the pickups they move.
+pub mod holidays;
+/// Bins a household has, for hiding pickups it does not care about.
+pub mod household;
+/// Importing pickups from calendar (ICS) exports.
+pub mod ics_import;
+/// Street name folding and fuzzy matching shared by providers.
+pub mod matching;
+/// Deduplicating merge of events from several sources.
+pub mod merge;
+/// Request, error and latency figures of provider calls.
+pub mod metrics;
 /// Domain models and identifiers shared by all providers.
 pub mod model;
+/// Spelling out abbreviated street names.
+pub mod normalize;
+/// Notification channels and due reminders.
+pub mod notify;
+/// User corrections applied on top of provider schedules.
+pub mod overrides;
 /// Registry and helpers for plugging city-specific providers into the service.
 pub mod plugin;
 /// Traits describing the provider interfaces.
 pub mod ports;
+/// Extrapolating regular pickups past the provider's horizon.
+pub mod predict;
+/// Per-city token buckets capping calls to provider backends.
+pub mod ratelimit;
+/// Incremental schedule refreshes and background refreshing of subscribed addresses.
+pub mod refresh;
+/// User settings for pickup reminders.
+pub mod reminder;
+/// Pluggable reporting of provider errors.
+pub mod report;
+/// JSON Schemas of the serialized models (feature `schema`).
+#[cfg(feature = "schema")]
+pub mod schema;
 /// High-level service facade used by clients.
 pub mod service;
+/// Combining several providers of one city.
+pub mod sources;
+/// Pluggable persistence of namespaced blobs.
+pub mod storage;
+/// Spans around provider calls (feature `tracing`).
+#[cfg(feature = "tracing")]
+pub mod trace;
+/// Sanity checks for provider schedules.
+pub mod validate;
+/// Notification channel posting to webhooks.
+pub mod webhook;
 
+pub use acknowledge::*;
+pub use cache::*;
+pub use chaos::*;
+pub use clock::*;
+pub use dates::*;
+pub use diff::*;
+pub use fanout::*;
+pub use favorites::*;
+pub use fraction_map::*;
+pub use geo::*;
+pub use health::*;
+pub use holidays::*;
+pub use household::*;
+pub use ics_import::*;
+pub use matching::*;
+pub use merge::*;
+pub use metrics::*;
 pub use model::*;
+pub use normalize::*;
+pub use notify::*;
+pub use overrides::*;
 pub use plugin::*;
 pub use ports::*;
+pub use predict::*;
+pub use ratelimit::*;
+pub use refresh::*;
+pub use reminder::*;
+pub use report::*;
 pub use service::*;
+pub use sources::*;
+pub use storage::*;
+#[cfg(feature = "tracing")]
+pub use trace::*;
+pub use validate::*;
+pub use webhook::*;
+
+// Dev-dependencies only used by the benchmarks in `benches/` and the property tests in `tests/`.
+#[cfg(test)]
+use {criterion as _, metrics_util as _, proptest as _};
@@ -0,0 +1,142 @@
+//! Diacritic-insensitive and fuzzy matching of street names.
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::normalize::street_key;
+
+/// Fold a string for comparison.
+///
+/// Lowercases, spells out `ß` as `ss`, strips diacritics, and folds the German
+/// transliterations `ae`/`oe`/`ue` so that "Nürnberger", "Nuernberger" and
+/// "nurnberger" all compare equal.
+#[must_use]
+pub fn fold(input: &str) -> String {
+    let stripped = input
+        .nfd()
+        .filter(|ch| !is_combining_mark(*ch))
+        .flat_map(char::to_lowercase)
+        .collect::<String>();
+
+    let mut folded = String::with_capacity(stripped.len());
+    let mut chars = stripped.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            'ß' => folded.push_str("ss"),
+            'a' | 'o' | 'u' if chars.peek() == Some(&'e') => {
+                folded.push(ch);
+                chars.next();
+            }
+            _ => folded.push(ch),
+        }
+    }
+    folded
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// How well a candidate matched, best first.
+pub enum MatchRank {
+    /// The candidate equals the query.
+    Exact,
+    /// The candidate starts with the query.
+    Prefix,
+    /// The candidate contains the query.
+    Contains,
+    /// The candidate contains the query within the edit budget.
+    Fuzzy,
+}
+
+/// Matches candidate street names against a user query.
+#[derive(Debug, Clone)]
+pub struct StreetMatcher {
+    /// The query as typed, folded.
+    literal: String,
+    /// The query with abbreviations spelled out, see [`street_key`].
+    normalized: String,
+    max_edits: usize,
+}
+
+impl StreetMatcher {
+    /// Build a matcher for `query`.
+    ///
+    /// Candidates are compared with the query as typed and with its [`street_key`],
+    /// so abbreviations like "Str." and hyphen or space variants match the
+    /// provider's spelling without hiding candidates the literal query finds.
+    ///
+    /// With `fuzzy` enabled, candidates may differ by one edit per five query
+    /// characters (typos, missing letters).
+    #[must_use]
+    pub fn new(query: &str, fuzzy: bool) -> Self {
+        let literal = fold(query.trim());
+        let max_edits = if fuzzy {
+            literal.chars().count() / 5
+        } else {
+            0
+        };
+        Self {
+            normalized: street_key(query),
+            literal,
+            max_edits,
+        }
+    }
+
+    /// Whether the candidate contains the query (after folding, within the edit budget).
+    #[must_use]
+    pub fn matches(&self, candidate: &str) -> bool {
+        self.rank(candidate).is_some()
+    }
+
+    /// How well the candidate matches, or `None` if it does not.
+    #[must_use]
+    pub fn rank(&self, candidate: &str) -> Option<MatchRank> {
+        let literal = self.rank_folded(&self.literal, &fold(candidate));
+        if literal == Some(MatchRank::Exact) {
+            return literal;
+        }
+        let normalized = self.rank_folded(&self.normalized, &street_key(candidate));
+        literal.into_iter().chain(normalized).min()
+    }
+
+    fn rank_folded(&self, query: &str, candidate: &str) -> Option<MatchRank> {
+        if candidate == query {
+            Some(MatchRank::Exact)
+        } else if candidate.starts_with(query) {
+            Some(MatchRank::Prefix)
+        } else if candidate.contains(query) {
+            Some(MatchRank::Contains)
+        } else if self.max_edits > 0
+            && substring_distance(
+                &query.chars().collect::<Vec<_>>(),
+                &candidate.chars().collect::<Vec<_>>(),
+            ) <= self.max_edits
+        {
+            Some(MatchRank::Fuzzy)
+        } else {
+            None
+        }
+    }
+}
+
+/// Smallest edit distance between `pattern` and any substring of `text`.
+fn substring_distance(pattern: &[char], text: &[char]) -> usize {
+    // Sellers' algorithm: Levenshtein where the match may start anywhere in `text`.
+    let mut previous = (0..=pattern.len()).collect::<Vec<_>>();
+    let mut best = pattern.len();
+
+    for text_char in text {
+        let mut current = Vec::with_capacity(previous.len());
+        current.push(0);
+        for (pattern_char, (diagonal, above)) in pattern
+            .iter()
+            .zip(previous.iter().zip(previous.iter().skip(1)))
+        {
+            let left = current.last().copied().unwrap_or(0);
+            let substitution = diagonal + usize::from(pattern_char != text_char);
+            current.push(substitution.min(above + 1).min(left + 1));
+        }
+        best = best.min(current.last().copied().unwrap_or(best));
+        previous = current;
+    }
+
+    best
+}
@@ -0,0 +1,44 @@
+//! Merging pickup events from several sources for one address.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use crate::model::PickupEvent;
+
+/// Merge event lists, keeping one event per [`EventKey`](crate::model::EventKey).
+///
+/// When the same pickup appears more than once, the longest note wins, so a
+/// detailed provider note is not replaced by an empty or terse one. The result is
+/// sorted by date; events on the same day keep their first-seen order.
+#[must_use]
+pub fn merge_events<I>(sources: I) -> Vec<PickupEvent>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator<Item = PickupEvent>,
+{
+    let mut merged = Vec::<PickupEvent>::new();
+    let mut positions = HashMap::new();
+
+    for event in sources.into_iter().flatten() {
+        match positions.entry(event.key()) {
+            Entry::Vacant(slot) => {
+                slot.insert(merged.len());
+                merged.push(event);
+            }
+            Entry::Occupied(slot) => {
+                if let Some(existing) = merged.get_mut(*slot.get())
+                    && note_len(&event) > note_len(existing)
+                {
+                    existing.note = event.note;
+                }
+            }
+        }
+    }
+
+    merged.sort_by_key(|event| event.date);
+    merged
+}
+
+fn note_len(event: &PickupEvent) -> usize {
+    event.note.as_deref().map_or(0, str::len)
+}
@@ -0,0 +1,409 @@
+//! Request, error and latency figures of provider calls, for dashboards and alerts.
+//!
+//! [`metered`] wraps both ports of a plugin and hands every call to a
+//! [`MetricsRecorder`]. With the `metrics` feature, `MetricsFacade` forwards the
+//! figures to the `metrics` crate as [`REQUESTS_METRIC`], [`ERRORS_METRIC`] and
+//! [`LATENCY_METRIC`] with `city` and `port` labels; [`MemoryMetrics`] keeps
+//! totals in the process. Implement the trait for any other backend.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use web_time::Instant;
+
+use async_trait::async_trait;
+#[cfg(feature = "metrics")]
+use metrics::{counter, histogram};
+
+use crate::model::{
+    Address, AddressId, CityId, CityMeta, DateRange, IdMigration, PickupEvent, VersionedAddressId,
+};
+use crate::plugin::CityPlugin;
+use crate::ports::{
+    AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport, SearchResults,
+};
+
+/// Suggested counter name for provider calls.
+pub const REQUESTS_METRIC: &str = "tonneli_provider_requests_total";
+/// Suggested counter name for failed provider calls.
+pub const ERRORS_METRIC: &str = "tonneli_provider_errors_total";
+/// Suggested histogram name for the duration of provider calls, in seconds.
+pub const LATENCY_METRIC: &str = "tonneli_provider_latency_seconds";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Port a provider call went to, the `port` label.
+pub enum PortKind {
+    /// [`AddressPort`]: searches, towns and address ids.
+    Address,
+    /// [`SchedulePort`]: schedules.
+    Schedule,
+}
+
+impl fmt::Display for PortKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Address => "address",
+            Self::Schedule => "schedule",
+        })
+    }
+}
+
+#[derive(Debug)]
+/// One finished provider call.
+pub struct ProviderCall<'call> {
+    /// City whose provider was called.
+    pub city: &'call CityId,
+    /// Port that was called.
+    pub port: PortKind,
+    /// Time until the provider answered or failed.
+    pub duration: Duration,
+    /// Why the call failed, if it did.
+    pub error: Option<&'call PortError>,
+}
+
+/// Receives every provider call made through a [`metered`] plugin.
+///
+/// Called on the request path; implementations should only bump counters.
+pub trait MetricsRecorder: Send + Sync {
+    /// Record a finished call.
+    fn record(&self, call: &ProviderCall<'_>);
+}
+
+/// Shared recorders, so the caller keeps a handle to e.g. a [`MemoryMetrics`].
+impl<R: MetricsRecorder + ?Sized> MetricsRecorder for Arc<R> {
+    fn record(&self, call: &ProviderCall<'_>) {
+        (**self).record(call);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Totals of the calls to one port of one city.
+pub struct CallStats {
+    /// Calls made.
+    pub requests: u64,
+    /// Calls that failed.
+    pub errors: u64,
+    /// Summed duration of all calls.
+    pub total_latency: Duration,
+    /// Slowest call.
+    pub max_latency: Duration,
+}
+
+impl CallStats {
+    /// Average duration of a call, zero before the first one.
+    #[must_use]
+    pub fn mean_latency(&self) -> Duration {
+        u32::try_from(self.requests)
+            .ok()
+            .and_then(|requests| self.total_latency.checked_div(requests))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Default)]
+/// Recorder keeping [`CallStats`] per city and port in memory.
+pub struct MemoryMetrics {
+    stats: Mutex<BTreeMap<(CityId, PortKind), CallStats>>,
+}
+
+impl MemoryMetrics {
+    /// Totals so far, sorted by city id and port.
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<(CityId, PortKind, CallStats)> {
+        self.stats
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|((city, port), stats)| (city.clone(), *port, *stats))
+            .collect()
+    }
+}
+
+impl MetricsRecorder for MemoryMetrics {
+    fn record(&self, call: &ProviderCall<'_>) {
+        let mut stats = self.stats.lock().unwrap_or_else(PoisonError::into_inner);
+        let entry = stats.entry((call.city.clone(), call.port)).or_default();
+        entry.requests += 1;
+        entry.errors += u64::from(call.error.is_some());
+        entry.total_latency += call.duration;
+        entry.max_latency = entry.max_latency.max(call.duration);
+    }
+}
+
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default)]
+/// Recorder emitting every call through the `metrics` crate (feature `metrics`).
+///
+/// Counts [`REQUESTS_METRIC`] and [`ERRORS_METRIC`] and records [`LATENCY_METRIC`]
+/// in seconds, labelled with `city` and `port`. Install an exporter such as
+/// `metrics-exporter-prometheus` to collect them.
+pub struct MetricsFacade;
+
+#[cfg(feature = "metrics")]
+impl MetricsRecorder for MetricsFacade {
+    fn record(&self, call: &ProviderCall<'_>) {
+        let labels = [
+            ("city", call.city.0.clone()),
+            ("port", call.port.to_string()),
+        ];
+        counter!(REQUESTS_METRIC, &labels).increment(1);
+        if call.error.is_some() {
+            counter!(ERRORS_METRIC, &labels).increment(1);
+        }
+        histogram!(LATENCY_METRIC, &labels).record(call.duration.as_secs_f64());
+    }
+}
+
+/// Port decorator passing every call to a [`MetricsRecorder`].
+pub struct MeteredPort<P: ?Sized> {
+    inner: Arc<P>,
+    recorder: Arc<dyn MetricsRecorder>,
+}
+
+impl<P: ?Sized> MeteredPort<P> {
+    async fn measure<T, F>(&self, meta: &CityMeta, port: PortKind, call: F) -> Result<T, PortError>
+    where
+        F: Future<Output = Result<T, PortError>>,
+    {
+        let started = Instant::now();
+        let result = call.await;
+        self.recorder.record(&ProviderCall {
+            city: &meta.id,
+            port,
+            duration: started.elapsed(),
+            error: result.as_ref().err(),
+        });
+        result
+    }
+}
+
+/// Record the calls to both ports of `plugin` with `recorder`.
+#[must_use]
+pub fn metered(plugin: CityPlugin, recorder: Arc<dyn MetricsRecorder>) -> CityPlugin {
+    CityPlugin {
+        meta: plugin.meta,
+        address_port: Arc::new(MeteredPort {
+            inner: plugin.address_port,
+            recorder: Arc::clone(&recorder),
+        }),
+        schedule_port: Arc::new(MeteredPort {
+            inner: plugin.schedule_port,
+            recorder,
+        }),
+    }
+}
+
+#[async_trait]
+impl AddressPort for MeteredPort<dyn AddressPort> {
+    fn city(&self) -> &CityMeta {
+        self.inner.city()
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        let call = self.inner.search(query, limit);
+        self.measure(self.city(), PortKind::Address, call).await
+    }
+
+    async fn search_results(
+        &self,
+        query: &AddressSearch,
+        limit: usize,
+    ) -> Result<SearchResults, PortError> {
+        let call = self.inner.search_results(query, limit);
+        self.measure(self.city(), PortKind::Address, call).await
+    }
+
+    async fn towns(&self) -> Result<Vec<String>, PortError> {
+        let call = self.inner.towns();
+        self.measure(self.city(), PortKind::Address, call).await
+    }
+
+    async fn resolve(&self, address_id: &AddressId) -> Result<Address, PortError> {
+        let call = self.inner.resolve(address_id);
+        self.measure(self.city(), PortKind::Address, call).await
+    }
+
+    /// Probes are not counted, so monitoring does not show up in its own figures.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        self.inner.health_check().await
+    }
+
+    fn id_version(&self) -> u32 {
+        self.inner.id_version()
+    }
+
+    async fn migrate_id(&self, stored: &VersionedAddressId) -> Result<IdMigration, PortError> {
+        let call = self.inner.migrate_id(stored);
+        self.measure(self.city(), PortKind::Address, call).await
+    }
+}
+
+#[async_trait]
+impl SchedulePort for MeteredPort<dyn SchedulePort> {
+    fn city(&self) -> &CityMeta {
+        self.inner.city()
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        let call = self.inner.schedule(address_id, range);
+        self.measure(self.city(), PortKind::Schedule, call).await
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let call = self.inner.schedule_report(address_id, range);
+        self.measure(self.city(), PortKind::Schedule, call).await
+    }
+
+    fn supports_partial_fetch(&self) -> bool {
+        self.inner.supports_partial_fetch()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use futures::executor::block_on;
+    #[cfg(feature = "metrics")]
+    use {
+        metrics::with_local_recorder,
+        metrics_util::debugging::{DebugValue, DebuggingRecorder},
+    };
+
+    use super::*;
+
+    struct Flaky(CityMeta);
+
+    #[async_trait]
+    impl AddressPort for Flaky {
+        fn city(&self) -> &CityMeta {
+            &self.0
+        }
+
+        async fn search(&self, query: &AddressSearch, _: usize) -> Result<Vec<Address>, PortError> {
+            if query.street == "down" {
+                return Err(PortError::Timeout);
+            }
+            Ok(Vec::new())
+        }
+    }
+
+    #[async_trait]
+    impl SchedulePort for Flaky {
+        fn city(&self) -> &CityMeta {
+            &self.0
+        }
+
+        async fn schedule(
+            &self,
+            _: &AddressId,
+            _: DateRange,
+        ) -> Result<Vec<PickupEvent>, PortError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn counts_calls_and_errors_per_port() {
+        let port = Arc::new(Flaky(CityMeta::new("cologne", "Köln")));
+        let metrics = Arc::new(MemoryMetrics::default());
+        let plugin = metered(
+            CityPlugin {
+                meta: port.0.clone(),
+                address_port: Arc::<Flaky>::clone(&port),
+                schedule_port: port,
+            },
+            Arc::<MemoryMetrics>::clone(&metrics),
+        );
+        let day = NaiveDate::MIN;
+
+        block_on(async {
+            for street in ["Domkloster", "down", "Hohe Straße"] {
+                let _found = plugin
+                    .address_port
+                    .search(&AddressSearch::new(street, None::<String>), 5)
+                    .await;
+            }
+            let _events = plugin
+                .schedule_port
+                .schedule(
+                    &AddressId("1".to_owned()),
+                    DateRange {
+                        start: day,
+                        end: day,
+                    },
+                )
+                .await;
+        });
+
+        let counts = metrics
+            .snapshot()
+            .into_iter()
+            .map(|(city, kind, stats)| (city.0, kind.to_string(), stats.requests, stats.errors))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            [
+                ("cologne".to_owned(), "address".to_owned(), 3, 1),
+                ("cologne".to_owned(), "schedule".to_owned(), 1, 0),
+            ],
+            "calls and failures are counted per city and port"
+        );
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn facade_emits_labelled_metrics() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let city = CityId("cologne".to_owned());
+        with_local_recorder(&recorder, || {
+            MetricsFacade.record(&ProviderCall {
+                city: &city,
+                port: PortKind::Schedule,
+                duration: Duration::from_millis(250),
+                error: Some(&PortError::Timeout),
+            });
+        });
+
+        let mut emitted = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let labels = key
+                    .key()
+                    .labels()
+                    .map(|label| format!("{}={}", label.key(), label.value()))
+                    .collect::<Vec<_>>();
+                let count = match value {
+                    DebugValue::Counter(count) => count,
+                    DebugValue::Histogram(samples) => samples.len().try_into().unwrap_or(0),
+                    DebugValue::Gauge(_) => 0,
+                };
+                (key.key().name().to_owned(), labels, count)
+            })
+            .collect::<Vec<_>>();
+        emitted.sort();
+        let labels = vec!["city=cologne".to_owned(), "port=schedule".to_owned()];
+        assert_eq!(
+            emitted,
+            [
+                (ERRORS_METRIC.to_owned(), labels.clone(), 1),
+                (LATENCY_METRIC.to_owned(), labels.clone(), 1),
+                (REQUESTS_METRIC.to_owned(), labels, 1),
+            ],
+            "one request, one error and one latency sample per call"
+        );
+    }
+}
@@ -1,10 +1,13 @@
 //! Domain data structures for cities, addresses, and pickup schedules.
 
 use std::fmt;
+use std::sync::Arc;
 
-use chrono::NaiveDate;
+use chrono::{Datelike, Days, Months, NaiveDate};
 use serde::{Deserialize, Serialize};
 
+use crate::holidays::HolidayShift;
+
 /// Built-in cities supported by the application.
 pub enum Cities {
     /// Aachen, Germany.
@@ -15,7 +18,8 @@ pub enum Cities {
     Nuremberg,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 /// Identifier for a city known to tonneli.
 pub struct CityId(pub String);
 
@@ -36,6 +40,7 @@ impl From<Cities> for CityId {
     }
 }
 
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// Metadata describing a city and its human-friendly name.
 pub struct CityMeta {
@@ -43,29 +48,248 @@ pub struct CityMeta {
     pub id: CityId,
     /// Localized display name.
     pub name: String,
+    /// Federal state or region, e.g. `Nordrhein-Westfalen`, for grouping cities.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// ISO 3166-1 alpha-2 country code, e.g. `DE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    /// IANA time zone the pickup dates refer to, e.g. `Europe/Berlin`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Publisher of the schedule data, shown as attribution.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_name: Option<String>,
+    /// Where the publisher offers the data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+    /// Area the provider covers, for detecting the city from a position or postal code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub area: Option<CityArea>,
+    /// What the provider supports beyond the basic search and schedule.
+    #[serde(default)]
+    pub capabilities: Capabilities,
+}
+
+impl CityMeta {
+    /// City with only an id and a name; the other details are unknown.
+    #[must_use]
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: CityId(id.into()),
+            name: name.into(),
+            region: None,
+            country: None,
+            timezone: None,
+            source_name: None,
+            source_url: None,
+            area: None,
+            capabilities: Capabilities::default(),
+        }
+    }
+
+    /// Set the federal state or region.
+    #[must_use]
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    /// Set the country code and the time zone of the city.
+    #[must_use]
+    pub fn with_locale(mut self, country: impl Into<String>, timezone: impl Into<String>) -> Self {
+        self.country = Some(country.into());
+        self.timezone = Some(timezone.into());
+        self
+    }
+
+    /// Set who publishes the data and where.
+    #[must_use]
+    pub fn with_source(mut self, name: impl Into<String>, url: impl Into<String>) -> Self {
+        self.source_name = Some(name.into());
+        self.source_url = Some(url.into());
+        self
+    }
+
+    /// Set the area the provider covers, see [`GeoPort`](crate::geo::GeoPort).
+    #[must_use]
+    pub fn with_area(mut self, area: CityArea) -> Self {
+        self.area = Some(area);
+        self
+    }
+
+    /// Set what the provider supports, so frontends can adapt before a call fails.
+    #[must_use]
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Attribution line such as `Daten: AWB Köln (https://www.awbkoeln.de)`, without the prefix.
+    #[must_use]
+    pub fn attribution(&self) -> Option<String> {
+        match (&self.source_name, &self.source_url) {
+            (Some(name), Some(url)) => Some(format!("{name} ({url})")),
+            (Some(name), None) => Some(name.clone()),
+            (None, Some(url)) => Some(url.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "independent flags read better by name than as a bit set"
+)]
+/// Features a provider supports; everything is off by default.
+pub struct Capabilities {
+    /// Searches only find addresses when they include a house number.
+    pub house_number_required: bool,
+    /// Schedules can span several calendar years, not just the current one.
+    pub multi_year: bool,
+    /// The backend can be asked for selected fractions only.
+    pub fraction_filter: bool,
+    /// Bulky waste pickups can be booked through the provider.
+    pub bulky_waste_booking: bool,
+}
+
+/// Mean earth radius in kilometers, for great-circle distances.
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// WGS 84 position in decimal degrees.
+pub struct Coordinates {
+    /// Latitude, positive north of the equator.
+    pub latitude: f64,
+    /// Longitude, positive east of Greenwich.
+    pub longitude: f64,
 }
 
+impl Coordinates {
+    /// Position at `latitude` and `longitude`.
+    #[must_use]
+    pub const fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Great-circle distance to `other` in kilometers.
+    #[must_use]
+    pub fn distance_km(self, other: Self) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let half_lat = (lat2 - lat1) / 2.0;
+        let half_lon = (other.longitude - self.longitude).to_radians() / 2.0;
+        let chord = half_lat.sin().powi(2) + lat1.cos() * lat2.cos() * half_lon.sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * chord.sqrt().min(1.0).asin()
+    }
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Rough extent of a city: a circle around its center plus its postal codes.
+pub struct CityArea {
+    /// Center of the city, usually its town hall.
+    pub center: Coordinates,
+    /// Distance from the center still counted as the city, in kilometers.
+    pub radius_km: f64,
+    /// Prefixes of the postal codes within the city, e.g. `506` for Köln's `50667`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub postal_codes: Vec<String>,
+}
+
+impl CityArea {
+    /// Circle of `radius_km` around `center`, without postal codes.
+    #[must_use]
+    pub fn new(center: Coordinates, radius_km: f64) -> Self {
+        Self {
+            center,
+            radius_km,
+            postal_codes: Vec::new(),
+        }
+    }
+
+    /// Set the postal code prefixes of the city.
+    #[must_use]
+    pub fn with_postal_codes<I, S>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.postal_codes = prefixes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Whether `position` lies within the radius.
+    #[must_use]
+    pub fn contains(&self, position: Coordinates) -> bool {
+        self.center.distance_km(position) <= self.radius_km
+    }
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 /// Identifier for a concrete address.
 pub struct AddressId(pub String);
 
+/// Id encoding version assumed for ids persisted without an explicit version.
+pub const INITIAL_ADDRESS_ID_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Address identifier tagged with the provider's id encoding version, for persistence.
+pub struct VersionedAddressId {
+    /// Encoding version reported by the provider when the id was stored.
+    #[serde(default = "initial_address_id_version")]
+    pub version: u32,
+    /// The provider-specific identifier.
+    pub id: AddressId,
+}
+
+fn initial_address_id_version() -> u32 {
+    INITIAL_ADDRESS_ID_VERSION
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Outcome of upgrading a persisted address identifier.
+pub enum IdMigration {
+    /// The id already uses the current encoding.
+    Current(AddressId),
+    /// The id was rewritten to the current encoding.
+    Migrated(AddressId),
+    /// The id cannot be upgraded; the address has to be searched again.
+    NeedsResolution,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// Address returned from a provider search.
+///
+/// Text fields are shared strings, so cloning an address (or a whole result list)
+/// does not copy them and all house numbers of a street can share one street name.
 pub struct Address {
     /// Unique identifier used by a provider when requesting schedules.
     pub id: AddressId,
     /// City the address belongs to.
     pub city: CityId,
     /// Human-friendly label combining street and house number.
-    pub label: String,
+    pub label: Arc<str>,
     /// Street name.
-    pub street: String,
+    pub street: Arc<str>,
     /// House number including additions such as “A”.
-    pub house_number: String,
+    pub house_number: Arc<str>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-/// Waste fractions that can be collected.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Waste fractions that can be collected, plus other municipal services on a schedule.
+///
+/// Serialized as the variant name (`"BulkyWaste"`), which caches, exports and
+/// settings files rely on; variants must not be renamed.
 pub enum Fraction {
     /// Residual/gray bin.
     Residual,
@@ -79,11 +303,26 @@ pub enum Fraction {
     Glass,
     /// Metal scrap.
     Metal,
-    /// Provider-specific additional fraction.
-    Other(String),
+    /// Street cleaning; not a bin, but scheduled per address like one.
+    StreetCleaning,
+    /// Bulky waste (Sperrmüll), usually collected on request.
+    BulkyWaste,
+    /// Garden and green waste such as leaves and cuttings.
+    GardenWaste,
+    /// Hazardous waste, e.g. a mobile collection point for paint and batteries.
+    Hazardous,
+    /// Christmas trees, collected once a year.
+    ChristmasTree,
+    /// Used clothes and other textiles.
+    Textiles,
+    /// Electrical and electronic equipment.
+    Electronics,
+    /// Provider-specific additional fraction; providers reuse one name per fraction.
+    Other(Arc<str>),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 /// Scheduled pickup for a specific day.
 pub struct PickupEvent {
     /// Date of the pickup.
@@ -91,9 +330,80 @@ pub struct PickupEvent {
     /// Type of waste collected.
     pub fraction: Fraction,
     /// Optional provider note describing the pickup.
-    pub note: Option<String>,
+    pub note: Option<Arc<str>>,
+    /// Container the pickup applies to (e.g. “1100 l”) when a provider distinguishes them.
+    #[serde(default)]
+    pub container: Option<Arc<str>>,
+    /// Set when a local override added or moved the pickup, see [`crate::overrides`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correction: Option<Correction>,
+    /// Set when the pickup falls on a public holiday or later in its week, see
+    /// [`crate::holidays`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holiday: Option<HolidayShift>,
+    /// Extrapolated from the rhythm of earlier pickups rather than published by the
+    /// provider, see [`crate::predict`].
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub predicted: bool,
+}
+
+/// Serde helper leaving unset flags out of the output.
+#[expect(
+    clippy::trivially_copy_pass_by_ref,
+    reason = "serde passes fields by reference"
+)]
+const fn is_false(flag: &bool) -> bool {
+    !*flag
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// All pickups of one day, see [`crate::service::group_by_day`].
+pub struct PickupDay {
+    /// Day of the pickups.
+    pub date: NaiveDate,
+    /// Pickups on that day, at least one, in the provider's order.
+    pub events: Vec<PickupEvent>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// How a local override changed a pickup the provider published.
+pub enum Correction {
+    /// The provider does not list the pickup; the user added it.
+    Added,
+    /// The provider lists the pickup on another day.
+    Moved {
+        /// Day the provider published.
+        from: NaiveDate,
+    },
+}
+
+impl PickupEvent {
+    /// Identity of the pickup independent of notes; equal keys describe the same collection.
+    #[must_use]
+    pub fn key(&self) -> EventKey {
+        EventKey {
+            date: self.date,
+            fraction: self.fraction.clone(),
+            container: self.container.clone(),
+        }
+    }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Canonical identity of a pickup used to recognize the same event from several sources.
+pub struct EventKey {
+    /// Date of the pickup.
+    pub date: NaiveDate,
+    /// Type of waste collected.
+    pub fraction: Fraction,
+    /// Container the pickup applies to, if known.
+    pub container: Option<Arc<str>>,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 /// Inclusive start/end range for requested schedules.
 pub struct DateRange {
@@ -102,3 +412,126 @@ pub struct DateRange {
     /// End date (inclusive).
     pub end: NaiveDate,
 }
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+/// Reasons a [`DateRange`] is rejected.
+pub enum DateRangeError {
+    /// The range ends before it starts.
+    #[error("Date range ends ({end}) before it starts ({start})")]
+    Inverted {
+        /// Requested first day.
+        start: NaiveDate,
+        /// Requested last day.
+        end: NaiveDate,
+    },
+}
+
+impl DateRange {
+    /// Range from `start` to `end`, both included.
+    ///
+    /// # Errors
+    ///
+    /// [`DateRangeError::Inverted`] when `end` is before `start`.
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Result<Self, DateRangeError> {
+        Self { start, end }.validated()
+    }
+
+    /// `today` and the `days` days after it.
+    #[must_use]
+    pub fn next_days(today: NaiveDate, days: u32) -> Self {
+        Self {
+            start: today,
+            end: today
+                .checked_add_days(Days::new(days.into()))
+                .unwrap_or(NaiveDate::MAX),
+        }
+    }
+
+    /// The calendar month `today` falls in, from its first to its last day.
+    #[must_use]
+    pub fn this_month(today: NaiveDate) -> Self {
+        let start = today.with_day(1).unwrap_or(today);
+        let end = start
+            .checked_add_months(Months::new(1))
+            .and_then(|next| next.pred_opt())
+            .unwrap_or(NaiveDate::MAX);
+        Self { start, end }
+    }
+
+    /// `today` until New Year's Eve.
+    #[must_use]
+    pub fn rest_of_year(today: NaiveDate) -> Self {
+        Self {
+            start: today,
+            end: NaiveDate::from_ymd_opt(today.year(), 12, 31).unwrap_or(NaiveDate::MAX),
+        }
+    }
+
+    /// The range itself, when it does not end before it starts.
+    ///
+    /// Fields are public, so ranges built or deserialized directly are checked here
+    /// before they reach a provider.
+    ///
+    /// # Errors
+    ///
+    /// [`DateRangeError::Inverted`] when `end` is before `start`.
+    pub fn validated(self) -> Result<Self, DateRangeError> {
+        if self.end < self.start {
+            return Err(DateRangeError::Inverted {
+                start: self.start,
+                end: self.end,
+            });
+        }
+        Ok(self)
+    }
+
+    /// Whether `date` lies within the range, both ends included.
+    #[must_use]
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap_or_default()
+    }
+
+    #[test]
+    fn constructors_cover_the_expected_days() {
+        let today = day(2, 10);
+        let next = DateRange::next_days(today, 30);
+        assert_eq!((next.start, next.end), (today, day(3, 11)), "leap February");
+        let month = DateRange::this_month(today);
+        assert_eq!(
+            (month.start, month.end),
+            (day(2, 1), day(2, 29)),
+            "whole month"
+        );
+        let rest = DateRange::rest_of_year(today);
+        assert_eq!(
+            (rest.start, rest.end),
+            (today, day(12, 31)),
+            "until New Year's Eve"
+        );
+    }
+
+    #[test]
+    fn inverted_ranges_are_rejected() {
+        assert_eq!(
+            DateRange::new(day(3, 1), day(2, 1)).err(),
+            Some(DateRangeError::Inverted {
+                start: day(3, 1),
+                end: day(2, 1),
+            }),
+            "end before start"
+        );
+        assert!(
+            DateRange::new(day(3, 1), day(3, 1)).is_ok(),
+            "a single day is a valid range"
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Normalization of street names as users type them.
+//!
+//! Users write "Hauptstr. 5" or "Konrad - Adenauer - Str", providers store
+//! "Hauptstraße" and "Konrad-Adenauer-Straße". [`normalize_street`] spells out the
+//! common abbreviations before a query is sent, [`street_key`] additionally folds
+//! case, diacritics and hyphens for client-side comparisons.
+
+use crate::matching::fold;
+
+/// Abbreviated endings of a word and their full form, lowercase.
+const SUFFIXES: [(&str, &str); 2] = [("str", "straße"), ("pl", "platz")];
+
+/// Spell out abbreviations and tidy up spacing in a street name.
+///
+/// - `Str.`/`str.` at the end of a word becomes `Straße`/`straße`, e.g.
+///   "Hauptstr." → "Hauptstraße"; without the dot only in compounds ("Hauptstr")
+///   or after another word ("Aachener Str"), so a lone "Str" still finds "Strandweg".
+/// - `Pl.` becomes `Platz` the same way, `St.` before a name becomes `Sankt`.
+/// - Spaces around hyphens are removed and runs of whitespace collapsed.
+#[must_use]
+pub fn normalize_street(input: &str) -> String {
+    let joined = input
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(" -", "-")
+        .replace("- ", "-");
+
+    let mut words = Vec::new();
+    for (position, word) in joined.split(' ').enumerate() {
+        let parts = word.split('-').collect::<Vec<_>>();
+        let last = parts.len().saturating_sub(1);
+        let expanded = parts
+            .iter()
+            .enumerate()
+            .map(|(index, part)| {
+                let follows_word = position > 0 || index > 0;
+                let precedes_name = index < last;
+                expand(part, follows_word, precedes_name)
+            })
+            .collect::<Vec<_>>();
+        words.push(expanded.join("-"));
+    }
+    words.join(" ")
+}
+
+/// Key for comparing street names: normalized, folded and with hyphens as spaces.
+#[must_use]
+pub fn street_key(input: &str) -> String {
+    fold(&normalize_street(input))
+        .replace('-', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Expand one hyphen-separated part of a word.
+fn expand(part: &str, follows_word: bool, precedes_name: bool) -> String {
+    let (stem, dotted) = match part.strip_suffix('.') {
+        Some(stem) => (stem, true),
+        None => (part, false),
+    };
+    let lower = stem.to_lowercase();
+
+    if dotted && lower == "st" && precedes_name {
+        return with_case(stem, "sankt");
+    }
+    for (short, full) in SUFFIXES {
+        if !lower.ends_with(short) {
+            continue;
+        }
+        let compound = lower.len() > short.len();
+        if !(dotted || compound || follows_word) {
+            continue;
+        }
+        // Lowercasing keeps the byte length of these ASCII suffixes, so the split is exact.
+        let (prefix, abbreviation) = stem.split_at(stem.len() - short.len());
+        return format!("{prefix}{}", with_case(abbreviation, full));
+    }
+    part.to_owned()
+}
+
+/// `full` capitalized like the first letter of `abbreviation`.
+fn with_case(abbreviation: &str, full: &str) -> String {
+    let mut chars = full.chars();
+    match (abbreviation.chars().next(), chars.next()) {
+        (Some(first), Some(full_first)) if first.is_uppercase() => {
+            full_first.to_uppercase().chain(chars).collect()
+        }
+        _ => full.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_abbreviations() {
+        let cases = [
+            ("Hauptstr. 5", "Hauptstraße 5"),
+            ("hauptstr", "hauptstraße"),
+            ("Aachener Str", "Aachener Straße"),
+            ("Aachener  Str.", "Aachener Straße"),
+            ("Konrad - Adenauer - Str.", "Konrad-Adenauer-Straße"),
+            ("Marktpl.", "Marktplatz"),
+            ("Neumarkt Pl.", "Neumarkt Platz"),
+            ("St.-Apern-Str.", "Sankt-Apern-Straße"),
+            ("Str", "Str"),
+            ("Strandweg", "Strandweg"),
+            ("Am Markt", "Am Markt"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize_street(input), expected, "normalizing {input:?}");
+        }
+    }
+
+    #[test]
+    fn keys_ignore_spelling_variants() {
+        assert_eq!(
+            street_key("Konrad Adenauer Str."),
+            street_key("Konrad-Adenauer-Straße"),
+            "hyphens and spaces are interchangeable"
+        );
+        assert_eq!(
+            street_key("HAUPTSTR"),
+            street_key("Hauptstrasse"),
+            "case and ß spelling are folded"
+        );
+    }
+}
@@ -0,0 +1,355 @@
+//! Pluggable notification channels and the reminders due on them.
+//!
+//! Desktop, e-mail, webhook, MQTT or chat integrations implement
+//! [`NotifierPort`] and only deliver; deciding what is due, see [`due_reminders`],
+//! and sending to every configured channel, see [`Notifiers`], happens once here.
+
+use std::error::Error as StdError;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+use futures::future::join_all;
+use serde::Serialize;
+
+use crate::acknowledge::ReminderStates;
+use crate::diff::{ScheduleChange, diff_schedules};
+use crate::household::HouseholdProfile;
+use crate::model::{Address, DateRange, PickupEvent};
+use crate::reminder::ReminderSettings;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+/// Message handed to every notification channel; serialized, it is the payload of webhooks.
+pub enum Notification {
+    /// Pickups of one address the household should prepare for.
+    Reminder {
+        /// Address the pickups belong to.
+        address: Address,
+        /// Due pickups, sorted by date.
+        events: Vec<PickupEvent>,
+    },
+    /// Overview of the upcoming pickups of one address.
+    Digest {
+        /// Address the pickups belong to.
+        address: Address,
+        /// Period the digest covers.
+        range: DateRange,
+        /// Pickups within `range`, sorted by date.
+        events: Vec<PickupEvent>,
+    },
+    /// The provider changed the schedule of an address.
+    ScheduleChanged {
+        /// Address whose schedule changed.
+        address: Address,
+        /// Added, removed and moved pickups, sorted by date.
+        changes: Vec<ScheduleChange>,
+    },
+}
+
+impl Notification {
+    /// Changes between two schedules of `address`, or `None` if nothing changed.
+    ///
+    /// See [`diff_schedules`] for how pickups are compared and moves detected.
+    #[must_use]
+    pub fn schedule_changed(
+        address: &Address,
+        old: &[PickupEvent],
+        new: &[PickupEvent],
+    ) -> Option<Self> {
+        let changes = diff_schedules(old, new);
+        (!changes.is_empty()).then(|| Self::ScheduleChanged {
+            address: address.clone(),
+            changes,
+        })
+    }
+
+    /// Address the notification is about.
+    #[must_use]
+    pub fn address(&self) -> &Address {
+        match self {
+            Self::Reminder { address, .. }
+            | Self::Digest { address, .. }
+            | Self::ScheduleChanged { address, .. } => address,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+/// Errors reported by notification channels.
+pub enum NotifyError {
+    /// The channel lacks settings it needs, e.g. an SMTP host.
+    #[error("Notification channel is not configured: {0}")]
+    NotConfigured(String),
+    /// The channel could not deliver the notification.
+    #[error("Delivering the notification failed: {0}")]
+    Delivery(#[source] Box<dyn StdError + Send + Sync>),
+}
+
+#[async_trait]
+/// Channel that delivers notifications, e.g. as desktop notification or webhook call.
+pub trait NotifierPort: Send + Sync {
+    /// Short channel name for logs and delivery reports, e.g. `"webhook"`.
+    fn name(&self) -> &str;
+
+    /// Deliver one notification.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`NotifyError`] when the channel is not set up or delivery fails.
+    async fn send(&self, notification: &Notification) -> Result<(), NotifyError>;
+}
+
+#[derive(Debug)]
+/// Outcome of sending a notification to one channel.
+pub struct Delivery {
+    /// Name of the channel, see [`NotifierPort::name`].
+    pub channel: String,
+    /// Whether the channel accepted the notification.
+    pub outcome: Result<(), NotifyError>,
+}
+
+#[derive(Clone, Default)]
+/// Configured combination of channels every notification is sent to.
+pub struct Notifiers {
+    channels: Vec<Arc<dyn NotifierPort>>,
+}
+
+impl Notifiers {
+    /// No channels; notifications go nowhere.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also send to `channel`.
+    #[must_use]
+    pub fn with(mut self, channel: Arc<dyn NotifierPort>) -> Self {
+        self.channels.push(channel);
+        self
+    }
+
+    /// Whether no channel is configured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    /// Send `notification` to all channels concurrently.
+    ///
+    /// A failing channel never keeps the others from delivering; the result has
+    /// one entry per channel, in the order they were added.
+    pub async fn dispatch(&self, notification: &Notification) -> Vec<Delivery> {
+        join_all(self.channels.iter().map(|channel| async move {
+            Delivery {
+                channel: channel.name().to_owned(),
+                outcome: channel.send(notification).await,
+            }
+        }))
+        .await
+    }
+}
+
+/// Pickups a reminder is due for at `now`, the local time at `address`, see
+/// [`Clock::now_in`](crate::clock::Clock::now_in).
+///
+/// A reminder becomes due `lead_time_hours` before the pickup day starts and stays
+/// due until the day is over. Nothing is due while reminders are off, during quiet
+/// hours, for switched-off fractions, for bins the household does not put out itself
+/// or for pickups at `address` the user acknowledged or snoozed.
+#[must_use]
+pub fn due_reminders(
+    settings: &ReminderSettings,
+    household: &HouseholdProfile,
+    states: &ReminderStates,
+    address: &Address,
+    events: &[PickupEvent],
+    now: NaiveDateTime,
+) -> Vec<PickupEvent> {
+    if !settings.enabled
+        || settings
+            .quiet_hours
+            .is_some_and(|quiet| quiet.contains(now.time()))
+    {
+        return Vec::new();
+    }
+    let lead_time = Duration::hours(settings.lead_time_hours.into());
+    events
+        .iter()
+        .filter(|event| {
+            settings.is_enabled_for(&event.fraction)
+                && household.puts_out(&event.fraction)
+                && !states.silences(address, event, now)
+        })
+        .filter(|event| {
+            let day_start = event.date.and_time(NaiveTime::MIN);
+            day_start - lead_time <= now && now < day_start + Duration::days(1)
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use chrono::NaiveDate;
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::acknowledge::ReminderState;
+    use crate::household::BinOwnership;
+    use crate::model::{AddressId, CityId, Fraction};
+    use crate::reminder::QuietHours;
+    use crate::storage::MemoryStorage;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).expect("valid test date")
+    }
+
+    fn at(day_of_month: u32, hour: u32) -> NaiveDateTime {
+        day(day_of_month)
+            .and_hms_opt(hour, 0, 0)
+            .expect("valid test time")
+    }
+
+    fn event(date: NaiveDate, fraction: Fraction) -> PickupEvent {
+        PickupEvent {
+            date,
+            fraction,
+            note: None,
+            container: None,
+            correction: None,
+            holiday: None,
+            predicted: false,
+        }
+    }
+
+    fn address() -> Address {
+        Address {
+            id: AddressId("1".to_owned()),
+            city: CityId("cologne".to_owned()),
+            label: "Domkloster 4".into(),
+            street: "Domkloster".into(),
+            house_number: "4".into(),
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl NotifierPort for Recorder {
+        fn name(&self) -> &'static str {
+            "recorder"
+        }
+
+        async fn send(&self, notification: &Notification) -> Result<(), NotifyError> {
+            self.sent
+                .lock()
+                .expect("recorder lock is never poisoned")
+                .push(notification.clone());
+            Ok(())
+        }
+    }
+
+    struct Broken;
+
+    #[async_trait]
+    impl NotifierPort for Broken {
+        fn name(&self) -> &'static str {
+            "broken"
+        }
+
+        async fn send(&self, _notification: &Notification) -> Result<(), NotifyError> {
+            Err(NotifyError::NotConfigured("no host".to_owned()))
+        }
+    }
+
+    #[test]
+    fn dispatches_to_every_channel() {
+        let recorder = Arc::new(Recorder::default());
+        let notifiers = Notifiers::new()
+            .with(Arc::new(Broken))
+            .with(Arc::<Recorder>::clone(&recorder));
+        let notification = Notification::Reminder {
+            address: address(),
+            events: vec![event(day(11), Fraction::Paper)],
+        };
+
+        let deliveries = block_on(notifiers.dispatch(&notification));
+        let outcomes = deliveries
+            .iter()
+            .map(|delivery| (delivery.channel.as_str(), delivery.outcome.is_ok()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            [("broken", false), ("recorder", true)],
+            "one failing channel does not stop the others"
+        );
+        let sent = recorder
+            .sent
+            .lock()
+            .expect("recorder lock is never poisoned")
+            .iter()
+            .map(|sent| sent.address().id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sent,
+            [notification.address().id.clone()],
+            "the working channel received the notification"
+        );
+    }
+
+    #[test]
+    fn reminders_are_due_from_the_lead_time_until_the_day_ends() {
+        let settings = ReminderSettings {
+            enabled: true,
+            lead_time_hours: 6,
+            quiet_hours: Some(QuietHours {
+                start: NaiveTime::from_hms_opt(22, 0, 0).expect("valid test time"),
+                end: NaiveTime::from_hms_opt(7, 0, 0).expect("valid test time"),
+            }),
+            ..ReminderSettings::default()
+        };
+        let household = HouseholdProfile {
+            paper: BinOwnership::Shared,
+            ..HouseholdProfile::default()
+        };
+        let events = [
+            event(day(11), Fraction::Residual),
+            event(day(11), Fraction::Paper),
+            event(day(12), Fraction::Residual),
+        ];
+        let address = address();
+        let mut states = ReminderStates::default();
+        block_on(states.set(
+            &MemoryStorage::default(),
+            &address,
+            &event(day(12), Fraction::Residual),
+            Some(ReminderState::Snoozed { until: at(11, 21) }),
+        ))
+        .expect("memory storage accepts states");
+        let due = |now| {
+            due_reminders(&settings, &household, &states, &address, &events, now)
+                .into_iter()
+                .map(|event| event.date)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(due(at(10, 17)).is_empty(), "too early");
+        assert_eq!(due(at(10, 18)), [day(11)], "shared bins are left out");
+        assert!(
+            due(at(10, 23)).is_empty(),
+            "quiet hours hold reminders back"
+        );
+        assert_eq!(due(at(11, 20)), [day(11)], "snoozed pickups are held back");
+        assert_eq!(
+            due(at(11, 21)),
+            [day(11), day(12)],
+            "due until the day ends"
+        );
+    }
+}
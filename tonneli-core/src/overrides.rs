@@ -0,0 +1,296 @@
+//! User-maintained corrections of provider schedules.
+//!
+//! Providers occasionally publish wrong dates. Overrides add, remove or move
+//! single pickups of one address; the service applies them on top of the
+//! provider's events and marks what it changed with a [`Correction`].
+
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{AddressId, CityId, Correction, DateRange, Fraction, PickupEvent};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+/// Change to one pickup.
+pub enum OverrideAction {
+    /// Add a pickup the provider does not list.
+    Add {
+        /// Day of the pickup.
+        date: NaiveDate,
+        /// Collected fraction.
+        fraction: Fraction,
+        /// Optional note shown with the pickup.
+        #[serde(default)]
+        note: Option<String>,
+    },
+    /// Drop a pickup the provider lists.
+    Remove {
+        /// Day the provider lists.
+        date: NaiveDate,
+        /// Collected fraction.
+        fraction: Fraction,
+    },
+    /// Move a pickup to another day.
+    Move {
+        /// Day the provider lists.
+        date: NaiveDate,
+        /// Collected fraction.
+        fraction: Fraction,
+        /// Actual day of the pickup.
+        to: NaiveDate,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Override for one address.
+pub struct EventOverride {
+    /// City of the address.
+    pub city: CityId,
+    /// Provider id of the address.
+    pub address: AddressId,
+    /// What to change.
+    #[serde(flatten)]
+    pub action: OverrideAction,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+/// All overrides, in the order they are applied.
+pub struct ScheduleOverrides {
+    /// Overrides of all addresses, `[[event]]` entries in the overrides file.
+    #[serde(rename = "event")]
+    pub events: Vec<EventOverride>,
+}
+
+impl ScheduleOverrides {
+    /// Whether there is nothing to apply.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Apply the overrides of one address to `events` fetched for `range`.
+    ///
+    /// Moves of pickups that fall outside `range` are still applied, from the override
+    /// alone; moves and removals of pickups the provider no longer lists within `range`
+    /// are skipped, as the provider has presumably fixed its data. Events stay sorted by date.
+    pub fn apply(
+        &self,
+        city: &CityId,
+        address: &AddressId,
+        range: DateRange,
+        events: &mut Vec<PickupEvent>,
+    ) {
+        let mut changed = false;
+        for entry in &self.events {
+            if entry.city != *city || entry.address != *address {
+                continue;
+            }
+            changed = true;
+            match &entry.action {
+                OverrideAction::Add {
+                    date,
+                    fraction,
+                    note,
+                } => {
+                    let listed = events
+                        .iter()
+                        .any(|event| event.date == *date && event.fraction == *fraction);
+                    if range.contains(*date) && !listed {
+                        events.push(PickupEvent {
+                            date: *date,
+                            fraction: fraction.clone(),
+                            note: note.as_deref().map(Arc::from),
+                            container: None,
+                            correction: Some(Correction::Added),
+                            holiday: None,
+                            predicted: false,
+                        });
+                    }
+                }
+                OverrideAction::Remove { date, fraction } => {
+                    take(events, *date, fraction);
+                }
+                OverrideAction::Move { date, fraction, to } => {
+                    let original = take(events, *date, fraction);
+                    if original.is_none() && range.contains(*date) {
+                        continue;
+                    }
+                    if range.contains(*to) {
+                        let original = original.unwrap_or_else(|| PickupEvent {
+                            date: *date,
+                            fraction: fraction.clone(),
+                            note: None,
+                            container: None,
+                            correction: None,
+                            holiday: None,
+                            predicted: false,
+                        });
+                        events.push(PickupEvent {
+                            date: *to,
+                            correction: Some(Correction::Moved { from: *date }),
+                            ..original
+                        });
+                    }
+                }
+            }
+        }
+        if changed {
+            events.sort_by_key(|event| event.date);
+        }
+    }
+}
+
+/// Remove the events of `fraction` on `date`, returning the first of them.
+fn take(
+    events: &mut Vec<PickupEvent>,
+    date: NaiveDate,
+    fraction: &Fraction,
+) -> Option<PickupEvent> {
+    let mut first = None;
+    events.retain(|event| {
+        let matches = event.date == date && event.fraction == *fraction;
+        if matches && first.is_none() {
+            first = Some(event.clone());
+        }
+        !matches
+    });
+    first
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).expect("valid test date")
+    }
+
+    fn event(date: NaiveDate, fraction: Fraction) -> PickupEvent {
+        PickupEvent {
+            date,
+            fraction,
+            note: Some("Provider".into()),
+            container: None,
+            correction: None,
+            holiday: None,
+            predicted: false,
+        }
+    }
+
+    fn entry(address: &str, action: OverrideAction) -> EventOverride {
+        EventOverride {
+            city: CityId("cologne".to_owned()),
+            address: AddressId(address.to_owned()),
+            action,
+        }
+    }
+
+    #[test]
+    fn adds_removes_and_moves_pickups() {
+        let overrides = ScheduleOverrides {
+            events: vec![
+                entry(
+                    "1",
+                    OverrideAction::Move {
+                        date: day(10),
+                        fraction: Fraction::Paper,
+                        to: day(12),
+                    },
+                ),
+                entry(
+                    "1",
+                    OverrideAction::Remove {
+                        date: day(11),
+                        fraction: Fraction::Organic,
+                    },
+                ),
+                entry(
+                    "1",
+                    OverrideAction::Add {
+                        date: day(14),
+                        fraction: Fraction::Glass,
+                        note: None,
+                    },
+                ),
+                entry(
+                    "2",
+                    OverrideAction::Remove {
+                        date: day(10),
+                        fraction: Fraction::Residual,
+                    },
+                ),
+            ],
+        };
+        let mut events = vec![
+            event(day(10), Fraction::Paper),
+            event(day(10), Fraction::Residual),
+            event(day(11), Fraction::Organic),
+        ];
+        let range = DateRange {
+            start: day(1),
+            end: day(31),
+        };
+
+        overrides.apply(
+            &CityId("cologne".to_owned()),
+            &AddressId("1".to_owned()),
+            range,
+            &mut events,
+        );
+        let summary = events
+            .iter()
+            .map(|event| (event.date, event.fraction.clone(), event.correction))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                (day(10), Fraction::Residual, None),
+                (
+                    day(12),
+                    Fraction::Paper,
+                    Some(Correction::Moved { from: day(10) })
+                ),
+                (day(14), Fraction::Glass, Some(Correction::Added)),
+            ],
+            "overrides of other addresses are ignored"
+        );
+        assert_eq!(
+            events.get(1).and_then(|event| event.note.as_deref()),
+            Some("Provider"),
+            "moved pickups keep their note"
+        );
+    }
+
+    #[test]
+    fn skips_moves_the_provider_already_fixed() {
+        let overrides = ScheduleOverrides {
+            events: vec![entry(
+                "1",
+                OverrideAction::Move {
+                    date: day(10),
+                    fraction: Fraction::Paper,
+                    to: day(12),
+                },
+            )],
+        };
+        let mut events = vec![event(day(12), Fraction::Paper)];
+        overrides.apply(
+            &CityId("cologne".to_owned()),
+            &AddressId("1".to_owned()),
+            DateRange {
+                start: day(1),
+                end: day(31),
+            },
+            &mut events,
+        );
+        assert_eq!(events.len(), 1, "no second pickup appears");
+        assert_eq!(
+            events.first().and_then(|event| event.correction),
+            None,
+            "the provider's pickup is left alone"
+        );
+    }
+}
@@ -1,10 +1,12 @@
 //! Registry for all city plugins and their ports.
 
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::sync::Arc;
 
 use crate::model::{CityId, CityMeta};
 use crate::ports::{AddressPort, PortError, SchedulePort};
+use crate::sources::{SourcePolicy, combine};
 
 /// Collection of ports implementing a provider for a single city.
 pub struct CityPlugin {
@@ -16,6 +18,28 @@ pub struct CityPlugin {
     pub schedule_port: Arc<dyn SchedulePort>,
 }
 
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+/// Reasons a set of plugins cannot form a [`PluginRegistry`].
+pub enum RegistryError {
+    /// A plugin has a blank city id.
+    #[error("A provider has an empty city id")]
+    EmptyId,
+    /// A plugin has a blank display name.
+    #[error("City {0:?} has an empty name")]
+    EmptyName(CityId),
+    /// Two plugins use the same city id.
+    #[error("City {0:?} is provided more than once")]
+    DuplicateId(CityId),
+    /// Two city ids only differ in case or surrounding whitespace.
+    #[error("Cities {first:?} and {second:?} only differ in spelling")]
+    ConflictingAlias {
+        /// Id of the plugin registered first.
+        first: CityId,
+        /// Id of the conflicting plugin.
+        second: CityId,
+    },
+}
+
 /// Registry that resolves plugins by city identifier.
 pub struct PluginRegistry {
     plugins: HashMap<CityId, CityPlugin>,
@@ -23,6 +47,9 @@ pub struct PluginRegistry {
 
 impl PluginRegistry {
     /// Build a registry from the provided plugin list.
+    ///
+    /// A later plugin silently replaces an earlier one with the same id; use
+    /// [`PluginRegistry::try_new`] to reject such lists instead.
     #[must_use]
     pub fn new(plugins: Vec<CityPlugin>) -> Self {
         let plugins_map = plugins
@@ -34,6 +61,71 @@ impl PluginRegistry {
         }
     }
 
+    /// Build a registry, rejecting plugins that would shadow or be confused with each other.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RegistryError`] for the first plugin with empty metadata, an id that
+    /// is already registered, or an id that differs from a registered one only in case
+    /// or surrounding whitespace.
+    pub fn try_new(plugins: Vec<CityPlugin>) -> Result<Self, RegistryError> {
+        Self::try_with_policies(plugins, &HashMap::new())
+    }
+
+    /// Build a registry where the cities in `policies` may have several plugins.
+    ///
+    /// Plugins sharing such a city id are combined according to its [`SourcePolicy`],
+    /// see [`combine`]; for all other cities this behaves like [`PluginRegistry::try_new`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`PluginRegistry::try_new`].
+    pub fn try_with_policies(
+        plugins: Vec<CityPlugin>,
+        policies: &HashMap<CityId, SourcePolicy>,
+    ) -> Result<Self, RegistryError> {
+        let mut aliases = HashMap::<String, CityId>::new();
+        let mut groups = HashMap::<CityId, Vec<CityPlugin>>::with_capacity(plugins.len());
+
+        for plugin in plugins {
+            let id = &plugin.meta.id;
+            let alias = id.0.trim().to_lowercase();
+            if alias.is_empty() {
+                return Err(RegistryError::EmptyId);
+            }
+            if plugin.meta.name.trim().is_empty() {
+                return Err(RegistryError::EmptyName(id.clone()));
+            }
+            match aliases.entry(alias) {
+                Entry::Occupied(first) if first.get() == id && policies.contains_key(id) => {}
+                Entry::Occupied(first) if first.get() == id => {
+                    return Err(RegistryError::DuplicateId(id.clone()));
+                }
+                Entry::Occupied(first) => {
+                    return Err(RegistryError::ConflictingAlias {
+                        first: first.get().clone(),
+                        second: id.clone(),
+                    });
+                }
+                Entry::Vacant(slot) => {
+                    slot.insert(id.clone());
+                }
+            }
+            groups.entry(id.clone()).or_default().push(plugin);
+        }
+
+        let plugins_map = groups
+            .into_iter()
+            .map(|(id, group)| {
+                let policy = policies.get(&id).copied().unwrap_or_default();
+                Ok((id, combine(policy, group)?))
+            })
+            .collect::<Result<_, RegistryError>>()?;
+        Ok(Self {
+            plugins: plugins_map,
+        })
+    }
+
     /// Return metadata for all registered cities.
     #[must_use]
     pub fn cities(&self) -> Vec<CityMeta> {
@@ -48,6 +140,11 @@ impl PluginRegistry {
         self.plugins.values().map(|plugin| &plugin.meta)
     }
 
+    /// Iterator over all registered plugins.
+    pub fn plugins(&self) -> impl Iterator<Item = &CityPlugin> {
+        self.plugins.values()
+    }
+
     /// Look up a plugin for the given city.
     ///
     /// # Errors
@@ -57,3 +154,121 @@ impl PluginRegistry {
         self.plugins.get(city).ok_or(PortError::UnsupportedCity)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::model::{Address, AddressId, DateRange, PickupEvent};
+    use crate::ports::AddressSearch;
+
+    struct NoopPort(CityMeta);
+
+    #[async_trait]
+    impl AddressPort for NoopPort {
+        fn city(&self) -> &CityMeta {
+            &self.0
+        }
+
+        async fn search(&self, _: &AddressSearch, _: usize) -> Result<Vec<Address>, PortError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[async_trait]
+    impl SchedulePort for NoopPort {
+        fn city(&self) -> &CityMeta {
+            &self.0
+        }
+
+        async fn schedule(
+            &self,
+            _: &AddressId,
+            _: DateRange,
+        ) -> Result<Vec<PickupEvent>, PortError> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn plugin(id: &str, name: &str) -> CityPlugin {
+        let meta = CityMeta::new(id, name);
+        CityPlugin {
+            meta: meta.clone(),
+            address_port: Arc::new(NoopPort(meta.clone())),
+            schedule_port: Arc::new(NoopPort(meta)),
+        }
+    }
+
+    fn error(plugins: Vec<CityPlugin>) -> Option<RegistryError> {
+        PluginRegistry::try_new(plugins).err()
+    }
+
+    #[test]
+    fn accepts_distinct_cities() {
+        let registry =
+            PluginRegistry::try_new(vec![plugin("aachen", "Aachen"), plugin("cologne", "Köln")])
+                .expect("distinct cities must register");
+        assert_eq!(registry.cities().len(), 2, "both cities are registered");
+    }
+
+    #[test]
+    fn rejects_shadowing_and_empty_metadata() {
+        let cologne = || CityId("cologne".to_owned());
+        let cases = [
+            (
+                vec![plugin("cologne", "Köln"), plugin("cologne", "Cologne")],
+                RegistryError::DuplicateId(cologne()),
+            ),
+            (
+                vec![plugin("cologne", "Köln"), plugin(" Cologne", "Köln")],
+                RegistryError::ConflictingAlias {
+                    first: cologne(),
+                    second: CityId(" Cologne".to_owned()),
+                },
+            ),
+            (vec![plugin("  ", "Nowhere")], RegistryError::EmptyId),
+            (
+                vec![plugin("cologne", "")],
+                RegistryError::EmptyName(cologne()),
+            ),
+        ];
+        for (plugins, expected) in cases {
+            assert_eq!(
+                error(plugins),
+                Some(expected.clone()),
+                "expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn policies_allow_several_plugins_per_city() {
+        let policies = HashMap::from([(CityId("cologne".to_owned()), SourcePolicy::Merge)]);
+        let registry = PluginRegistry::try_with_policies(
+            vec![
+                plugin("cologne", "Köln"),
+                plugin("aachen", "Aachen"),
+                plugin("cologne", "Köln (Papier)"),
+            ],
+            &policies,
+        )
+        .expect("a city with a policy may have several plugins");
+        let mut names = registry
+            .cities()
+            .into_iter()
+            .map(|meta| meta.name)
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            ["Aachen", "Köln"],
+            "the combined city keeps its first plugin's name"
+        );
+        assert_eq!(
+            error(vec![plugin("aachen", "Aachen"), plugin("aachen", "Aachen")]),
+            Some(RegistryError::DuplicateId(CityId("aachen".to_owned()))),
+            "cities without a policy still reject duplicates"
+        );
+    }
+}
@@ -1,23 +1,48 @@
 //! Traits describing provider capabilities and shared helper types.
 
+use std::error::Error as StdError;
+use std::time::Duration;
+
 use async_trait::async_trait;
 use chrono::ParseError as ChronoParseError;
-use reqwest::Error as ReqwestError;
+use reqwest::StatusCode;
+use tonneli_http::HttpError;
 
-use crate::model::{Address, AddressId, CityMeta, DateRange, PickupEvent};
+use crate::matching::fold;
+use crate::model::{
+    Address, AddressId, CityId, CityMeta, DateRange, DateRangeError, INITIAL_ADDRESS_ID_VERSION,
+    IdMigration, PickupEvent, VersionedAddressId,
+};
+use crate::normalize::normalize_street;
+use crate::plugin::RegistryError;
 
 #[derive(thiserror::Error, Debug)]
 /// Errors that can occur while talking to provider backends.
 pub enum PortError {
     /// Network layer failed.
     #[error("Network error: {0}")]
-    Network(#[from] ReqwestError),
+    Network(#[from] NetworkError),
+    /// The provider did not answer within the configured timeouts.
+    #[error("Provider did not respond in time")]
+    Timeout,
     /// Failed to parse a date from the provider response.
     #[error("Parse error: {0}")]
     Parse(#[from] ChronoParseError),
     /// Requested address could not be found.
     #[error("Address not found")]
-    AddressNotFound,
+    AddressNotFound {
+        /// Similar street names the provider does know, best match first.
+        suggestions: Vec<String>,
+        /// HTTP status the provider answered with, usually 404; `None` when it
+        /// answered normally but without the address.
+        status: Option<u16>,
+    },
+    /// The city is a county; the search has to name one of its member towns.
+    #[error("Search needs one of the county's towns")]
+    TownRequired {
+        /// Member towns of the county, see [`AddressPort::towns`].
+        towns: Vec<String>,
+    },
     /// The city has no registered plugin.
     #[error("Unsupported city")]
     UnsupportedCity,
@@ -27,9 +52,218 @@ pub enum PortError {
     /// Provider returned an unknown waste fraction.
     #[error("Unknown fraction: {0}")]
     UnknownFraction(String),
+    /// The provider throttled us; retry after the given delay if known.
+    #[error("Rate limited by provider")]
+    RateLimited {
+        /// Delay requested via `Retry-After`, if any.
+        retry_after: Option<Duration>,
+        /// HTTP status of the throttling answer, 429 or 503; `None` when the
+        /// provider signalled it otherwise.
+        status: Option<u16>,
+    },
+    /// Provider answered with an unexpected HTTP status.
+    #[error("Unexpected HTTP status {0}")]
+    HttpStatus(u16),
+    /// Fetching from the provider exceeded the configured total duration.
+    #[error("Provider request took longer than {limit:?}")]
+    DeadlineExceeded {
+        /// Configured maximum duration.
+        limit: Duration,
+    },
+    /// Provider response exceeded the configured size limit.
+    #[error("Response larger than {limit} bytes")]
+    ResponseTooLarge {
+        /// Configured maximum body size in bytes.
+        limit: usize,
+    },
+    /// The provider is down for maintenance or otherwise not serving data.
+    #[error("Provider unavailable: {0}")]
+    ProviderUnavailable(String),
+    /// Provider response could not be decoded.
+    #[error("Invalid provider response: {0}")]
+    Decode(String),
     /// Internal provider error.
     #[error("Internal error: {0}")]
     Internal(String),
+    /// The requested date range is malformed, e.g. ends before it starts.
+    #[error("Invalid date range: {0}")]
+    InvalidRange(#[from] DateRangeError),
+    /// The registered providers conflict with each other.
+    #[error("Invalid provider setup: {0}")]
+    Registry(#[from] RegistryError),
+    /// Error of the provider of `city`, as returned by the service.
+    ///
+    /// Match on [`PortError::kind`] to look past it; the accessors below already do.
+    #[error("{}: {error}", city.0)]
+    Provider {
+        /// City whose provider failed.
+        city: CityId,
+        /// What went wrong.
+        error: Box<PortError>,
+    },
+}
+
+impl PortError {
+    /// Attach the city whose provider failed; errors that name one already keep it.
+    #[must_use]
+    pub fn with_provider(self, city: &CityId) -> Self {
+        match self {
+            Self::Provider { .. } => self,
+            error => Self::Provider {
+                city: city.clone(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// City whose provider failed, when the error came through the service.
+    #[must_use]
+    pub fn provider(&self) -> Option<&CityId> {
+        match self {
+            Self::Provider { city, .. } => Some(city),
+            _ => None,
+        }
+    }
+
+    /// The error itself, without the [`PortError::Provider`] around it.
+    #[must_use]
+    pub fn kind(&self) -> &Self {
+        match self {
+            Self::Provider { error, .. } => error.kind(),
+            _ => self,
+        }
+    }
+
+    /// Delay a caller should wait before asking the provider again.
+    #[must_use]
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self.kind() {
+            Self::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// HTTP status the provider answered with, when the error came from one,
+    /// e.g. 404 for a [`PortError::AddressNotFound`] or 429 for a
+    /// [`PortError::RateLimited`] the HTTP layer reported.
+    #[must_use]
+    pub fn status(&self) -> Option<u16> {
+        match self.kind() {
+            Self::HttpStatus(status) => Some(*status),
+            Self::AddressNotFound { status, .. } | Self::RateLimited { status, .. } => *status,
+            _ => None,
+        }
+    }
+
+    /// Whether asking the provider again later might succeed, e.g. for retry or
+    /// circuit-breaker middleware or a "try again" button.
+    ///
+    /// Throttling, timeouts, maintenance, connection failures and 5xx answers are
+    /// transient; unknown addresses, bad ids and undecodable responses are not.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Provider { error, .. } => error.is_retryable(),
+            Self::Timeout | Self::RateLimited { .. } | Self::ProviderUnavailable(_) => true,
+            Self::Network(err) => err.is_retryable(),
+            Self::HttpStatus(status) => (500..600).contains(status),
+            Self::Parse(_)
+            | Self::AddressNotFound { .. }
+            | Self::TownRequired { .. }
+            | Self::UnsupportedCity
+            | Self::InvalidAddressId
+            | Self::InvalidRange(_)
+            | Self::UnknownFraction(_)
+            | Self::DeadlineExceeded { .. }
+            | Self::ResponseTooLarge { .. }
+            | Self::Decode(_)
+            | Self::Internal(_)
+            | Self::Registry(_) => false,
+        }
+    }
+
+    /// Error of a lookup keyed by the address itself, e.g. `/termine/{id}`, where a
+    /// 404 means the provider does not know the address.
+    ///
+    /// Other requests keep the 404 as [`PortError::HttpStatus`]; a moved endpoint
+    /// is not an unknown address.
+    #[must_use]
+    pub fn from_address_lookup(err: HttpError) -> Self {
+        match err {
+            HttpError::Status(StatusCode::NOT_FOUND) => Self::AddressNotFound {
+                suggestions: Vec::new(),
+                status: Some(StatusCode::NOT_FOUND.as_u16()),
+            },
+            other => other.into(),
+        }
+    }
+
+    /// "Did you mean" street names attached to an [`PortError::AddressNotFound`].
+    #[must_use]
+    pub fn suggestions(&self) -> &[String] {
+        match self.kind() {
+            Self::AddressNotFound { suggestions, .. } => suggestions,
+            _ => &[],
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("{message}")]
+/// A request that got no HTTP answer, e.g. a failed DNS lookup, a refused
+/// connection or a TLS error.
+///
+/// The HTTP stack's own error stays available as [`StdError::source`].
+pub struct NetworkError {
+    message: String,
+    retryable: bool,
+    #[source]
+    source: Option<Box<dyn StdError + Send + Sync>>,
+}
+
+impl NetworkError {
+    /// Error described by `message`; `retryable` if trying again later may succeed.
+    #[must_use]
+    pub fn new(message: impl Into<String>, retryable: bool) -> Self {
+        Self {
+            message: message.into(),
+            retryable,
+            source: None,
+        }
+    }
+
+    /// Whether trying again later may succeed, e.g. after a refused connection.
+    #[must_use]
+    pub const fn is_retryable(&self) -> bool {
+        self.retryable
+    }
+}
+
+impl From<HttpError> for PortError {
+    fn from(err: HttpError) -> Self {
+        let retryable = err.is_retryable();
+        match err {
+            HttpError::Transport(source) => Self::Network(NetworkError {
+                message: source.to_string(),
+                retryable,
+                source: Some(Box::new(source)),
+            }),
+            HttpError::Timeout => Self::Timeout,
+            HttpError::RateLimited {
+                retry_after,
+                status,
+            } => Self::RateLimited {
+                retry_after,
+                status: Some(status.as_u16()),
+            },
+            HttpError::Status(status) => Self::HttpStatus(status.as_u16()),
+            HttpError::DeadlineExceeded { limit } => Self::DeadlineExceeded { limit },
+            HttpError::TooLarge { limit } => Self::ResponseTooLarge { limit },
+            HttpError::Decode(err) => Self::Decode(err.to_string()),
+            HttpError::Unavailable { message } => Self::ProviderUnavailable(message),
+            HttpError::Config(message) | HttpError::Fixture(message) => Self::Internal(message),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -39,6 +273,17 @@ pub struct AddressSearch {
     pub street: String,
     /// Optional house number filter.
     pub house_number: Option<String>,
+    /// Member town to search in when the city is a county, see [`AddressPort::towns`].
+    ///
+    /// Providers of single towns ignore it.
+    pub town: Option<String>,
+    /// Optional house number addition such as the “a” in “12a”.
+    ///
+    /// When unset, providers fall back to the suffix of `house_number`, see
+    /// [`AddressSearch::house_number_parts`].
+    pub house_number_addition: Option<String>,
+    /// Tolerate small typos when providers filter street names client-side.
+    pub fuzzy: bool,
 }
 
 impl AddressSearch {
@@ -48,6 +293,106 @@ impl AddressSearch {
         Self {
             street: street.into(),
             house_number: house_number.map(Into::into),
+            town: None,
+            house_number_addition: None,
+            fuzzy: false,
+        }
+    }
+
+    /// Parse free-form input such as “Aachener Str 12a” into a fuzzy search.
+    ///
+    /// The last word is taken as house number when it contains a digit and is not the
+    /// only word; everything else is the street.
+    #[must_use]
+    pub fn parse(input: &str) -> Self {
+        let parts = input.split_whitespace().collect::<Vec<_>>();
+        let search = match parts.split_last() {
+            Some((last, street_parts))
+                if !street_parts.is_empty() && last.chars().any(|ch| ch.is_ascii_digit()) =>
+            {
+                Self::new(street_parts.join(" "), Some(*last))
+            }
+            _ => Self::new(parts.join(" "), None::<String>),
+        };
+        search.with_fuzzy(true)
+    }
+
+    /// Spell out abbreviations in the street name, see [`normalize_street`].
+    #[must_use]
+    pub fn normalized(mut self) -> Self {
+        self.street = normalize_street(&self.street);
+        self
+    }
+
+    /// Enable or disable typo-tolerant street matching.
+    #[must_use]
+    pub fn with_fuzzy(mut self, fuzzy: bool) -> Self {
+        self.fuzzy = fuzzy;
+        self
+    }
+
+    /// Search within the member town `town` of a county.
+    #[must_use]
+    pub fn with_town<T: Into<String>>(mut self, town: Option<T>) -> Self {
+        self.town = town.map(Into::into);
+        self
+    }
+
+    /// Take a leading “Town,” off the street when it names one of `towns`.
+    ///
+    /// Lets free-form input such as “Siegburg, Kaiserstraße 12” select a member town
+    /// of a county; the comparison ignores case and umlauts.
+    #[must_use]
+    pub fn with_town_prefix(self, towns: &[String]) -> Self {
+        let Some((prefix, street)) = self.street.split_once(',') else {
+            return self;
+        };
+        let wanted = fold(prefix);
+        match towns.iter().find(|town| fold(town) == wanted) {
+            Some(town) => Self {
+                town: Some(town.clone()),
+                street: street.trim().to_owned(),
+                ..self
+            },
+            None => self,
+        }
+    }
+
+    /// Set the house number addition explicitly.
+    #[must_use]
+    pub fn with_house_number_addition<A: Into<String>>(mut self, addition: Option<A>) -> Self {
+        self.house_number_addition = addition.map(Into::into);
+        self
+    }
+
+    /// House number and addition, e.g. `("12", Some("a"))` for “12a” or “12 a”.
+    ///
+    /// An explicit [`AddressSearch::house_number_addition`] wins over a suffix parsed
+    /// from the house number. Returns `None` without a (non-blank) house number.
+    #[must_use]
+    pub fn house_number_parts(&self) -> Option<(&str, Option<&str>)> {
+        let house_number = self.house_number.as_deref().map(str::trim)?;
+        if house_number.is_empty() {
+            return None;
+        }
+        let explicit = self
+            .house_number_addition
+            .as_deref()
+            .map(str::trim)
+            .filter(|addition| !addition.is_empty());
+        if explicit.is_some() {
+            return Some((house_number, explicit));
+        }
+
+        let digits_end = house_number
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(house_number.len());
+        let (number, suffix) = house_number.split_at(digits_end);
+        let suffix = suffix.trim_start_matches([' ', '-', '/']);
+        if number.is_empty() || suffix.is_empty() {
+            Some((house_number, None))
+        } else {
+            Some((number, Some(suffix)))
         }
     }
 
@@ -58,6 +403,15 @@ impl AddressSearch {
     }
 }
 
+#[derive(Debug, Clone, Default)]
+/// Addresses returned by a search and whether further matches were cut off.
+pub struct SearchResults {
+    /// Matching addresses, at most the requested limit.
+    pub addresses: Vec<Address>,
+    /// `true` when the provider had more matches than were returned.
+    pub truncated: bool,
+}
+
 #[async_trait]
 /// Trait for provider-specific address search backends.
 pub trait AddressPort: Send + Sync {
@@ -70,6 +424,95 @@ pub trait AddressPort: Send + Sync {
     ///
     /// Returns a [`PortError`] when the provider request fails.
     async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError>;
+
+    /// Perform an address search and report whether results were truncated.
+    ///
+    /// The default assumes truncation whenever `limit` addresses came back; providers
+    /// that know the total number of matches should override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortError`] when the provider request fails.
+    async fn search_results(
+        &self,
+        query: &AddressSearch,
+        limit: usize,
+    ) -> Result<SearchResults, PortError> {
+        let addresses = self.search(query, limit).await?;
+        let truncated = limit > 0 && addresses.len() >= limit;
+        Ok(SearchResults {
+            addresses,
+            truncated,
+        })
+    }
+
+    /// Member towns when the city is a county (Landkreis) served as a whole.
+    ///
+    /// Searches in such a city name the town with [`AddressSearch::town`]; providers of
+    /// single towns keep the default, which lists none.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortError`] when the provider request fails.
+    async fn towns(&self) -> Result<Vec<String>, PortError> {
+        Ok(Vec::new())
+    }
+
+    /// Address behind an id returned by [`AddressPort::search`], e.g. to label a stored id.
+    ///
+    /// Providers whose ids carry or look up the street and house number override it;
+    /// the default cannot tell and reports the address as not found.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PortError::AddressNotFound`] when the provider does not know the id,
+    /// [`PortError::InvalidAddressId`] for malformed ids, or another [`PortError`] when
+    /// the provider request fails.
+    async fn resolve(&self, _address_id: &AddressId) -> Result<Address, PortError> {
+        Err(PortError::AddressNotFound {
+            suggestions: Vec::new(),
+            status: None,
+        })
+    }
+
+    /// Probe the backend with a cheap request, e.g. for a monitoring endpoint.
+    ///
+    /// Returns `None` when the provider has no such request, the default; providers
+    /// with a small listing or status endpoint override it.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        None
+    }
+
+    /// Version of the id encoding currently produced by [`AddressPort::search`].
+    ///
+    /// Bump this whenever the encoding changes and override [`AddressPort::migrate_id`].
+    fn id_version(&self) -> u32 {
+        INITIAL_ADDRESS_ID_VERSION
+    }
+
+    /// Upgrade an id persisted with an older encoding.
+    ///
+    /// The default accepts ids of the current version and asks for re-resolution otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortError`] when a provider lookup needed for the migration fails.
+    async fn migrate_id(&self, stored: &VersionedAddressId) -> Result<IdMigration, PortError> {
+        if stored.version == self.id_version() {
+            Ok(IdMigration::Current(stored.id.clone()))
+        } else {
+            Ok(IdMigration::NeedsResolution)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Events parsed from a provider response plus the entries that had to be skipped.
+pub struct ScheduleReport {
+    /// Successfully parsed events.
+    pub events: Vec<PickupEvent>,
+    /// Raw date values that could not be parsed; their entries were skipped.
+    pub malformed_dates: Vec<String>,
 }
 
 #[async_trait]
@@ -88,4 +531,144 @@ pub trait SchedulePort: Send + Sync {
         address_id: &AddressId,
         range: DateRange,
     ) -> Result<Vec<PickupEvent>, PortError>;
+
+    /// Fetch pickup events and report entries skipped because of malformed dates.
+    ///
+    /// The default reports nothing skipped; providers parsing dates with
+    /// [`MalformedDates`](crate::dates::MalformedDates) override it and implement
+    /// [`SchedulePort::schedule`] on top of it.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`SchedulePort::schedule`].
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        Ok(ScheduleReport {
+            events: self.schedule(address_id, range).await?,
+            malformed_dates: Vec::new(),
+        })
+    }
+
+    /// Whether [`SchedulePort::schedule`] only downloads the part of the calendar
+    /// covered by `range`.
+    ///
+    /// Providers returning `true` are refreshed incrementally; the others are always
+    /// refetched in full because a narrower range would not save any requests.
+    fn supports_partial_fetch(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn http_errors_keep_status_and_retry_hints() {
+        let cases = [
+            (
+                HttpError::Status(StatusCode::NOT_FOUND),
+                Some(404),
+                false,
+                "a 404 is kept",
+            ),
+            (
+                HttpError::RateLimited {
+                    retry_after: Some(Duration::from_secs(30)),
+                    status: StatusCode::TOO_MANY_REQUESTS,
+                },
+                Some(429),
+                true,
+                "throttling passes",
+            ),
+            (
+                HttpError::Status(StatusCode::BAD_GATEWAY),
+                Some(502),
+                true,
+                "server errors pass",
+            ),
+            (
+                HttpError::Status(StatusCode::FORBIDDEN),
+                Some(403),
+                false,
+                "client errors stay",
+            ),
+        ];
+        for (http, status, retryable, reason) in cases {
+            let err = PortError::from(http);
+            assert_eq!(
+                (err.status(), err.is_retryable()),
+                (status, retryable),
+                "{reason}: {err:?}"
+            );
+        }
+        let throttled = PortError::from(HttpError::RateLimited {
+            retry_after: Some(Duration::from_secs(30)),
+            status: StatusCode::SERVICE_UNAVAILABLE,
+        });
+        assert_eq!(
+            throttled.retry_after(),
+            Some(Duration::from_secs(30)),
+            "Retry-After is kept"
+        );
+        assert_eq!(throttled.status(), Some(503), "the 503 is kept");
+        assert!(
+            matches!(
+                PortError::from(HttpError::Status(StatusCode::NOT_FOUND)),
+                PortError::HttpStatus(404)
+            ),
+            "a 404 alone says nothing about the address"
+        );
+        assert!(
+            matches!(
+                PortError::from_address_lookup(HttpError::Status(StatusCode::NOT_FOUND)),
+                PortError::AddressNotFound {
+                    status: Some(404),
+                    ..
+                }
+            ),
+            "a 404 of an address lookup is an unknown address"
+        );
+        assert!(
+            matches!(
+                PortError::from_address_lookup(HttpError::Status(StatusCode::FORBIDDEN)),
+                PortError::HttpStatus(403)
+            ),
+            "address lookups keep other statuses"
+        );
+        assert!(
+            PortError::from(NetworkError::new("connection refused", true)).is_retryable(),
+            "refused connections pass"
+        );
+    }
+
+    #[test]
+    fn provider_errors_keep_their_kind() {
+        let city = CityId("cologne".to_owned());
+        let err = PortError::RateLimited {
+            retry_after: Some(Duration::from_secs(30)),
+            status: Some(429),
+        }
+        .with_provider(&city)
+        .with_provider(&CityId("bremen".to_owned()));
+
+        assert_eq!(err.provider(), Some(&city), "the first city is kept");
+        assert!(
+            matches!(err.kind(), PortError::RateLimited { .. }),
+            "the kind looks past the city, got {err:?}"
+        );
+        assert_eq!(
+            (err.status(), err.retry_after(), err.is_retryable()),
+            (Some(429), Some(Duration::from_secs(30)), true),
+            "status and retry hints pass through"
+        );
+        assert_eq!(
+            err.to_string(),
+            "cologne: Rate limited by provider",
+            "messages name the city"
+        );
+    }
 }
@@ -0,0 +1,143 @@
+//! Extrapolating regular pickups past the horizon a provider publishes.
+//!
+//! Many providers only publish a few months ahead. [`predict_pickups`] finds the
+//! weekly rhythm of each fraction in the fetched events and continues it; the
+//! predicted pickups carry [`PickupEvent::predicted`], so clients can show them as
+//! tentative.
+
+use std::collections::HashMap;
+
+use chrono::{Days, NaiveDate};
+
+use crate::model::{Fraction, PickupEvent};
+
+/// Fewest fetched pickups of a fraction to infer a rhythm from.
+pub const MIN_PICKUPS_FOR_PREDICTION: usize = 3;
+/// Longest rhythm that is recognized, in weeks.
+pub const MAX_INTERVAL_WEEKS: i64 = 4;
+
+/// Date the pickup would have had without a holiday shift.
+fn regular_date(event: &PickupEvent) -> NaiveDate {
+    event
+        .holiday
+        .and_then(|shift| shift.shifted_from)
+        .unwrap_or(event.date)
+}
+
+/// Days between pickups, when all gaps are the same whole number of weeks.
+///
+/// A gap of a multiple of the rhythm (a skipped pickup) does not break it.
+fn rhythm(dates: &[NaiveDate]) -> Option<u64> {
+    let gaps = dates
+        .windows(2)
+        .filter_map(|pair| match pair {
+            [earlier, later] => Some((*later - *earlier).num_days()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let interval = gaps.iter().copied().min()?;
+    let weeks = interval / 7;
+    let regular = interval % 7 == 0
+        && (1..=MAX_INTERVAL_WEEKS).contains(&weeks)
+        && gaps.iter().all(|gap| gap % interval == 0);
+    regular.then(|| u64::try_from(interval).ok()).flatten()
+}
+
+/// Pickups after the last fetched one of each fraction and container, up to `until`.
+///
+/// Fractions with fewer than [`MIN_PICKUPS_FOR_PREDICTION`] pickups or without a
+/// steady weekly to four-weekly rhythm are not predicted. Holiday-shifted pickups
+/// count with their regular date. The result is sorted by date.
+#[must_use]
+pub fn predict_pickups(events: &[PickupEvent], until: NaiveDate) -> Vec<PickupEvent> {
+    let mut series: HashMap<(&Fraction, Option<&str>), Vec<&PickupEvent>> = HashMap::new();
+    for event in events.iter().filter(|event| !event.predicted) {
+        series
+            .entry((&event.fraction, event.container.as_deref()))
+            .or_default()
+            .push(event);
+    }
+
+    let mut predicted = Vec::new();
+    for pickups in series.into_values() {
+        if pickups.len() < MIN_PICKUPS_FOR_PREDICTION {
+            continue;
+        }
+        let mut dates = pickups
+            .iter()
+            .map(|event| regular_date(event))
+            .collect::<Vec<_>>();
+        dates.sort_unstable();
+        dates.dedup();
+        let (Some(interval), Some(&last), Some(template)) =
+            (rhythm(&dates), dates.last(), pickups.last())
+        else {
+            continue;
+        };
+        let last_fetched = pickups.iter().map(|event| event.date).max().unwrap_or(last);
+        let mut date = last;
+        while let Some(next) = date.checked_add_days(Days::new(interval)) {
+            if next > until {
+                break;
+            }
+            if next > last_fetched {
+                predicted.push(PickupEvent {
+                    date: next,
+                    fraction: template.fraction.clone(),
+                    note: None,
+                    container: template.container.clone(),
+                    correction: None,
+                    holiday: None,
+                    predicted: true,
+                });
+            }
+            date = next;
+        }
+    }
+    predicted.sort_by_key(|event| event.date);
+    predicted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pickup(month: u32, day: u32, fraction: Fraction) -> PickupEvent {
+        PickupEvent {
+            date: NaiveDate::from_ymd_opt(2025, month, day).unwrap_or_default(),
+            fraction,
+            note: None,
+            container: None,
+            correction: None,
+            holiday: None,
+            predicted: false,
+        }
+    }
+
+    #[test]
+    fn continues_biweekly_rhythm_past_the_horizon() {
+        let events = [
+            pickup(3, 3, Fraction::Paper),
+            pickup(3, 17, Fraction::Paper),
+            pickup(4, 14, Fraction::Paper),
+            pickup(3, 5, Fraction::Glass),
+            pickup(3, 9, Fraction::Glass),
+            pickup(3, 30, Fraction::Glass),
+        ];
+        let until = NaiveDate::from_ymd_opt(2025, 5, 20).unwrap_or_default();
+
+        let predicted = predict_pickups(&events, until);
+
+        assert_eq!(
+            predicted
+                .iter()
+                .map(|event| (event.date.to_string(), event.predicted))
+                .collect::<Vec<_>>(),
+            [
+                ("2025-04-28".to_owned(), true),
+                ("2025-05-12".to_owned(), true),
+            ],
+            "paper every two weeks despite a skipped pickup, glass has no rhythm"
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Capping the calls sent to each city's backend, so busy deployments stay within
+//! what the provider tolerates.
+//!
+//! [`RateLimiter`] is a token bucket; [`rate_limited`] puts one in front of both
+//! ports of a plugin, so searches and schedules of a city share one budget. Calls
+//! over the budget fail at once with [`PortError::RateLimited`] and the delay until
+//! the next token, without reaching the provider.
+
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use web_time::Instant;
+
+use async_trait::async_trait;
+
+use crate::model::{
+    Address, AddressId, CityMeta, DateRange, IdMigration, PickupEvent, VersionedAddressId,
+};
+use crate::plugin::CityPlugin;
+use crate::ports::{
+    AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport, SearchResults,
+};
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+/// Token bucket allowing a number of calls per minute, in bursts of up to that many.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    per_second: f64,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    /// Allow `requests_per_minute` calls, at least one, starting with a full bucket.
+    #[must_use]
+    pub fn new(requests_per_minute: u32) -> Self {
+        let capacity = f64::from(requests_per_minute.max(1));
+        Self {
+            capacity,
+            per_second: capacity / 60.0,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                refilled: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token for one call.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PortError::RateLimited`] with the time until the next token when
+    /// the bucket is empty.
+    pub fn acquire(&self) -> Result<(), PortError> {
+        self.acquire_at(Instant::now())
+    }
+
+    fn acquire_at(&self, now: Instant) -> Result<(), PortError> {
+        let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+        let elapsed = now.saturating_duration_since(bucket.refilled);
+        bucket.tokens = elapsed
+            .as_secs_f64()
+            .mul_add(self.per_second, bucket.tokens)
+            .min(self.capacity);
+        bucket.refilled = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        Err(PortError::RateLimited {
+            retry_after: Some(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.per_second,
+            )),
+            status: None,
+        })
+    }
+}
+
+/// Port decorator rejecting calls beyond the budget of its [`RateLimiter`].
+pub struct RateLimitedPort<P: ?Sized> {
+    inner: Arc<P>,
+    limiter: Arc<RateLimiter>,
+}
+
+/// Limit both ports of `plugin` to `requests_per_minute` calls together.
+///
+/// Every port call counts once, even when the provider needs several requests
+/// for it; wrap before [`cached`](crate::cache::cached) so cache hits stay free.
+#[must_use]
+pub fn rate_limited(plugin: CityPlugin, requests_per_minute: u32) -> CityPlugin {
+    let limiter = Arc::new(RateLimiter::new(requests_per_minute));
+    CityPlugin {
+        meta: plugin.meta,
+        address_port: Arc::new(RateLimitedPort {
+            inner: plugin.address_port,
+            limiter: Arc::clone(&limiter),
+        }),
+        schedule_port: Arc::new(RateLimitedPort {
+            inner: plugin.schedule_port,
+            limiter,
+        }),
+    }
+}
+
+#[async_trait]
+impl AddressPort for RateLimitedPort<dyn AddressPort> {
+    fn city(&self) -> &CityMeta {
+        self.inner.city()
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        self.limiter.acquire()?;
+        self.inner.search(query, limit).await
+    }
+
+    async fn search_results(
+        &self,
+        query: &AddressSearch,
+        limit: usize,
+    ) -> Result<SearchResults, PortError> {
+        self.limiter.acquire()?;
+        self.inner.search_results(query, limit).await
+    }
+
+    async fn towns(&self) -> Result<Vec<String>, PortError> {
+        self.limiter.acquire()?;
+        self.inner.towns().await
+    }
+
+    async fn resolve(&self, address_id: &AddressId) -> Result<Address, PortError> {
+        self.limiter.acquire()?;
+        self.inner.resolve(address_id).await
+    }
+
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        if let Err(err) = self.limiter.acquire() {
+            return Some(Err(err));
+        }
+        self.inner.health_check().await
+    }
+
+    fn id_version(&self) -> u32 {
+        self.inner.id_version()
+    }
+
+    async fn migrate_id(&self, stored: &VersionedAddressId) -> Result<IdMigration, PortError> {
+        self.limiter.acquire()?;
+        self.inner.migrate_id(stored).await
+    }
+}
+
+#[async_trait]
+impl SchedulePort for RateLimitedPort<dyn SchedulePort> {
+    fn city(&self) -> &CityMeta {
+        self.inner.city()
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        self.limiter.acquire()?;
+        self.inner.schedule(address_id, range).await
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        self.limiter.acquire()?;
+        self.inner.schedule_report(address_id, range).await
+    }
+
+    fn supports_partial_fetch(&self) -> bool {
+        self.inner.supports_partial_fetch()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bursts_up_to_the_budget_then_refills() {
+        let limiter = RateLimiter::new(60);
+        let start = Instant::now();
+        limiter
+            .bucket
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .refilled = start;
+
+        assert!(
+            (0..60).all(|_| limiter.acquire_at(start).is_ok()),
+            "a full minute's budget is available at once"
+        );
+        let rejected = limiter.acquire_at(start + Duration::from_millis(250));
+        assert_eq!(
+            rejected.as_ref().err().and_then(PortError::retry_after),
+            Some(Duration::from_millis(750)),
+            "one token per second, a quarter of it has refilled: {rejected:?}"
+        );
+        let later = start + Duration::from_secs(1);
+        assert!(
+            limiter.acquire_at(later).is_ok(),
+            "a token is back after a second"
+        );
+        assert!(limiter.acquire_at(later).is_err(), "and only one");
+    }
+}
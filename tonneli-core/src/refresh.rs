@@ -0,0 +1,553 @@
+//! Incremental schedule refreshes based on an earlier snapshot, and a background
+//! task that keeps subscribed addresses up to date.
+//!
+//! The background task runs on Tokio and is not available on `wasm32`, where a web
+//! app schedules refreshes with the browser's timers instead.
+
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    chrono::Days,
+    std::collections::HashMap,
+    std::sync::{Arc, Mutex, MutexGuard, PoisonError},
+    tokio::sync::{Notify, mpsc},
+    tokio::task::JoinHandle,
+    tokio::time::{Instant, sleep_until},
+};
+
+use crate::merge::merge_events;
+use crate::model::{AddressId, CityId, DateRange, PickupEvent};
+use crate::ports::PortError;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::service::TonneliService;
+
+/// Time between two background refreshes of an address unless configured otherwise.
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_hours(6);
+/// Days ahead, starting today, a background refresh loads unless configured otherwise.
+pub const DEFAULT_REFRESH_DAYS: u64 = 60;
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Schedule fetched earlier, kept so later refreshes only need to fetch what may have changed.
+pub struct ScheduleSnapshot {
+    /// Range the events were fetched for.
+    pub range: DateRange,
+    /// Day the snapshot was taken.
+    pub fetched_on: NaiveDate,
+    /// Events within `range`, sorted by date.
+    pub events: Vec<PickupEvent>,
+}
+
+/// Part of `range` that has to be fetched again to bring `snapshot` up to date.
+///
+/// Pickups that were already in the past when the snapshot was taken are considered
+/// settled; everything from that day on, and anything the snapshot did not cover,
+/// is refetched. Returns `None` when the snapshot still covers the whole range.
+#[must_use]
+pub fn refresh_window(snapshot: &ScheduleSnapshot, range: DateRange) -> Option<DateRange> {
+    let start = if range.start < snapshot.range.start {
+        range.start
+    } else {
+        range.start.max(snapshot.fetched_on)
+    };
+    (start <= range.end).then_some(DateRange {
+        start,
+        end: range.end,
+    })
+}
+
+/// Combine the settled part of `snapshot` with `fresh` events fetched for `window`.
+///
+/// Only events within `range` are kept, from either side.
+#[must_use]
+pub fn merge_refresh(
+    snapshot: &ScheduleSnapshot,
+    range: DateRange,
+    window: Option<DateRange>,
+    fresh: Vec<PickupEvent>,
+    today: NaiveDate,
+) -> ScheduleSnapshot {
+    let settled = snapshot
+        .events
+        .iter()
+        .filter(|event| {
+            range.contains(event.date) && window.is_none_or(|window| event.date < window.start)
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    let fresh = fresh
+        .into_iter()
+        .filter(|event| range.contains(event.date))
+        .collect::<Vec<_>>();
+    let events = merge_events([settled, fresh]);
+
+    ScheduleSnapshot {
+        range,
+        fetched_on: today,
+        events,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// How often and how far ahead [`BackgroundRefresh`] loads schedules.
+pub struct RefreshConfig {
+    /// Time between two refreshes of the same address.
+    pub interval: Duration,
+    /// Days ahead, starting today, each refresh covers.
+    pub days_ahead: u64,
+}
+
+impl Default for RefreshConfig {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_REFRESH_INTERVAL,
+            days_ahead: DEFAULT_REFRESH_DAYS,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// New schedule of a subscribed address, or why refreshing it failed.
+pub struct ScheduleUpdate {
+    /// City the address belongs to.
+    pub city: CityId,
+    /// Subscribed address.
+    pub address_id: AddressId,
+    /// Refreshed schedule; only sent when its events differ from the previous one.
+    pub outcome: Result<ScheduleSnapshot, PortError>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Latest snapshot of a subscription and when it is due again.
+struct Subscription {
+    snapshot: Option<ScheduleSnapshot>,
+    due: Instant,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+type Subscriptions = Arc<Mutex<HashMap<(CityId, AddressId), Subscription>>>;
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Tokio task refreshing the schedules of subscribed addresses in the background.
+///
+/// Each subscription is loaded right away and then every [`RefreshConfig::interval`],
+/// reusing its last snapshot via [`TonneliService::refresh_schedule`]. Updates are
+/// published on the receiver returned by [`BackgroundRefresh::spawn`] whenever a
+/// schedule changed or a refresh failed. A city that answers with
+/// [`PortError::RateLimited`] is left alone, for all its subscriptions, until its
+/// `Retry-After` delay or the interval passed, whichever is longer. The task stops
+/// when the handle is dropped or the receiver is closed.
+pub struct BackgroundRefresh {
+    subscriptions: Subscriptions,
+    wake: Arc<Notify>,
+    task: JoinHandle<()>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BackgroundRefresh {
+    /// Start the task on the current tokio runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called outside a tokio runtime.
+    #[must_use]
+    pub fn spawn(
+        service: Arc<TonneliService>,
+        config: RefreshConfig,
+    ) -> (Self, mpsc::UnboundedReceiver<ScheduleUpdate>) {
+        let subscriptions = Subscriptions::default();
+        let wake = Arc::new(Notify::new());
+        let (updates, receiver) = mpsc::unbounded_channel();
+        let task = tokio::spawn(run(
+            service,
+            config,
+            Arc::clone(&subscriptions),
+            Arc::clone(&wake),
+            updates,
+        ));
+        let refresh = Self {
+            subscriptions,
+            wake,
+            task,
+        };
+        (refresh, receiver)
+    }
+
+    /// Keep the schedule of `address_id` up to date, starting with an immediate refresh.
+    ///
+    /// Subscribing to an address twice keeps its current snapshot.
+    pub fn subscribe(&self, city: CityId, address_id: AddressId) {
+        self.lock()
+            .entry((city, address_id))
+            .or_insert_with(|| Subscription {
+                snapshot: None,
+                due: Instant::now(),
+            });
+        self.wake.notify_one();
+    }
+
+    /// Stop refreshing `address_id`; `false` when it was not subscribed.
+    #[must_use]
+    pub fn unsubscribe(&self, city: &CityId, address_id: &AddressId) -> bool {
+        self.lock()
+            .remove(&(city.clone(), address_id.clone()))
+            .is_some()
+    }
+
+    /// Subscribed addresses, in no particular order.
+    #[must_use]
+    pub fn subscriptions(&self) -> Vec<(CityId, AddressId)> {
+        self.lock().keys().cloned().collect()
+    }
+
+    /// Last schedule loaded for a subscribed address.
+    #[must_use]
+    pub fn snapshot(&self, city: &CityId, address_id: &AddressId) -> Option<ScheduleSnapshot> {
+        self.lock()
+            .get(&(city.clone(), address_id.clone()))
+            .and_then(|subscription| subscription.snapshot.clone())
+    }
+
+    /// Refresh every subscription now instead of waiting for the interval; cities
+    /// that asked to be left alone are still refreshed only once they allow it.
+    pub fn refresh_now(&self) {
+        let now = Instant::now();
+        for subscription in self.lock().values_mut() {
+            subscription.due = now;
+        }
+        self.wake.notify_one();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<(CityId, AddressId), Subscription>> {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for BackgroundRefresh {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn run(
+    service: Arc<TonneliService>,
+    config: RefreshConfig,
+    subscriptions: Subscriptions,
+    wake: Arc<Notify>,
+    updates: mpsc::UnboundedSender<ScheduleUpdate>,
+) {
+    // Rate-limited cities and when they may be asked again.
+    let mut throttled = HashMap::<CityId, Instant>::new();
+    loop {
+        let now = Instant::now();
+        throttled.retain(|_, until| *until > now);
+        let due = subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|((city, _), subscription)| {
+                subscription.due <= now && !throttled.contains_key(city)
+            })
+            .map(|(key, subscription)| (key.clone(), subscription.snapshot.clone()))
+            .collect::<Vec<_>>();
+
+        for (key, previous) in due {
+            let (city, address_id) = &key;
+            // Rate limited by an earlier subscription of this round.
+            if throttled.contains_key(city) {
+                continue;
+            }
+            let today = service.today_in(city);
+            let range = DateRange {
+                start: today,
+                end: today
+                    .checked_add_days(Days::new(config.days_ahead))
+                    .unwrap_or(NaiveDate::MAX),
+            };
+            let outcome = service
+                .refresh_schedule(city.clone(), address_id, previous.as_ref(), range)
+                .await;
+
+            let changed = match (&outcome, &previous) {
+                (Ok(fresh), Some(previous)) => fresh.events != previous.events,
+                _ => true,
+            };
+            let delay = match &outcome {
+                Err(err) if matches!(err.kind(), PortError::RateLimited { .. }) => {
+                    let delay = err
+                        .retry_after()
+                        .map_or(config.interval, |after| after.max(config.interval));
+                    throttled.insert(city.clone(), Instant::now() + delay);
+                    delay
+                }
+                _ => config.interval,
+            };
+            {
+                let mut subscriptions =
+                    subscriptions.lock().unwrap_or_else(PoisonError::into_inner);
+                // Unsubscribed while loading: neither keep nor publish the result.
+                let Some(subscription) = subscriptions.get_mut(&key) else {
+                    continue;
+                };
+                subscription.due = Instant::now() + delay;
+                if let Ok(fresh) = &outcome {
+                    subscription.snapshot = Some(fresh.clone());
+                }
+            }
+            if changed {
+                let update = ScheduleUpdate {
+                    city: key.0,
+                    address_id: key.1,
+                    outcome,
+                };
+                if updates.send(update).is_err() {
+                    return;
+                }
+            }
+        }
+
+        let next = subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|((city, _), subscription)| {
+                throttled
+                    .get(city)
+                    .map_or(subscription.due, |until| subscription.due.max(*until))
+            })
+            .min()
+            .unwrap_or_else(|| Instant::now() + config.interval);
+        tokio::select! {
+            () = sleep_until(next) => {}
+            () = wake.notified() => {}
+            () = updates.closed() => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use tokio::time::{sleep, timeout};
+
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::model::{Address, CityMeta, Fraction};
+    use crate::plugin::{CityPlugin, PluginRegistry};
+    use crate::ports::{AddressPort, AddressSearch, SchedulePort};
+
+    /// Provider whose single address is collected on the days in `days`.
+    struct Changing {
+        meta: CityMeta,
+        days: Mutex<Vec<u32>>,
+    }
+
+    #[async_trait]
+    impl AddressPort for Changing {
+        fn city(&self) -> &CityMeta {
+            &self.meta
+        }
+
+        async fn search(&self, _: &AddressSearch, _: usize) -> Result<Vec<Address>, PortError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[async_trait]
+    impl SchedulePort for Changing {
+        fn city(&self) -> &CityMeta {
+            &self.meta
+        }
+
+        async fn schedule(
+            &self,
+            address_id: &AddressId,
+            _: DateRange,
+        ) -> Result<Vec<PickupEvent>, PortError> {
+            if address_id.0 != "1" {
+                return Err(PortError::InvalidAddressId);
+            }
+            let days = self.days.lock().unwrap_or_else(PoisonError::into_inner);
+            Ok(days.iter().map(|day| pickup(*day)).collect())
+        }
+    }
+
+    fn pickup(day: u32) -> PickupEvent {
+        PickupEvent {
+            date: NaiveDate::from_ymd_opt(2025, 3, day).expect("valid test date"),
+            fraction: Fraction::Paper,
+            note: None,
+            container: None,
+            correction: None,
+            holiday: None,
+            predicted: false,
+        }
+    }
+
+    /// Provider that counts its schedule requests and answers each with 429.
+    struct Throttled {
+        meta: CityMeta,
+        requests: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl AddressPort for Throttled {
+        fn city(&self) -> &CityMeta {
+            &self.meta
+        }
+
+        async fn search(&self, _: &AddressSearch, _: usize) -> Result<Vec<Address>, PortError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[async_trait]
+    impl SchedulePort for Throttled {
+        fn city(&self) -> &CityMeta {
+            &self.meta
+        }
+
+        async fn schedule(
+            &self,
+            _: &AddressId,
+            _: DateRange,
+        ) -> Result<Vec<PickupEvent>, PortError> {
+            *self.requests.lock().unwrap_or_else(PoisonError::into_inner) += 1;
+            Err(PortError::RateLimited {
+                retry_after: Some(Duration::from_hours(1)),
+                status: Some(429),
+            })
+        }
+    }
+
+    fn service<P: AddressPort + SchedulePort + 'static>(port: &Arc<P>) -> Arc<TonneliService> {
+        let plugin = CityPlugin {
+            meta: AddressPort::city(&**port).clone(),
+            address_port: Arc::<P>::clone(port),
+            schedule_port: Arc::<P>::clone(port),
+        };
+        let today = NaiveDate::from_ymd_opt(2025, 3, 1).expect("valid test date");
+        Arc::new(
+            TonneliService::new(Arc::new(PluginRegistry::new(vec![plugin]))).with_clock(Arc::new(
+                FixedClock::new(today.and_hms_opt(8, 0, 0).expect("valid test time")),
+            )),
+        )
+    }
+
+    async fn next(updates: &mut mpsc::UnboundedReceiver<ScheduleUpdate>) -> ScheduleUpdate {
+        timeout(Duration::from_secs(5), updates.recv())
+            .await
+            .expect("an update arrives in time")
+            .expect("the task is running")
+    }
+
+    fn dates(update: &ScheduleUpdate) -> Vec<u32> {
+        use chrono::Datelike as _;
+        update
+            .outcome
+            .as_ref()
+            .map(|snapshot| {
+                snapshot
+                    .events
+                    .iter()
+                    .map(|event| event.date.day())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn publishes_changed_schedules_only() {
+        let port = Arc::new(Changing {
+            meta: CityMeta::new("cologne", "Köln"),
+            days: Mutex::new(vec![10]),
+        });
+        let (refresh, mut updates) =
+            BackgroundRefresh::spawn(service(&port), RefreshConfig::default());
+        let city = CityId("cologne".to_owned());
+        refresh.subscribe(city.clone(), AddressId("1".to_owned()));
+        refresh.subscribe(city.clone(), AddressId("2".to_owned()));
+
+        let mut first = [next(&mut updates).await, next(&mut updates).await];
+        first.sort_by(|left, right| left.address_id.0.cmp(&right.address_id.0));
+        assert_eq!(
+            dates(&first[0]),
+            [10],
+            "a new subscription is loaded at once"
+        );
+        assert!(
+            matches!(
+                first[1].outcome.as_ref().map_err(PortError::kind),
+                Err(PortError::InvalidAddressId)
+            ),
+            "failures are published, got {:?}",
+            first[1].outcome
+        );
+        assert!(
+            refresh.unsubscribe(&city, &AddressId("2".to_owned())),
+            "the failing address was subscribed"
+        );
+
+        refresh.refresh_now();
+        port.days
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(12);
+        refresh.refresh_now();
+        let changed = next(&mut updates).await;
+        assert_eq!(
+            dates(&changed),
+            [10, 12],
+            "only the changed schedule is published"
+        );
+        assert_eq!(
+            refresh
+                .snapshot(&city, &AddressId("1".to_owned()))
+                .map(|snapshot| snapshot.events.len()),
+            Some(2),
+            "the latest snapshot is kept"
+        );
+    }
+
+    #[tokio::test]
+    async fn rate_limited_city_waits_for_retry_after() {
+        let port = Arc::new(Throttled {
+            meta: CityMeta::new("cologne", "Köln"),
+            requests: Mutex::new(0),
+        });
+        let config = RefreshConfig {
+            interval: Duration::from_millis(10),
+            ..RefreshConfig::default()
+        };
+        let (refresh, mut updates) = BackgroundRefresh::spawn(service(&port), config);
+        let city = CityId("cologne".to_owned());
+        refresh.subscribe(city.clone(), AddressId("1".to_owned()));
+        refresh.subscribe(city, AddressId("2".to_owned()));
+
+        let update = next(&mut updates).await;
+        assert!(
+            matches!(
+                update.outcome.as_ref().map_err(PortError::kind),
+                Err(PortError::RateLimited { .. })
+            ),
+            "the throttling is published, got {:?}",
+            update.outcome
+        );
+        refresh.refresh_now();
+        sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(
+            *port.requests.lock().unwrap_or_else(PoisonError::into_inner),
+            1,
+            "neither the interval, the other address nor refresh_now asks again within the hour"
+        );
+        assert!(updates.try_recv().is_err(), "nothing else was loaded");
+    }
+}
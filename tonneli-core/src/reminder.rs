@@ -0,0 +1,204 @@
+//! User-facing settings for pickup reminders.
+
+use std::fmt;
+
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+use crate::model::Fraction;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+/// Settings consumed by the reminder engine.
+pub struct ReminderSettings {
+    /// Master switch for all reminders.
+    pub enabled: bool,
+    /// Per-fraction switches.
+    pub fractions: FractionToggles,
+    /// How many hours before the pickup day starts the reminder fires.
+    pub lead_time_hours: u32,
+    /// Time window in which no reminders are delivered.
+    pub quiet_hours: Option<QuietHours>,
+    /// Channel used to deliver reminders.
+    pub channel: NotificationChannel,
+}
+
+impl Default for ReminderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            fractions: FractionToggles::default(),
+            lead_time_hours: 6,
+            quiet_hours: None,
+            channel: NotificationChannel::Desktop,
+        }
+    }
+}
+
+impl ReminderSettings {
+    /// Whether reminders should be sent for the given fraction.
+    #[must_use]
+    pub fn is_enabled_for(&self, fraction: &Fraction) -> bool {
+        self.enabled && self.fractions.get(fraction)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "one plain switch per fraction keeps the config file readable"
+)]
+/// Reminder switches for each fraction.
+pub struct FractionToggles {
+    /// Residual waste.
+    pub residual: bool,
+    /// Organic waste.
+    pub organic: bool,
+    /// Paper and cardboard.
+    pub paper: bool,
+    /// Light packaging or plastics.
+    pub plastic: bool,
+    /// Glass collection.
+    pub glass: bool,
+    /// Metal scrap.
+    pub metal: bool,
+    /// Street cleaning.
+    pub street_cleaning: bool,
+    /// Bulky waste.
+    pub bulky_waste: bool,
+    /// Garden and green waste.
+    pub garden_waste: bool,
+    /// Hazardous waste.
+    pub hazardous: bool,
+    /// Christmas trees.
+    pub christmas_tree: bool,
+    /// Used clothes and textiles.
+    pub textiles: bool,
+    /// Electrical and electronic equipment.
+    pub electronics: bool,
+    /// Any provider-specific fraction.
+    pub other: bool,
+}
+
+impl Default for FractionToggles {
+    fn default() -> Self {
+        Self {
+            residual: true,
+            organic: true,
+            paper: true,
+            plastic: true,
+            glass: true,
+            metal: true,
+            street_cleaning: true,
+            bulky_waste: true,
+            garden_waste: true,
+            hazardous: true,
+            christmas_tree: true,
+            textiles: true,
+            electronics: true,
+            other: true,
+        }
+    }
+}
+
+impl FractionToggles {
+    /// Switch for the given fraction.
+    #[must_use]
+    pub fn get(&self, fraction: &Fraction) -> bool {
+        match fraction {
+            Fraction::Residual => self.residual,
+            Fraction::Organic => self.organic,
+            Fraction::Paper => self.paper,
+            Fraction::Plastic => self.plastic,
+            Fraction::Glass => self.glass,
+            Fraction::Metal => self.metal,
+            Fraction::StreetCleaning => self.street_cleaning,
+            Fraction::BulkyWaste => self.bulky_waste,
+            Fraction::GardenWaste => self.garden_waste,
+            Fraction::Hazardous => self.hazardous,
+            Fraction::ChristmasTree => self.christmas_tree,
+            Fraction::Textiles => self.textiles,
+            Fraction::Electronics => self.electronics,
+            Fraction::Other(_) => self.other,
+        }
+    }
+
+    /// Mutable switch for the given fraction.
+    pub fn get_mut(&mut self, fraction: &Fraction) -> &mut bool {
+        match fraction {
+            Fraction::Residual => &mut self.residual,
+            Fraction::Organic => &mut self.organic,
+            Fraction::Paper => &mut self.paper,
+            Fraction::Plastic => &mut self.plastic,
+            Fraction::Glass => &mut self.glass,
+            Fraction::Metal => &mut self.metal,
+            Fraction::StreetCleaning => &mut self.street_cleaning,
+            Fraction::BulkyWaste => &mut self.bulky_waste,
+            Fraction::GardenWaste => &mut self.garden_waste,
+            Fraction::Hazardous => &mut self.hazardous,
+            Fraction::ChristmasTree => &mut self.christmas_tree,
+            Fraction::Textiles => &mut self.textiles,
+            Fraction::Electronics => &mut self.electronics,
+            Fraction::Other(_) => &mut self.other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Daily window during which reminders are held back.
+pub struct QuietHours {
+    /// Start of the quiet window.
+    pub start: NaiveTime,
+    /// End of the quiet window; may be earlier than `start` to wrap past midnight.
+    pub end: NaiveTime,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap_or(NaiveTime::MIN),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap_or(NaiveTime::MIN),
+        }
+    }
+}
+
+impl QuietHours {
+    /// Whether `time` falls into the quiet window.
+    #[must_use]
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// Delivery channel for reminders.
+pub enum NotificationChannel {
+    /// Desktop notification.
+    Desktop,
+    /// E-mail.
+    Email,
+    /// HTTP webhook.
+    Webhook,
+}
+
+impl NotificationChannel {
+    /// All channels in display order.
+    pub const ALL: [Self; 3] = [Self::Desktop, Self::Email, Self::Webhook];
+}
+
+impl fmt::Display for NotificationChannel {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Desktop => "desktop",
+            Self::Email => "email",
+            Self::Webhook => "webhook",
+        };
+        write!(formatter, "{name}")
+    }
+}
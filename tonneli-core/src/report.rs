@@ -0,0 +1,173 @@
+//! Hook for forwarding provider errors to monitoring without depending on a vendor.
+
+use std::fmt;
+
+use crate::model::CityId;
+use crate::ports::{AddressSearch, PortError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Service call an error was reported from.
+pub enum Operation {
+    /// Address search, for one or all cities.
+    Search,
+    /// Tagging, upgrading or resolving a persisted address id.
+    AddressId,
+    /// Loading a schedule.
+    Schedule,
+    /// Incrementally refreshing a schedule.
+    Refresh,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Search => "search",
+            Self::AddressId => "address_id",
+            Self::Schedule => "schedule",
+            Self::Refresh => "refresh",
+        };
+        formatter.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Where an error happened, without anything that identifies the user.
+pub struct ErrorContext {
+    /// City whose provider failed.
+    pub city: CityId,
+    /// Service call that failed.
+    pub operation: Operation,
+    /// Shape of the search input for searches, see [`anonymize_query`].
+    pub query: Option<String>,
+}
+
+/// Receives every [`PortError`] produced by the service, including errors of single
+/// providers that a fan-out call recovered from.
+///
+/// Implementations are called on the request path and should hand the error off
+/// quickly, e.g. by bumping a counter or queueing it for a monitoring client.
+pub trait ErrorReporter: Send + Sync {
+    /// Record `error` that occurred in `context`.
+    fn report(&self, error: &PortError, context: &ErrorContext);
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Reporter that discards everything; the service default.
+pub struct NoopReporter;
+
+impl ErrorReporter for NoopReporter {
+    fn report(&self, _error: &PortError, _context: &ErrorContext) {}
+}
+
+/// Shape of a search with its content masked, e.g. `Xxxxxxxx Xxx 99x` for
+/// “Aachener Str 12a”.
+///
+/// Letters become `X`/`x` and digits `9`; whitespace and punctuation are kept, so
+/// reports still show how input was structured without revealing the address.
+#[must_use]
+pub fn anonymize_query(query: &AddressSearch) -> String {
+    let mut parts = vec![mask(&query.street)];
+    parts.extend(query.house_number.as_deref().map(mask));
+    parts.extend(query.house_number_addition.as_deref().map(mask));
+    parts.join(" ")
+}
+
+/// Shape of the street of a search only, e.g. `Xxxxxxxx Xxx` for “Aachener Str 12a”,
+/// for users who want house numbers kept out of reports entirely.
+#[must_use]
+pub fn anonymize_street(query: &AddressSearch) -> String {
+    mask(&query.street)
+}
+
+fn mask(text: &str) -> String {
+    text.trim()
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_digit() {
+                '9'
+            } else if ch.is_uppercase() {
+                'X'
+            } else if ch.is_alphabetic() {
+                'x'
+            } else {
+                ch
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex, PoisonError};
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::plugin::PluginRegistry;
+    use crate::service::TonneliService;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(String, ErrorContext)>>);
+
+    impl ErrorReporter for Recorder {
+        fn report(&self, error: &PortError, context: &ErrorContext) {
+            self.0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push((error.to_string(), context.clone()));
+        }
+    }
+
+    #[test]
+    fn masks_letters_and_digits() {
+        let query = AddressSearch::parse("Aachener Str. 12a");
+        assert_eq!(
+            anonymize_query(&query),
+            "Xxxxxxxx Xxx. 99x",
+            "only the shape of the input remains"
+        );
+        assert_eq!(
+            anonymize_street(&query),
+            "Xxxxxxxx Xxx.",
+            "minimized reports drop the house number"
+        );
+    }
+
+    #[test]
+    fn service_reports_failed_calls_with_context() {
+        let recorder = Arc::new(Recorder::default());
+        let reporter: Arc<dyn ErrorReporter> = Arc::<Recorder>::clone(&recorder);
+        let service = TonneliService::new(Arc::new(PluginRegistry::new(Vec::new())))
+            .with_error_reporter(reporter);
+        let city = CityId("atlantis".to_owned());
+
+        let result = block_on(service.search_addresses(
+            city.clone(),
+            AddressSearch::parse("Hauptstraße 1"),
+            10,
+        ));
+
+        assert_eq!(
+            result.as_ref().err().and_then(PortError::provider),
+            Some(&city),
+            "the returned error names the city"
+        );
+        let reports = recorder
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        assert_eq!(
+            reports,
+            vec![(
+                PortError::UnsupportedCity.to_string(),
+                ErrorContext {
+                    city,
+                    operation: Operation::Search,
+                    query: Some("Xxxxxxxxxxx 9".to_owned()),
+                },
+            )],
+            "the error is reported once with an anonymized query"
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! JSON Schemas describing the models tonneli serializes.
+//!
+//! Integrators can validate stored or exported data against these instead of
+//! reverse-engineering the serde layout; `cargo xtask schema` writes them to disk.
+
+use schemars::{Schema, schema_for};
+
+use crate::model::{Address, CityMeta, PickupEvent};
+use crate::refresh::ScheduleSnapshot;
+
+/// Schemas of the serialized models, as (file stem, schema) pairs.
+#[must_use]
+pub fn schemas() -> Vec<(&'static str, Schema)> {
+    vec![
+        ("address", schema_for!(Address)),
+        ("city-meta", schema_for!(CityMeta)),
+        ("pickup-event", schema_for!(PickupEvent)),
+        ("schedule-snapshot", schema_for!(ScheduleSnapshot)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schemas_list_required_fields() {
+        let schemas = schemas();
+        let (_, event) = schemas
+            .iter()
+            .find(|(name, _)| *name == "pickup-event")
+            .expect("pickup events have a schema");
+        let required = event
+            .get("required")
+            .and_then(|required| required.as_array())
+            .expect("pickup events have required fields");
+        for field in ["date", "fraction"] {
+            assert!(
+                required.iter().any(|value| value == field),
+                "{field} is required"
+            );
+        }
+        assert!(
+            !required.iter().any(|value| value == "container"),
+            "the container defaults to none"
+        );
+    }
+}
@@ -1,21 +1,177 @@
 //! High-level service facade combining all providers.
 
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::model::{Address, AddressId, CityId, DateRange, PickupEvent};
-use crate::plugin::PluginRegistry;
-use crate::ports::{AddressSearch, PortError};
+use chrono::{Days, NaiveDate, TimeDelta};
+use futures::stream::{self, StreamExt};
+/// Audit types of the HTTP layer, for [`ServiceBuilder::request_auditor`].
+pub use tonneli_http::{AuditDetail, AuditEntry, RequestAuditor};
+use tonneli_http::{HttpClient, HttpConfig};
+#[cfg(feature = "tracing")]
+use tracing::instrument;
+
+use crate::acknowledge::ReminderStates;
+use crate::cache::cached;
+use crate::chaos::{ChaosConfig, chaotic};
+use crate::clock::{Clock, SystemClock};
+use crate::fanout::{FanOut, MAX_CONCURRENT_PROVIDER_CALLS, fan_out};
+use crate::favorites::{Favorite, FavoritesError, FavoritesStore};
+use crate::geo::{AreaLocator, GeoPort, GeoQuery};
+use crate::health::{ProviderHealth, probe};
+use crate::holidays::{State, annotate_holidays};
+use crate::household::HouseholdProfile;
+use crate::metrics::{MetricsRecorder, metered};
+use crate::model::{
+    Address, AddressId, CityId, CityMeta, DateRange, IdMigration, PickupDay, PickupEvent,
+    VersionedAddressId,
+};
+use crate::notify::{Delivery, Notification, NotifierPort, Notifiers, due_reminders};
+use crate::overrides::ScheduleOverrides;
+use crate::plugin::{CityPlugin, PluginRegistry};
+use crate::ports::{AddressSearch, PortError, SearchResults};
+use crate::predict::predict_pickups;
+use crate::ratelimit::rate_limited;
+use crate::refresh::{ScheduleSnapshot, merge_refresh, refresh_window};
+use crate::reminder::ReminderSettings;
+use crate::report::{
+    ErrorContext, ErrorReporter, NoopReporter, Operation, anonymize_query, anonymize_street,
+};
+use crate::sources::SourcePolicy;
+#[cfg(feature = "tracing")]
+use crate::trace::traced;
+use crate::validate::{ValidatedSchedule, ValidationWarning, validate_schedule};
+
+/// Days ahead [`TonneliService::next_pickups`] looks; a quarter covers the rarest regular rounds.
+const NEXT_PICKUP_DAYS: u64 = 92;
+
+#[derive(Debug)]
+/// Outcome of loading the schedule of one address, see [`TonneliService::schedules_for_many`].
+pub struct AddressOutcome {
+    /// City the address belongs to.
+    pub city: CityId,
+    /// Address the schedule was requested for.
+    pub address_id: AddressId,
+    /// Validated events, or why loading them failed.
+    pub outcome: Result<Vec<PickupEvent>, PortError>,
+}
 
 /// Public entry point for searching addresses and schedules.
 pub struct TonneliService {
     registry: Arc<PluginRegistry>,
+    reporter: Arc<dyn ErrorReporter>,
+    clock: Arc<dyn Clock>,
+    household: HouseholdProfile,
+    overrides: Arc<ScheduleOverrides>,
+    favorites: FavoritesStore,
+    geo: Arc<dyn GeoPort>,
+    notifiers: Notifiers,
+    minimize_data: bool,
 }
 
 impl TonneliService {
     /// Create a new service bound to the provided registry.
     #[must_use]
     pub fn new(registry: Arc<PluginRegistry>) -> Self {
-        Self { registry }
+        let geo = Arc::new(AreaLocator::from_cities(&registry.cities()));
+        Self {
+            registry,
+            reporter: Arc::new(NoopReporter),
+            clock: Arc::new(SystemClock),
+            household: HouseholdProfile::default(),
+            overrides: Arc::default(),
+            favorites: FavoritesStore::in_memory(),
+            geo,
+            notifiers: Notifiers::new(),
+            minimize_data: false,
+        }
+    }
+
+    /// Hand every provider error to `reporter`, e.g. to count or forward them.
+    #[must_use]
+    pub fn with_error_reporter(mut self, reporter: Arc<dyn ErrorReporter>) -> Self {
+        self.reporter = reporter;
+        self
+    }
+
+    /// Take "today" from `clock` instead of the system time, e.g. to test around New Year.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Leave out pickups of fractions the household has no bin for, in every schedule.
+    #[must_use]
+    pub fn with_household(mut self, household: HouseholdProfile) -> Self {
+        self.household = household;
+        self
+    }
+
+    /// Bins of the household schedules are filtered for.
+    #[must_use]
+    pub fn household(&self) -> &HouseholdProfile {
+        &self.household
+    }
+
+    /// Apply the user's corrections on top of every schedule, see [`ScheduleOverrides::apply`].
+    #[must_use]
+    pub fn with_overrides(mut self, overrides: ScheduleOverrides) -> Self {
+        self.overrides = Arc::new(overrides);
+        self
+    }
+
+    /// Keep the user's saved addresses in `store` instead of in memory only.
+    #[must_use]
+    pub fn with_favorites(mut self, store: FavoritesStore) -> Self {
+        self.favorites = store;
+        self
+    }
+
+    /// Detect cities with `geo` instead of the areas the providers declare.
+    #[must_use]
+    pub fn with_geo_port(mut self, geo: Arc<dyn GeoPort>) -> Self {
+        self.geo = geo;
+        self
+    }
+
+    /// Deliver reminders sent by [`TonneliService::send_reminders`] on `notifiers`.
+    #[must_use]
+    pub fn with_notifiers(mut self, notifiers: Notifiers) -> Self {
+        self.notifiers = notifiers;
+        self
+    }
+
+    /// Leave house numbers out of error reports, see [`anonymize_street`].
+    #[must_use]
+    pub fn with_minimized_data(mut self, minimize: bool) -> Self {
+        self.minimize_data = minimize;
+        self
+    }
+
+    /// Clock the service dates validations and snapshots by.
+    #[must_use]
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.clock)
+    }
+
+    /// Today's date in the time zone of `city`, see [`Clock::today_in`].
+    ///
+    /// Unknown cities get the clock's own date.
+    #[must_use]
+    pub fn today_in(&self, city: &CityId) -> NaiveDate {
+        self.registry.plugin(city).map_or_else(
+            |_| self.clock.today(),
+            |plugin| self.clock.today_in(&plugin.meta),
+        )
+    }
+
+    /// Start configuring a service with a shared HTTP client.
+    #[must_use]
+    pub fn builder() -> ServiceBuilder {
+        ServiceBuilder::default()
     }
 
     /// List all available cities and their display names.
@@ -28,34 +184,1030 @@ impl TonneliService {
             .collect()
     }
 
+    /// Metadata of a registered city, e.g. for its region, time zone or data attribution.
+    #[must_use]
+    pub fn city_meta(&self, city: &CityId) -> Option<CityMeta> {
+        self.registry
+            .plugin(city)
+            .ok()
+            .map(|plugin| plugin.meta.clone())
+    }
+
+    /// Registered city covering a position or postal code, so frontends can skip the city list.
+    ///
+    /// By default this matches the [`CityArea`](crate::model::CityArea)s in the providers'
+    /// metadata, see [`AreaLocator`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PortError::UnsupportedCity`] when no registered city covers the location,
+    /// or the error of a custom [`GeoPort`].
+    pub async fn detect_city(&self, query: &GeoQuery) -> Result<CityId, PortError> {
+        let city = self.geo.locate(query).await?;
+        self.registry.plugin(&city)?;
+        Ok(city)
+    }
+
+    /// Probe every registered provider, see [`AddressPort::health_check`](crate::ports::AddressPort::health_check).
+    ///
+    /// Probes run concurrently, at most [`MAX_CONCURRENT_PROVIDER_CALLS`] at a time;
+    /// the result has one entry per city, sorted by city id. Failed probes are not
+    /// passed to the error reporter, the returned status already carries them.
+    pub async fn provider_health(&self) -> Vec<ProviderHealth> {
+        let mut health = stream::iter(self.registry.plugins())
+            .map(probe)
+            .buffer_unordered(MAX_CONCURRENT_PROVIDER_CALLS)
+            .collect::<Vec<_>>()
+            .await;
+        health.sort_by(|first, second| first.city.0.cmp(&second.city.0));
+        health
+    }
+
+    /// Member towns of a county-level city; empty for single towns.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortError`] if the city is unsupported or the provider call fails.
+    #[cfg_attr(feature = "tracing", instrument(skip_all, fields(city = %city.0), err))]
+    pub async fn towns(&self, city: CityId) -> Result<Vec<String>, PortError> {
+        let result = match self.registry.plugin(&city) {
+            Ok(plugin) => plugin.address_port.towns().await,
+            Err(err) => Err(err),
+        };
+        self.reported(result, &city, Operation::Search, None)
+    }
+
     /// Search for addresses in the given city.
     ///
+    /// Abbreviated street names are spelled out first, see [`AddressSearch::normalized`].
+    ///
     /// # Errors
     ///
     /// Returns a [`PortError`] if the city is unsupported or the provider call fails.
+    #[cfg_attr(feature = "tracing", instrument(skip_all, fields(city = %city.0), err))]
     pub async fn search_addresses(
         &self,
         city: CityId,
         query: AddressSearch,
         limit: usize,
     ) -> Result<Vec<Address>, PortError> {
-        let plugin = self.registry.plugin(&city)?;
-        plugin.address_port.search(&query, limit).await
+        let query = query.normalized();
+        let result = match self.registry.plugin(&city) {
+            Ok(plugin) => plugin.address_port.search(&query, limit).await,
+            Err(err) => Err(err),
+        };
+        self.reported(result, &city, Operation::Search, Some(&query))
+    }
+
+    /// Search for addresses and report whether the provider had more matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortError`] if the city is unsupported or the provider call fails.
+    #[cfg_attr(feature = "tracing", instrument(skip_all, fields(city = %city.0), err))]
+    pub async fn search_addresses_with_status(
+        &self,
+        city: CityId,
+        query: AddressSearch,
+        limit: usize,
+    ) -> Result<SearchResults, PortError> {
+        let query = query.normalized();
+        let result = match self.registry.plugin(&city) {
+            Ok(plugin) => plugin.address_port.search_results(&query, limit).await,
+            Err(err) => Err(err),
+        };
+        self.reported(result, &city, Operation::Search, Some(&query))
+    }
+
+    /// Search all registered cities concurrently.
+    ///
+    /// A failing provider does not fail the call; its error is reported in
+    /// [`FanOut::statuses`] while the other cities' addresses are still returned.
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    pub async fn search_all_cities(&self, query: &AddressSearch, limit: usize) -> FanOut<Address> {
+        let query = &query.clone().normalized();
+        let found = fan_out(self.registry.plugins().map(|plugin| {
+            (
+                plugin.meta.id.clone(),
+                plugin.address_port.search(query, limit),
+            )
+        }))
+        .await;
+        for status in found.failures() {
+            if let Err(err) = &status.outcome {
+                self.report(err, &status.city, Operation::Search, Some(query));
+            }
+        }
+        found
+    }
+
+    /// Tag an address id with its provider's current encoding version for persistence.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PortError::UnsupportedCity`] if no plugin is registered for the city.
+    pub fn versioned_id(
+        &self,
+        city: &CityId,
+        address_id: &AddressId,
+    ) -> Result<VersionedAddressId, PortError> {
+        let result = self.registry.plugin(city).map(|plugin| VersionedAddressId {
+            version: plugin.address_port.id_version(),
+            id: address_id.clone(),
+        });
+        self.reported(result, city, Operation::AddressId, None)
+    }
+
+    /// Upgrade a persisted address id to the provider's current encoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortError`] if the city is unsupported or the provider migration fails.
+    #[cfg_attr(feature = "tracing", instrument(skip_all, fields(city = %city.0), err))]
+    pub async fn migrate_address_id(
+        &self,
+        city: &CityId,
+        stored: &VersionedAddressId,
+    ) -> Result<IdMigration, PortError> {
+        let result = match self.registry.plugin(city) {
+            Ok(plugin) => plugin.address_port.migrate_id(stored).await,
+            Err(err) => Err(err),
+        };
+        self.reported(result, city, Operation::AddressId, None)
+    }
+
+    /// Look up the address behind a stored id, e.g. to show a human-readable label again.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortError`] if the city is unsupported, the provider cannot resolve
+    /// ids ([`PortError::AddressNotFound`]) or the provider request fails.
+    #[cfg_attr(feature = "tracing", instrument(skip_all, fields(city = %city.0), err))]
+    pub async fn resolve_address(
+        &self,
+        city: &CityId,
+        address_id: &AddressId,
+    ) -> Result<Address, PortError> {
+        let result = match self.registry.plugin(city) {
+            Ok(plugin) => plugin.address_port.resolve(address_id).await,
+            Err(err) => Err(err),
+        };
+        self.reported(result, city, Operation::AddressId, None)
+    }
+
+    /// Saved addresses in the order they were added.
+    #[must_use]
+    pub fn favorites(&self) -> Vec<Favorite> {
+        self.favorites.list()
+    }
+
+    /// Save `address` under `label`, replacing a favorite with the same label.
+    ///
+    /// The favorite records the provider's id encoding version, so it can be
+    /// migrated with [`TonneliService::migrate_address_id`] after provider updates.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FavoritesError`] for a blank label or when the favorites cannot be written.
+    pub async fn add_favorite(
+        &self,
+        label: impl Into<String>,
+        address: Address,
+    ) -> Result<Favorite, FavoritesError> {
+        let city = address.city.clone();
+        let mut favorite = Favorite::new(label, city, address);
+        if let Ok(plugin) = self.registry.plugin(&favorite.city) {
+            favorite.id_version = plugin.address_port.id_version();
+        }
+        self.favorites.add(favorite.clone()).await?;
+        Ok(favorite)
+    }
+
+    /// Drop the favorite with `label`, ignoring case; `false` when there was none.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FavoritesError`] when the favorites cannot be written.
+    pub async fn remove_favorite(&self, label: &str) -> Result<bool, FavoritesError> {
+        self.favorites.remove(label).await
     }
 
     /// Load pickup schedule for an address within a date range.
     ///
+    /// Pickups come sorted by date, each pickup once, whichever order and however
+    /// many copies the provider sent; see [`validate_schedule`].
+    ///
     /// # Errors
     ///
     /// Returns a [`PortError`] if the city is unsupported, the address id is invalid,
-    /// or the provider request fails.
+    /// the range ends before it starts ([`PortError::InvalidRange`]) or the provider
+    /// request fails.
     pub async fn schedule_for(
         &self,
         city: CityId,
         address_id: &AddressId,
         range: DateRange,
     ) -> Result<Vec<PickupEvent>, PortError> {
-        let plugin = self.registry.plugin(&city)?;
-        plugin.schedule_port.schedule(address_id, range).await
+        self.validated_schedule_for(city, address_id, range)
+            .await
+            .map(|validated| validated.events)
+    }
+
+    /// Load a pickup schedule and report which provider entries were dropped.
+    ///
+    /// Duplicates and implausible dates are removed and note whitespace is normalized,
+    /// see [`validate_schedule`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TonneliService::schedule_for`].
+    #[cfg_attr(feature = "tracing", instrument(skip_all, fields(city = %city.0), err))]
+    pub async fn validated_schedule_for(
+        &self,
+        city: CityId,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ValidatedSchedule, PortError> {
+        let result = self.load_validated(&city, address_id, range).await;
+        self.reported(result, &city, Operation::Schedule, None)
+    }
+
+    async fn load_validated(
+        &self,
+        city: &CityId,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ValidatedSchedule, PortError> {
+        let range = range.validated()?;
+        let plugin = self.registry.plugin(city)?;
+        let report = plugin
+            .schedule_port
+            .schedule_report(address_id, range)
+            .await?;
+        let mut validated =
+            validate_schedule(report.events, range, self.clock.today_in(&plugin.meta));
+        self.overrides
+            .apply(city, address_id, range, &mut validated.events);
+        self.household.retain_collected(&mut validated.events);
+        if let Some(state) = State::of_city(&plugin.meta) {
+            annotate_holidays(&mut validated.events, state);
+        }
+        validated.warnings.extend(
+            report
+                .malformed_dates
+                .into_iter()
+                .map(|raw| ValidationWarning::MalformedDate { raw }),
+        );
+        Ok(validated)
+    }
+
+    /// Load a pickup schedule and continue each fraction's rhythm where the
+    /// provider's data ends, up to the end of `range`.
+    ///
+    /// Predicted pickups carry [`PickupEvent::predicted`]; see [`predict_pickups`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TonneliService::schedule_for`].
+    pub async fn schedule_with_predictions(
+        &self,
+        city: CityId,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        let mut events = self.schedule_for(city, address_id, range).await?;
+        let predicted = predict_pickups(&events, range.end);
+        events.extend(
+            predicted
+                .into_iter()
+                .filter(|event| range.contains(event.date)),
+        );
+        events.sort_by_key(|event| event.date);
+        Ok(events)
+    }
+
+    /// Load a pickup schedule with the pickups of each day grouped together.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TonneliService::schedule_for`].
+    pub async fn schedule_by_day(
+        &self,
+        city: CityId,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupDay>, PortError> {
+        let events = self.schedule_for(city, address_id, range).await?;
+        Ok(group_by_day(events))
+    }
+
+    /// Next pickup of every fraction collected at the address, in date order.
+    ///
+    /// Looks at the coming three months, starting today; fractions without a pickup
+    /// in that time are missing. Where a fraction is collected from several
+    /// containers on the same day, the first one listed stands for all.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TonneliService::schedule_for`].
+    pub async fn next_pickups(
+        &self,
+        city: CityId,
+        address_id: &AddressId,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        let today = self.today_in(&city);
+        let range = DateRange {
+            start: today,
+            end: today
+                .checked_add_days(Days::new(NEXT_PICKUP_DAYS))
+                .unwrap_or(NaiveDate::MAX),
+        };
+        let events = self.schedule_for(city, address_id, range).await?;
+        Ok(next_per_fraction(events, today))
+    }
+
+    /// Bring a schedule up to date, reusing `snapshot` where nothing can have changed.
+    ///
+    /// Providers that support partial fetches only download the window computed by
+    /// [`refresh_window`]; everything else, or a missing snapshot, triggers a full
+    /// refetch of `range`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TonneliService::schedule_for`].
+    #[cfg_attr(feature = "tracing", instrument(skip_all, fields(city = %city.0), err))]
+    pub async fn refresh_schedule(
+        &self,
+        city: CityId,
+        address_id: &AddressId,
+        snapshot: Option<&ScheduleSnapshot>,
+        range: DateRange,
+    ) -> Result<ScheduleSnapshot, PortError> {
+        let result = self.refresh(&city, address_id, snapshot, range).await;
+        self.reported(result, &city, Operation::Refresh, None)
+    }
+
+    async fn refresh(
+        &self,
+        city: &CityId,
+        address_id: &AddressId,
+        snapshot: Option<&ScheduleSnapshot>,
+        range: DateRange,
+    ) -> Result<ScheduleSnapshot, PortError> {
+        let range = range.validated()?;
+        let plugin = self.registry.plugin(city)?;
+        let today = self.clock.today_in(&plugin.meta);
+
+        let snapshot = snapshot.filter(|_| plugin.schedule_port.supports_partial_fetch());
+        let window = snapshot.map_or(Some(range), |snapshot| refresh_window(snapshot, range));
+        let fresh = if let Some(window) = window {
+            let events = plugin.schedule_port.schedule(address_id, window).await?;
+            let mut fresh = validate_schedule(events, window, today).events;
+            self.overrides.apply(city, address_id, window, &mut fresh);
+            fresh
+        } else {
+            Vec::new()
+        };
+
+        let empty = ScheduleSnapshot {
+            range,
+            fetched_on: today,
+            events: Vec::new(),
+        };
+        let mut refreshed = merge_refresh(snapshot.unwrap_or(&empty), range, window, fresh, today);
+        // Also drops pickups a snapshot kept from before the profile changed.
+        self.household.retain_collected(&mut refreshed.events);
+        if let Some(state) = State::of_city(&plugin.meta) {
+            annotate_holidays(&mut refreshed.events, state);
+        }
+        Ok(refreshed)
+    }
+
+    /// Send a [`Notification::Reminder`] for the pickups at `address` that are due now,
+    /// by the local time of its city.
+    ///
+    /// Loads the schedule up to the end of the lead time and picks the due pickups
+    /// with [`due_reminders`] and the service's household. Nothing is sent, and no
+    /// delivery returned, when no pickup is due; otherwise every configured channel
+    /// gets the reminder, see [`Notifiers::dispatch`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TonneliService::schedule_for`]; failing channels are reported in
+    /// the returned deliveries instead.
+    pub async fn send_reminders(
+        &self,
+        address: &Address,
+        settings: &ReminderSettings,
+        states: &ReminderStates,
+    ) -> Result<Vec<Delivery>, PortError> {
+        if !settings.enabled || self.notifiers.is_empty() {
+            return Ok(Vec::new());
+        }
+        // Quiet hours and lead times are meant in the household's local time.
+        let plugin = self.registry.plugin(&address.city)?;
+        let now = self.clock.now_in(&plugin.meta);
+        let lead_time = TimeDelta::hours(settings.lead_time_hours.into());
+        let range = DateRange {
+            start: now.date(),
+            end: now
+                .checked_add_signed(lead_time)
+                .map_or(NaiveDate::MAX, |until| until.date()),
+        };
+        let events = self
+            .schedule_for(address.city.clone(), &address.id, range)
+            .await?;
+        let due = due_reminders(settings, &self.household, states, address, &events, now);
+        if due.is_empty() {
+            return Ok(Vec::new());
+        }
+        let reminder = Notification::Reminder {
+            address: address.clone(),
+            events: due,
+        };
+        Ok(self.notifiers.dispatch(&reminder).await)
+    }
+
+    /// Load the schedules of several addresses, e.g. home, family and office, with one call.
+    ///
+    /// At most [`MAX_CONCURRENT_PROVIDER_CALLS`] requests run at a time. The result
+    /// has one entry per address, in input order, each with its own outcome.
+    #[cfg_attr(feature = "tracing", instrument(skip_all, fields(addresses = addresses.len())))]
+    pub async fn schedules_for_many(
+        &self,
+        addresses: &[(CityId, AddressId)],
+        range: DateRange,
+    ) -> Vec<AddressOutcome> {
+        stream::iter(addresses)
+            .map(|(city, address_id)| async move {
+                AddressOutcome {
+                    city: city.clone(),
+                    address_id: address_id.clone(),
+                    outcome: self.schedule_for(city.clone(), address_id, range).await,
+                }
+            })
+            .buffered(MAX_CONCURRENT_PROVIDER_CALLS)
+            .collect()
+            .await
+    }
+
+    /// Pass an error on to the reporter and return it with the failing city attached.
+    fn reported<T>(
+        &self,
+        result: Result<T, PortError>,
+        city: &CityId,
+        operation: Operation,
+        query: Option<&AddressSearch>,
+    ) -> Result<T, PortError> {
+        result.map_err(|err| {
+            self.report(&err, city, operation, query);
+            err.with_provider(city)
+        })
+    }
+
+    fn report(
+        &self,
+        error: &PortError,
+        city: &CityId,
+        operation: Operation,
+        query: Option<&AddressSearch>,
+    ) {
+        let context = ErrorContext {
+            city: city.clone(),
+            operation,
+            query: query.map(if self.minimize_data {
+                anonymize_street
+            } else {
+                anonymize_query
+            }),
+        };
+        self.reporter.report(error, &context);
+    }
+}
+
+/// Earliest event on or after `today` for each fraction, in date order.
+#[must_use]
+pub fn next_per_fraction(mut events: Vec<PickupEvent>, today: NaiveDate) -> Vec<PickupEvent> {
+    events.sort_by_key(|event| event.date);
+    let mut seen = HashSet::new();
+    events.retain(|event| event.date >= today && seen.insert(event.fraction.clone()));
+    events
+}
+
+/// Collapse date-sorted events into one entry per day, in date order.
+#[must_use]
+pub fn group_by_day(events: Vec<PickupEvent>) -> Vec<PickupDay> {
+    let mut days: Vec<PickupDay> = Vec::new();
+    for event in events {
+        match days.last_mut() {
+            Some(day) if day.date == event.date => day.events.push(event),
+            _ => days.push(PickupDay {
+                date: event.date,
+                events: vec![event],
+            }),
+        }
+    }
+    days
+}
+
+/// Constructs a provider plugin from the shared HTTP client and the service's clock.
+pub type ProviderFactory = Box<dyn FnOnce(HttpClient, Arc<dyn Clock>) -> CityPlugin>;
+
+/// Builder that creates one configured HTTP client and hands it to every provider.
+#[derive(Default)]
+pub struct ServiceBuilder {
+    http: HttpConfig,
+    providers: Vec<ProviderFactory>,
+    enabled: Option<HashSet<CityId>>,
+    reporter: Option<Arc<dyn ErrorReporter>>,
+    clock: Option<Arc<dyn Clock>>,
+    household: HouseholdProfile,
+    overrides: ScheduleOverrides,
+    chaos: Option<ChaosConfig>,
+    schedule_cache: Option<Duration>,
+    rate_limits: HashMap<CityId, u32>,
+    metrics: Option<Arc<dyn MetricsRecorder>>,
+    policies: HashMap<CityId, SourcePolicy>,
+    auditor: Option<(Arc<dyn RequestAuditor>, AuditDetail)>,
+    geo: Option<Arc<dyn GeoPort>>,
+    notifiers: Notifiers,
+    minimize_data: bool,
+}
+
+impl ServiceBuilder {
+    /// Replace all HTTP settings at once.
+    #[must_use]
+    pub fn http_config(mut self, config: HttpConfig) -> Self {
+        self.http = config;
+        self
+    }
+
+    /// Maximum time to establish a connection to a provider backend.
+    #[must_use]
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.http.connect_timeout = timeout;
+        self
+    }
+
+    /// Largest accepted provider response body in bytes.
+    #[must_use]
+    pub fn max_response_bytes(mut self, limit: usize) -> Self {
+        self.http.max_response_bytes = limit;
+        self
+    }
+
+    /// Longest a single provider fetch may take, including retries.
+    #[must_use]
+    pub fn max_request_duration(mut self, limit: Duration) -> Self {
+        self.http.max_request_duration = limit;
+        self
+    }
+
+    /// Maximum time to wait for data while reading a provider response.
+    #[must_use]
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.http.read_timeout = timeout;
+        self
+    }
+
+    /// `User-Agent` sent to provider backends.
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.http.user_agent = user_agent.into();
+        self
+    }
+
+    /// Route all provider requests through the given proxy URL.
+    #[must_use]
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.http.proxy = Some(url.into());
+        self
+    }
+
+    /// Trust the root certificates in an additional PEM file.
+    #[must_use]
+    pub fn root_certificate(mut self, path: impl Into<PathBuf>) -> Self {
+        self.http.root_certificates.push(path.into());
+        self
+    }
+
+    /// Trust only the certificates added via [`ServiceBuilder::root_certificate`].
+    #[must_use]
+    pub fn only_custom_roots(mut self, only: bool) -> Self {
+        self.http.only_custom_roots = only;
+        self
+    }
+
+    /// Maximum number of idle connections kept per provider host.
+    #[must_use]
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.http.pool_max_idle_per_host = max;
+        self
+    }
+
+    /// Register a provider, e.g. `tonneli_provider_cologne::plugin`.
+    #[must_use]
+    pub fn provider<F>(mut self, factory: F) -> Self
+    where
+        F: FnOnce(HttpClient) -> CityPlugin + 'static,
+    {
+        self.providers
+            .push(Box::new(|client, _clock| factory(client)));
+        self
+    }
+
+    /// Register a provider that also needs the service's [`Clock`], e.g. for the
+    /// current year, such as `tonneli_provider_aachen::plugin_with_clock`.
+    #[must_use]
+    pub fn provider_with_clock<F>(mut self, factory: F) -> Self
+    where
+        F: FnOnce(HttpClient, Arc<dyn Clock>) -> CityPlugin + 'static,
+    {
+        self.providers.push(Box::new(factory));
+        self
+    }
+
+    /// Keep only the providers of `cities` and drop every other registered one.
+    #[must_use]
+    pub fn enabled_cities(mut self, cities: impl IntoIterator<Item = CityId>) -> Self {
+        self.enabled = Some(cities.into_iter().collect());
+        self
+    }
+
+    /// Allow several providers for `city` and combine them according to `policy`.
+    ///
+    /// Without a policy, registering a second provider for a city fails the build.
+    #[must_use]
+    pub fn source_policy(mut self, city: CityId, policy: SourcePolicy) -> Self {
+        self.policies.insert(city, policy);
+        self
+    }
+
+    /// Hand every provider error to `reporter`, see [`ErrorReporter`].
+    #[must_use]
+    pub fn error_reporter<R: ErrorReporter + 'static>(mut self, reporter: R) -> Self {
+        self.reporter = Some(Arc::new(reporter));
+        self
+    }
+
+    /// Take "today" from `clock` instead of the system time, see [`Clock`].
+    #[must_use]
+    pub fn clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// Hide pickups of fractions the household has no bin for, see [`HouseholdProfile`].
+    #[must_use]
+    pub fn household(mut self, household: HouseholdProfile) -> Self {
+        self.household = household;
+        self
+    }
+
+    /// Correct provider schedules with the user's overrides, see [`ScheduleOverrides`].
+    #[must_use]
+    pub fn overrides(mut self, overrides: ScheduleOverrides) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Detect cities with `geo`, e.g. an online geocoder, see [`TonneliService::detect_city`].
+    #[must_use]
+    pub fn geo_port<G: GeoPort + 'static>(mut self, geo: G) -> Self {
+        self.geo = Some(Arc::new(geo));
+        self
+    }
+
+    /// Also deliver reminders on `channel`, see [`TonneliService::send_reminders`].
+    #[must_use]
+    pub fn notifier<N: NotifierPort + 'static>(mut self, channel: N) -> Self {
+        self.notifiers = self.notifiers.with(Arc::new(channel));
+        self
+    }
+
+    /// Record every request sent to a provider, see [`RequestAuditor`].
+    #[must_use]
+    pub fn request_auditor<A: RequestAuditor + 'static>(
+        mut self,
+        auditor: A,
+        detail: AuditDetail,
+    ) -> Self {
+        self.auditor = Some((Arc::new(auditor), detail));
+        self
+    }
+
+    /// Keep house numbers out of error reports, see [`TonneliService::with_minimized_data`],
+    /// and query strings out of HTTP logs and transport errors.
+    #[must_use]
+    pub fn minimize_data(mut self, minimize: bool) -> Self {
+        self.minimize_data = minimize;
+        self.http.redact_queries = minimize;
+        self
+    }
+
+    /// Inject the failures described by `config` into every provider, see [`chaotic`].
+    #[must_use]
+    pub fn chaos(mut self, config: ChaosConfig) -> Self {
+        self.chaos = Some(config);
+        self
+    }
+
+    /// Hand every provider call to `recorder`, see [`metered`].
+    #[must_use]
+    pub fn metrics_recorder<R: MetricsRecorder + 'static>(mut self, recorder: R) -> Self {
+        self.metrics = Some(Arc::new(recorder));
+        self
+    }
+
+    /// Let at most `requests_per_minute` calls through to the backend of `city`, see
+    /// [`rate_limited`]; calls beyond fail with [`PortError::RateLimited`].
+    #[must_use]
+    pub fn rate_limit(mut self, city: CityId, requests_per_minute: u32) -> Self {
+        self.rate_limits.insert(city, requests_per_minute);
+        self
+    }
+
+    /// Reuse each provider's schedules for `ttl` after fetching them, see [`cached`].
+    #[must_use]
+    pub fn schedule_cache(mut self, ttl: Duration) -> Self {
+        self.schedule_cache = Some(ttl);
+        self
+    }
+
+    /// Create the HTTP client and the service.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortError`] when the HTTP client cannot be initialized, or
+    /// [`PortError::Registry`] when providers share a city id or lack metadata.
+    pub fn build(self) -> Result<TonneliService, PortError> {
+        let mut client = HttpClient::new(&self.http)?;
+        if let Some((auditor, detail)) = self.auditor {
+            client = client.with_auditor(auditor, detail);
+        }
+        let clock = self.clock.unwrap_or_else(|| Arc::new(SystemClock));
+        let plugins = self
+            .providers
+            .into_iter()
+            .map(|factory| factory(client.clone(), Arc::clone(&clock)))
+            .filter(|plugin| {
+                self.enabled
+                    .as_ref()
+                    .is_none_or(|enabled| enabled.contains(&plugin.meta.id))
+            });
+        // Innermost, so spans and metrics time the provider itself and skip cache hits.
+        #[cfg(feature = "tracing")]
+        let plugins = plugins.map(traced);
+        let plugins = plugins
+            .map(|plugin| match &self.metrics {
+                Some(recorder) => metered(plugin, Arc::clone(recorder)),
+                None => plugin,
+            })
+            .map(|plugin| match self.chaos {
+                Some(config) => chaotic(plugin, config),
+                None => plugin,
+            })
+            .map(|plugin| match self.rate_limits.get(&plugin.meta.id) {
+                Some(per_minute) => rate_limited(plugin, *per_minute),
+                None => plugin,
+            })
+            .map(|plugin| match self.schedule_cache {
+                Some(ttl) => cached(plugin, ttl),
+                None => plugin,
+            })
+            .collect::<Vec<_>>();
+        let mut service = TonneliService::new(Arc::new(PluginRegistry::try_with_policies(
+            plugins,
+            &self.policies,
+        )?));
+        if let Some(reporter) = self.reporter {
+            service = service.with_error_reporter(reporter);
+        }
+        if let Some(geo) = self.geo {
+            service = service.with_geo_port(geo);
+        }
+        Ok(service
+            .with_clock(clock)
+            .with_household(self.household)
+            .with_overrides(self.overrides)
+            .with_notifiers(self.notifiers)
+            .with_minimized_data(self.minimize_data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Mutex, PoisonError};
+
+    use async_trait::async_trait;
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::model::Fraction;
+    use crate::notify::NotifyError;
+    use crate::ports::{AddressPort, SchedulePort};
+
+    fn pickup(day: u32, fraction: Fraction) -> PickupEvent {
+        PickupEvent {
+            date: NaiveDate::from_ymd_opt(2025, 3, day).expect("valid test date"),
+            fraction,
+            note: None,
+            container: None,
+            correction: None,
+            holiday: None,
+            predicted: false,
+        }
+    }
+
+    #[test]
+    fn keeps_the_next_pickup_of_each_fraction() {
+        let events = vec![
+            pickup(20, Fraction::Paper),
+            pickup(3, Fraction::Residual),
+            pickup(10, Fraction::Residual),
+            pickup(12, Fraction::Paper),
+            pickup(17, Fraction::Residual),
+            pickup(11, Fraction::Other("Sperrmüll".into())),
+        ];
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).expect("valid test date");
+
+        let next = next_per_fraction(events, today)
+            .into_iter()
+            .map(|event| (event.date.to_string(), event.fraction))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            next,
+            [
+                ("2025-03-10".to_owned(), Fraction::Residual),
+                ("2025-03-11".to_owned(), Fraction::Other("Sperrmüll".into())),
+                ("2025-03-12".to_owned(), Fraction::Paper),
+            ],
+            "past pickups are skipped, today counts, one per fraction in date order"
+        );
+    }
+
+    struct Fixed(CityMeta);
+
+    #[async_trait]
+    impl AddressPort for Fixed {
+        fn city(&self) -> &CityMeta {
+            &self.0
+        }
+
+        async fn search(&self, _: &AddressSearch, _: usize) -> Result<Vec<Address>, PortError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[async_trait]
+    impl SchedulePort for Fixed {
+        fn city(&self) -> &CityMeta {
+            &self.0
+        }
+
+        async fn schedule(
+            &self,
+            _: &AddressId,
+            range: DateRange,
+        ) -> Result<Vec<PickupEvent>, PortError> {
+            Ok(
+                [pickup(10, Fraction::Paper), pickup(11, Fraction::Residual)]
+                    .into_iter()
+                    .filter(|event| range.contains(event.date))
+                    .collect(),
+            )
+        }
+    }
+
+    fn fixed_service() -> TonneliService {
+        let port = Arc::new(Fixed(
+            CityMeta::new("cologne", "Köln").with_locale("DE", "Europe/Berlin"),
+        ));
+        let plugin = CityPlugin {
+            meta: port.0.clone(),
+            address_port: Arc::<Fixed>::clone(&port),
+            schedule_port: port,
+        };
+        TonneliService::new(Arc::new(PluginRegistry::new(vec![plugin])))
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl NotifierPort for Recorder {
+        fn name(&self) -> &'static str {
+            "recorder"
+        }
+
+        async fn send(&self, notification: &Notification) -> Result<(), NotifyError> {
+            self.sent
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(notification.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn sends_reminders_for_due_pickups() {
+        let recorder = Arc::new(Recorder::default());
+        let service = |hour| {
+            let now = NaiveDate::from_ymd_opt(2025, 3, 9)
+                .and_then(|day| day.and_hms_opt(hour, 0, 0))
+                .expect("valid test time");
+            fixed_service()
+                .with_clock(Arc::new(FixedClock::new(now)))
+                .with_notifiers(Notifiers::new().with(Arc::<Recorder>::clone(&recorder)))
+        };
+        let address = Address {
+            id: AddressId("1".to_owned()),
+            city: CityId("cologne".to_owned()),
+            label: "Domkloster 4".into(),
+            street: "Domkloster".into(),
+            house_number: "4".into(),
+        };
+        let settings = ReminderSettings {
+            enabled: true,
+            lead_time_hours: 6,
+            ..ReminderSettings::default()
+        };
+        let states = ReminderStates::default();
+
+        let early = block_on(service(12).send_reminders(&address, &settings, &states))
+            .expect("the schedule loads");
+        assert!(early.is_empty(), "nothing is due before the lead time");
+
+        // 17:00 UTC is 18:00 in Cologne, six hours before the pickup day starts there.
+        let deliveries = block_on(service(17).send_reminders(&address, &settings, &states))
+            .expect("the schedule loads");
+        assert!(
+            deliveries.iter().all(|delivery| delivery.outcome.is_ok()),
+            "the reminder is delivered"
+        );
+        let sent = recorder
+            .sent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let Some(Notification::Reminder { events, .. }) = sent.first() else {
+            unreachable!("exactly one reminder is sent, got {sent:?}");
+        };
+        assert_eq!(sent.len(), 1, "one reminder per call with due pickups");
+        assert_eq!(
+            events.iter().map(|event| event.date).collect::<Vec<_>>(),
+            [pickup(10, Fraction::Paper).date],
+            "only the pickup within the lead time is due"
+        );
+    }
+
+    #[test]
+    fn batches_keep_one_outcome_per_address() {
+        let city = |id: &str| CityId(id.to_owned());
+        let addresses = [
+            (city("cologne"), AddressId("home".to_owned())),
+            (city("atlantis"), AddressId("parents".to_owned())),
+            (city("cologne"), AddressId("office".to_owned())),
+        ];
+        let range = DateRange {
+            start: pickup(1, Fraction::Paper).date,
+            end: pickup(31, Fraction::Paper).date,
+        };
+
+        let outcomes = block_on(fixed_service().schedules_for_many(&addresses, range));
+        let summary = outcomes
+            .iter()
+            .map(|outcome| {
+                (
+                    outcome.address_id.0.as_str(),
+                    outcome.outcome.as_ref().map(Vec::len).ok(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [("home", Some(2)), ("parents", None), ("office", Some(2))],
+            "every address gets its own outcome, in input order"
+        );
+    }
+
+    #[test]
+    fn same_day_pickups_share_one_group() {
+        let days = group_by_day(vec![
+            pickup(10, Fraction::Paper),
+            pickup(10, Fraction::Glass),
+            pickup(11, Fraction::Residual),
+        ]);
+
+        assert_eq!(
+            days.iter()
+                .map(|day| (day.date.to_string(), day.events.len()))
+                .collect::<Vec<_>>(),
+            [("2025-03-10".to_owned(), 2), ("2025-03-11".to_owned(), 1)],
+            "one group per day, in date order"
+        );
     }
 }
@@ -0,0 +1,518 @@
+//! Several providers serving one city, combined into a single plugin.
+//!
+//! Some cities have separate operators for household waste and paper or glass, or
+//! an official API plus a calendar export to fall back on. [`combine`] wraps such
+//! plugins so the registry and the service see one city with one set of ports.
+//!
+//! Addresses are matched across providers by their folded street and house number.
+//! The combined [`AddressId`] lists the id of every provider that knows the address,
+//! so a schedule request reaches each of them with its own id.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::future::join_all;
+
+use crate::matching::fold;
+use crate::merge::merge_events;
+use crate::model::{Address, AddressId, CityMeta, DateRange, PickupEvent};
+use crate::plugin::{CityPlugin, RegistryError};
+use crate::ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport};
+
+/// Separates the per-provider parts of a combined address id.
+const PART_SEPARATOR: char = '|';
+/// Separates a provider's position from its id within one part.
+const INDEX_SEPARATOR: char = ':';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// How the providers of one city are combined.
+pub enum SourcePolicy {
+    /// Load schedules from the providers in registration order and use the first that
+    /// answers.
+    #[default]
+    Fallback,
+    /// Ask every provider and merge their results, e.g. for separate operators.
+    ///
+    /// A failing provider is skipped as long as another one answers.
+    Merge,
+}
+
+/// Combine plugins registered for the same city into one plugin.
+///
+/// The first plugin provides the city's metadata and comes first in
+/// [`SourcePolicy::Fallback`].
+///
+/// # Errors
+///
+/// Returns [`RegistryError::EmptyId`] for an empty list and
+/// [`RegistryError::ConflictingAlias`] when the plugins are for different cities.
+pub fn combine(
+    policy: SourcePolicy,
+    mut plugins: Vec<CityPlugin>,
+) -> Result<CityPlugin, RegistryError> {
+    let Some(first) = plugins.first() else {
+        return Err(RegistryError::EmptyId);
+    };
+    let meta = first.meta.clone();
+    if let Some(other) = plugins.iter().find(|plugin| plugin.meta.id != meta.id) {
+        return Err(RegistryError::ConflictingAlias {
+            first: meta.id,
+            second: other.meta.id.clone(),
+        });
+    }
+    if plugins.len() == 1
+        && let Some(plugin) = plugins.pop()
+    {
+        return Ok(plugin);
+    }
+
+    let (address_ports, schedule_ports) = plugins
+        .into_iter()
+        .map(|plugin| (plugin.address_port, plugin.schedule_port))
+        .unzip();
+    Ok(CityPlugin {
+        meta: meta.clone(),
+        address_port: Arc::new(CombinedAddressPort {
+            meta: meta.clone(),
+            ports: address_ports,
+        }),
+        schedule_port: Arc::new(CombinedSchedulePort {
+            meta,
+            policy,
+            ports: schedule_ports,
+        }),
+    })
+}
+
+/// Address search over all providers of a city.
+///
+/// Every policy asks all providers, so a fallback provider's id is known before the
+/// primary one fails.
+struct CombinedAddressPort {
+    meta: CityMeta,
+    ports: Vec<Arc<dyn AddressPort>>,
+}
+
+#[async_trait]
+impl AddressPort for CombinedAddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        let results = join_all(self.ports.iter().map(|port| port.search(query, limit))).await;
+        let found = collect_answers(results)?;
+
+        let mut addresses = group_addresses(found);
+        addresses.truncate(limit);
+        Ok(addresses)
+    }
+
+    async fn towns(&self) -> Result<Vec<String>, PortError> {
+        let results = join_all(self.ports.iter().map(|port| port.towns())).await;
+        let mut towns = Vec::<String>::new();
+        for town in collect_answers(results)?
+            .into_iter()
+            .flat_map(|(_, found)| found)
+        {
+            if !towns.contains(&town) {
+                towns.push(town);
+            }
+        }
+        Ok(towns)
+    }
+
+    /// The address as the first provider that knows it describes it, under the combined id.
+    async fn resolve(&self, address_id: &AddressId) -> Result<Address, PortError> {
+        let mut first_error = None;
+        for (index, id) in decode_id(address_id)? {
+            let port = self.ports.get(index).ok_or(PortError::InvalidAddressId)?;
+            match port.resolve(&id).await {
+                Ok(address) => {
+                    return Ok(Address {
+                        id: address_id.clone(),
+                        ..address
+                    });
+                }
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        Err(first_error.unwrap_or(PortError::InvalidAddressId))
+    }
+
+    /// Up when any provider with a probe answers, like a search would still succeed.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        let probes = join_all(self.ports.iter().map(|port| port.health_check())).await;
+        let mut first_error = None;
+        for probe in probes.into_iter().flatten() {
+            match probe {
+                Ok(()) => return Some(Ok(())),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        first_error.map(Err)
+    }
+}
+
+/// Schedules of all providers of a city.
+struct CombinedSchedulePort {
+    meta: CityMeta,
+    policy: SourcePolicy,
+    ports: Vec<Arc<dyn SchedulePort>>,
+}
+
+#[async_trait]
+impl SchedulePort for CombinedSchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let parts = decode_id(address_id)?
+            .into_iter()
+            .map(|(index, id)| {
+                self.ports
+                    .get(index)
+                    .map(|port| (port, id))
+                    .ok_or(PortError::InvalidAddressId)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let reports = match self.policy {
+            SourcePolicy::Fallback => {
+                let mut first_error = None;
+                let mut answer = None;
+                for (port, id) in parts {
+                    match port.schedule_report(&id, range).await {
+                        Ok(report) => {
+                            answer = Some(report);
+                            break;
+                        }
+                        Err(err) => {
+                            first_error.get_or_insert(err);
+                        }
+                    }
+                }
+                match (answer, first_error) {
+                    (Some(report), _) => vec![report],
+                    (None, err) => return Err(err.unwrap_or(PortError::InvalidAddressId)),
+                }
+            }
+            SourcePolicy::Merge => {
+                let results = join_all(
+                    parts
+                        .iter()
+                        .map(|(port, id)| port.schedule_report(id, range)),
+                )
+                .await;
+                collect_answers(results)?
+                    .into_iter()
+                    .map(|(_, report)| report)
+                    .collect()
+            }
+        };
+
+        let (events, malformed_dates): (Vec<_>, Vec<_>) = reports
+            .into_iter()
+            .map(|report| (report.events, report.malformed_dates))
+            .unzip();
+        Ok(ScheduleReport {
+            events: merge_events(events),
+            malformed_dates: malformed_dates.into_iter().flatten().collect(),
+        })
+    }
+
+    fn supports_partial_fetch(&self) -> bool {
+        self.ports.iter().all(|port| port.supports_partial_fetch())
+    }
+}
+
+/// Successful answers with the position of their provider, or the first error if none answered.
+fn collect_answers<T>(results: Vec<Result<T, PortError>>) -> Result<Vec<(usize, T)>, PortError> {
+    let mut first_error = None;
+    let mut answers = Vec::new();
+    for (index, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(answer) => answers.push((index, answer)),
+            Err(err) => {
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+    match first_error {
+        Some(err) if answers.is_empty() => Err(err),
+        _ => Ok(answers),
+    }
+}
+
+/// Merge addresses found by several providers, giving each a combined id.
+///
+/// Addresses keep the order and label of the provider that found them first.
+fn group_addresses(found: Vec<(usize, Vec<Address>)>) -> Vec<Address> {
+    let mut grouped = Vec::<(Address, Vec<(usize, AddressId)>)>::new();
+    let mut positions = HashMap::new();
+
+    for (index, addresses) in found {
+        for address in addresses {
+            let key = (fold(&address.street), fold(&address.house_number));
+            let part = (index, address.id.clone());
+            match positions.entry(key) {
+                Entry::Vacant(slot) => {
+                    slot.insert(grouped.len());
+                    grouped.push((address, vec![part]));
+                }
+                Entry::Occupied(slot) => {
+                    if let Some((_, parts)) = grouped.get_mut(*slot.get()) {
+                        parts.push(part);
+                    }
+                }
+            }
+        }
+    }
+
+    grouped
+        .into_iter()
+        .map(|(address, parts)| Address {
+            id: encode_id(&parts),
+            ..address
+        })
+        .collect()
+}
+
+/// Join `(provider position, provider id)` pairs into one id, e.g. `0:4711|1:A-12`.
+fn encode_id(parts: &[(usize, AddressId)]) -> AddressId {
+    let encoded = parts
+        .iter()
+        .map(|(index, id)| format!("{index}{INDEX_SEPARATOR}{}", escape(&id.0)))
+        .collect::<Vec<_>>();
+    AddressId(encoded.join(&PART_SEPARATOR.to_string()))
+}
+
+fn decode_id(id: &AddressId) -> Result<Vec<(usize, AddressId)>, PortError> {
+    id.0.split(PART_SEPARATOR)
+        .map(|part| {
+            let (index, inner) = part
+                .split_once(INDEX_SEPARATOR)
+                .ok_or(PortError::InvalidAddressId)?;
+            let index = index.parse().map_err(|_err| PortError::InvalidAddressId)?;
+            Ok((index, AddressId(unescape(inner)?)))
+        })
+        .collect()
+}
+
+fn escape(id: &str) -> String {
+    id.replace('%', "%25").replace(PART_SEPARATOR, "%7C")
+}
+
+fn unescape(id: &str) -> Result<String, PortError> {
+    let mut unescaped = String::with_capacity(id.len());
+    let mut rest = id;
+    while let Some(position) = rest.find('%') {
+        let (before, escaped) = rest.split_at(position);
+        unescaped.push_str(before);
+        let replacement = match escaped.get(..3) {
+            Some("%25") => '%',
+            Some("%7C") => PART_SEPARATOR,
+            _ => return Err(PortError::InvalidAddressId),
+        };
+        unescaped.push(replacement);
+        rest = escaped.get(3..).unwrap_or_default();
+    }
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::model::Fraction;
+
+    /// Provider knowing one address, with a single pickup of `fraction` on March 10.
+    struct Operator {
+        meta: CityMeta,
+        id: &'static str,
+        fraction: Option<Fraction>,
+    }
+
+    #[async_trait]
+    impl AddressPort for Operator {
+        fn city(&self) -> &CityMeta {
+            &self.meta
+        }
+
+        async fn search(&self, _: &AddressSearch, _: usize) -> Result<Vec<Address>, PortError> {
+            Ok(vec![self.address()])
+        }
+
+        async fn resolve(&self, address_id: &AddressId) -> Result<Address, PortError> {
+            if address_id.0 != self.id {
+                return Err(PortError::InvalidAddressId);
+            }
+            self.fraction.as_ref().ok_or(PortError::Timeout)?;
+            Ok(self.address())
+        }
+    }
+
+    impl Operator {
+        fn address(&self) -> Address {
+            Address {
+                id: AddressId(self.id.to_owned()),
+                city: self.meta.id.clone(),
+                label: Arc::from("Markt 1"),
+                street: Arc::from("Markt"),
+                house_number: Arc::from("1"),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SchedulePort for Operator {
+        fn city(&self) -> &CityMeta {
+            &self.meta
+        }
+
+        async fn schedule(
+            &self,
+            address_id: &AddressId,
+            _: DateRange,
+        ) -> Result<Vec<PickupEvent>, PortError> {
+            if address_id.0 != self.id {
+                return Err(PortError::InvalidAddressId);
+            }
+            let fraction = self.fraction.clone().ok_or(PortError::Timeout)?;
+            Ok(vec![PickupEvent {
+                date: march(10),
+                fraction,
+                note: None,
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            }])
+        }
+    }
+
+    fn march(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).expect("valid test date")
+    }
+
+    fn operator(id: &'static str, fraction: Option<Fraction>) -> CityPlugin {
+        let meta = CityMeta::new("aachen", "Aachen");
+        let port = Arc::new(Operator {
+            meta: meta.clone(),
+            id,
+            fraction,
+        });
+        CityPlugin {
+            meta,
+            address_port: Arc::<Operator>::clone(&port),
+            schedule_port: port,
+        }
+    }
+
+    fn fractions(policy: SourcePolicy, plugins: Vec<CityPlugin>) -> Vec<Fraction> {
+        let plugin = combine(policy, plugins).expect("plugins of one city combine");
+        let query = AddressSearch::parse("Markt 1");
+        let addresses = block_on(plugin.address_port.search(&query, 10)).expect("search succeeds");
+        let [address] = addresses.as_slice() else {
+            return Vec::new();
+        };
+        let range = DateRange {
+            start: march(1),
+            end: march(31),
+        };
+        block_on(plugin.schedule_port.schedule(&address.id, range))
+            .expect("schedule succeeds")
+            .into_iter()
+            .map(|event| event.fraction)
+            .collect()
+    }
+
+    #[test]
+    fn merge_combines_operators_of_one_address() {
+        let plugins = vec![
+            operator("rest|1", Some(Fraction::Residual)),
+            operator("paper%1", Some(Fraction::Paper)),
+            operator("glass", None),
+        ];
+        assert_eq!(
+            fractions(SourcePolicy::Merge, plugins),
+            [Fraction::Residual, Fraction::Paper],
+            "both answering operators contribute, the failing one is skipped"
+        );
+    }
+
+    #[test]
+    fn fallback_uses_the_first_answering_provider() {
+        let plugins = vec![
+            operator("api", None),
+            operator("ics", Some(Fraction::Organic)),
+            operator("other", Some(Fraction::Paper)),
+        ];
+        assert_eq!(
+            fractions(SourcePolicy::Fallback, plugins),
+            [Fraction::Organic],
+            "the failing primary is skipped and later providers are not asked"
+        );
+    }
+
+    #[test]
+    fn resolve_keeps_the_combined_id() {
+        let plugin = combine(
+            SourcePolicy::Fallback,
+            vec![
+                operator("api", None),
+                operator("ics", Some(Fraction::Organic)),
+            ],
+        )
+        .expect("plugins of one city combine");
+        let query = AddressSearch::parse("Markt 1");
+        let address = block_on(plugin.address_port.search(&query, 10))
+            .expect("search succeeds")
+            .pop()
+            .expect("both providers know the address");
+
+        let resolved = block_on(plugin.address_port.resolve(&address.id))
+            .expect("the second provider answers");
+        assert_eq!(resolved.id, address.id, "the combined id is kept");
+        assert_eq!(&*resolved.label, "Markt 1", "the provider's label is used");
+    }
+
+    #[test]
+    fn ids_survive_encoding() {
+        let parts = vec![
+            (0, AddressId("a|b%7C".to_owned())),
+            (2, AddressId(String::new())),
+        ];
+        assert_eq!(
+            decode_id(&encode_id(&parts)).ok(),
+            Some(parts),
+            "separators inside provider ids are escaped"
+        );
+        assert!(
+            decode_id(&AddressId("4711".to_owned())).is_err(),
+            "plain provider ids are rejected"
+        );
+    }
+}
@@ -0,0 +1,566 @@
+//! Key-value persistence for favorites, caches, acknowledgments and history.
+//!
+//! Values are opaque blobs grouped by namespace, e.g. `favorites` or
+//! `schedule-cache`; callers choose their own encoding. [`MemoryStorage`] keeps
+//! everything in memory, `SqliteStorage` (feature `sqlite`) in a database file.
+//! `EncryptedStorage` (feature `encryption`) encrypts the values of any backend.
+
+use std::collections::BTreeMap;
+use std::error::Error as StdError;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use async_trait::async_trait;
+
+#[derive(thiserror::Error, Debug)]
+/// Errors reported by storage backends.
+pub enum StorageError {
+    /// Namespaces and keys must not be empty.
+    #[error("Storage namespace and key must not be empty")]
+    EmptyKey,
+    /// The backend failed to read or write.
+    #[error("Storage backend failed: {0}")]
+    Backend(#[source] Box<dyn StdError + Send + Sync>),
+    /// A stored value does not decrypt with the key, or was tampered with.
+    #[error("Stored value cannot be decrypted; wrong key or modified data")]
+    Decryption,
+    /// The encryption key cannot be read, created or derived.
+    #[error("Storage key unusable: {0}")]
+    Key(String),
+}
+
+#[async_trait]
+/// Persistence backend storing blobs under a namespace and key.
+pub trait StoragePort: Send + Sync {
+    /// Value stored under `key`, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] when the backend cannot be read.
+    async fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// Store `value` under `key`, replacing any previous value.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] for empty names or when the backend cannot be written.
+    async fn put(&self, namespace: &str, key: &str, value: &[u8]) -> Result<(), StorageError>;
+
+    /// Remove `key`, returning whether it existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] when the backend cannot be written.
+    async fn delete(&self, namespace: &str, key: &str) -> Result<bool, StorageError>;
+
+    /// All keys of `namespace` in ascending order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] when the backend cannot be read.
+    async fn list(&self, namespace: &str) -> Result<Vec<String>, StorageError>;
+}
+
+fn check_names(namespace: &str, key: &str) -> Result<(), StorageError> {
+    if namespace.is_empty() || key.is_empty() {
+        return Err(StorageError::EmptyKey);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+/// Storage that lives as long as the process, e.g. for tests or ephemeral sessions.
+pub struct MemoryStorage {
+    blobs: Mutex<BTreeMap<(String, String), Vec<u8>>>,
+}
+
+impl MemoryStorage {
+    fn blobs(&self) -> MutexGuard<'_, BTreeMap<(String, String), Vec<u8>>> {
+        self.blobs.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait]
+impl StoragePort for MemoryStorage {
+    async fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self
+            .blobs()
+            .get(&(namespace.to_owned(), key.to_owned()))
+            .cloned())
+    }
+
+    async fn put(&self, namespace: &str, key: &str, value: &[u8]) -> Result<(), StorageError> {
+        check_names(namespace, key)?;
+        self.blobs()
+            .insert((namespace.to_owned(), key.to_owned()), value.to_vec());
+        Ok(())
+    }
+
+    async fn delete(&self, namespace: &str, key: &str) -> Result<bool, StorageError> {
+        Ok(self
+            .blobs()
+            .remove(&(namespace.to_owned(), key.to_owned()))
+            .is_some())
+    }
+
+    async fn list(&self, namespace: &str) -> Result<Vec<String>, StorageError> {
+        Ok(self
+            .blobs()
+            .keys()
+            .filter(|(space, _)| space == namespace)
+            .map(|(_, key)| key.clone())
+            .collect())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStorage;
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::path::Path;
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
+    use async_trait::async_trait;
+    use rusqlite::{Connection, OptionalExtension, params};
+
+    use super::{StorageError, StoragePort, check_names};
+
+    impl From<rusqlite::Error> for StorageError {
+        fn from(err: rusqlite::Error) -> Self {
+            Self::Backend(Box::new(err))
+        }
+    }
+
+    /// Storage in a single `SQLite` table.
+    ///
+    /// Statements run on the calling thread; they are small enough not to stall an
+    /// async runtime noticeably.
+    #[derive(Debug)]
+    pub struct SqliteStorage {
+        connection: Mutex<Connection>,
+    }
+
+    impl SqliteStorage {
+        /// Open or create the database at `path`.
+        ///
+        /// # Errors
+        ///
+        /// Returns a [`StorageError`] when the file cannot be opened or initialized.
+        pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+            Self::init(Connection::open(path)?)
+        }
+
+        /// Database that is discarded when dropped.
+        ///
+        /// # Errors
+        ///
+        /// Returns a [`StorageError`] when `SQLite` cannot be initialized.
+        pub fn in_memory() -> Result<Self, StorageError> {
+            Self::init(Connection::open_in_memory()?)
+        }
+
+        fn init(connection: Connection) -> Result<Self, StorageError> {
+            connection.execute_batch(
+                "CREATE TABLE IF NOT EXISTS blobs (
+                    namespace TEXT NOT NULL,
+                    key TEXT NOT NULL,
+                    value BLOB NOT NULL,
+                    PRIMARY KEY (namespace, key)
+                ) WITHOUT ROWID;",
+            )?;
+            Ok(Self {
+                connection: Mutex::new(connection),
+            })
+        }
+
+        fn connection(&self) -> MutexGuard<'_, Connection> {
+            self.connection
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    #[async_trait]
+    impl StoragePort for SqliteStorage {
+        async fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+            Ok(self
+                .connection()
+                .query_row(
+                    "SELECT value FROM blobs WHERE namespace = ?1 AND key = ?2",
+                    params![namespace, key],
+                    |row| row.get(0),
+                )
+                .optional()?)
+        }
+
+        async fn put(&self, namespace: &str, key: &str, value: &[u8]) -> Result<(), StorageError> {
+            check_names(namespace, key)?;
+            self.connection().execute(
+                "INSERT INTO blobs (namespace, key, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (namespace, key) DO UPDATE SET value = excluded.value",
+                params![namespace, key, value],
+            )?;
+            Ok(())
+        }
+
+        async fn delete(&self, namespace: &str, key: &str) -> Result<bool, StorageError> {
+            let removed = self.connection().execute(
+                "DELETE FROM blobs WHERE namespace = ?1 AND key = ?2",
+                params![namespace, key],
+            )?;
+            Ok(removed > 0)
+        }
+
+        async fn list(&self, namespace: &str) -> Result<Vec<String>, StorageError> {
+            let connection = self.connection();
+            let mut statement =
+                connection.prepare("SELECT key FROM blobs WHERE namespace = ?1 ORDER BY key")?;
+            let keys = statement
+                .query_map(params![namespace], |row| row.get(0))?
+                .collect::<Result<_, _>>()?;
+            Ok(keys)
+        }
+    }
+}
+
+#[cfg(feature = "encryption")]
+pub use encrypted::{EncryptedStorage, StorageKey};
+
+#[cfg(feature = "encryption")]
+mod encrypted {
+    use std::fmt;
+    use std::fs::{self, OpenOptions};
+    use std::io::{ErrorKind, Write as _};
+    #[cfg(unix)]
+    use std::os::unix::fs::OpenOptionsExt as _;
+    use std::path::Path;
+
+    use argon2::Argon2;
+    use async_trait::async_trait;
+    use chacha20poly1305::aead::rand_core::RngCore as _;
+    use chacha20poly1305::aead::{Aead as _, AeadCore as _, OsRng, Payload};
+    use chacha20poly1305::{ChaCha20Poly1305, KeyInit as _, Nonce};
+
+    use super::{StorageError, StoragePort};
+
+    const KEY_LEN: usize = 32;
+    const NONCE_LEN: usize = 12;
+    const SALT_LEN: usize = argon2::RECOMMENDED_SALT_LEN;
+    /// Where [`EncryptedStorage::with_passphrase`] keeps its salt, unencrypted.
+    const SALT_NAMESPACE: &str = "encryption";
+    const SALT_KEY: &str = "salt";
+
+    /// 256-bit key for [`EncryptedStorage`].
+    #[derive(Clone)]
+    pub struct StorageKey([u8; KEY_LEN]);
+
+    impl fmt::Debug for StorageKey {
+        fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("StorageKey(..)")
+        }
+    }
+
+    impl StorageKey {
+        /// Fresh random key from the operating system.
+        #[must_use]
+        pub fn generate() -> Self {
+            let mut key = [0; KEY_LEN];
+            OsRng.fill_bytes(&mut key);
+            Self(key)
+        }
+
+        /// Key from raw bytes, e.g. out of a secret store.
+        #[must_use]
+        pub fn from_bytes(key: [u8; KEY_LEN]) -> Self {
+            Self(key)
+        }
+
+        /// Raw key bytes, e.g. to put into a secret store.
+        #[must_use]
+        pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+            &self.0
+        }
+
+        /// Derive a key from `passphrase` with Argon2id.
+        ///
+        /// The same passphrase and `salt` always give the same key; use a random
+        /// salt of at least 8 bytes per store and keep it next to the data.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`StorageError::Key`] for salts shorter than 8 bytes.
+        pub fn from_passphrase(passphrase: &str, salt: &[u8]) -> Result<Self, StorageError> {
+            let mut key = [0; KEY_LEN];
+            Argon2::default()
+                .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                .map_err(|err| StorageError::Key(err.to_string()))?;
+            Ok(Self(key))
+        }
+
+        /// Read the key file at `path`, or create it with a random key.
+        ///
+        /// Key files hold the 32 raw key bytes; new ones are only readable by the
+        /// current user on Unix.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`StorageError::Key`] when the file cannot be read or written,
+        /// or does not hold a key.
+        pub fn load_or_create(path: &Path) -> Result<Self, StorageError> {
+            let unusable =
+                |err: &dyn fmt::Display| StorageError::Key(format!("{}: {err}", path.display()));
+            match fs::read(path) {
+                Ok(bytes) => <[u8; KEY_LEN]>::try_from(bytes.as_slice())
+                    .map(Self)
+                    .map_err(|_wrong_len| unusable(&format_args!("expected {KEY_LEN} bytes"))),
+                Err(err) if err.kind() == ErrorKind::NotFound => {
+                    let key = Self::generate();
+                    let mut options = OpenOptions::new();
+                    options.write(true).create_new(true);
+                    #[cfg(unix)]
+                    options.mode(0o600);
+                    options
+                        .open(path)
+                        .and_then(|mut file| file.write_all(&key.0))
+                        .map_err(|write_err| unusable(&write_err))?;
+                    Ok(key)
+                }
+                Err(err) => Err(unusable(&err)),
+            }
+        }
+    }
+
+    /// Encrypts the values of another backend with ChaCha20-Poly1305.
+    ///
+    /// Each value gets a random nonce and is bound to its namespace and key, so
+    /// blobs cannot be swapped between entries unnoticed. Namespaces and keys
+    /// themselves are stored in plain text, as backends need them for lookups.
+    pub struct EncryptedStorage<S> {
+        inner: S,
+        cipher: ChaCha20Poly1305,
+    }
+
+    impl<S> fmt::Debug for EncryptedStorage<S>
+    where
+        S: fmt::Debug,
+    {
+        fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter
+                .debug_struct("EncryptedStorage")
+                .field("inner", &self.inner)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl<S: StoragePort> EncryptedStorage<S> {
+        /// Encrypt everything stored in `inner` with `key`.
+        #[must_use]
+        pub fn new(inner: S, key: &StorageKey) -> Self {
+            Self {
+                inner,
+                cipher: ChaCha20Poly1305::new(&key.0.into()),
+            }
+        }
+
+        /// Encrypt with a key derived from `passphrase`.
+        ///
+        /// The salt is created on first use and kept unencrypted in `inner`
+        /// under the `encryption` namespace.
+        ///
+        /// # Errors
+        ///
+        /// Returns a [`StorageError`] when the salt cannot be read or stored.
+        pub async fn with_passphrase(inner: S, passphrase: &str) -> Result<Self, StorageError> {
+            let salt = if let Some(salt) = inner.get(SALT_NAMESPACE, SALT_KEY).await? {
+                salt
+            } else {
+                let mut salt = vec![0; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                inner.put(SALT_NAMESPACE, SALT_KEY, &salt).await?;
+                salt
+            };
+            let key = StorageKey::from_passphrase(passphrase, &salt)?;
+            Ok(Self::new(inner, &key))
+        }
+
+        /// The backend holding the encrypted values.
+        pub fn inner(&self) -> &S {
+            &self.inner
+        }
+    }
+
+    fn associated_data(namespace: &str, key: &str) -> Vec<u8> {
+        [namespace.as_bytes(), b"\0", key.as_bytes()].concat()
+    }
+
+    #[async_trait]
+    impl<S: StoragePort> StoragePort for EncryptedStorage<S> {
+        async fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+            let Some(blob) = self.inner.get(namespace, key).await? else {
+                return Ok(None);
+            };
+            let (nonce, msg) = blob
+                .split_at_checked(NONCE_LEN)
+                .ok_or(StorageError::Decryption)?;
+            let aad = associated_data(namespace, key);
+            self.cipher
+                .decrypt(Nonce::from_slice(nonce), Payload { msg, aad: &aad })
+                .map(Some)
+                .map_err(|_opaque| StorageError::Decryption)
+        }
+
+        async fn put(&self, namespace: &str, key: &str, value: &[u8]) -> Result<(), StorageError> {
+            let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+            let aad = associated_data(namespace, key);
+            let ciphertext = self
+                .cipher
+                .encrypt(
+                    &nonce,
+                    Payload {
+                        msg: value,
+                        aad: &aad,
+                    },
+                )
+                .map_err(|_too_long| StorageError::Backend("value too large to encrypt".into()))?;
+            let blob = [nonce.as_slice(), &ciphertext].concat();
+            self.inner.put(namespace, key, &blob).await
+        }
+
+        async fn delete(&self, namespace: &str, key: &str) -> Result<bool, StorageError> {
+            self.inner.delete(namespace, key).await
+        }
+
+        async fn list(&self, namespace: &str) -> Result<Vec<String>, StorageError> {
+            self.inner.list(namespace).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    /// Behavior every backend has to share.
+    async fn check_backend(storage: &dyn StoragePort) -> Result<(), StorageError> {
+        storage
+            .put("favorites", "cologne:2", b"Domkloster 4")
+            .await?;
+        storage.put("favorites", "aachen:1", b"Markt 1").await?;
+        storage.put("favorites", "aachen:1", b"Markt 1a").await?;
+        storage.put("history", "aachen:1", b"").await?;
+
+        assert_eq!(
+            storage.get("favorites", "aachen:1").await?.as_deref(),
+            Some(&b"Markt 1a"[..]),
+            "put replaces earlier values"
+        );
+        assert_eq!(
+            storage.list("favorites").await?,
+            ["aachen:1", "cologne:2"],
+            "keys are listed per namespace in order"
+        );
+        assert!(
+            storage.delete("favorites", "aachen:1").await?,
+            "existing keys are deleted"
+        );
+        assert!(
+            !storage.delete("favorites", "aachen:1").await?,
+            "deleting twice is a no-op"
+        );
+        assert_eq!(
+            storage.get("history", "aachen:1").await?,
+            Some(Vec::new()),
+            "other namespaces are untouched"
+        );
+        assert!(
+            matches!(
+                storage.put("", "key", b"value").await,
+                Err(StorageError::EmptyKey)
+            ),
+            "empty namespaces are rejected"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn memory_backend() {
+        block_on(check_backend(&MemoryStorage::default())).expect("memory storage never fails");
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_backend() {
+        let storage = SqliteStorage::in_memory().expect("in-memory database opens");
+        block_on(check_backend(&storage)).expect("in-memory database works");
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn encrypted_backend() {
+        let storage = EncryptedStorage::new(MemoryStorage::default(), &StorageKey::generate());
+        block_on(check_backend(&storage)).expect("encrypted memory storage works");
+    }
+
+    #[cfg(feature = "encryption")]
+    async fn check_encryption() -> Result<(), StorageError> {
+        let storage =
+            EncryptedStorage::with_passphrase(MemoryStorage::default(), "correct horse").await?;
+        storage
+            .put("favorites", "cologne:1", b"Domkloster 4")
+            .await?;
+        storage.put("favorites", "cologne:2", b"Markt 1").await?;
+        let blob = storage
+            .inner()
+            .get("favorites", "cologne:1")
+            .await?
+            .unwrap_or_default();
+        assert!(
+            !blob.windows(10).any(|window| window == b"Domkloster"),
+            "values are not stored in plain text"
+        );
+
+        let salt = storage
+            .inner()
+            .get("encryption", "salt")
+            .await?
+            .unwrap_or_default();
+        let copy = |passphrase| -> Result<_, StorageError> {
+            let key = StorageKey::from_passphrase(passphrase, &salt)?;
+            Ok(EncryptedStorage::new(MemoryStorage::default(), &key))
+        };
+        let same = copy("correct horse")?;
+        same.inner().put("favorites", "cologne:1", &blob).await?;
+        assert_eq!(
+            same.get("favorites", "cologne:1").await?.as_deref(),
+            Some(&b"Domkloster 4"[..]),
+            "the same passphrase and salt decrypt again"
+        );
+        let other = copy("battery staple")?;
+        other.inner().put("favorites", "cologne:1", &blob).await?;
+        assert!(
+            matches!(
+                other.get("favorites", "cologne:1").await,
+                Err(StorageError::Decryption)
+            ),
+            "another passphrase cannot decrypt"
+        );
+
+        storage.inner().put("favorites", "cologne:2", &blob).await?;
+        assert!(
+            matches!(
+                storage.get("favorites", "cologne:2").await,
+                Err(StorageError::Decryption)
+            ),
+            "values moved to another key are rejected"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn encrypted_values_need_the_key() {
+        block_on(check_encryption()).expect("memory storage never fails");
+    }
+}
@@ -0,0 +1,146 @@
+//! Spans around provider calls, for operators debugging slow or failing providers.
+//!
+//! [`traced`] wraps both ports of a plugin; every call gets a `provider_call` span
+//! with the city, the operation, its duration and whether it failed. The service
+//! wraps every plugin this way when the `tracing` feature is on.
+
+use std::future::Future;
+use std::sync::Arc;
+use web_time::Instant;
+
+use async_trait::async_trait;
+use tracing::{Instrument as _, field, info_span};
+
+use crate::model::{
+    Address, AddressId, CityMeta, DateRange, IdMigration, PickupEvent, VersionedAddressId,
+};
+use crate::plugin::CityPlugin;
+use crate::ports::{
+    AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport, SearchResults,
+};
+
+/// Port decorator recording a span per call.
+pub struct TracedPort<P: ?Sized> {
+    inner: Arc<P>,
+}
+
+/// Wrap both ports of `plugin` so every call is traced.
+#[must_use]
+pub fn traced(plugin: CityPlugin) -> CityPlugin {
+    CityPlugin {
+        meta: plugin.meta,
+        address_port: Arc::new(TracedPort {
+            inner: plugin.address_port,
+        }),
+        schedule_port: Arc::new(TracedPort {
+            inner: plugin.schedule_port,
+        }),
+    }
+}
+
+/// Run `call` in a span recording its duration and outcome.
+async fn in_span<T, F>(meta: &CityMeta, operation: &'static str, call: F) -> Result<T, PortError>
+where
+    F: Future<Output = Result<T, PortError>>,
+{
+    let span = info_span!(
+        "provider_call",
+        city = %meta.id.0,
+        operation,
+        duration_ms = field::Empty,
+        error = field::Empty,
+    );
+    let started = Instant::now();
+    let result = call.instrument(span.clone()).await;
+    span.record("duration_ms", started.elapsed().as_millis());
+    if let Err(err) = &result {
+        span.record("error", field::display(err));
+    }
+    result
+}
+
+#[async_trait]
+impl AddressPort for TracedPort<dyn AddressPort> {
+    fn city(&self) -> &CityMeta {
+        self.inner.city()
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        in_span(self.city(), "search", self.inner.search(query, limit)).await
+    }
+
+    async fn search_results(
+        &self,
+        query: &AddressSearch,
+        limit: usize,
+    ) -> Result<SearchResults, PortError> {
+        in_span(
+            self.city(),
+            "search",
+            self.inner.search_results(query, limit),
+        )
+        .await
+    }
+
+    async fn towns(&self) -> Result<Vec<String>, PortError> {
+        in_span(self.city(), "towns", self.inner.towns()).await
+    }
+
+    async fn resolve(&self, address_id: &AddressId) -> Result<Address, PortError> {
+        in_span(self.city(), "resolve", self.inner.resolve(address_id)).await
+    }
+
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        self.inner
+            .health_check()
+            .instrument(
+                info_span!("provider_call", city = %self.city().id.0, operation = "health_check"),
+            )
+            .await
+    }
+
+    fn id_version(&self) -> u32 {
+        self.inner.id_version()
+    }
+
+    async fn migrate_id(&self, stored: &VersionedAddressId) -> Result<IdMigration, PortError> {
+        in_span(self.city(), "migrate_id", self.inner.migrate_id(stored)).await
+    }
+}
+
+#[async_trait]
+impl SchedulePort for TracedPort<dyn SchedulePort> {
+    fn city(&self) -> &CityMeta {
+        self.inner.city()
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        in_span(
+            self.city(),
+            "schedule",
+            self.inner.schedule(address_id, range),
+        )
+        .await
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        in_span(
+            self.city(),
+            "schedule",
+            self.inner.schedule_report(address_id, range),
+        )
+        .await
+    }
+
+    fn supports_partial_fetch(&self) -> bool {
+        self.inner.supports_partial_fetch()
+    }
+}
@@ -0,0 +1,289 @@
+//! Sanity checks applied to provider schedules before they reach clients.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate};
+
+use crate::model::{DateRange, EventKey, Fraction, PickupEvent};
+
+/// Events further than this outside the requested range are treated as provider glitches.
+pub const RANGE_TOLERANCE_DAYS: i64 = 31;
+/// Events further in the future than this (from today) are treated as provider glitches.
+pub const MAX_HORIZON_DAYS: i64 = 2 * 365;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Reason an event was dropped during validation.
+pub enum ValidationWarning {
+    /// The same pickup (date, fraction and container) was reported more than once.
+    Duplicate {
+        /// Date of the duplicate.
+        date: NaiveDate,
+        /// Fraction of the duplicate.
+        fraction: Fraction,
+    },
+    /// The event lies far outside the requested range.
+    OutOfRange {
+        /// Date of the dropped event.
+        date: NaiveDate,
+    },
+    /// The event lies implausibly far in the future.
+    TooFarAhead {
+        /// Date of the dropped event.
+        date: NaiveDate,
+    },
+    /// The provider sent a date that could not be parsed.
+    MalformedDate {
+        /// The raw value as sent by the provider.
+        raw: String,
+    },
+}
+
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate { date, fraction } => {
+                write!(formatter, "duplicate {fraction:?} pickup on {date}")
+            }
+            Self::OutOfRange { date } => write!(formatter, "pickup on {date} outside range"),
+            Self::TooFarAhead { date } => write!(formatter, "pickup on {date} too far ahead"),
+            Self::MalformedDate { raw } => write!(formatter, "unreadable date {raw:?}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Cleaned-up events together with everything that was dropped.
+pub struct ValidatedSchedule {
+    /// Events that passed validation.
+    pub events: Vec<PickupEvent>,
+    /// One entry per dropped event.
+    pub warnings: Vec<ValidationWarning>,
+}
+
+/// Drop duplicates and implausible dates, normalize whitespace in notes and sort by date.
+///
+/// Pickups with the same [`PickupEvent::key`] are duplicates even when their notes
+/// differ; the first one listed is kept and takes the note of a later duplicate if
+/// it has none. Pickups on the same day keep the provider's order.
+#[must_use]
+pub fn validate_schedule(
+    events: Vec<PickupEvent>,
+    range: DateRange,
+    today: NaiveDate,
+) -> ValidatedSchedule {
+    let tolerance = Duration::days(RANGE_TOLERANCE_DAYS);
+    let earliest = range.start - tolerance;
+    let latest = range.end + tolerance;
+    let horizon = today + Duration::days(MAX_HORIZON_DAYS);
+
+    let mut seen = HashMap::<EventKey, usize>::new();
+    let mut validated = ValidatedSchedule::default();
+
+    for mut event in events {
+        if event.date < earliest || event.date > latest {
+            validated
+                .warnings
+                .push(ValidationWarning::OutOfRange { date: event.date });
+            continue;
+        }
+        if event.date > horizon {
+            validated
+                .warnings
+                .push(ValidationWarning::TooFarAhead { date: event.date });
+            continue;
+        }
+
+        event.note = event.note.take().and_then(normalize_whitespace);
+
+        if let Some(&kept) = seen.get(&event.key()) {
+            if let Some(first) = validated.events.get_mut(kept)
+                && first.note.is_none()
+            {
+                first.note = event.note;
+            }
+            validated.warnings.push(ValidationWarning::Duplicate {
+                date: event.date,
+                fraction: event.fraction,
+            });
+            continue;
+        }
+        seen.insert(event.key(), validated.events.len());
+        validated.events.push(event);
+    }
+
+    validated.events.sort_by_key(|event| event.date);
+    validated
+}
+
+/// Collapse runs of whitespace into single spaces; empty notes become `None`.
+///
+/// Already normalized notes are kept as they are, so shared notes stay shared.
+fn normalize_whitespace(note: Arc<str>) -> Option<Arc<str>> {
+    let normalized = note.split_whitespace().collect::<Vec<_>>().join(" ");
+    if normalized.is_empty() {
+        None
+    } else if *normalized == *note {
+        Some(note)
+    } else {
+        Some(normalized.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pickup(day: u32, fraction: Fraction, note: Option<&str>) -> PickupEvent {
+        PickupEvent {
+            date: NaiveDate::from_ymd_opt(2025, 3, day).unwrap_or_default(),
+            fraction,
+            note: note.map(Arc::from),
+            container: None,
+            correction: None,
+            holiday: None,
+            predicted: false,
+        }
+    }
+
+    fn on(date: NaiveDate) -> PickupEvent {
+        PickupEvent {
+            date,
+            ..pickup(1, Fraction::Residual, None)
+        }
+    }
+
+    #[test]
+    fn duplicates_collapse_and_events_come_sorted() {
+        let range = DateRange::next_days(pickup(1, Fraction::Paper, None).date, 30);
+        let validated = validate_schedule(
+            vec![
+                pickup(12, Fraction::Paper, None),
+                pickup(5, Fraction::Residual, Some("Restmüll")),
+                pickup(12, Fraction::Paper, Some("Blaue Tonne")),
+                pickup(5, Fraction::Glass, None),
+            ],
+            range,
+            range.start,
+        );
+
+        assert_eq!(
+            validated.events,
+            [
+                pickup(5, Fraction::Residual, Some("Restmüll")),
+                pickup(5, Fraction::Glass, None),
+                pickup(12, Fraction::Paper, Some("Blaue Tonne")),
+            ],
+            "sorted by date, same-day order kept, the duplicate's note adopted"
+        );
+        assert_eq!(
+            validated.warnings,
+            [ValidationWarning::Duplicate {
+                date: pickup(12, Fraction::Paper, None).date,
+                fraction: Fraction::Paper,
+            }],
+            "the dropped duplicate is reported"
+        );
+    }
+
+    #[test]
+    fn events_far_outside_the_range_are_dropped() {
+        let range = DateRange {
+            start: pickup(1, Fraction::Residual, None).date,
+            end: pickup(31, Fraction::Residual, None).date,
+        };
+        let tolerance = Duration::days(RANGE_TOLERANCE_DAYS);
+        let before = range.start - tolerance - Duration::days(1);
+        let after = range.end + tolerance + Duration::days(1);
+        let validated = validate_schedule(
+            vec![
+                on(before),
+                on(range.start - tolerance),
+                on(range.end + tolerance),
+                on(after),
+            ],
+            range,
+            range.start,
+        );
+
+        assert_eq!(
+            validated.events,
+            [on(range.start - tolerance), on(range.end + tolerance)],
+            "events up to the tolerance around the range are kept"
+        );
+        assert_eq!(
+            validated.warnings,
+            [
+                ValidationWarning::OutOfRange { date: before },
+                ValidationWarning::OutOfRange { date: after },
+            ],
+            "each dropped event is reported"
+        );
+    }
+
+    #[test]
+    fn events_beyond_the_horizon_are_dropped() {
+        let today = pickup(1, Fraction::Residual, None).date;
+        let horizon = today + Duration::days(MAX_HORIZON_DAYS);
+        let range = DateRange {
+            start: today,
+            end: horizon + Duration::days(1),
+        };
+        let validated = validate_schedule(
+            vec![on(horizon), on(horizon + Duration::days(1))],
+            range,
+            today,
+        );
+
+        assert_eq!(
+            validated.events,
+            [on(horizon)],
+            "events up to two years ahead are kept, even inside the range"
+        );
+        assert_eq!(
+            validated.warnings,
+            [ValidationWarning::TooFarAhead {
+                date: horizon + Duration::days(1)
+            }],
+            "the dropped event is reported"
+        );
+    }
+
+    #[test]
+    fn notes_get_single_spaces() {
+        let range = DateRange::next_days(pickup(1, Fraction::Paper, None).date, 30);
+        let shared = Arc::<str>::from("Blaue Tonne");
+        let validated = validate_schedule(
+            vec![
+                pickup(3, Fraction::Residual, Some("  Rest-\t und\n  Sperrmüll ")),
+                pickup(4, Fraction::Glass, Some(" \n ")),
+                PickupEvent {
+                    note: Some(Arc::clone(&shared)),
+                    ..pickup(5, Fraction::Paper, None)
+                },
+            ],
+            range,
+            range.start,
+        );
+
+        assert_eq!(
+            validated.events,
+            [
+                pickup(3, Fraction::Residual, Some("Rest- und Sperrmüll")),
+                pickup(4, Fraction::Glass, None),
+                pickup(5, Fraction::Paper, Some("Blaue Tonne")),
+            ],
+            "runs of whitespace collapse and blank notes disappear"
+        );
+        assert!(
+            validated
+                .events
+                .last()
+                .and_then(|event| event.note.as_ref())
+                .is_some_and(|note| Arc::ptr_eq(note, &shared)),
+            "normalized notes stay shared"
+        );
+        assert!(validated.warnings.is_empty(), "{:?}", validated.warnings);
+    }
+}
@@ -0,0 +1,131 @@
+//! Notification channel posting JSON to a webhook.
+
+use async_trait::async_trait;
+use tonneli_http::HttpClient;
+
+use crate::notify::{Notification, NotifierPort, NotifyError};
+
+#[derive(Debug, Clone)]
+/// Posts every [`Notification`] as JSON to a URL, e.g. for home automation.
+///
+/// The body is the serialized notification, tagged by `kind` (`reminder`,
+/// `digest` or `schedule_changed`); schedule changes carry their `changes`, each
+/// tagged `added`, `removed` or `moved`.
+///
+/// Calls go through an [`HttpClient`], so its proxy, certificate, timeout and
+/// audit settings apply to them as to provider requests.
+pub struct WebhookNotifier {
+    client: HttpClient,
+    url: String,
+    changes_only: bool,
+}
+
+impl WebhookNotifier {
+    /// Webhook at `url`, sent through `client`.
+    #[must_use]
+    pub fn new(client: HttpClient, url: impl Into<String>) -> Self {
+        Self {
+            client,
+            url: url.into(),
+            changes_only: false,
+        }
+    }
+
+    /// Only post schedule changes and skip reminders and digests.
+    #[must_use]
+    pub fn changes_only(mut self) -> Self {
+        self.changes_only = true;
+        self
+    }
+}
+
+#[async_trait]
+impl NotifierPort for WebhookNotifier {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), NotifyError> {
+        if self.changes_only && !matches!(notification, Notification::ScheduleChanged { .. }) {
+            return Ok(());
+        }
+        self.client
+            .fetch_bytes(self.client.post(&self.url).json(notification))
+            .await
+            .map_err(|err| NotifyError::Delivery(Box::new(err)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use serde_json::json;
+    use tonneli_http::HttpConfig;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::model::{Address, AddressId, CityId, Fraction, PickupEvent};
+
+    fn pickup(day: u32) -> PickupEvent {
+        PickupEvent {
+            date: NaiveDate::from_ymd_opt(2025, 3, day).expect("valid test date"),
+            fraction: Fraction::Paper,
+            note: None,
+            container: None,
+            correction: None,
+            holiday: None,
+            predicted: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn posts_schedule_changes() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({
+                "kind": "schedule_changed",
+                "changes": [{
+                    "kind": "moved",
+                    "from": { "date": "2025-03-11", "fraction": "Paper" },
+                    "to": { "date": "2025-03-12", "fraction": "Paper" },
+                }],
+            })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let address = Address {
+            id: AddressId("1".to_owned()),
+            city: CityId("cologne".to_owned()),
+            label: "Domkloster 4".into(),
+            street: "Domkloster".into(),
+            house_number: "4".into(),
+        };
+        let webhook = WebhookNotifier::new(
+            HttpClient::new(&HttpConfig::default()).expect("default client builds"),
+            server.uri(),
+        )
+        .changes_only();
+
+        let reminder = Notification::Reminder {
+            address: address.clone(),
+            events: vec![pickup(11)],
+        };
+        webhook
+            .send(&reminder)
+            .await
+            .expect("skipped notifications succeed");
+        let change = Notification::schedule_changed(&address, &[pickup(11)], &[pickup(12)])
+            .expect("a moved pickup is a change");
+        webhook
+            .send(&change)
+            .await
+            .expect("the webhook accepts the change");
+        assert!(
+            Notification::schedule_changed(&address, &[pickup(11)], &[pickup(11)]).is_none(),
+            "unchanged schedules notify nobody"
+        );
+    }
+}
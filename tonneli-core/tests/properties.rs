@@ -0,0 +1,247 @@
+//! Property tests for range filtering, event merging and search input handling.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use std::collections::HashSet;
+
+use chrono::{Duration, NaiveDate};
+use proptest::prelude::*;
+use proptest::{collection, option};
+use tonneli_core::{
+    AddressSearch, DateRange, Fraction, PickupEvent, ScheduleSnapshot, merge_events, merge_refresh,
+    refresh_window, validate_schedule,
+};
+
+#[cfg(feature = "encryption")]
+use argon2 as _;
+use async_trait as _;
+#[cfg(feature = "encryption")]
+use chacha20poly1305 as _;
+use chrono_tz as _;
+use criterion as _;
+use dirs as _;
+use fastrand as _;
+use futures as _;
+#[cfg(feature = "metrics")]
+use metrics as _;
+use metrics_util as _;
+use reqwest as _;
+#[cfg(feature = "sqlite")]
+use rusqlite as _;
+#[cfg(feature = "schema")]
+use schemars as _;
+use serde as _;
+use thiserror as _;
+use tokio as _;
+use tonneli_http as _;
+#[cfg(feature = "tracing")]
+use tracing as _;
+use unicode_normalization as _;
+use web_time as _;
+use wiremock as _;
+
+fn epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 1, 1).expect("valid epoch")
+}
+
+/// Dates within about two years of the epoch.
+fn date() -> impl Strategy<Value = NaiveDate> {
+    (0_i64..730).prop_map(|days| epoch() + Duration::days(days))
+}
+
+fn range() -> impl Strategy<Value = DateRange> {
+    (date(), 0_i64..120).prop_map(|(start, days)| DateRange {
+        start,
+        end: start + Duration::days(days),
+    })
+}
+
+fn fraction() -> impl Strategy<Value = Fraction> {
+    prop_oneof![
+        Just(Fraction::Residual),
+        Just(Fraction::Organic),
+        Just(Fraction::Paper),
+        Just(Fraction::Plastic),
+        Just(Fraction::BulkyWaste),
+        Just(Fraction::ChristmasTree),
+        Just(Fraction::Other("Windeltonne".into())),
+    ]
+}
+
+fn event() -> impl Strategy<Value = PickupEvent> {
+    (
+        date(),
+        fraction(),
+        option::of("[a-z ]{0,8}"),
+        option::of(prop_oneof![Just("120 l"), Just("1100 l")]),
+    )
+        .prop_map(|(date, fraction, note, container)| PickupEvent {
+            date,
+            fraction,
+            note: note.map(Into::into),
+            container: container.map(Into::into),
+            correction: None,
+            holiday: None,
+            predicted: false,
+        })
+}
+
+fn events() -> impl Strategy<Value = Vec<PickupEvent>> {
+    collection::vec(event(), 0..40)
+}
+
+/// Strings made only of (Unicode) whitespace.
+fn blank() -> impl Strategy<Value = String> {
+    collection::vec(
+        prop_oneof![
+            Just(' '),
+            Just('\t'),
+            Just('\n'),
+            Just('\u{a0}'),
+            Just('\u{3000}')
+        ],
+        0..6,
+    )
+    .prop_map(|chars| chars.into_iter().collect())
+}
+
+proptest! {
+    #[test]
+    fn contains_matches_inclusive_bounds(range in range(), date in date()) {
+        prop_assert_eq!(
+            range.contains(date),
+            range.start <= date && date <= range.end,
+            "both ends are inclusive"
+        );
+    }
+
+    #[test]
+    fn refresh_never_leaves_the_range(
+        old_range in range(),
+        range in range(),
+        fetched_after in 0_i64..60,
+        cached in events(),
+        fresh in events(),
+    ) {
+        let snapshot = ScheduleSnapshot {
+            range: old_range,
+            fetched_on: old_range.start + Duration::days(fetched_after),
+            events: cached,
+        };
+        let window = refresh_window(&snapshot, range);
+        let refreshed = merge_refresh(&snapshot, range, window, fresh, snapshot.fetched_on);
+
+        prop_assert!(
+            refreshed.events.iter().all(|event| range.contains(event.date)),
+            "refreshed events must lie within {range:?}"
+        );
+        if let Some(window) = window {
+            prop_assert!(
+                window.start >= range.start && window.end == range.end,
+                "the refetch window {window:?} must be a tail of {range:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn merge_keeps_one_event_per_key(sources in collection::vec(events(), 0..4)) {
+        let keys = sources
+            .iter()
+            .flatten()
+            .map(PickupEvent::key)
+            .collect::<HashSet<_>>();
+        let merged = merge_events(sources.clone());
+
+        prop_assert_eq!(merged.len(), keys.len(), "one event per distinct key");
+        prop_assert!(
+            merged.is_sorted_by_key(|event| event.date),
+            "merged events are sorted by date"
+        );
+        prop_assert_eq!(
+            merge_events([merged.clone(), merged.clone()]).len(),
+            merged.len(),
+            "merging is idempotent"
+        );
+    }
+
+    #[test]
+    fn grouping_by_date_keeps_every_event(sources in collection::vec(events(), 0..4)) {
+        let merged = merge_events(sources);
+        let groups = merged
+            .chunk_by(|left, right| left.date == right.date)
+            .collect::<Vec<_>>();
+
+        prop_assert_eq!(
+            groups.iter().map(|group| group.len()).sum::<usize>(),
+            merged.len(),
+            "every event lands in exactly one group"
+        );
+        prop_assert_eq!(
+            groups.len(),
+            merged.iter().map(|event| event.date).collect::<HashSet<_>>().len(),
+            "sorted events form one group per day"
+        );
+    }
+
+    #[test]
+    fn validation_accounts_for_every_event(events in events(), range in range()) {
+        let total = events.len();
+        let validated = validate_schedule(events, range, epoch());
+
+        prop_assert_eq!(
+            validated.events.len() + validated.warnings.len(),
+            total,
+            "each event is either kept or reported"
+        );
+    }
+
+    #[test]
+    fn fractions_round_trip_through_serde(fraction in fraction()) {
+        let json = serde_json::to_string(&fraction).expect("fractions serialize");
+        let back: Fraction = serde_json::from_str(&json).expect("fractions deserialize");
+        prop_assert_eq!(back, fraction);
+    }
+
+    #[test]
+    fn blank_input_is_an_empty_search(blank in blank(), house_number in blank()) {
+        prop_assert!(AddressSearch::parse(&blank).is_empty(), "parsed blank input");
+        prop_assert!(
+            AddressSearch::new(blank.clone(), Some(house_number.clone())).is_empty(),
+            "blank street with a house number"
+        );
+        prop_assert!(
+            AddressSearch::new("Markt", Some(house_number)).house_number_parts().is_none(),
+            "a blank house number is no house number"
+        );
+    }
+
+    #[test]
+    fn parsed_search_is_empty_only_for_blank_input(input in "\\PC{0,20}") {
+        prop_assert_eq!(
+            AddressSearch::parse(&input).is_empty(),
+            input.trim().is_empty(),
+            "emptiness of {:?}", input
+        );
+    }
+}
+
+#[test]
+fn fraction_tags_are_stable() {
+    let tags = [
+        (Fraction::BulkyWaste, "\"BulkyWaste\""),
+        (Fraction::GardenWaste, "\"GardenWaste\""),
+        (Fraction::Hazardous, "\"Hazardous\""),
+        (Fraction::ChristmasTree, "\"ChristmasTree\""),
+        (Fraction::Textiles, "\"Textiles\""),
+        (Fraction::Electronics, "\"Electronics\""),
+    ];
+    for (fraction, tag) in tags {
+        assert_eq!(
+            serde_json::to_string(&fraction).expect("fractions serialize"),
+            tag,
+            "stored fractions must keep their tag"
+        );
+    }
+}
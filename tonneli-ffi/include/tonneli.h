@@ -0,0 +1,57 @@
+/*
+ * C interface of tonneli-ffi: city listing, address search and pickup schedules.
+ *
+ * Every call blocks until the provider answered and returns a JSON string owned
+ * by the caller, to be released with tonneli_string_free():
+ *
+ *   {"ok": ...}                                  the result
+ *   {"error": {"message": "...", "retryable": false}}   what went wrong
+ *
+ * An error may also carry "retry_after_secs", rounded up to whole seconds,
+ * "provider" with the id of the city whose provider failed and, for an unknown
+ * address, "suggestions" with similar street names. Functions
+ * taking a client return NULL only when the client is NULL. A panic inside the
+ * library never unwinds into the caller; it is reported as an error.
+ */
+
+#ifndef TONNELI_H
+#define TONNELI_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Providers and the runtime their calls run on. */
+typedef struct TonneliClient TonneliClient;
+
+/* Client for the built-in providers; NULL if they cannot be set up, including
+ * when setting them up panics. */
+TonneliClient *tonneli_client_new(void);
+
+/* Release a client; NULL is ignored. */
+void tonneli_client_free(TonneliClient *client);
+
+/* Registered cities: {"ok": [{"id": "cologne", "name": "Köln", ...}, ...]} */
+char *tonneli_cities(const TonneliClient *client);
+
+/* Addresses in a city matching free text such as "Aachener Str 1", at most
+ * `limit`: {"ok": [{"id": "...", "label": "...", ...}, ...]} */
+char *tonneli_search(const TonneliClient *client, const char *city_id,
+                     const char *query, size_t limit);
+
+/* Pickups of an address from `from` to `to`, both "YYYY-MM-DD" and included,
+ * sorted by date: {"ok": [{"date": "2025-03-10", "fraction": "Paper", ...}]} */
+char *tonneli_schedule(const TonneliClient *client, const char *city_id,
+                       const char *address_id, const char *from,
+                       const char *to);
+
+/* Release a string returned by this library; NULL is ignored. */
+void tonneli_string_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* TONNELI_H */
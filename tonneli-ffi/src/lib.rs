@@ -0,0 +1,486 @@
+//! C ABI over the Tonneli service, for native mobile apps and other non-Rust callers.
+//!
+//! [`tonneli_client_new`] registers the built-in providers that need no configuration
+//! and starts a Tokio runtime; every call blocks until the provider answered. Results
+//! are JSON strings owned by the caller and released with [`tonneli_string_free`]:
+//!
+//! ```json
+//! {"ok": [{"id": "cologne", "name": "Köln", ...}]}
+//! {"error": {"message": "Address not found", "retryable": false, "suggestions": ["Aachener Straße"]}}
+//! ```
+//!
+//! The values under `ok` are the serialized models of `tonneli-core`. A panic
+//! never unwinds into the caller: it ends up as an error result, or as null from
+//! [`tonneli_client_new`]. The C declarations are in `include/tonneli.h`.
+#![expect(unsafe_code, reason = "the C ABI takes and hands out raw pointers")]
+
+use std::any::Any;
+use std::ffi::{CStr, CString, c_char};
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use tokio::runtime::Runtime;
+use tonneli_core::{
+    model::{Address, AddressId, CityId, CityMeta, DateRange, PickupEvent},
+    ports::{AddressSearch, PortError},
+    service::TonneliService,
+};
+use tonneli_provider_aachen as aachen;
+use tonneli_provider_bremen as bremen;
+use tonneli_provider_cologne as cologne;
+use tonneli_provider_ics as ics;
+use tonneli_provider_luxembourg as luxembourg;
+use tonneli_provider_nuremberg as nuremberg;
+use tonneli_provider_potsdam as potsdam;
+use tonneli_provider_rhein_sieg as rhein_sieg;
+use tonneli_provider_uk::{cambridge, reading};
+use tonneli_provider_wuppertal as wuppertal;
+
+/// Format of the dates passed to [`tonneli_schedule`].
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Result returned when the actual one cannot be serialized, so calls with a
+/// client never return null.
+const UNSERIALIZABLE: &CStr =
+    cr#"{"error": {"message": "the result cannot be serialized", "retryable": false}}"#;
+
+/// Service with the built-in providers and the runtime its calls block on.
+pub struct TonneliClient {
+    runtime: Runtime,
+    service: TonneliService,
+}
+
+impl TonneliClient {
+    /// Client for all built-in providers that need no configuration.
+    fn new() -> Result<Self, PortError> {
+        let builder = TonneliService::builder()
+            .provider_with_clock(aachen::plugin_with_clock)
+            .provider(bremen::plugin)
+            .provider(cambridge::plugin)
+            .provider(cologne::plugin)
+            .provider(ics::plugin)
+            .provider(luxembourg::plugin)
+            .provider_with_clock(nuremberg::plugin_with_clock)
+            .provider(potsdam::plugin)
+            .provider(reading::plugin)
+            .provider_with_clock(rhein_sieg::plugin_with_clock)
+            .provider(wuppertal::plugin);
+        #[cfg(feature = "demo")]
+        let builder = builder.provider(tonneli_provider_demo::plugin);
+        let service = builder.build()?;
+        Self::with_service(service).map_err(|err| PortError::Internal(err.to_string()))
+    }
+
+    fn with_service(service: TonneliService) -> io::Result<Self> {
+        Ok(Self {
+            runtime: Runtime::new()?,
+            service,
+        })
+    }
+
+    fn cities(&self) -> Vec<CityMeta> {
+        self.service
+            .cities()
+            .into_iter()
+            .filter_map(|(city, _)| self.service.city_meta(&city))
+            .collect()
+    }
+
+    fn search(&self, city: &str, query: &str, limit: usize) -> Result<Vec<Address>, ErrorBody> {
+        let search = self.service.search_addresses(
+            CityId(city.to_owned()),
+            AddressSearch::parse(query),
+            limit,
+        );
+        Ok(self.runtime.block_on(search)?)
+    }
+
+    fn schedule(
+        &self,
+        city: &str,
+        address_id: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<PickupEvent>, ErrorBody> {
+        let range = DateRange::new(parse_date(from)?, parse_date(to)?).map_err(PortError::from)?;
+        let address_id = AddressId(address_id.to_owned());
+        let schedule = self
+            .service
+            .schedule_for(CityId(city.to_owned()), &address_id, range);
+        Ok(self.runtime.block_on(schedule)?)
+    }
+}
+
+#[derive(Debug, Serialize)]
+/// What went wrong, under `error` in a result.
+struct ErrorBody {
+    message: String,
+    /// Whether asking again later might succeed, see [`PortError::is_retryable`].
+    retryable: bool,
+    /// Requested delay in whole seconds, rounded up so callers never ask too early.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after_secs: Option<u64>,
+    /// Similar street names for an unknown address.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suggestions: Vec<String>,
+    /// City whose provider failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<String>,
+}
+
+impl ErrorBody {
+    /// Error in the arguments of a call.
+    fn invalid(message: String) -> Self {
+        Self {
+            message,
+            retryable: false,
+            retry_after_secs: None,
+            suggestions: Vec::new(),
+            provider: None,
+        }
+    }
+
+    /// Panic caught at the C boundary, with its message if it has one.
+    fn panicked(payload: &(dyn Any + Send)) -> Self {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown cause");
+        Self {
+            message: format!("internal error: {message}"),
+            retryable: false,
+            retry_after_secs: None,
+            suggestions: Vec::new(),
+            provider: None,
+        }
+    }
+}
+
+impl From<PortError> for ErrorBody {
+    fn from(err: PortError) -> Self {
+        Self {
+            message: err.to_string(),
+            retryable: err.is_retryable(),
+            retry_after_secs: err.retry_after().map(|delay| {
+                delay
+                    .as_secs()
+                    .saturating_add(u64::from(delay.subsec_nanos() > 0))
+            }),
+            suggestions: err.suggestions().to_vec(),
+            provider: err.provider().map(|city| city.0.clone()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+/// JSON shape of every result.
+enum Outcome<T> {
+    Ok(T),
+    Error(ErrorBody),
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, ErrorBody> {
+    NaiveDate::parse_from_str(value, DATE_FORMAT).map_err(|err| {
+        ErrorBody::invalid(format!(
+            "invalid date {value:?}, expected YYYY-MM-DD: {err}"
+        ))
+    })
+}
+
+/// Result of `call` as a JSON string for the caller; a panic becomes an error.
+fn respond<T: Serialize>(call: impl FnOnce() -> Result<T, ErrorBody>) -> *mut c_char {
+    to_json(
+        panic::catch_unwind(AssertUnwindSafe(call))
+            .unwrap_or_else(|payload| Err(ErrorBody::panicked(&*payload))),
+    )
+}
+
+/// Result as a JSON string for the caller, [`UNSERIALIZABLE`] if it cannot be
+/// serialized.
+fn to_json<T: Serialize>(result: Result<T, ErrorBody>) -> *mut c_char {
+    let outcome = match result {
+        Ok(value) => Outcome::Ok(value),
+        Err(err) => Outcome::Error(err),
+    };
+    // JSON escapes control characters, so the string has no interior NUL.
+    serde_json::to_string(&outcome)
+        .ok()
+        .and_then(|json| CString::new(json).ok())
+        .unwrap_or_else(|| UNSERIALIZABLE.to_owned())
+        .into_raw()
+}
+
+/// String arguments, each given with its name for error messages.
+///
+/// # Safety
+///
+/// Each pointer is null or points to a NUL-terminated string that outlives the call.
+unsafe fn str_args<'call, const N: usize>(
+    args: [(*const c_char, &str); N],
+) -> Result<[&'call str; N], ErrorBody> {
+    let mut strs = [""; N];
+    for (slot, (arg, name)) in strs.iter_mut().zip(args) {
+        if arg.is_null() {
+            return Err(ErrorBody::invalid(format!("{name} is null")));
+        }
+        // SAFETY: not null, and the caller guarantees a NUL-terminated string.
+        let arg = unsafe { CStr::from_ptr(arg) };
+        *slot = arg
+            .to_str()
+            .map_err(|_err| ErrorBody::invalid(format!("{name} is not valid UTF-8")))?;
+    }
+    Ok(strs)
+}
+
+/// Create a client for the built-in providers.
+///
+/// Returns null when the providers or the runtime cannot be set up, e.g. because
+/// the TLS backend is unavailable, or setting them up panics. Release the client
+/// with [`tonneli_client_free`].
+#[unsafe(no_mangle)]
+pub extern "C" fn tonneli_client_new() -> *mut TonneliClient {
+    panic::catch_unwind(TonneliClient::new)
+        .ok()
+        .and_then(Result::ok)
+        .map_or(ptr::null_mut(), |client| Box::into_raw(Box::new(client)))
+}
+
+/// Release a client; null is ignored.
+///
+/// # Safety
+///
+/// `client` is null or was returned by [`tonneli_client_new`] and is not used
+/// afterwards, nor concurrently by another call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tonneli_client_free(client: *mut TonneliClient) {
+    if !client.is_null() {
+        // SAFETY: the caller hands back a pointer from `tonneli_client_new` once.
+        let client = unsafe { Box::from_raw(client) };
+        // A provider panicking on drop must not unwind into the caller; the client
+        // is gone either way.
+        let _panicked = panic::catch_unwind(AssertUnwindSafe(|| drop(client)));
+    }
+}
+
+/// Registered cities as a JSON array of city metadata under `ok`.
+///
+/// Returns null for a null client.
+///
+/// # Safety
+///
+/// `client` is null or a live client from [`tonneli_client_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tonneli_cities(client: *const TonneliClient) -> *mut c_char {
+    // SAFETY: the caller passes null or a live client.
+    let Some(client) = (unsafe { client.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    respond(|| Ok(client.cities()))
+}
+
+/// Addresses in `city_id` matching `query`, e.g. `"Aachener Str 1"`, as a JSON
+/// array under `ok`; at most `limit`.
+///
+/// Returns null for a null client.
+///
+/// # Safety
+///
+/// `client` is null or a live client from [`tonneli_client_new`]; `city_id` and
+/// `query` are null or NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tonneli_search(
+    client: *const TonneliClient,
+    city_id: *const c_char,
+    query: *const c_char,
+    limit: usize,
+) -> *mut c_char {
+    // SAFETY: the caller passes null or a live client.
+    let Some(client) = (unsafe { client.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    // SAFETY: the caller passes null or NUL-terminated strings.
+    let args = unsafe { str_args([(city_id, "city_id"), (query, "query")]) };
+    respond(|| args.and_then(|[city, text]| client.search(city, text, limit)))
+}
+
+/// Pickups of `address_id` in `city_id` from `from` to `to` (both `YYYY-MM-DD`,
+/// included) as a JSON array under `ok`, sorted by date.
+///
+/// Returns null for a null client.
+///
+/// # Safety
+///
+/// `client` is null or a live client from [`tonneli_client_new`]; the other
+/// arguments are null or NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tonneli_schedule(
+    client: *const TonneliClient,
+    city_id: *const c_char,
+    address_id: *const c_char,
+    from: *const c_char,
+    to: *const c_char,
+) -> *mut c_char {
+    // SAFETY: the caller passes null or a live client.
+    let Some(client) = (unsafe { client.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    // SAFETY: the caller passes null or NUL-terminated strings.
+    let args = unsafe {
+        str_args([
+            (city_id, "city_id"),
+            (address_id, "address_id"),
+            (from, "from"),
+            (to, "to"),
+        ])
+    };
+    respond(|| {
+        args.and_then(|[city, address, start, end]| client.schedule(city, address, start, end))
+    })
+}
+
+/// Release a string returned by this library; null is ignored.
+///
+/// # Safety
+///
+/// `string` is null or was returned by this library and is not used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tonneli_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: the caller hands back a string from `to_json` once.
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::{Value, json};
+    use tonneli_core::model::Fraction;
+    use tonneli_provider_mock::{MOCK_CITY_ID, MockProvider};
+
+    use super::*;
+
+    /// Parsed result of a call, releasing the string.
+    fn result(json: *mut c_char) -> Value {
+        assert!(!json.is_null(), "calls with a client return a result");
+        // SAFETY: a non-null result of this library, released right after.
+        let value = serde_json::from_slice(unsafe { CStr::from_ptr(json) }.to_bytes())
+            .expect("results are JSON");
+        // SAFETY: returned by this library and not used afterwards.
+        unsafe { tonneli_string_free(json) }
+        value
+    }
+
+    #[test]
+    fn searches_and_loads_schedules_through_the_c_abi() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap_or_default();
+        let service = TonneliService::builder()
+            .provider(move |_client| {
+                MockProvider::new(MOCK_CITY_ID, "Mock City")
+                    .address("Musterstraße", "1", [(day, Fraction::Paper)])
+                    .plugin()
+            })
+            .build()
+            .expect("mock provider registers");
+        let client = Box::into_raw(Box::new(
+            TonneliClient::with_service(service).expect("runtime starts"),
+        ));
+        let city = CString::new(MOCK_CITY_ID).unwrap_or_default();
+        let query = CString::new("Musterstr 1").unwrap_or_default();
+        let address = CString::new("1").unwrap_or_default();
+        let from = CString::new("2025-03-01").unwrap_or_default();
+        let to = CString::new("2025-03-31").unwrap_or_default();
+        let inverted = CString::new("2025-02-01").unwrap_or_default();
+
+        // SAFETY: here and below, a live client and NUL-terminated strings that
+        // outlive the call.
+        let cities = result(unsafe { tonneli_cities(client) });
+        // SAFETY: as above.
+        let found = result(unsafe { tonneli_search(client, city.as_ptr(), query.as_ptr(), 5) });
+        // SAFETY: as above.
+        let schedule = result(unsafe {
+            tonneli_schedule(
+                client,
+                city.as_ptr(),
+                address.as_ptr(),
+                from.as_ptr(),
+                to.as_ptr(),
+            )
+        });
+        // SAFETY: as above.
+        let invalid = result(unsafe {
+            tonneli_schedule(
+                client,
+                city.as_ptr(),
+                address.as_ptr(),
+                from.as_ptr(),
+                inverted.as_ptr(),
+            )
+        });
+        // SAFETY: from `Box::into_raw` above and not used afterwards.
+        unsafe { tonneli_client_free(client) }
+
+        assert_eq!(
+            cities.pointer("/ok/0/id"),
+            Some(&json!(MOCK_CITY_ID)),
+            "{cities}"
+        );
+        assert_eq!(found.pointer("/ok/0/id"), Some(&json!("1")), "{found}");
+        assert_eq!(
+            schedule.pointer("/ok/0/date"),
+            Some(&json!("2025-03-10")),
+            "{schedule}"
+        );
+        assert_eq!(
+            invalid.pointer("/error/retryable"),
+            Some(&json!(false)),
+            "a range ending before it starts is an error: {invalid}"
+        );
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        #[expect(clippy::panic, reason = "the panic is what is under test")]
+        let json = result(respond(|| -> Result<(), ErrorBody> {
+            panic!("provider bug")
+        }));
+
+        assert_eq!(
+            json.pointer("/error/message"),
+            Some(&json!("internal error: provider bug")),
+            "{json}"
+        );
+    }
+
+    #[test]
+    fn retry_after_rounds_up() {
+        let err = PortError::RateLimited {
+            retry_after: Some(Duration::from_millis(1500)),
+            status: Some(429),
+        };
+
+        assert_eq!(
+            ErrorBody::from(err).retry_after_secs,
+            Some(2),
+            "callers must not ask again too early"
+        );
+    }
+
+    #[test]
+    fn errors_name_the_failing_provider() {
+        let err = PortError::Timeout.with_provider(&CityId("cologne".to_owned()));
+
+        let body = ErrorBody::from(err);
+        assert_eq!(
+            (body.provider.as_deref(), body.retryable),
+            (Some("cologne"), true),
+            "the city and the classification of the error are kept"
+        );
+    }
+}
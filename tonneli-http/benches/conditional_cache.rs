@@ -0,0 +1,74 @@
+//! Lookups in the conditional request cache.
+#![expect(
+    missing_docs,
+    reason = "criterion_group! generates an undocumented pub fn"
+)]
+#![expect(
+    unused_crate_dependencies,
+    reason = "benchmarks only exercise part of the library's dependencies"
+)]
+
+use std::hint::black_box;
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use reqwest::header::{ETAG, HeaderMap, HeaderValue};
+use reqwest::{Method, Request, Url};
+use tonneli_http::{ConditionalCache, DEFAULT_CONDITIONAL_CACHE_CAPACITY};
+
+const BASE_URL: &str = "https://abfallapp.example/rest/strassen";
+
+fn url(index: usize) -> String {
+    format!("{BASE_URL}/{index}")
+}
+
+fn request(index: usize) -> Request {
+    let url = Url::parse(&url(index)).unwrap_or_else(|_| unreachable!("static test url"));
+    Request::new(Method::GET, url)
+}
+
+/// A full cache of street detail responses with `ETag` validators.
+fn filled_cache(body: &[u8]) -> ConditionalCache {
+    let cache = ConditionalCache::default();
+    for index in 0..DEFAULT_CONDITIONAL_CACHE_CAPACITY {
+        let mut headers = HeaderMap::new();
+        if let Ok(etag) = HeaderValue::from_str(&format!("\"{index:08x}\"")) {
+            headers.insert(ETAG, etag);
+        }
+        cache.store(&url(index), &headers, body);
+    }
+    cache
+}
+
+fn bench_conditional_cache(criterion: &mut Criterion) {
+    let body = vec![b'x'; 4 * 1024];
+    let cache = filled_cache(&body);
+
+    criterion.bench_function("prepare_hit", |bencher| {
+        bencher.iter_batched(
+            || request(DEFAULT_CONDITIONAL_CACHE_CAPACITY / 2),
+            |mut request| black_box(cache.prepare(&mut request)),
+            BatchSize::SmallInput,
+        );
+    });
+
+    criterion.bench_function("prepare_miss", |bencher| {
+        bencher.iter_batched(
+            || request(DEFAULT_CONDITIONAL_CACHE_CAPACITY * 2),
+            |mut request| black_box(cache.prepare(&mut request)),
+            BatchSize::SmallInput,
+        );
+    });
+
+    let mut headers = HeaderMap::new();
+    headers.insert(ETAG, HeaderValue::from_static("\"fresh\""));
+    let mut next = DEFAULT_CONDITIONAL_CACHE_CAPACITY;
+    criterion.bench_function("store_with_eviction", |bencher| {
+        bencher.iter(|| {
+            next += 1;
+            cache.store(&url(next), &headers, &body);
+        });
+    });
+}
+
+criterion_group!(benches, bench_conditional_cache);
+criterion_main!(benches);
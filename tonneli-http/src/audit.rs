@@ -0,0 +1,81 @@
+//! Record of every request sent, so users can check what goes where.
+
+use std::time::SystemTime;
+
+use reqwest::{Method, Url};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// How much of a request the audit log keeps.
+pub enum AuditDetail {
+    /// Method, host and path; query strings often contain the searched address.
+    #[default]
+    Endpoint,
+    /// Also the query string.
+    Verbose,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// One request as seen by a [`RequestAuditor`].
+pub struct AuditEntry {
+    /// When the request was sent.
+    pub time: SystemTime,
+    /// HTTP method.
+    pub method: Method,
+    /// Host the request went to, i.e. the provider's backend.
+    pub host: String,
+    /// Request path.
+    pub path: String,
+    /// Query string, only with [`AuditDetail::Verbose`].
+    pub query: Option<String>,
+    /// Response status, or `None` if no response arrived.
+    pub status: Option<u16>,
+}
+
+impl AuditEntry {
+    pub(crate) fn new(method: &Method, url: &Url, detail: AuditDetail) -> Self {
+        Self {
+            time: SystemTime::now(),
+            method: method.clone(),
+            host: url.host_str().unwrap_or_default().to_owned(),
+            path: url.path().to_owned(),
+            query: match detail {
+                AuditDetail::Endpoint => None,
+                AuditDetail::Verbose => url.query().map(str::to_owned),
+            },
+            status: None,
+        }
+    }
+}
+
+/// Receives an [`AuditEntry`] for every request the client sends, including retries.
+pub trait RequestAuditor: Send + Sync {
+    /// Record `entry`; called on the request path, so keep it quick.
+    fn record(&self, entry: &AuditEntry);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_queries_only_when_verbose() {
+        let url = Url::parse("https://abfall.example/api/streets?q=Domkloster+4")
+            .expect("valid test url");
+        let endpoint = AuditEntry::new(&Method::GET, &url, AuditDetail::Endpoint);
+        assert_eq!(
+            (
+                endpoint.host.as_str(),
+                endpoint.path.as_str(),
+                endpoint.query
+            ),
+            ("abfall.example", "/api/streets", None),
+            "the endpoint is recorded without the search"
+        );
+        let verbose = AuditEntry::new(&Method::GET, &url, AuditDetail::Verbose);
+        assert_eq!(
+            verbose.query.as_deref(),
+            Some("q=Domkloster+4"),
+            "verbose entries keep the query"
+        );
+    }
+}
@@ -0,0 +1,415 @@
+//! HTTP client wrapper shared by all providers.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, IntoUrl, Request, RequestBuilder, StatusCode};
+use serde::de::{DeserializeOwned, DeserializeSeed};
+use tracing::{debug, warn};
+use web_time::SystemTime;
+#[cfg(feature = "tracing")]
+use {
+    reqwest::Url,
+    tracing::{Instrument as _, field, info_span},
+    web_time::Instant,
+};
+
+#[cfg(feature = "fixtures")]
+use crate::fixtures::{FixtureMode, Fixtures};
+
+use crate::audit::{AuditDetail, AuditEntry, RequestAuditor};
+use crate::conditional::{ConditionalCache, DEFAULT_CONDITIONAL_CACHE_CAPACITY};
+use crate::error::HttpError;
+use crate::retry::{NoRetry, RetryPolicy};
+use crate::timer::{sleep, timeout};
+use crate::transport::{ReqwestTransport, Transport};
+
+/// User agent sent with every request unless overridden.
+pub const DEFAULT_USER_AGENT: &str = concat!("tonneli/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Clone)]
+/// Settings applied to the underlying HTTP client.
+///
+/// In the browser, connections, proxies and certificates are up to the browser;
+/// the timeouts, pool and TLS settings are ignored there.
+pub struct HttpConfig {
+    /// `User-Agent` header value.
+    pub user_agent: String,
+    /// Maximum time to establish a connection.
+    pub connect_timeout: Duration,
+    /// Maximum time to wait for the next chunk of the response.
+    pub read_timeout: Duration,
+    /// Largest accepted response body in bytes.
+    pub max_response_bytes: usize,
+    /// Longest a single fetch may take, including body download and retries.
+    pub max_request_duration: Duration,
+    /// Number of responses remembered for conditional requests.
+    pub cache_capacity: usize,
+    /// Proxy URL for all requests; `None` uses the system proxy settings.
+    pub proxy: Option<String>,
+    /// PEM files with additional trusted root certificates.
+    pub root_certificates: Vec<PathBuf>,
+    /// Trust only `root_certificates`, not the built-in roots.
+    pub only_custom_roots: bool,
+    /// Maximum number of idle connections kept per host.
+    pub pool_max_idle_per_host: usize,
+    /// How long idle connections are kept in the pool.
+    pub pool_idle_timeout: Duration,
+    /// Log URLs without their query string, which often contains the searched address.
+    pub redact_queries: bool,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            connect_timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(20),
+            max_response_bytes: 16 * 1024 * 1024,
+            max_request_duration: Duration::from_mins(1),
+            cache_capacity: DEFAULT_CONDITIONAL_CACHE_CAPACITY,
+            proxy: None,
+            root_certificates: Vec::new(),
+            only_custom_roots: false,
+            pool_max_idle_per_host: usize::MAX,
+            pool_idle_timeout: Duration::from_secs(90),
+            redact_queries: false,
+        }
+    }
+}
+
+/// Cheaply cloneable HTTP client with conditional caching, size limits, and retries.
+#[derive(Clone)]
+pub struct HttpClient {
+    client: Client,
+    transport: Arc<dyn Transport>,
+    cache: Arc<ConditionalCache>,
+    max_response_bytes: usize,
+    max_request_duration: Duration,
+    retry: Arc<dyn RetryPolicy>,
+    audit: Option<(Arc<dyn RequestAuditor>, AuditDetail)>,
+    redact_queries: bool,
+    #[cfg(feature = "fixtures")]
+    fixtures: Option<Arc<Fixtures>>,
+}
+
+impl fmt::Debug for HttpClient {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("HttpClient")
+            .field("max_response_bytes", &self.max_response_bytes)
+            .field("max_request_duration", &self.max_request_duration)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpClient {
+    /// Build a client from the given settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HttpError::Config`] for an invalid proxy URL or unreadable certificate
+    /// and [`HttpError::Transport`] when the TLS backend cannot be initialized.
+    pub fn new(config: &HttpConfig) -> Result<Self, HttpError> {
+        let transport = ReqwestTransport::new(config)?;
+        let client = transport.client().clone();
+        Ok(Self::with_parts(config, client, Arc::new(transport)))
+    }
+
+    /// Build a client that sends its requests through `transport`.
+    ///
+    /// Only the cache, size, duration and logging settings of `config` apply; the
+    /// connection settings are up to the transport.
+    #[must_use]
+    pub fn with_transport(config: &HttpConfig, transport: Arc<dyn Transport>) -> Self {
+        Self::with_parts(config, Client::new(), transport)
+    }
+
+    fn with_parts(config: &HttpConfig, client: Client, transport: Arc<dyn Transport>) -> Self {
+        Self {
+            client,
+            transport,
+            cache: Arc::new(ConditionalCache::new(config.cache_capacity)),
+            max_response_bytes: config.max_response_bytes,
+            max_request_duration: config.max_request_duration,
+            retry: Arc::new(NoRetry),
+            audit: None,
+            redact_queries: config.redact_queries,
+            #[cfg(feature = "fixtures")]
+            fixtures: None,
+        }
+    }
+
+    /// Replace the retry policy consulted after failed attempts.
+    #[must_use]
+    pub fn with_retry_policy<P: RetryPolicy + 'static>(mut self, policy: P) -> Self {
+        self.retry = Arc::new(policy);
+        self
+    }
+
+    /// Report every request to `auditor`, keeping as much of it as `detail` allows.
+    #[must_use]
+    pub fn with_auditor(mut self, auditor: Arc<dyn RequestAuditor>, detail: AuditDetail) -> Self {
+        self.audit = Some((auditor, detail));
+        self
+    }
+
+    /// Record responses to disk or answer requests from recorded ones, see
+    /// [`Fixtures`].
+    #[cfg(feature = "fixtures")]
+    #[must_use]
+    pub fn with_fixtures(mut self, fixtures: Fixtures) -> Self {
+        self.fixtures = Some(Arc::new(fixtures));
+        self
+    }
+
+    /// Start a GET request.
+    pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.client.get(url)
+    }
+
+    /// Start a POST request, e.g. for form-based backends.
+    pub fn post<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    /// Send the request and decode the JSON body.
+    ///
+    /// # Errors
+    ///
+    /// See [`HttpClient::fetch_bytes`]; additionally [`HttpError::Decode`] for invalid JSON.
+    pub async fn fetch_json<T: DeserializeOwned>(
+        &self,
+        req: RequestBuilder,
+    ) -> Result<T, HttpError> {
+        let body = self.fetch_body(req, true).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Send the request and decode the JSON body with a seed such as
+    /// [`CappedField`](crate::decode::CappedField).
+    ///
+    /// # Errors
+    ///
+    /// See [`HttpClient::fetch_json`].
+    pub async fn fetch_json_seed<S, V>(&self, req: RequestBuilder, seed: S) -> Result<V, HttpError>
+    where
+        S: for<'de> DeserializeSeed<'de, Value = V>,
+    {
+        let body = self.fetch_body(req, true).await?;
+        let mut deserializer = serde_json::Deserializer::from_slice(&body);
+        let value = seed.deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(value)
+    }
+
+    /// Send the request (retrying per policy) and return the body.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HttpError::Transport`] or [`HttpError::Status`] when the request fails,
+    /// [`HttpError::Timeout`] when a configured timeout expires, and
+    /// [`HttpError::TooLarge`] when the body exceeds the configured limit, and
+    /// [`HttpError::DeadlineExceeded`] when the whole fetch takes too long.
+    pub async fn fetch_bytes(&self, req: RequestBuilder) -> Result<Vec<u8>, HttpError> {
+        self.fetch_body(req, false).await
+    }
+
+    /// Retry loop behind the fetch methods; `expect_json` rejects HTML bodies as
+    /// [`HttpError::Unavailable`] so maintenance pages are retried like outages.
+    async fn fetch_body(
+        &self,
+        req: RequestBuilder,
+        expect_json: bool,
+    ) -> Result<Vec<u8>, HttpError> {
+        // Bounds slow-drip responses that keep resetting the read timeout.
+        let limit = self.max_request_duration;
+        timeout(limit, self.fetch_attempts(req, expect_json))
+            .await
+            .ok_or(HttpError::DeadlineExceeded { limit })?
+    }
+
+    async fn fetch_attempts(
+        &self,
+        req: RequestBuilder,
+        expect_json: bool,
+    ) -> Result<Vec<u8>, HttpError> {
+        let mut request = req.build()?;
+        let mut attempt = 1;
+        loop {
+            // Keep a copy for a possible retry; requests with streaming bodies
+            // cannot be cloned and are only sent once.
+            let retry_request = request.try_clone();
+            let result = self.send_once(request).await.and_then(|body| {
+                if expect_json {
+                    reject_html(body)
+                } else {
+                    Ok(body)
+                }
+            });
+            let err = match result {
+                Ok(body) => return Ok(body),
+                Err(err) => err,
+            };
+
+            let Some(delay) = self.retry.retry_delay(attempt, &err) else {
+                return Err(err);
+            };
+            let Some(next_request) = retry_request else {
+                return Err(err);
+            };
+            warn!(attempt, ?delay, %err, "request failed, retrying");
+            sleep(delay).await;
+            request = next_request;
+            attempt += 1;
+        }
+    }
+
+    async fn send_once(&self, mut request: Request) -> Result<Vec<u8>, HttpError> {
+        let url = request.url().to_string();
+        let logged_url = if self.redact_queries {
+            let mut redacted = request.url().clone();
+            redacted.set_query(None);
+            redacted.to_string()
+        } else {
+            url.clone()
+        };
+
+        #[cfg(feature = "fixtures")]
+        let fixture = match self.fixtures.as_deref() {
+            Some(fixtures) if fixtures.mode() == FixtureMode::Replay => {
+                debug!(method = %request.method(), url = %logged_url, "replaying fixture");
+                return fixtures.load(&request);
+            }
+            Some(fixtures) => Some((fixtures, fixtures.path(&request))),
+            None => None,
+        };
+
+        let cached = self.cache.prepare(&mut request);
+        let mut audit = self.audit.as_ref().map(|(auditor, detail)| {
+            (
+                auditor,
+                AuditEntry::new(request.method(), request.url(), *detail),
+            )
+        });
+        debug!(method = %request.method(), url = %logged_url, conditional = cached.is_some(), "sending request");
+        #[cfg(feature = "tracing")]
+        let span = info_span!(
+            "http_request",
+            method = %request.method(),
+            endpoint = %endpoint(request.url()),
+            status = field::Empty,
+            duration_ms = field::Empty,
+            error = field::Empty,
+        );
+        #[cfg(feature = "tracing")]
+        let started = Instant::now();
+        let sent = self.transport.execute(request, self.max_response_bytes);
+        #[cfg(feature = "tracing")]
+        let sent = sent.instrument(span.clone());
+        // Transport errors carry the full URL into logs and error reports.
+        let resp = sent.await.map_err(|err| match err {
+            HttpError::Transport(transport) if self.redact_queries => {
+                HttpError::Transport(transport.without_url())
+            }
+            other => other,
+        });
+        #[cfg(feature = "tracing")]
+        {
+            span.record("duration_ms", started.elapsed().as_millis());
+            match &resp {
+                Ok(resp) => span.record("status", resp.status.as_u16()),
+                Err(err) => span.record("error", field::display(err)),
+            };
+        }
+        if let Some((auditor, entry)) = &mut audit {
+            entry.status = resp.as_ref().ok().map(|resp| resp.status.as_u16());
+            auditor.record(entry);
+        }
+        let resp = resp?;
+        debug!(url = %logged_url, status = %resp.status, "received response");
+
+        if resp.status == StatusCode::NOT_MODIFIED
+            && let Some(cached) = cached
+        {
+            return Ok(cached.into_body());
+        }
+
+        if !resp.status.is_success() {
+            return Err(status_error(resp.status, &resp.headers));
+        }
+
+        let body = resp.body;
+        self.cache.store(&url, &resp.headers, &body);
+        #[cfg(feature = "fixtures")]
+        if let Some((fixtures, path)) = fixture {
+            fixtures.save(&path, &body)?;
+        }
+        Ok(body)
+    }
+}
+
+/// Municipal APIs often answer outages with an HTML maintenance page and status 200.
+fn reject_html(body: Vec<u8>) -> Result<Vec<u8>, HttpError> {
+    if body.iter().find(|byte| !byte.is_ascii_whitespace()) != Some(&b'<') {
+        return Ok(body);
+    }
+    let html = String::from_utf8_lossy(&body);
+    let message = html_title(&html)
+        .unwrap_or("the provider returned a web page instead of data")
+        .to_owned();
+    Err(HttpError::Unavailable { message })
+}
+
+/// Text of the `<title>` element, if any.
+fn html_title(html: &str) -> Option<&str> {
+    // ASCII lowercasing keeps byte offsets, so they apply to `html` as well.
+    let lower = html.to_ascii_lowercase();
+    let start = lower.find("<title")?;
+    let content_start = start + lower.get(start..)?.find('>')? + 1;
+    let content_end = content_start + lower.get(content_start..)?.find("</title")?;
+    let title = html.get(content_start..content_end)?.trim();
+    (!title.is_empty()).then_some(title)
+}
+
+/// Host and path of `url`; spans leave out the query, which often holds the address.
+#[cfg(feature = "tracing")]
+fn endpoint(url: &Url) -> String {
+    format!("{}{}", url.host_str().unwrap_or_default(), url.path())
+}
+
+fn status_error(status: StatusCode, headers: &HeaderMap) -> HttpError {
+    let retry_after = headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_retry_after);
+
+    match (status, retry_after) {
+        (StatusCode::TOO_MANY_REQUESTS, retry_after)
+        | (StatusCode::SERVICE_UNAVAILABLE, retry_after @ Some(_)) => HttpError::RateLimited {
+            retry_after,
+            status,
+        },
+        _ => HttpError::Status(status),
+    }
+}
+
+/// Parse `Retry-After` as either delta-seconds or an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    // `httpdate` speaks `std` time, which has no clock in the browser.
+    let at = httpdate::parse_http_date(value)
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    Some(at.saturating_sub(now))
+}
@@ -0,0 +1,108 @@
+//! ETag/Last-Modified storage for conditional HTTP requests.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, PoisonError};
+
+use reqwest::Request;
+use reqwest::header::{
+    ETAG, HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+
+/// Default number of responses kept per cache.
+pub const DEFAULT_CONDITIONAL_CACHE_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone)]
+/// Response body stored together with its validators.
+pub struct CachedResponse {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    body: Vec<u8>,
+}
+
+impl CachedResponse {
+    /// The stored response body.
+    #[must_use]
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Take the stored response body.
+    #[must_use]
+    pub fn into_body(self) -> Vec<u8> {
+        self.body
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    by_url: HashMap<String, CachedResponse>,
+    insertion_order: VecDeque<String>,
+}
+
+/// Remembers response validators per URL so repeated requests can be answered with
+/// `304 Not Modified` instead of a full body.
+#[derive(Debug)]
+pub struct ConditionalCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+impl Default for ConditionalCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CONDITIONAL_CACHE_CAPACITY)
+    }
+}
+
+impl ConditionalCache {
+    /// Create a cache holding at most `capacity` responses; the oldest entry is evicted first.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    /// Add `If-None-Match`/`If-Modified-Since` for a known URL and return the stored response.
+    pub fn prepare(&self, request: &mut Request) -> Option<CachedResponse> {
+        let cached = {
+            let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+            entries.by_url.get(request.url().as_str()).cloned()
+        }?;
+
+        let headers = request.headers_mut();
+        if let Some(etag) = &cached.etag {
+            headers.insert(IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+        }
+        Some(cached)
+    }
+
+    /// Remember a response body if the server sent validators for it.
+    pub fn store(&self, url: &str, headers: &HeaderMap, body: &[u8]) {
+        let etag = headers.get(ETAG).cloned();
+        let last_modified = headers.get(LAST_MODIFIED).cloned();
+        if self.capacity == 0 || (etag.is_none() && last_modified.is_none()) {
+            return;
+        }
+
+        let response = CachedResponse {
+            etag,
+            last_modified,
+            body: body.to_vec(),
+        };
+
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if entries.by_url.insert(url.to_owned(), response).is_none() {
+            entries.insertion_order.push_back(url.to_owned());
+        }
+        while entries.by_url.len() > self.capacity {
+            let Some(oldest) = entries.insertion_order.pop_front() else {
+                break;
+            };
+            entries.by_url.remove(&oldest);
+        }
+    }
+}
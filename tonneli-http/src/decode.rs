@@ -0,0 +1,122 @@
+//! Bounded JSON decoding for endpoints that may return huge arrays.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::Deserialize;
+use serde::de::{
+    DeserializeSeed, Deserializer, Error as DeError, IgnoredAny, MapAccess, SeqAccess, Visitor,
+};
+
+#[derive(Debug, Clone)]
+/// Leading elements of a JSON array plus the total number of elements it had.
+pub struct Capped<T> {
+    /// At most `limit` decoded elements.
+    pub items: Vec<T>,
+    /// Number of elements in the array, including skipped ones.
+    pub total: usize,
+}
+
+impl<T> Capped<T> {
+    /// Whether elements were skipped.
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        self.total > self.items.len()
+    }
+}
+
+/// Seed decoding the array stored under `field` of a JSON object, keeping only the first
+/// `limit` elements. Remaining elements and other fields are skipped without allocating.
+#[derive(Debug, Clone, Copy)]
+pub struct CappedField<T> {
+    field: &'static str,
+    limit: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> CappedField<T> {
+    /// Decode at most `limit` elements of the array at `field`.
+    #[must_use]
+    pub fn new(field: &'static str, limit: usize) -> Self {
+        Self {
+            field,
+            limit,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for CappedField<T> {
+    type Value = Capped<T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for CappedField<T> {
+    type Value = Capped<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "an object with an array field `{}`", self.field)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut capped = None;
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.field {
+                capped = Some(map.next_value_seed(CappedSeq::<T>::new(self.limit))?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        capped.ok_or_else(|| DeError::missing_field(self.field))
+    }
+}
+
+struct CappedSeq<T> {
+    limit: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> CappedSeq<T> {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for CappedSeq<T> {
+    type Value = Capped<T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for CappedSeq<T> {
+    type Value = Capped<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut items = Vec::with_capacity(self.limit.min(seq.size_hint().unwrap_or(0)));
+        let mut total = 0;
+        loop {
+            if items.len() < self.limit {
+                match seq.next_element::<T>()? {
+                    Some(item) => items.push(item),
+                    None => break,
+                }
+            } else if seq.next_element::<IgnoredAny>()?.is_none() {
+                break;
+            }
+            total += 1;
+        }
+        Ok(Capped { items, total })
+    }
+}
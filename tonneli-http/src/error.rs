@@ -0,0 +1,106 @@
+//! Errors produced by the shared HTTP layer.
+
+use std::time::Duration;
+
+use reqwest::{Error as ReqwestError, StatusCode};
+use serde_json::Error as JsonError;
+
+#[derive(thiserror::Error, Debug)]
+/// Errors that can occur while fetching from a provider backend.
+pub enum HttpError {
+    /// Connecting, sending, or reading the response failed.
+    #[error("Network error: {0}")]
+    Transport(ReqwestError),
+    /// The connect or read timeout expired.
+    #[error("Request timed out")]
+    Timeout,
+    /// The server throttled the client (429, or 503 with `Retry-After`).
+    #[error("Rate limited by provider")]
+    RateLimited {
+        /// Delay requested by the server via `Retry-After`, if any.
+        retry_after: Option<Duration>,
+        /// Status of the answer, 429 or 503.
+        status: StatusCode,
+    },
+    /// The server answered with a non-success status code.
+    #[error("Unexpected HTTP status {0}")]
+    Status(StatusCode),
+    /// The whole fetch, including retries, exceeded the configured duration.
+    #[error("Request took longer than {limit:?}")]
+    DeadlineExceeded {
+        /// Configured maximum duration.
+        limit: Duration,
+    },
+    /// The response body exceeded the configured size limit.
+    #[error("Response larger than {limit} bytes")]
+    TooLarge {
+        /// Configured maximum body size in bytes.
+        limit: usize,
+    },
+    /// The response body was not valid JSON for the expected type.
+    #[error("Invalid response body: {0}")]
+    Decode(#[from] JsonError),
+    /// The provider answered with an HTML page (usually maintenance) instead of data.
+    #[error("Provider unavailable: {message}")]
+    Unavailable {
+        /// Page title or a generic description.
+        message: String,
+    },
+    /// The client configuration is invalid (proxy URL, certificate file).
+    #[error("Invalid HTTP configuration: {0}")]
+    Config(String),
+    /// A recorded response is missing or could not be saved (feature `fixtures`).
+    #[error("Fixture error: {0}")]
+    Fixture(String),
+}
+
+impl HttpError {
+    /// Whether repeating the request might succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Timeout | Self::RateLimited { .. } | Self::Unavailable { .. } => true,
+            Self::Transport(err) => is_connect(err) || err.is_request(),
+            Self::Status(status) => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            Self::DeadlineExceeded { .. }
+            | Self::TooLarge { .. }
+            | Self::Decode(_)
+            | Self::Config(_)
+            | Self::Fixture(_) => false,
+        }
+    }
+}
+
+impl HttpError {
+    /// Delay the server asked for before the next attempt.
+    #[must_use]
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+/// The browser does not tell connection failures apart from other request errors.
+#[cfg(not(target_arch = "wasm32"))]
+fn is_connect(err: &ReqwestError) -> bool {
+    err.is_connect()
+}
+
+#[cfg(target_arch = "wasm32")]
+const fn is_connect(_err: &ReqwestError) -> bool {
+    false
+}
+
+impl From<ReqwestError> for HttpError {
+    fn from(err: ReqwestError) -> Self {
+        if err.is_timeout() {
+            Self::Timeout
+        } else {
+            Self::Transport(err)
+        }
+    }
+}
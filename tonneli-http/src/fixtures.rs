@@ -0,0 +1,193 @@
+//! Recording provider responses to disk and replaying them in tests.
+//!
+//! A client with [`HttpClient::with_fixtures`] either sends every request and
+//! saves the body of each successful response ([`FixtureMode::Record`]), or answers
+//! every request from the saved bodies without touching the network
+//! ([`FixtureMode::Replay`]). Fixtures are named after the method, the path and a
+//! hash of path, query and request body; the host is left out, so recordings of the
+//! live API replay for any base URL.
+//!
+//! [`HttpClient::with_fixtures`]: crate::client::HttpClient::with_fixtures
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use reqwest::Request;
+
+use crate::error::HttpError;
+
+/// Environment variable switching [`Fixtures::from_env`] to recording when set to `1`.
+pub const RECORD_FIXTURES_ENV: &str = "TONNELI_RECORD_FIXTURES";
+
+/// Longest path part of a fixture name, in bytes.
+const MAX_SLUG_LEN: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Whether requests go to the network or to the fixture directory.
+pub enum FixtureMode {
+    /// Send requests and save every successful response body.
+    Record,
+    /// Answer requests from saved bodies; a missing fixture is an error.
+    Replay,
+}
+
+#[derive(Debug, Clone)]
+/// Directory of recorded responses and what to do with it.
+pub struct Fixtures {
+    dir: PathBuf,
+    mode: FixtureMode,
+}
+
+impl Fixtures {
+    /// Record responses into `dir`.
+    #[must_use]
+    pub fn record(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            mode: FixtureMode::Record,
+        }
+    }
+
+    /// Replay responses from `dir`.
+    #[must_use]
+    pub fn replay(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            mode: FixtureMode::Replay,
+        }
+    }
+
+    /// Replay from `dir`, or record into it when [`RECORD_FIXTURES_ENV`] is `1`.
+    #[must_use]
+    pub fn from_env(dir: impl Into<PathBuf>) -> Self {
+        if env::var(RECORD_FIXTURES_ENV).is_ok_and(|value| value == "1") {
+            Self::record(dir)
+        } else {
+            Self::replay(dir)
+        }
+    }
+
+    /// Whether requests are recorded or replayed.
+    #[must_use]
+    pub const fn mode(&self) -> FixtureMode {
+        self.mode
+    }
+
+    /// File the response to `request` is saved in.
+    #[must_use]
+    pub fn path(&self, request: &Request) -> PathBuf {
+        let url = request.url();
+        let mut query = url.query_pairs().collect::<Vec<_>>();
+        query.sort();
+        let mut hash = Fnv::default();
+        hash.write(url.path().as_bytes());
+        for (key, value) in &query {
+            hash.write(b"&");
+            hash.write(key.as_bytes());
+            hash.write(b"=");
+            hash.write(value.as_bytes());
+        }
+        if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+            hash.write(b"\n");
+            hash.write(body);
+        }
+
+        let mut slug = url
+            .path()
+            .chars()
+            .map(|char| {
+                if char.is_ascii_alphanumeric() {
+                    char.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect::<String>();
+        slug = slug.trim_matches('-').to_owned();
+        slug.truncate(MAX_SLUG_LEN);
+        let method = request.method().as_str().to_ascii_lowercase();
+        self.dir
+            .join(format!("{method}-{slug}-{:016x}.body", hash.finish()))
+    }
+
+    /// Saved body for `request`.
+    pub(crate) fn load(&self, request: &Request) -> Result<Vec<u8>, HttpError> {
+        let path = self.path(request);
+        fs::read(&path).map_err(|err| {
+            HttpError::Fixture(format!(
+                "no recorded response for {} {} in {}: {err}",
+                request.method(),
+                request.url().path(),
+                path.display()
+            ))
+        })
+    }
+
+    /// Save `body` as the response to the request with fixture file `path`.
+    pub(crate) fn save(&self, path: &Path, body: &[u8]) -> Result<(), HttpError> {
+        fs::create_dir_all(&self.dir)
+            .and_then(|()| fs::write(path, body))
+            .map_err(|err| HttpError::Fixture(format!("writing {} failed: {err}", path.display())))
+    }
+}
+
+/// 64-bit FNV-1a, stable across Rust versions unlike the std hashers.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    const fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Client;
+
+    use super::*;
+
+    #[test]
+    fn names_ignore_host_and_query_order() {
+        let fixtures = Fixtures::replay("fixtures");
+        let client = Client::new();
+        let fixture = |url: &str| {
+            client
+                .get(url)
+                .build()
+                .map(|request| fixtures.path(&request))
+                .ok()
+        };
+
+        let live = fixture("https://example.org/api/streets?b=2&a=1");
+        assert_eq!(
+            live,
+            fixture("http://127.0.0.1:8080/api/streets?a=1&b=2"),
+            "same fixture for any base URL and parameter order"
+        );
+        assert_ne!(
+            live,
+            fixture("https://example.org/api/streets?a=1&b=3"),
+            "other parameters, other fixture"
+        );
+        assert!(
+            live.as_ref()
+                .and_then(|path| path.file_name())
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("get-api-streets-")),
+            "readable prefix: {live:?}"
+        );
+    }
+}
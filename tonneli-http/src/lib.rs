@@ -0,0 +1,43 @@
+//! Shared HTTP layer for tonneli providers: one configured client with conditional
+//! caching, response-size limits, status mapping, and retry hooks.
+//!
+//! Requests go out through a [`Transport`], by default reqwest, which also builds
+//! for `wasm32-unknown-unknown` and sends through the browser's `fetch` there.
+
+/// Audit log hook for outgoing requests.
+pub mod audit;
+/// Client wrapper and its configuration.
+pub mod client;
+/// Validator cache for conditional HTTP requests.
+pub mod conditional;
+/// Bounded decoding of large JSON arrays.
+pub mod decode;
+/// Error type of the HTTP layer.
+pub mod error;
+/// Recording responses to disk and replaying them in tests (feature `fixtures`).
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+/// Retry policies.
+pub mod retry;
+/// Delays and deadlines that work natively and in the browser.
+pub mod timer;
+/// Pluggable request sending.
+pub mod transport;
+
+pub use audit::*;
+pub use client::*;
+pub use conditional::*;
+pub use decode::*;
+pub use error::*;
+#[cfg(feature = "fixtures")]
+pub use fixtures::*;
+pub use retry::*;
+pub use timer::sleep;
+pub use transport::*;
+
+/// Request builder returned by [`HttpClient::get`].
+pub use reqwest::RequestBuilder;
+
+// Dev-dependency only used by the benchmarks in `benches/`.
+#[cfg(test)]
+use criterion as _;
@@ -0,0 +1,60 @@
+//! Retry hooks consulted by [`HttpClient`](crate::HttpClient) after a failed attempt.
+
+use std::time::Duration;
+
+use crate::error::HttpError;
+
+/// Decides whether and when a failed request is sent again.
+pub trait RetryPolicy: Send + Sync {
+    /// Delay before the next attempt, or `None` to give up.
+    ///
+    /// `attempt` is the number of attempts made so far (starting at 1).
+    fn retry_delay(&self, attempt: u32, error: &HttpError) -> Option<Duration>;
+}
+
+/// Never retries; the default policy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn retry_delay(&self, _attempt: u32, _error: &HttpError) -> Option<Duration> {
+        None
+    }
+}
+
+/// Retries retryable errors with exponentially growing delays.
+///
+/// A `Retry-After` delay sent by the server takes precedence over the computed backoff.
+#[derive(Debug, Clone, Copy)]
+pub struct ExponentialBackoff {
+    /// Maximum number of additional attempts.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for every further retry.
+    pub base_delay: Duration,
+    /// Longest delay worth waiting for; larger `Retry-After` values give up instead.
+    pub max_delay: Duration,
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn retry_delay(&self, attempt: u32, error: &HttpError) -> Option<Duration> {
+        if attempt > self.max_retries || !error.is_retryable() {
+            return None;
+        }
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        let backoff = self.base_delay.saturating_mul(factor);
+        let delay = error
+            .retry_after()
+            .map_or(backoff, |requested| requested.max(backoff));
+        (delay <= self.max_delay).then_some(delay)
+    }
+}
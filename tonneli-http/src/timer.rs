@@ -0,0 +1,59 @@
+//! Delays and deadlines on Tokio natively and on the browser's timers on `wasm32`.
+
+use std::future::Future;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time;
+#[cfg(target_arch = "wasm32")]
+use {
+    futures::channel::oneshot,
+    futures::future::{Either, select},
+    gloo_timers::future::TimeoutFuture,
+    std::pin::pin,
+    wasm_bindgen_futures::spawn_local,
+};
+
+/// Wait for `duration`, e.g. between retries.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn sleep(duration: Duration) {
+    time::sleep(duration).await;
+}
+
+/// Wait for `duration`, e.g. between retries.
+#[cfg(target_arch = "wasm32")]
+pub async fn sleep(duration: Duration) {
+    let millis = u32::try_from(duration.as_millis()).unwrap_or(u32::MAX);
+    in_browser(TimeoutFuture::new(millis)).await;
+}
+
+/// Output of `future`, or `None` if it takes longer than `limit`.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn timeout<F: Future>(limit: Duration, future: F) -> Option<F::Output> {
+    time::timeout(limit, future).await.ok()
+}
+
+/// Output of `future`, or `None` if it takes longer than `limit`.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn timeout<F: Future>(limit: Duration, future: F) -> Option<F::Output> {
+    match select(pin!(future), pin!(sleep(limit))).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(((), _)) => None,
+    }
+}
+
+/// Drive a browser future, which is not `Send`, on the page's event loop and wait
+/// for its output from a future that is; `None` if the task was dropped.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn in_browser<F>(future: F) -> impl Future<Output = Option<F::Output>> + Send
+where
+    F: Future + 'static,
+    F::Output: Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    spawn_local(async move {
+        // The receiver is gone when the caller gave up, e.g. after a timeout.
+        let _abandoned = sender.send(future.await);
+    });
+    async move { receiver.await.ok() }
+}
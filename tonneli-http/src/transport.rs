@@ -0,0 +1,275 @@
+//! Sending built requests: the [`Transport`] behind every [`HttpClient`] and its
+//! reqwest implementation.
+//!
+//! [`HttpClient`]: crate::client::HttpClient
+
+use std::fmt;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs, path::Path};
+
+use async_trait::async_trait;
+use reqwest::header::{CONTENT_LENGTH, HeaderMap};
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::{Certificate, Proxy};
+use reqwest::{Client, Request, Response, StatusCode};
+
+use crate::client::HttpConfig;
+use crate::error::HttpError;
+#[cfg(target_arch = "wasm32")]
+use crate::timer::in_browser;
+
+#[derive(Debug, Clone)]
+/// Answer of a [`Transport`] to one request.
+pub struct TransportResponse {
+    /// Response status.
+    pub status: StatusCode,
+    /// Response headers.
+    pub headers: HeaderMap,
+    /// Body of a successful response; empty for other statuses.
+    pub body: Vec<u8>,
+}
+
+#[async_trait]
+/// Sends requests over an HTTP stack; caching, retries and status handling stay in
+/// the [`HttpClient`](crate::client::HttpClient).
+///
+/// [`ReqwestTransport`] works natively and in the browser. Other implementations
+/// can route requests elsewhere, e.g. through a web app's own `fetch` wrapper.
+pub trait Transport: Send + Sync {
+    /// Send `request` and read the body of a successful response.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HttpError::Transport`] or [`HttpError::Timeout`] when no response
+    /// arrives and [`HttpError::TooLarge`] when a successful response's body exceeds
+    /// `max_body_bytes`. Error statuses are answers, not errors.
+    async fn execute(
+        &self,
+        request: Request,
+        max_body_bytes: usize,
+    ) -> Result<TransportResponse, HttpError>;
+}
+
+/// [`Transport`] over a reqwest client: hyper natively, the browser's `fetch` on
+/// `wasm32`.
+#[derive(Clone)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl fmt::Debug for ReqwestTransport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ReqwestTransport")
+            .finish_non_exhaustive()
+    }
+}
+
+impl ReqwestTransport {
+    /// Transport with the connection settings of `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HttpError::Config`] for an invalid proxy URL or unreadable certificate
+    /// and [`HttpError::Transport`] when the TLS backend cannot be initialized.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(config: &HttpConfig) -> Result<Self, HttpError> {
+        let mut builder = Client::builder()
+            .user_agent(config.user_agent.as_str())
+            .connect_timeout(config.connect_timeout)
+            .read_timeout(config.read_timeout)
+            .pool_max_idle_per_host(config.pool_max_idle_per_host)
+            .pool_idle_timeout(config.pool_idle_timeout)
+            .tls_built_in_root_certs(!config.only_custom_roots);
+
+        if let Some(proxy) = &config.proxy {
+            let proxy = Proxy::all(proxy)
+                .map_err(|err| HttpError::Config(format!("invalid proxy {proxy}: {err}")))?;
+            builder = builder.proxy(proxy);
+        }
+        for path in &config.root_certificates {
+            builder = builder.add_root_certificate(load_certificate(path)?);
+        }
+        Ok(Self {
+            client: builder.build()?,
+        })
+    }
+
+    /// Transport sending through the browser.
+    ///
+    /// Connections, proxies and certificates are the browser's business, so only
+    /// the user agent of `config` applies, where the browser allows it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HttpError::Transport`] for a user agent that is no valid header.
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(config: &HttpConfig) -> Result<Self, HttpError> {
+        Ok(Self {
+            client: Client::builder()
+                .user_agent(config.user_agent.as_str())
+                .build()?,
+        })
+    }
+
+    /// Underlying client, e.g. to start requests with its defaults.
+    #[must_use]
+    pub const fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn execute(
+        &self,
+        request: Request,
+        max_body_bytes: usize,
+    ) -> Result<TransportResponse, HttpError> {
+        let resp = self.client.execute(request).await?;
+        read_response(resp, max_body_bytes).await
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn execute(
+        &self,
+        request: Request,
+        max_body_bytes: usize,
+    ) -> Result<TransportResponse, HttpError> {
+        // Browser futures are not `Send`; they run on the page's event loop instead.
+        let client = self.client.clone();
+        in_browser(
+            async move { read_response(client.execute(request).await?, max_body_bytes).await },
+        )
+        .await
+        .ok_or_else(|| HttpError::Unavailable {
+            message: "the browser dropped the request".to_owned(),
+        })?
+    }
+}
+
+/// Status, headers and, for a successful response, the body up to `limit` bytes.
+async fn read_response(resp: Response, limit: usize) -> Result<TransportResponse, HttpError> {
+    let status = resp.status();
+    let headers = resp.headers().clone();
+    let body = if status.is_success() {
+        read_limited(resp, limit).await?
+    } else {
+        Vec::new()
+    };
+    Ok(TransportResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn read_limited(mut resp: Response, limit: usize) -> Result<Vec<u8>, HttpError> {
+    let announced = announced_length(&resp, limit)?;
+    let mut body = Vec::with_capacity(announced.unwrap_or(0));
+    while let Some(chunk) = resp.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            return Err(HttpError::TooLarge { limit });
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// The browser hands out the body in one piece, so the limit applies afterwards.
+#[cfg(target_arch = "wasm32")]
+async fn read_limited(resp: Response, limit: usize) -> Result<Vec<u8>, HttpError> {
+    announced_length(&resp, limit)?;
+    let body = resp.bytes().await?;
+    if body.len() > limit {
+        return Err(HttpError::TooLarge { limit });
+    }
+    Ok(body.to_vec())
+}
+
+/// `Content-Length` of `resp`, rejecting bodies announced to exceed `limit`.
+fn announced_length(resp: &Response, limit: usize) -> Result<Option<usize>, HttpError> {
+    let announced = resp
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if announced.is_some_and(|length| length > limit) {
+        return Err(HttpError::TooLarge { limit });
+    }
+    Ok(announced)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn load_certificate(path: &Path) -> Result<Certificate, HttpError> {
+    let invalid = |err: &dyn fmt::Display| {
+        HttpError::Config(format!("invalid certificate {}: {err}", path.display()))
+    };
+    let pem = fs::read(path).map_err(|err| invalid(&err))?;
+    Certificate::from_pem(&pem).map_err(|err| invalid(&err))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use reqwest::header::{HeaderValue, RETRY_AFTER};
+
+    use super::*;
+    use crate::client::HttpClient;
+
+    /// Answers `/busy` with 503 and everything else with a JSON body.
+    struct Canned;
+
+    #[async_trait]
+    impl Transport for Canned {
+        async fn execute(
+            &self,
+            request: Request,
+            _max_body_bytes: usize,
+        ) -> Result<TransportResponse, HttpError> {
+            let mut headers = HeaderMap::new();
+            if request.url().path() == "/busy" {
+                headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+                return Ok(TransportResponse {
+                    status: StatusCode::SERVICE_UNAVAILABLE,
+                    headers,
+                    body: Vec::new(),
+                });
+            }
+            Ok(TransportResponse {
+                status: StatusCode::OK,
+                headers,
+                body: br#"{"street":"Beispielweg"}"#.to_vec(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn client_sends_through_custom_transport() {
+        let client = HttpClient::with_transport(&HttpConfig::default(), Arc::new(Canned));
+
+        let body: serde_json::Value = client
+            .fetch_json(client.get("https://example.org/streets"))
+            .await
+            .expect("canned body decodes");
+        assert_eq!(
+            body.get("street").and_then(serde_json::Value::as_str),
+            Some("Beispielweg"),
+            "body from the transport"
+        );
+        assert!(
+            matches!(
+                client.fetch_bytes(client.get("https://example.org/busy")).await,
+                Err(HttpError::RateLimited {
+                    retry_after: Some(delay),
+                    status: StatusCode::SERVICE_UNAVAILABLE,
+                }) if delay == Duration::from_secs(7)
+            ),
+            "status handling stays in the client"
+        );
+    }
+}
@@ -0,0 +1,118 @@
+## Rahmen
+
+app-title = Tonneli
+app-subtitle = tonneli – Abfuhrtermine
+status-title = Status
+status-loading = Lädt…
+
+hint-city-select = ↑/↓ bewegen · Enter/Leertaste Stadt wählen · s Erinnerungen · q/Strg-C beenden
+hint-address-search = Tippen zum Bearbeiten · Enter suchen · Tab/→ Termine öffnen · Links/Esc zurück · q/Strg-C beenden
+hint-schedule = ↑/↓ bewegen · Leertaste raus · z später · f Favorit · Esc/←/b zurück · q/Strg-C beenden
+hint-settings = ↑/↓ bewegen · Leertaste/Enter umschalten · ←/→ anpassen · Esc/b zurück · q/Strg-C beenden
+hint-log = ↑/↓ blättern · Esc/b/Strg-L zurück · q/Strg-C beenden
+
+## Ansichten
+
+city-select-title = Stadt wählen (↑/↓, Enter)
+
+search-no-city = <keine Stadt>
+search-title = Suche in { $city } (Straße + optional Hausnummer, Enter)
+search-empty = Noch keine Treffer. Straße und Hausnummer eingeben.
+search-results-title = Adressen (↑/↓, Tab/→ öffnet die Termine)
+search-truncated = Die ersten { $limit } Treffer werden angezeigt, für mehr die Suche verfeinern
+
+schedule-no-city = <Stadt>
+schedule-no-address = <Adresse>
+schedule-title = Termine für { $address } in { $city } (Esc/←/b zurück)
+schedule-loading = Termine werden geladen…
+schedule-empty = Keine anstehenden Abholungen im aktuellen Zeitraum.
+schedule-source = Daten: { $source }
+schedule-column-out = Raus
+schedule-column-date = Datum
+schedule-column-day = Tag
+schedule-column-in = Wann
+schedule-column-fraction = Abfallart
+
+favorite-home = Zuhause
+favorite-saved = { $address } als { $label } gespeichert, öffnet beim nächsten Start
+favorite-removed = { $address } aus den Favoriten entfernt
+
+settings-title = Erinnerungen (gespeichert in config.toml)
+settings-enabled = Erinnerungen aktiv
+settings-lead-time = Vorlauf: { $hours } h vor dem Abholtag
+settings-quiet-hours = Ruhezeit
+settings-quiet-from = von { $time }
+settings-quiet-until = bis { $time }
+settings-channel = Kanal: { $channel }
+
+log-title = Protokoll ({ $path })
+log-title-memory = Protokoll (nicht gespeichert)
+log-empty = Noch nichts protokolliert
+
+## Datum
+
+day-relative-future =
+    { $days ->
+        [0] heute
+        [1] morgen
+       *[other] in { $days } Tagen
+    }
+day-relative-past =
+    { $days ->
+        [1] gestern
+       *[other] vor { $days } Tagen
+    }
+
+weekday-mon = Mo
+weekday-tue = Di
+weekday-wed = Mi
+weekday-thu = Do
+weekday-fri = Fr
+weekday-sat = Sa
+weekday-sun = So
+
+## Abfallarten und Kanäle
+
+fraction-residual = Restmüll
+fraction-organic = Bioabfall
+fraction-paper = Papier
+fraction-plastic = Verpackungen
+fraction-glass = Glas
+fraction-metal = Metall
+fraction-street-cleaning = Straßenreinigung
+fraction-bulky-waste = Sperrmüll
+fraction-garden-waste = Grünschnitt
+fraction-hazardous = Schadstoffe
+fraction-christmas-tree = Weihnachtsbäume
+fraction-textiles = Alttextilien
+fraction-electronics = Elektroschrott
+fraction-other = Sonstige
+
+channel-desktop = Desktop
+channel-email = E-Mail
+channel-webhook = Webhook
+
+correction-added = lokal ergänzt
+correction-moved = verschoben vom { $date }
+
+## Fehler
+
+error-empty-query = Straße eingeben (optional mit Hausnummer), dann Enter drücken
+error-house-number-required = { $city } findet Adressen nur mit Hausnummer, z. B. „Aachener Str 1“
+error-no-city = Zuerst eine Stadt wählen
+error-no-address = Keine Adresse gewählt (erst suchen und eine auswählen)
+error-search = Suche fehlgeschlagen: { $reason }
+error-schedule = Termine konnten nicht geladen werden: { $reason }
+error-save-checklist = Abhakliste konnte nicht gespeichert werden: { $error }
+error-save-favorite = Favoriten konnten nicht gespeichert werden: { $error }
+error-save-settings = Einstellungen konnten nicht gespeichert werden: { $error }
+error-suspicious-data = Verdächtige Daten des Anbieters ignoriert: { $dropped }
+
+reason-timeout = der Server der Stadt antwortet nicht, bitte später erneut versuchen
+reason-rate-limited = der Server der Stadt drosselt Anfragen, bitte später erneut versuchen
+reason-rate-limited-retry = der Server der Stadt drosselt Anfragen, bitte in { $seconds } s erneut versuchen
+reason-too-slow = der Server der Stadt antwortet zu langsam, bitte später erneut versuchen
+reason-unavailable = der Dienst der Stadt ist gerade nicht erreichbar ({ $message }), bitte später erneut versuchen
+reason-not-found = keine passende Adresse, Straße und Hausnummer prüfen
+reason-not-found-suggestions = keine passende Adresse, meinten Sie { $suggestions }?
+reason-town-required = bitte zuerst den Ort angeben, z. B. „{ $example }, Hauptstraße 1“; Orte: { $towns }
@@ -0,0 +1,118 @@
+## Frame
+
+app-title = Tonneli
+app-subtitle = tonneli – waste collection schedules
+status-title = Status
+status-loading = Loading…
+
+hint-city-select = ↑/↓ move · Enter/Space select city · s reminder settings · q/Ctrl-C quit
+hint-address-search = Type to edit · Enter search · Tab/→ open schedule · Left/Esc back · q/Ctrl-C quit
+hint-schedule = ↑/↓ move · Space bin put out · z snooze · f favorite · Esc/←/b back · q/Ctrl-C quit
+hint-settings = ↑/↓ move · Space/Enter toggle · ←/→ adjust · Esc/b back · q/Ctrl-C quit
+hint-log = ↑/↓ scroll · Esc/b/Ctrl-L back · q/Ctrl-C quit
+
+## Screens
+
+city-select-title = Select city (↑/↓, Enter)
+
+search-no-city = <no city>
+search-title = Search in { $city } (street + optional house number, Enter)
+search-empty = No results yet. Try typing a street plus house number.
+search-results-title = Addresses (↑/↓, Tab/→ to open schedule)
+search-truncated = Showing the first { $limit } matches, refine the search to see more
+
+schedule-no-city = <city>
+schedule-no-address = <address>
+schedule-title = Schedule for { $address } in { $city } (Esc/←/b to go back)
+schedule-loading = Loading schedule…
+schedule-empty = No upcoming pickups in the current range.
+schedule-source = Data: { $source }
+schedule-column-out = Out
+schedule-column-date = Date
+schedule-column-day = Day
+schedule-column-in = In
+schedule-column-fraction = Fraction
+
+favorite-home = Home
+favorite-saved = Saved { $address } as { $label }, it opens on the next start
+favorite-removed = Removed { $address } from the favorites
+
+settings-title = Reminder settings (saved to config.toml)
+settings-enabled = Reminders enabled
+settings-lead-time = Lead time: { $hours } h before pickup day
+settings-quiet-hours = Quiet hours
+settings-quiet-from = from { $time }
+settings-quiet-until = until { $time }
+settings-channel = Channel: { $channel }
+
+log-title = Log ({ $path })
+log-title-memory = Log (not written to disk)
+log-empty = Nothing logged yet
+
+## Dates
+
+day-relative-future =
+    { $days ->
+        [0] today
+        [1] tomorrow
+       *[other] in { $days } days
+    }
+day-relative-past =
+    { $days ->
+        [1] yesterday
+       *[other] { $days } days ago
+    }
+
+weekday-mon = Mon
+weekday-tue = Tue
+weekday-wed = Wed
+weekday-thu = Thu
+weekday-fri = Fri
+weekday-sat = Sat
+weekday-sun = Sun
+
+## Fractions and channels
+
+fraction-residual = Residual waste
+fraction-organic = Organic
+fraction-paper = Paper
+fraction-plastic = Plastics / packaging
+fraction-glass = Glass
+fraction-metal = Metal
+fraction-street-cleaning = Street cleaning
+fraction-bulky-waste = Bulky waste
+fraction-garden-waste = Garden waste
+fraction-hazardous = Hazardous waste
+fraction-christmas-tree = Christmas trees
+fraction-textiles = Textiles
+fraction-electronics = Electronics
+fraction-other = Other fractions
+
+channel-desktop = desktop
+channel-email = email
+channel-webhook = webhook
+
+correction-added = added locally
+correction-moved = moved from { $date }
+
+## Errors
+
+error-empty-query = Type a street (optionally add a house number), then press Enter
+error-house-number-required = { $city } only finds addresses with a house number, e.g. “Aachener Str 1”
+error-no-city = Select a city first
+error-no-address = No address selected (search and pick one first)
+error-search = Search failed: { $reason }
+error-schedule = Failed to load schedule: { $reason }
+error-save-checklist = Failed to save checklist: { $error }
+error-save-favorite = Failed to save favorites: { $error }
+error-save-settings = Failed to save settings: { $error }
+error-suspicious-data = Ignored suspicious provider data: { $dropped }
+
+reason-timeout = the city's server did not respond in time, try again later
+reason-rate-limited = the city's server is rate limiting requests, try again later
+reason-rate-limited-retry = the city's server is rate limiting requests, try again in { $seconds }s
+reason-too-slow = the city's server is responding too slowly, try again later
+reason-unavailable = the city's service is currently unavailable ({ $message }), try again later
+reason-not-found = no matching address, check the street name and house number
+reason-not-found-suggestions = no matching address, did you mean { $suggestions }?
+reason-town-required = name the town first, e.g. "{ $example }, Main Street 1"; towns: { $towns }
@@ -0,0 +1,274 @@
+//! Localized texts shared by the Tonneli frontends.
+//!
+//! Messages live in Fluent files (`locales/<language>/tonneli.ftl`) that are
+//! compiled into the crate. A [`Localizer`] negotiates the best available
+//! languages for a request and falls back along that chain, ending in English,
+//! so a message missing from one translation still shows up.
+
+use std::borrow::Cow;
+use std::env;
+
+use fluent_bundle::FluentResource;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_langneg::{NegotiationStrategy, negotiate_languages};
+
+pub use fluent_bundle::{FluentArgs, FluentValue};
+pub use unic_langid::LanguageIdentifier;
+
+/// Environment variables consulted for the locale, most specific first.
+const LOCALE_VARS: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+
+/// Language used when nothing else matches; it has every message.
+const FALLBACK: &str = "en";
+
+/// Bundled translations as (language, Fluent source).
+const RESOURCES: [(&str, &str); 2] = [
+    ("en", include_str!("../locales/en/tonneli.ftl")),
+    ("de", include_str!("../locales/de/tonneli.ftl")),
+];
+
+/// Languages with bundled translations.
+#[must_use]
+pub fn available() -> Vec<LanguageIdentifier> {
+    RESOURCES
+        .iter()
+        .filter_map(|(language, _)| language.parse().ok())
+        .collect()
+}
+
+/// Languages requested by the environment, in order of preference.
+///
+/// Reads the colon-separated `LANGUAGE` list, then the first of `LC_ALL`,
+/// `LC_MESSAGES` and `LANG` that is set; encodings and modifiers such as
+/// `de_DE.UTF-8@euro` are ignored, and `C`/`POSIX` request nothing.
+pub fn requested_from_env(var: impl Fn(&str) -> Option<String>) -> Vec<LanguageIdentifier> {
+    let mut requested = var("LANGUAGE")
+        .map(|list| list.split(':').filter_map(parse_locale).collect::<Vec<_>>())
+        .unwrap_or_default();
+    if let Some(locale) = LOCALE_VARS
+        .iter()
+        .filter_map(|name| var(name))
+        .find(|value| !value.is_empty())
+        .as_deref()
+        .and_then(parse_locale)
+    {
+        requested.push(locale);
+    }
+    requested
+}
+
+/// Parse a POSIX locale such as `de_DE.UTF-8` into a language identifier.
+fn parse_locale(locale: &str) -> Option<LanguageIdentifier> {
+    let name = locale
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-");
+    if name.is_empty() || name == "C" || name == "POSIX" {
+        return None;
+    }
+    name.parse().ok()
+}
+
+/// Looks up messages in the negotiated languages.
+pub struct Localizer {
+    /// Bundles in fallback order; the last one is always English.
+    bundles: Vec<FluentBundle<FluentResource>>,
+}
+
+impl Localizer {
+    /// Localizer for the best available matches of `requested`.
+    #[must_use]
+    pub fn new(requested: &[LanguageIdentifier]) -> Self {
+        let available = available();
+        let fallback = FALLBACK.parse::<LanguageIdentifier>().ok();
+        let mut languages = negotiate_languages(
+            requested,
+            &available,
+            fallback.as_ref(),
+            NegotiationStrategy::Filtering,
+        )
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+        if let Some(fallback) = fallback
+            && !languages.contains(&fallback)
+        {
+            languages.push(fallback);
+        }
+
+        let bundles = languages
+            .into_iter()
+            .filter_map(|language| {
+                let (_, source) = RESOURCES
+                    .iter()
+                    .find(|(name, _)| language.language.as_str() == *name)?;
+                Some(bundle(language, source))
+            })
+            .collect();
+        Self { bundles }
+    }
+
+    /// Localizer for the languages requested by the process environment.
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self::new(&requested_from_env(|name| env::var(name).ok()))
+    }
+
+    /// Language of the first bundle, i.e. the one most texts come from.
+    #[must_use]
+    pub fn language(&self) -> Option<&LanguageIdentifier> {
+        self.bundles
+            .first()
+            .and_then(|bundle| bundle.locales.first())
+    }
+
+    /// Text of message `id`, or the id itself if no language has it.
+    #[must_use]
+    pub fn text(&self, id: &str) -> String {
+        self.format(id, None)
+    }
+
+    /// Text of message `id` with the placeholders filled from `args`.
+    #[must_use]
+    pub fn text_with<'args>(&self, id: &str, args: &[(&'args str, FluentValue<'args>)]) -> String {
+        let args = args
+            .iter()
+            .map(|(name, value)| (*name, value.clone()))
+            .collect::<FluentArgs<'args>>();
+        self.format(id, Some(&args))
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs<'_>>) -> String {
+        for bundle in &self.bundles {
+            let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) else {
+                continue;
+            };
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, args, &mut errors);
+            if errors.is_empty() {
+                return Cow::into_owned(text);
+            }
+        }
+        id.to_owned()
+    }
+}
+
+impl Default for Localizer {
+    /// English only.
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+/// Bundle of one language; resources are bundled, so parser errors only drop broken entries.
+fn bundle(language: LanguageIdentifier, source: &str) -> FluentBundle<FluentResource> {
+    let resource =
+        FluentResource::try_new(source.to_owned()).unwrap_or_else(|(resource, _)| resource);
+    let mut bundle = FluentBundle::new_concurrent(vec![language]);
+    // Terminals render Unicode isolation marks around placeholders as stray characters.
+    bundle.set_use_isolating(false);
+    bundle.add_resource_overriding(resource);
+    bundle
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashMap};
+
+    use super::*;
+
+    fn language(tag: &str) -> LanguageIdentifier {
+        tag.parse().expect("valid test language")
+    }
+
+    /// Ids of the messages in a Fluent source, which must parse without errors.
+    fn message_ids(source: &str) -> BTreeSet<&str> {
+        assert!(
+            FluentResource::try_new(source.to_owned()).is_ok(),
+            "bundled resources parse"
+        );
+        source
+            .lines()
+            .filter(|line| line.starts_with(|first: char| first.is_ascii_lowercase()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id))
+            .collect()
+    }
+
+    #[test]
+    fn translations_parse_and_cover_english() {
+        let english = message_ids(include_str!("../locales/en/tonneli.ftl"));
+        for (name, source) in RESOURCES {
+            assert_eq!(
+                message_ids(source),
+                english,
+                "{name} has exactly the English messages"
+            );
+        }
+    }
+
+    #[test]
+    fn negotiates_from_environment() {
+        let vars = HashMap::from([
+            ("LANGUAGE", "fr:de_AT"),
+            ("LC_ALL", ""),
+            ("LANG", "en_GB.UTF-8"),
+        ]);
+        let requested = requested_from_env(|name| vars.get(name).map(|value| (*value).to_owned()));
+        assert_eq!(
+            requested,
+            [language("fr"), language("de-AT"), language("en-GB")],
+            "LANGUAGE comes first, empty variables are skipped"
+        );
+        let localizer = Localizer::new(&requested);
+        assert_eq!(
+            localizer.language(),
+            Some(&language("de")),
+            "regional variants match the bundled language"
+        );
+        assert_eq!(
+            localizer.text("error-no-city"),
+            "Zuerst eine Stadt wählen",
+            "texts come from the best match"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_english() {
+        let localizer = Localizer::new(&[language("fr")]);
+        assert_eq!(
+            localizer.language(),
+            Some(&language("en")),
+            "unsupported languages end up in English"
+        );
+        assert_eq!(
+            localizer.text("no-such-message"),
+            "no-such-message",
+            "unknown ids are shown as they are"
+        );
+        assert!(
+            requested_from_env(|_| Some("C.UTF-8".to_owned())).is_empty(),
+            "the C locale requests nothing"
+        );
+    }
+
+    #[test]
+    fn fills_placeholders_and_plurals() {
+        let german = Localizer::new(&[language("de-DE")]);
+        assert_eq!(
+            german.text_with("day-relative-future", &[("days", 1.into())]),
+            "morgen",
+            "exact numbers select their variant"
+        );
+        assert_eq!(
+            german.text_with("day-relative-past", &[("days", 3.into())]),
+            "vor 3 Tagen",
+            "other numbers are inserted"
+        );
+        assert_eq!(
+            Localizer::default().text_with("search-title", &[("city", "Köln".into())]),
+            "Search in Köln (street + optional house number, Enter)",
+            "placeholders are not wrapped in isolation marks"
+        );
+    }
+}
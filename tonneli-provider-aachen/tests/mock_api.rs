@@ -0,0 +1,136 @@
+//! Aachen ports against canned `RegioIT` responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+use tonneli_core::{
+    AddressId, AddressPort, AddressSearch, DateRange, FixedClock, Fraction, PortError, SchedulePort,
+};
+use tonneli_provider_regioit::{RegioItAddressPort, RegioItSchedulePort, RegioItTown};
+use tonneli_testkit::{client, json};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+use insta as _;
+use tonneli_http as _;
+
+fn town(base_url: impl Into<String>) -> RegioItTown {
+    RegioItTown {
+        base_url: base_url.into(),
+        ..tonneli_provider_aachen::town()
+    }
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+/// Clock in 2025, the year the street lists are requested for.
+fn clock() -> Arc<FixedClock> {
+    let now = NaiveDate::from_ymd_opt(2025, 6, 1)
+        .and_then(|day| day.and_hms_opt(12, 0, 0))
+        .expect("valid test time");
+    Arc::new(FixedClock::new(now))
+}
+
+#[tokio::test]
+async fn search_lists_matching_house_numbers() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orte/11155895/strassen"))
+        .and(query_param("jahr", "2025"))
+        .respond_with(json(include_str!("fixtures/strassen.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/strassen/1"))
+        .respond_with(json(include_str!("fixtures/strasse_1.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = RegioItAddressPort::for_town(client(), town(server.uri())).with_clock(clock());
+    let addresses = port
+        .search(&AddressSearch::new("markt", Some("1".to_owned())), 10)
+        .await
+        .expect("search against the mock must succeed");
+
+    assert_eq!(
+        addresses.len(),
+        1,
+        "only house number 1 matches: {addresses:?}"
+    );
+    let address = addresses.first().expect("one address");
+    assert_eq!(
+        address.id,
+        AddressId("101".to_owned()),
+        "id is the house number id"
+    );
+    assert_eq!(&*address.label, "Markt 1", "label joins street and number");
+}
+
+#[tokio::test]
+async fn schedule_maps_fractions_and_reports_malformed_dates() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/hausnummern/101/fraktionen"))
+        .respond_with(json(include_str!("fixtures/fraktionen.json")))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/hausnummern/101/termine"))
+        .and(query_param("fraktion", "1"))
+        .and(query_param("fraktion", "2"))
+        .respond_with(json(include_str!("fixtures/termine.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = RegioItSchedulePort::for_town(client(), town(server.uri()));
+    let range = DateRange {
+        start: date(2025, 3, 1),
+        end: date(2025, 12, 31),
+    };
+    let report = port
+        .schedule_report(&AddressId("101".to_owned()), range)
+        .await
+        .expect("schedule against the mock must succeed");
+
+    let events = report
+        .events
+        .iter()
+        .map(|event| (event.date, event.fraction.clone(), event.note.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (date(2025, 3, 21), Fraction::Organic, Some("Bioabfall")),
+            (date(2025, 3, 14), Fraction::Residual, Some("Restabfall")),
+        ],
+        "out-of-range and malformed pickups must be dropped"
+    );
+    assert_eq!(
+        report.malformed_dates,
+        vec!["14.03.20xx".to_owned()],
+        "unparseable dates must be reported"
+    );
+}
+
+#[tokio::test]
+async fn schedule_rejects_foreign_address_ids() {
+    let port = RegioItSchedulePort::for_town(client(), town("http://127.0.0.1:9"));
+    let range = DateRange {
+        start: date(2025, 1, 1),
+        end: date(2025, 12, 31),
+    };
+    let result = port.schedule(&AddressId("1234:4:".to_owned()), range).await;
+    assert!(
+        matches!(result, Err(PortError::InvalidAddressId)),
+        "non-numeric ids must be rejected before any request: {result:?}"
+    );
+}
@@ -0,0 +1,239 @@
+//! Just enough HTML reading for the Abfall.IO selection forms.
+//!
+//! The backend answers every step with a form fragment; the provider needs its
+//! hidden inputs (the session token), the options of one `<select>` and the
+//! waste type checkboxes.
+
+use std::iter;
+
+/// Attributes of the start tag beginning at `tag`, which must start after `<`.
+fn attributes(tag: &str) -> Vec<(String, String)> {
+    let end = tag.find('>').unwrap_or(tag.len());
+    let mut rest = tag.get(..end).unwrap_or(tag).trim_end_matches('/');
+    // Skip the tag name.
+    rest = rest.trim_start_matches(|ch: char| !ch.is_whitespace());
+
+    let mut attributes = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        let name_end = rest
+            .find(|ch: char| ch == '=' || ch.is_whitespace())
+            .unwrap_or(rest.len());
+        let Some(name) = rest.get(..name_end).filter(|name| !name.is_empty()) else {
+            return attributes;
+        };
+        let name = name.to_ascii_lowercase();
+        rest = rest.get(name_end..).unwrap_or_default().trim_start();
+        let Some(after_equals) = rest.strip_prefix('=') else {
+            attributes.push((name, String::new()));
+            continue;
+        };
+        let after_equals = after_equals.trim_start();
+        let (value, remaining) = if let Some(quote @ ('"' | '\'')) = after_equals.chars().next() {
+            let quoted = after_equals.get(1..).unwrap_or_default();
+            let close = quoted.find(quote).unwrap_or(quoted.len());
+            (
+                quoted.get(..close).unwrap_or_default(),
+                quoted.get(close + 1..).unwrap_or_default(),
+            )
+        } else {
+            let close = after_equals
+                .find(char::is_whitespace)
+                .unwrap_or(after_equals.len());
+            (
+                after_equals.get(..close).unwrap_or_default(),
+                after_equals.get(close..).unwrap_or_default(),
+            )
+        };
+        attributes.push((name, decode_entities(value)));
+        rest = remaining;
+    }
+}
+
+fn attribute<'attrs>(attributes: &'attrs [(String, String)], name: &str) -> Option<&'attrs str> {
+    attributes
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Start tags named `name`, as the text following `<name`.
+fn tags<'html>(html: &'html str, name: &str) -> impl Iterator<Item = &'html str> {
+    let open = format!("<{name}");
+    let mut rest = html;
+    iter::from_fn(move || {
+        loop {
+            let start = find_ignore_case(rest, &open)?;
+            let tag = rest.get(start + 1..)?;
+            rest = tag;
+            // `<input` must not match `<inputs`.
+            if tag
+                .get(name.len()..)
+                .and_then(|after| after.chars().next())
+                .is_none_or(|ch| ch.is_whitespace() || ch == '>' || ch == '/')
+            {
+                return Some(tag);
+            }
+        }
+    })
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .char_indices()
+        .map(|(index, _)| index)
+        .find(|&index| {
+            haystack
+                .get(index..index + needle.len())
+                .is_some_and(|candidate| candidate.eq_ignore_ascii_case(needle))
+        })
+}
+
+/// Names and values of all `<input type="hidden">` fields.
+pub(crate) fn hidden_inputs(html: &str) -> Vec<(String, String)> {
+    tags(html, "input")
+        .map(attributes)
+        .filter(|attrs| {
+            attribute(attrs, "type").is_some_and(|kind| kind.eq_ignore_ascii_case("hidden"))
+        })
+        .filter_map(|attrs| {
+            let name = attribute(&attrs, "name")?.to_owned();
+            let value = attribute(&attrs, "value").unwrap_or_default().to_owned();
+            Some((name, value))
+        })
+        .collect()
+}
+
+/// Values of checkboxes whose name starts with `prefix`.
+pub(crate) fn checkbox_values(html: &str, prefix: &str) -> Vec<String> {
+    tags(html, "input")
+        .map(attributes)
+        .filter(|attrs| {
+            attribute(attrs, "type").is_some_and(|kind| kind.eq_ignore_ascii_case("checkbox"))
+                && attribute(attrs, "name").is_some_and(|name| name.starts_with(prefix))
+        })
+        .filter_map(|attrs| attribute(&attrs, "value").map(str::to_owned))
+        .collect()
+}
+
+/// Values and labels of the options of `<select name="{name}">`, skipping placeholders without value.
+pub(crate) fn select_options(html: &str, name: &str) -> Vec<(String, String)> {
+    let Some(select) =
+        tags(html, "select").find(|tag| attribute(&attributes(tag), "name") == Some(name))
+    else {
+        return Vec::new();
+    };
+    let body = find_ignore_case(select, "</select")
+        .and_then(|end| select.get(..end))
+        .unwrap_or(select);
+
+    tags(body, "option")
+        .filter_map(|tag| {
+            let value = attribute(&attributes(tag), "value")?.to_owned();
+            let text = tag.get(tag.find('>')? + 1..)?;
+            let text = find_ignore_case(text, "<")
+                .and_then(|end| text.get(..end))
+                .unwrap_or(text);
+            let label = decode_entities(text.trim());
+            (!value.is_empty()).then_some((value, label))
+        })
+        .collect()
+}
+
+/// Resolve numeric character references and the named ones German forms use.
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_owned();
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(rest.get(..start).unwrap_or_default());
+        let entity = rest.get(start..).unwrap_or_default();
+        let Some(end) = entity.find(';').filter(|&end| end <= 8) else {
+            decoded.push('&');
+            rest = entity.get(1..).unwrap_or_default();
+            continue;
+        };
+        let name = entity.get(1..end).unwrap_or_default();
+        let resolved = match name {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "szlig" => Some('ß'),
+            "auml" => Some('ä'),
+            "ouml" => Some('ö'),
+            "uuml" => Some('ü'),
+            "Auml" => Some('Ä'),
+            "Ouml" => Some('Ö'),
+            "Uuml" => Some('Ü'),
+            _ => name
+                .strip_prefix("#x")
+                .or_else(|| name.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| name.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        if let Some(ch) = resolved {
+            decoded.push(ch);
+            rest = entity.get(end + 1..).unwrap_or_default();
+        } else {
+            decoded.push('&');
+            rest = entity.get(1..).unwrap_or_default();
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORM: &str = r#"<form>
+        <INPUT type="hidden" name="f_token" value="a1b2">
+        <input type='hidden' name=f_kommune value=3000>
+        <input type="checkbox" name="f_id_abfalltyp_0" value="31" checked>
+        <input type="checkbox" name="f_id_abfalltyp_1" value="17">
+        <input type="text" name="f_search" value="">
+        <select name="f_id_strasse" class="wide">
+            <option value="">Bitte wählen</option>
+            <option value="1042">Am Markt</option>
+            <option value="1043" selected>Bahnhofstra&szlig;e &amp; Platz</option>
+            <option value="1044">K&#246;nigsweg</option>
+        </select>
+    </form>"#;
+
+    #[test]
+    fn reads_form_fields() {
+        assert_eq!(
+            hidden_inputs(FORM),
+            [
+                ("f_token".to_owned(), "a1b2".to_owned()),
+                ("f_kommune".to_owned(), "3000".to_owned())
+            ],
+            "hidden inputs with any quoting"
+        );
+        assert_eq!(
+            checkbox_values(FORM, "f_id_abfalltyp_"),
+            ["31", "17"],
+            "all waste types are offered"
+        );
+        assert_eq!(
+            select_options(FORM, "f_id_strasse"),
+            [
+                ("1042".to_owned(), "Am Markt".to_owned()),
+                ("1043".to_owned(), "Bahnhofstraße & Platz".to_owned()),
+                ("1044".to_owned(), "Königsweg".to_owned()),
+            ],
+            "options without placeholder, with character references resolved"
+        );
+        assert!(
+            select_options(FORM, "f_id_strasse_hnr").is_empty(),
+            "missing selects have no options"
+        );
+    }
+}
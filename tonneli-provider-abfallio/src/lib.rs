@@ -0,0 +1,355 @@
+//! Generic provider for towns on the Abfall.IO (`AbfallPlus`) backend.
+//!
+//! Abfall.IO serves hundreds of German municipalities from one API. Each
+//! operator has a client key, and each municipality (Kommune) an id; a town is
+//! configured with both. The API is a form flow: every request returns an HTML
+//! fragment whose hidden inputs have to be sent along with the next selection,
+//! and the schedule itself is an iCalendar export.
+
+mod form;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::future::try_join_all;
+
+use tonneli_core::{
+    fraction_map::FractionMapper,
+    ics_import::{CalendarImport, ImportError},
+    matching::StreetMatcher,
+    model::{Address, AddressId, Capabilities, CityMeta, DateRange, PickupEvent},
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport},
+};
+use tonneli_http::HttpClient;
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {chrono as _, tokio as _, tonneli_testkit as _, wiremock as _};
+
+/// Base URL of the public API.
+pub const BASE_URL: &str = "https://api.abfall.io";
+
+/// Mode of the embeddable calendar widget whose form flow the provider follows.
+const MODUS: &str = "d6c5855a62cf32a4dadbc2831f0f295f";
+/// Matching streets whose house numbers are looked up per search.
+const MAX_STREETS: usize = 5;
+
+/// Form fields of the selection flow.
+const KOMMUNE_FIELD: &str = "f_id_kommune";
+const STREET_FIELD: &str = "f_id_strasse";
+const HOUSE_NUMBER_FIELD: &str = "f_id_strasse_hnr";
+const WASTE_TYPE_PREFIX: &str = "f_id_abfalltyp_";
+
+#[derive(Debug, Clone)]
+/// Municipality served by Abfall.IO; the same ports work for every one of them.
+pub struct AbfallIoTown {
+    /// Base URL of the API, without trailing slash.
+    pub base_url: String,
+    /// Client key of the operator, as embedded in the town's calendar widget.
+    pub key: String,
+    /// Id of the municipality (`f_id_kommune`).
+    pub kommune: String,
+    /// Metadata of the resulting city.
+    pub meta: CityMeta,
+}
+
+/// Parts of an Abfall.IO [`AddressId`]: `street_id:house_number_id`.
+///
+/// Streets with one schedule for all houses have no house number id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AbfallIoAddressId<'id> {
+    /// Street id (`f_id_strasse`).
+    pub street: &'id str,
+    /// House number id (`f_id_strasse_hnr`); empty when the street has none.
+    pub house_number: &'id str,
+}
+
+impl<'id> AbfallIoAddressId<'id> {
+    /// Split an id produced by [`AbfallIoAddressPort`].
+    ///
+    /// # Errors
+    ///
+    /// [`PortError::InvalidAddressId`] when the street id is blank or the id has more
+    /// than two segments.
+    pub fn parse(id: &'id AddressId) -> Result<Self, PortError> {
+        let mut parts = id.0.split(':');
+        let street = parts
+            .next()
+            .filter(|street| !street.trim().is_empty())
+            .ok_or(PortError::InvalidAddressId)?;
+        let house_number = parts.next().unwrap_or("");
+        if parts.next().is_some() {
+            return Err(PortError::InvalidAddressId);
+        }
+        Ok(Self {
+            street,
+            house_number,
+        })
+    }
+}
+
+/// One step of the form flow: the fields to send and what the backend answered.
+struct FormSession {
+    fields: Vec<(String, String)>,
+    html: String,
+}
+
+/// Set form field `name`, replacing an earlier value.
+fn set_field(fields: &mut Vec<(String, String)>, name: &str, value: String) {
+    fields.retain(|(field, _)| field != name);
+    fields.push((name.to_owned(), value));
+}
+
+/// Requests shared by both ports.
+struct Api {
+    client: HttpClient,
+    town: AbfallIoTown,
+}
+
+impl Api {
+    /// Post `fields` for `action` and keep the hidden inputs of the answer for the next step.
+    async fn step(
+        &self,
+        action: &str,
+        mut fields: Vec<(String, String)>,
+    ) -> Result<FormSession, PortError> {
+        let body = self.post(action, &fields).await?;
+        let html = String::from_utf8_lossy(&body).into_owned();
+        for (name, value) in form::hidden_inputs(&html) {
+            set_field(&mut fields, &name, value);
+        }
+        Ok(FormSession { fields, html })
+    }
+
+    async fn post(&self, action: &str, fields: &[(String, String)]) -> Result<Vec<u8>, PortError> {
+        let AbfallIoTown { base_url, key, .. } = &self.town;
+        Ok(self
+            .client
+            .fetch_bytes(
+                self.client
+                    .post(base_url.as_str())
+                    .query(&[("key", key.as_str()), ("modus", MODUS), ("waction", action)])
+                    .form(fields),
+            )
+            .await?)
+    }
+
+    /// Start the flow and select the municipality.
+    async fn kommune(&self) -> Result<FormSession, PortError> {
+        let init = self.step("init", Vec::new()).await?;
+        let mut fields = init.fields;
+        set_field(&mut fields, KOMMUNE_FIELD, self.town.kommune.clone());
+        self.step("auswahl_kommune_set", fields).await
+    }
+}
+
+/// Address search for a town on Abfall.IO.
+pub struct AbfallIoAddressPort {
+    api: Api,
+}
+
+impl AbfallIoAddressPort {
+    /// Create an address port for the given town.
+    #[must_use]
+    pub fn for_town(client: HttpClient, town: AbfallIoTown) -> Self {
+        Self {
+            api: Api { client, town },
+        }
+    }
+
+    /// House numbers of a street as `(id, number)`; empty for streets with one schedule.
+    async fn house_numbers(
+        &self,
+        session: &FormSession,
+        street_id: &str,
+    ) -> Result<Vec<(String, String)>, PortError> {
+        let mut fields = session.fields.clone();
+        set_field(&mut fields, STREET_FIELD, street_id.to_owned());
+        let street = self.api.step("auswahl_strasse_set", fields).await?;
+        Ok(form::select_options(&street.html, HOUSE_NUMBER_FIELD))
+    }
+}
+
+#[async_trait]
+impl AddressPort for AbfallIoAddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.api.town.meta
+    }
+
+    /// Starts the form flow without selecting anything.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        Some(self.api.step("init", Vec::new()).await.map(drop))
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let session = self.api.kommune().await?;
+        let matcher = StreetMatcher::new(&query.street, query.fuzzy);
+        let streets = form::select_options(&session.html, STREET_FIELD)
+            .into_iter()
+            .filter(|(_, name)| matcher.matches(name))
+            .take(MAX_STREETS)
+            .collect::<Vec<_>>();
+        if streets.is_empty() {
+            return Err(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            });
+        }
+        let house_numbers = try_join_all(
+            streets
+                .iter()
+                .map(|(street_id, _)| self.house_numbers(&session, street_id)),
+        )
+        .await?;
+
+        let house_filter = query
+            .house_number
+            .as_deref()
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty());
+        let city = &self.api.town.meta.id;
+        let mut addresses = Vec::new();
+        for ((street_id, name), numbers) in streets.into_iter().zip(house_numbers) {
+            let street = Arc::<str>::from(name);
+            if numbers.is_empty() {
+                addresses.push(Address {
+                    id: AddressId(street_id),
+                    city: city.clone(),
+                    label: Arc::clone(&street),
+                    street,
+                    house_number: "".into(),
+                });
+                continue;
+            }
+            addresses.extend(
+                numbers
+                    .into_iter()
+                    .filter(|(_, number)| {
+                        house_filter.is_none_or(|filter| number.eq_ignore_ascii_case(filter))
+                    })
+                    .map(|(number_id, number)| Address {
+                        id: AddressId(format!("{street_id}:{number_id}")),
+                        city: city.clone(),
+                        label: format!("{street} {number}").into(),
+                        street: Arc::clone(&street),
+                        house_number: number.into(),
+                    }),
+            );
+        }
+        addresses.truncate(limit);
+        Ok(addresses)
+    }
+}
+
+/// Pickup schedule for a town on Abfall.IO.
+pub struct AbfallIoSchedulePort {
+    api: Api,
+    fractions: FractionMapper,
+}
+
+impl AbfallIoSchedulePort {
+    /// Create a schedule port for the given town.
+    #[must_use]
+    pub fn for_town(client: HttpClient, town: AbfallIoTown) -> Self {
+        Self {
+            api: Api { client, town },
+            fractions: fraction_mapper(),
+        }
+    }
+}
+
+#[async_trait]
+impl SchedulePort for AbfallIoSchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.api.town.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let AbfallIoAddressId {
+            street,
+            house_number,
+        } = AbfallIoAddressId::parse(address_id)?;
+
+        let session = self.api.kommune().await?;
+        let mut fields = session.fields;
+        set_field(&mut fields, STREET_FIELD, street.to_owned());
+        if !house_number.is_empty() {
+            set_field(&mut fields, HOUSE_NUMBER_FIELD, house_number.to_owned());
+        }
+        // Ask for every waste type the form offers.
+        let waste_types = form::checkbox_values(&session.html, WASTE_TYPE_PREFIX);
+        if !waste_types.is_empty() {
+            set_field(&mut fields, "f_abfallarten", waste_types.join(","));
+        }
+        let period = format!(
+            "{}-{}",
+            range.start.format("%Y%m%d"),
+            range.end.format("%Y%m%d")
+        );
+        set_field(&mut fields, "f_zeitraum", period);
+
+        let body = self.api.post("export_ics", &fields).await?;
+        let import = match CalendarImport::parse(&String::from_utf8_lossy(&body), &self.fractions) {
+            Ok(import) => import,
+            Err(ImportError::NoEvents) => return Ok(ScheduleReport::default()),
+            Err(err) => return Err(PortError::Decode(err.to_string())),
+        };
+        let events = import
+            .events
+            .into_iter()
+            .filter(|event| range.contains(event.date))
+            .collect();
+
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: import.malformed,
+        })
+    }
+}
+
+/// Mapper from Abfall.IO waste type names to fractions: the shared German
+/// vocabulary plus bin colors, as operators name their types freely.
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    CalendarImport::mapper()
+}
+
+/// What Abfall.IO supports; [`plugin_for_town`] declares it for every town.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    house_number_required: false,
+    multi_year: true,
+    fraction_filter: true,
+    bulky_waste_booking: false,
+};
+
+/// Build a plugin bundle for a town on Abfall.IO.
+#[must_use]
+pub fn plugin_for_town(client: HttpClient, mut town: AbfallIoTown) -> CityPlugin {
+    town.meta = town.meta.with_capabilities(CAPABILITIES);
+    let meta = town.meta.clone();
+    let address_port = Arc::new(AbfallIoAddressPort::for_town(client.clone(), town.clone()));
+    let schedule_port = Arc::new(AbfallIoSchedulePort::for_town(client, town));
+
+    CityPlugin {
+        meta,
+        address_port,
+        schedule_port,
+    }
+}
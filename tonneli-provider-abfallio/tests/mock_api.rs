@@ -0,0 +1,177 @@
+//! Abfall.IO ports against canned form responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use tonneli_core::{
+    AddressId, AddressPort, AddressSearch, CityMeta, DateRange, Fraction, PortError, SchedulePort,
+};
+use tonneli_provider_abfallio::{AbfallIoAddressPort, AbfallIoSchedulePort, AbfallIoTown};
+use tonneli_testkit::client;
+use wiremock::matchers::{body_string_contains, method, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use async_trait as _;
+use futures as _;
+use tonneli_http as _;
+
+const KEY: &str = "0123456789abcdef0123456789abcdef";
+const TOKEN: &str = "d6c5855a62cf32a4dadbc2831f0f295f=3b2c6a7e-4f1d-4d8e-9d64-2f0c1a7b5e90";
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+fn town(server: &MockServer) -> AbfallIoTown {
+    AbfallIoTown {
+        base_url: server.uri(),
+        key: KEY.to_owned(),
+        kommune: "3000".to_owned(),
+        meta: CityMeta::new("musterstadt", "Musterstadt"),
+    }
+}
+
+fn html(body: &'static str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "text/html; charset=utf-8")
+}
+
+/// Mock server answering the form steps up to the selected municipality.
+async fn serve_form() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(query_param("key", KEY))
+        .and(query_param("waction", "init"))
+        .respond_with(html(include_str!("fixtures/init.html")))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(query_param("waction", "auswahl_kommune_set"))
+        .and(body_string_contains(TOKEN))
+        .and(body_string_contains("f_id_kommune=3000"))
+        .respond_with(html(include_str!("fixtures/kommune.html")))
+        .mount(&server)
+        .await;
+    for (street, body) in [
+        ("1042", include_str!("fixtures/strasse_1042.html")),
+        ("1044", include_str!("fixtures/strasse_1044.html")),
+    ] {
+        Mock::given(method("POST"))
+            .and(query_param("waction", "auswahl_strasse_set"))
+            .and(body_string_contains(format!("f_id_strasse={street}")))
+            .respond_with(html(body))
+            .mount(&server)
+            .await;
+    }
+    server
+}
+
+#[tokio::test]
+async fn health_check_starts_the_form() {
+    let server = serve_form().await;
+    let port = AbfallIoAddressPort::for_town(client(), town(&server));
+    let probe = port.health_check().await;
+    assert!(
+        matches!(probe, Some(Ok(()))),
+        "the form answers, got {probe:?}"
+    );
+
+    let unknown_key = AbfallIoTown {
+        key: "ffffffffffffffffffffffffffffffff".to_owned(),
+        ..town(&server)
+    };
+    let failed = AbfallIoAddressPort::for_town(client(), unknown_key)
+        .health_check()
+        .await;
+    assert!(
+        matches!(failed, Some(Err(_))),
+        "a failing backend is reported, got {failed:?}"
+    );
+}
+
+/// Labels and ids of the addresses found for `street` and `house_number`.
+async fn found(
+    port: &AbfallIoAddressPort,
+    street: &str,
+    house_number: Option<&str>,
+) -> Vec<(String, String)> {
+    port.search(&AddressSearch::new(street, house_number), 10)
+        .await
+        .expect("search against the mock must succeed")
+        .into_iter()
+        .map(|address| (address.label.to_string(), address.id.0))
+        .collect()
+}
+
+fn pair(label: &str, id: &str) -> (String, String) {
+    (label.to_owned(), id.to_owned())
+}
+
+#[tokio::test]
+async fn search_walks_the_selection_form() {
+    let server = serve_form().await;
+    let port = AbfallIoAddressPort::for_town(client(), town(&server));
+
+    assert_eq!(
+        found(&port, "Am Markt", Some("3A")).await,
+        [pair("Am Markt 3a", "1042:88103")],
+        "house numbers are matched regardless of case"
+    );
+    assert_eq!(
+        found(&port, "Königsweg", None).await,
+        [pair("Königsweg", "1044")],
+        "streets without house numbers are one address"
+    );
+    let missing = port
+        .search(&AddressSearch::new("Nirgendwo", Some("1")), 10)
+        .await;
+    assert!(
+        matches!(missing, Err(PortError::AddressNotFound { .. })),
+        "unknown streets are not found, got {missing:?}"
+    );
+}
+
+#[tokio::test]
+async fn schedule_exports_the_calendar() {
+    let server = serve_form().await;
+    Mock::given(method("POST"))
+        .and(query_param("waction", "export_ics"))
+        .and(body_string_contains(TOKEN))
+        .and(body_string_contains("f_id_strasse=1042"))
+        .and(body_string_contains("f_id_strasse_hnr=88103"))
+        .and(body_string_contains("f_abfallarten=31%2C17%2C50"))
+        .and(body_string_contains("f_zeitraum=20250301-20250331"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(include_str!("fixtures/calendar.ics"), "text/calendar"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = AbfallIoSchedulePort::for_town(client(), town(&server));
+    let range = DateRange {
+        start: date(2025, 3, 1),
+        end: date(2025, 3, 31),
+    };
+    let events = port
+        .schedule(&AddressId("1042:88103".to_owned()), range)
+        .await
+        .expect("schedule against the mock must succeed");
+
+    let events = events
+        .iter()
+        .map(|event| (event.date, event.fraction.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (date(2025, 3, 3), Fraction::Residual),
+            (date(2025, 3, 5), Fraction::Paper),
+            (date(2025, 3, 10), Fraction::Plastic),
+            (date(2025, 3, 17), Fraction::Hazardous),
+        ],
+        "events are mapped and sorted by date"
+    );
+}
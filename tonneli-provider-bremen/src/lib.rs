@@ -0,0 +1,310 @@
+//! Provider implementation for Bremen using the Abfallkalender of Die Bremer Stadtreinigung.
+//!
+//! The calendar runs on c-trace: streets and house numbers are looked up as JSON
+//! lists, and the schedule of an address is an iCalendar export.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::future::try_join_all;
+
+use tonneli_core::{
+    fraction_map::FractionMapper,
+    ics_import::{CalendarImport, ImportError},
+    matching::StreetMatcher,
+    model::{
+        Address, AddressId, Capabilities, CityArea, CityMeta, Coordinates, DateRange, PickupEvent,
+    },
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport},
+};
+use tonneli_http::HttpClient;
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {chrono as _, tokio as _, tonneli_testkit as _, wiremock as _};
+
+/// Base URL of the public calendar.
+pub const BASE_URL: &str = "https://web.c-trace.de/bremenabfallkalender";
+
+/// Town parameter the calendar expects on every request.
+const TOWN: &str = "Bremen";
+/// Waste types requested from the export; unknown numbers are ignored upstream.
+const ALL_WASTE_TYPES: &str = "0|1|2|3|4|5|6|7|";
+/// Matching streets whose house numbers are looked up per search.
+const MAX_STREETS: usize = 5;
+/// Street looked up by the health probe; the short list it returns is cheap to serve.
+const PROBE_STREET: &str = "Am Markt";
+
+/// Parts of a Bremen [`AddressId`]: `street:house_number`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BremenAddressId<'id> {
+    /// Street name as listed by the calendar.
+    pub street: &'id str,
+    /// House number including additions such as `a`.
+    pub house_number: &'id str,
+}
+
+impl<'id> BremenAddressId<'id> {
+    /// Split an id produced by [`BremenAddressPort`].
+    ///
+    /// # Errors
+    ///
+    /// [`PortError::InvalidAddressId`] when the street or house number is blank.
+    pub fn parse(id: &'id AddressId) -> Result<Self, PortError> {
+        let (street, house_number) = id.0.rsplit_once(':').ok_or(PortError::InvalidAddressId)?;
+        if street.trim().is_empty() || house_number.trim().is_empty() {
+            return Err(PortError::InvalidAddressId);
+        }
+        Ok(Self {
+            street,
+            house_number,
+        })
+    }
+}
+
+/// Address search implementation for Bremen.
+pub struct BremenAddressPort {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+}
+
+impl BremenAddressPort {
+    /// Create a new address port bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_owned(),
+            meta: city_meta(),
+        }
+    }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    async fn streets(&self, prefix: &str) -> Result<Vec<String>, PortError> {
+        let base_url = &self.base_url;
+        Ok(self
+            .client
+            .fetch_json::<Vec<String>>(
+                self.client
+                    .get(format!("{base_url}/Data/Strassen"))
+                    .query(&[("Ort", TOWN), ("Strasse", prefix)]),
+            )
+            .await?)
+    }
+
+    async fn house_numbers(&self, street: &str) -> Result<Vec<String>, PortError> {
+        let base_url = &self.base_url;
+        Ok(self
+            .client
+            .fetch_json::<Vec<String>>(
+                self.client
+                    .get(format!("{base_url}/Data/Hausnummern"))
+                    .query(&[("Ort", TOWN), ("Strasse", street)]),
+            )
+            .await?)
+    }
+}
+
+#[async_trait]
+impl AddressPort for BremenAddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let streets = self.streets(query.street.trim()).await?;
+
+        let matcher = StreetMatcher::new(&query.street, query.fuzzy);
+        let streets = streets
+            .into_iter()
+            .filter(|street| matcher.matches(street))
+            .take(MAX_STREETS)
+            .collect::<Vec<_>>();
+        if streets.is_empty() {
+            return Err(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            });
+        }
+        let house_numbers =
+            try_join_all(streets.iter().map(|street| self.house_numbers(street))).await?;
+
+        let house_filter = query
+            .house_number
+            .as_deref()
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty());
+        let mut addresses = Vec::new();
+        for (street, numbers) in streets.into_iter().zip(house_numbers) {
+            let street = Arc::<str>::from(street);
+            addresses.extend(
+                numbers
+                    .into_iter()
+                    .filter(|number| {
+                        house_filter.is_none_or(|filter| number.eq_ignore_ascii_case(filter))
+                    })
+                    .map(|number| Address {
+                        id: AddressId(format!("{street}:{number}")),
+                        city: self.meta.id.clone(),
+                        label: format!("{street} {number}").into(),
+                        street: Arc::clone(&street),
+                        house_number: number.into(),
+                    }),
+            );
+        }
+        addresses.truncate(limit);
+        Ok(addresses)
+    }
+
+    /// Looks up the streets matching a well-known one.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        Some(self.streets(PROBE_STREET).await.map(drop))
+    }
+
+    /// Ids carry the street and house number, so no request is needed.
+    async fn resolve(&self, address_id: &AddressId) -> Result<Address, PortError> {
+        let parts = BremenAddressId::parse(address_id)?;
+        Ok(Address {
+            id: address_id.clone(),
+            city: self.meta.id.clone(),
+            label: format!("{} {}", parts.street, parts.house_number).into(),
+            street: parts.street.into(),
+            house_number: parts.house_number.into(),
+        })
+    }
+}
+
+/// Pickup schedule implementation for Bremen.
+pub struct BremenSchedulePort {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+    fractions: FractionMapper,
+}
+
+impl BremenSchedulePort {
+    /// Create a new schedule port bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_owned(),
+            meta: city_meta(),
+            fractions: fraction_mapper(),
+        }
+    }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[async_trait]
+impl SchedulePort for BremenSchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let BremenAddressId {
+            street,
+            house_number,
+        } = BremenAddressId::parse(address_id)?;
+
+        let base_url = &self.base_url;
+        let body = self
+            .client
+            .fetch_bytes(
+                self.client
+                    .get(format!("{base_url}/abfallkalender/cal"))
+                    .query(&[
+                        ("Ort", TOWN),
+                        ("Strasse", street),
+                        ("Hausnr", house_number),
+                        ("abfall", ALL_WASTE_TYPES),
+                    ]),
+            )
+            .await?;
+
+        let import = match CalendarImport::parse(&String::from_utf8_lossy(&body), &self.fractions) {
+            Ok(import) => import,
+            // Addresses without collections in the export's period get an empty calendar.
+            Err(ImportError::NoEvents) => return Ok(ScheduleReport::default()),
+            Err(err) => return Err(PortError::Decode(err.to_string())),
+        };
+        let events = import
+            .events
+            .into_iter()
+            .filter(|event| range.contains(event.date))
+            .collect();
+
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: import.malformed,
+        })
+    }
+}
+
+/// Mapper from Bremen calendar summaries to [`Fraction`](tonneli_core::model::Fraction).
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    FractionMapper::german()
+}
+
+/// Build the plugin bundle for the Bremen provider.
+#[must_use]
+pub fn plugin(client: HttpClient) -> CityPlugin {
+    let address_port = Arc::new(BremenAddressPort::new(client.clone()));
+    let schedule_port = Arc::new(BremenSchedulePort::new(client));
+
+    CityPlugin {
+        meta: city_meta(),
+        address_port,
+        schedule_port,
+    }
+}
+
+fn city_meta() -> CityMeta {
+    CityMeta::new("bremen", "Bremen")
+        .with_region("Bremen")
+        .with_locale("DE", "Europe/Berlin")
+        .with_area(
+            CityArea::new(Coordinates::new(53.0793, 8.8017), 20.0)
+                .with_postal_codes(["281", "282", "283", "287"]),
+        )
+        .with_source(
+            "Die Bremer Stadtreinigung",
+            "https://www.die-bremer-stadtreinigung.de",
+        )
+        .with_capabilities(Capabilities {
+            fraction_filter: true,
+            ..Capabilities::default()
+        })
+}
@@ -0,0 +1,46 @@
+//! Bremen calendar summaries must map onto known fractions.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use tonneli_core::Fraction;
+use tonneli_provider_bremen::fraction_mapper;
+use tonneli_testkit::unexpected_other_fractions;
+
+use async_trait as _;
+use chrono as _;
+use futures as _;
+use tokio as _;
+use tonneli_http as _;
+use wiremock as _;
+
+/// Summaries known from the calendar export; it is iCalendar, so there is no JSON fixture to scan.
+const KNOWN_LABELS: &[&str] = &[
+    "Restmülltonne",
+    "Restmüll",
+    "Bioabfall",
+    "Biotonne",
+    "Papiertonne",
+    "Papier",
+    "Gelber Sack",
+    "Weihnachtsbaumabfuhr",
+    "Tannenbaumabfuhr",
+];
+
+#[test]
+fn known_vocabulary_maps_to_fractions() {
+    let mapper = fraction_mapper();
+    let unexpected = unexpected_other_fractions(&mapper, KNOWN_LABELS.iter().copied(), &[]);
+    assert!(
+        unexpected.is_empty(),
+        "map these labels or list them as expected: {unexpected:?}"
+    );
+    for label in ["Weihnachtsbaumabfuhr", "Tannenbaumabfuhr"] {
+        assert_eq!(
+            mapper.try_map(label),
+            Some(Fraction::ChristmasTree),
+            "{label} is a Christmas tree collection"
+        );
+    }
+}
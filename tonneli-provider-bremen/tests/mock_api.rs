@@ -0,0 +1,193 @@
+//! Bremen ports against canned c-trace responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use tonneli_core::{
+    AddressId, AddressPort, AddressSearch, DateRange, Fraction, PortError, SchedulePort,
+};
+use tonneli_provider_bremen::{BremenAddressPort, BremenSchedulePort};
+use tonneli_testkit::{client, json};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use async_trait as _;
+use futures as _;
+use tonneli_http as _;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+#[tokio::test]
+async fn ids_resolve_without_a_request() {
+    let server = MockServer::start().await;
+    let port = BremenAddressPort::new(client()).with_base_url(server.uri());
+    let address = port
+        .resolve(&AddressId("Am Markt:21a".to_owned()))
+        .await
+        .expect("the id carries the address");
+    assert_eq!(
+        (&*address.label, &*address.street, &*address.house_number),
+        ("Am Markt 21a", "Am Markt", "21a"),
+        "street and house number come from the id"
+    );
+
+    let invalid = port.resolve(&AddressId("Am Markt".to_owned())).await;
+    assert!(
+        matches!(invalid, Err(PortError::InvalidAddressId)),
+        "ids without a house number are rejected, got {invalid:?}"
+    );
+    let requests = server.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "no request is sent, got {requests:?}");
+}
+
+#[tokio::test]
+async fn health_check_lists_streets() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/Data/Strassen"))
+        .and(query_param("Ort", "Bremen"))
+        .respond_with(json(include_str!("fixtures/strassen.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = BremenAddressPort::new(client()).with_base_url(server.uri());
+    let probe = port.health_check().await;
+    assert!(
+        matches!(probe, Some(Ok(()))),
+        "the street list answers, got {probe:?}"
+    );
+
+    let down = BremenAddressPort::new(client()).with_base_url(format!("{}/down", server.uri()));
+    let failed = down.health_check().await;
+    assert!(
+        matches!(failed, Some(Err(_))),
+        "a failing backend is reported, got {failed:?}"
+    );
+}
+
+#[tokio::test]
+async fn search_lists_house_numbers_of_matching_streets() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/Data/Strassen"))
+        .and(query_param("Ort", "Bremen"))
+        .and(query_param("Strasse", "Am Markt"))
+        .respond_with(json(include_str!("fixtures/strassen.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/Data/Hausnummern"))
+        .and(query_param("Strasse", "Am Markt"))
+        .respond_with(json(include_str!("fixtures/hausnummern_am_markt.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = BremenAddressPort::new(client()).with_base_url(server.uri());
+    let addresses = port
+        .search(&AddressSearch::new("Am Markt", Some("21A")), 10)
+        .await
+        .expect("search against the mock must succeed");
+
+    let found = addresses
+        .iter()
+        .map(|address| (&*address.label, address.id.0.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [("Am Markt 21a", "Am Markt:21a")],
+        "house numbers match regardless of case"
+    );
+}
+
+#[tokio::test]
+async fn search_reports_unknown_streets() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/Data/Strassen"))
+        .respond_with(json("[]"))
+        .mount(&server)
+        .await;
+
+    let port = BremenAddressPort::new(client()).with_base_url(server.uri());
+    let result = port
+        .search(&AddressSearch::new("Nirgendwo", Some("1")), 10)
+        .await;
+
+    assert!(
+        matches!(result, Err(PortError::AddressNotFound { .. })),
+        "unknown streets are not found, got {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn schedule_reads_the_calendar_export() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/abfallkalender/cal"))
+        .and(query_param("Ort", "Bremen"))
+        .and(query_param("Strasse", "Am Markt"))
+        .and(query_param("Hausnr", "21a"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(include_str!("fixtures/calendar.ics"), "text/calendar"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = BremenSchedulePort::new(client()).with_base_url(server.uri());
+    let range = DateRange {
+        start: date(2025, 1, 1),
+        end: date(2025, 1, 31),
+    };
+    let report = port
+        .schedule_report(&AddressId("Am Markt:21a".to_owned()), range)
+        .await
+        .expect("schedule against the mock must succeed");
+
+    let events = report
+        .events
+        .iter()
+        .map(|event| (event.date, event.fraction.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (date(2025, 1, 7), Fraction::Residual),
+            (date(2025, 1, 10), Fraction::Plastic),
+            (date(2025, 1, 14), Fraction::ChristmasTree),
+            (date(2025, 1, 16), Fraction::Organic),
+            (date(2025, 1, 21), Fraction::Paper),
+        ],
+        "events within the range are mapped and sorted by date"
+    );
+    assert_eq!(
+        report.malformed_dates,
+        ["2025012"],
+        "unparseable dates must be reported"
+    );
+}
+
+#[tokio::test]
+async fn schedule_rejects_incomplete_ids() {
+    let port = BremenSchedulePort::new(client());
+    let range = DateRange {
+        start: date(2025, 1, 1),
+        end: date(2025, 1, 31),
+    };
+    let result = port
+        .schedule(&AddressId("Am Markt:".to_owned()), range)
+        .await;
+
+    assert!(
+        matches!(result, Err(PortError::InvalidAddressId)),
+        "ids need a house number, got {result:?}"
+    );
+}
@@ -1,28 +1,53 @@
 //! Provider implementation for Cologne using the AWB API.
 
+use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use chrono::{Datelike, NaiveDate};
-use reqwest::{Client, RequestBuilder};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use serde::Deserialize;
-use serde::de::DeserializeOwned;
 
 use tonneli_core::{
-    model::{Address, AddressId, CityId, CityMeta, DateRange, Fraction, PickupEvent},
+    dates::MalformedDates,
+    fraction_map::FractionMapper,
+    matching::StreetMatcher,
+    model::{
+        Address, AddressId, Capabilities, CityArea, CityMeta, Coordinates, DateRange, Fraction,
+        PickupEvent,
+    },
     plugin::CityPlugin,
-    ports::{AddressPort, AddressSearch, PortError, SchedulePort},
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport, SearchResults},
 };
-
-const BASE_URL: &str = "https://www.awbkoeln.de/api";
-
-/// Response wrapper from /api/streets
-#[derive(Debug, Deserialize)]
-struct StreetsResponse {
-    data: Vec<StreetEntry>,
-}
-
-/// Single street/house entry from /api/streets
+use tonneli_http::{CappedField, HttpClient, RequestBuilder};
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {insta as _, tokio as _, tonneli_testkit as _, wiremock as _};
+
+/// Base URL of the public API.
+pub const BASE_URL: &str = "https://www.awbkoeln.de/api";
+
+/// Leading characters of the query used to look up "did you mean" streets.
+const SUGGESTION_PREFIX_CHARS: usize = 3;
+/// Entries decoded from the prefix query when collecting suggestions.
+const SUGGESTION_SCAN_LIMIT: usize = 200;
+/// Street names offered to the user when nothing matched.
+const MAX_SUGGESTIONS: usize = 5;
+/// Address looked up by [`CologneAddressPort::health_check`].
+const PROBE_ADDRESS: (&str, &str) = ("Domkloster", "4");
+/// Calendar years requested at once for a range spanning several years.
+const MAX_CONCURRENT_YEAR_FETCHES: usize = 3;
+
+/// AWB colour tags on top of the shared German vocabulary (`wertstoff` is covered there).
+const AWB_TYPES: &[(&str, Fraction)] = &[
+    ("grey", Fraction::Residual),
+    ("blue", Fraction::Paper),
+    ("brown", Fraction::Organic),
+];
+
+/// Single street/house entry from the `data` array of /api/streets
 #[derive(Debug, Deserialize)]
 struct StreetEntry {
     street_name: String,
@@ -57,21 +82,134 @@ struct CalendarEntry {
     typ: String, // "grey", "blue", ...
 }
 
+/// Parts of a Cologne [`AddressId`]: `street_code:building_number:building_number_addition`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AwbAddressId<'id> {
+    /// AWB street code.
+    pub street_code: &'id str,
+    /// House number without addition.
+    pub building_number: &'id str,
+    /// House number addition such as `a`; empty when there is none.
+    pub building_number_addition: &'id str,
+}
+
+impl<'id> AwbAddressId<'id> {
+    /// Split an id produced by [`CologneAddressPort`].
+    ///
+    /// # Errors
+    ///
+    /// [`PortError::InvalidAddressId`] when the street code or house number is blank, or
+    /// the id has more than three segments.
+    pub fn parse(id: &'id AddressId) -> Result<Self, PortError> {
+        let mut parts = id.0.split(':');
+        let mut required = || {
+            parts
+                .next()
+                .filter(|part| !part.trim().is_empty())
+                .ok_or(PortError::InvalidAddressId)
+        };
+        let street_code = required()?;
+        let building_number = required()?;
+        let building_number_addition = parts.next().unwrap_or("");
+        if parts.next().is_some() {
+            return Err(PortError::InvalidAddressId);
+        }
+
+        Ok(Self {
+            street_code,
+            building_number,
+            building_number_addition,
+        })
+    }
+}
+
+impl fmt::Display for AwbAddressId<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.street_code, self.building_number, self.building_number_addition
+        )
+    }
+}
+
 /// Address search implementation for Cologne.
 pub struct CologneAddressPort {
-    client: Client,
+    client: HttpClient,
+    base_url: String,
     meta: CityMeta,
 }
 
 impl CologneAddressPort {
     /// Create a new address port bound to the given HTTP client.
     #[must_use]
-    pub fn new(client: Client) -> Self {
+    pub fn new(client: HttpClient) -> Self {
         Self {
             client,
+            base_url: BASE_URL.to_owned(),
             meta: city_meta(),
         }
     }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    fn streets_request(
+        &self,
+        street_name: &str,
+        building_number: &str,
+        building_number_addition: &str,
+    ) -> RequestBuilder {
+        let base_url = &self.base_url;
+        self.client.get(format!("{base_url}/streets")).query(&[
+            ("street_name", street_name),
+            ("building_number", building_number),
+            ("building_number_addition", building_number_addition),
+            ("form", "json"),
+        ])
+    }
+
+    /// Streets sharing a short prefix with `street_name`, close matches first.
+    ///
+    /// Failures are swallowed: suggestions only decorate an `AddressNotFound`.
+    async fn suggest_streets(&self, street_name: &str, building_number: &str) -> Vec<String> {
+        let prefix = street_name
+            .chars()
+            .take(SUGGESTION_PREFIX_CHARS)
+            .collect::<String>();
+        let req = self.streets_request(&prefix, building_number, "");
+        let Ok(streets) = self
+            .client
+            .fetch_json_seed(
+                req,
+                CappedField::<StreetEntry>::new("data", SUGGESTION_SCAN_LIMIT),
+            )
+            .await
+        else {
+            return Vec::new();
+        };
+
+        let mut names = Vec::<String>::new();
+        for entry in streets.items {
+            let name = if entry.user_street_name.is_empty() {
+                entry.street_name
+            } else {
+                entry.user_street_name
+            };
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        let matcher = StreetMatcher::new(street_name, true);
+        names.sort_by_key(|name| !matcher.matches(name));
+        names.truncate(MAX_SUGGESTIONS);
+        names
+    }
 }
 
 #[async_trait]
@@ -81,81 +219,134 @@ impl AddressPort for CologneAddressPort {
     }
 
     async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        Ok(self.search_results(query, limit).await?.addresses)
+    }
+
+    /// Looks up a well-known address.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        let (street_name, building_number) = PROBE_ADDRESS;
+        let req = self.streets_request(street_name, building_number, "");
+        Some(
+            self.client
+                .fetch_bytes(req)
+                .await
+                .map(drop)
+                .map_err(PortError::from),
+        )
+    }
+
+    async fn search_results(
+        &self,
+        query: &AddressSearch,
+        limit: usize,
+    ) -> Result<SearchResults, PortError> {
         if limit == 0 || query.is_empty() {
-            return Ok(Vec::new());
+            return Ok(SearchResults::default());
         }
 
         let street_name = query.street.trim();
 
         // AWB API needs a house number to return data; allow empty to keep API surface
-        // consistent with other cities.
-        let building_number = query
-            .house_number
-            .as_deref()
-            .map(str::trim)
-            .filter(|segment| !segment.is_empty())
-            .unwrap_or("");
-
-        let req = self.client.get(format!("{BASE_URL}/streets")).query(&[
-            ("street_name", street_name),
-            ("building_number", building_number),
-            ("building_number_addition", ""),
-            ("form", "json"),
-        ]);
-
-        let resp = fetch_json::<StreetsResponse>(req).await?;
+        // consistent with other cities. "12a" is sent as number "12" plus addition "a".
+        let (building_number, building_number_addition) =
+            query.house_number_parts().unwrap_or(("", None));
+        let building_number_addition = building_number_addition.unwrap_or("");
+
+        let req = self.streets_request(street_name, building_number, building_number_addition);
+
+        // Broad queries can match thousands of entries; only decode as many as requested.
+        let streets = self
+            .client
+            .fetch_json_seed(req, CappedField::<StreetEntry>::new("data", limit))
+            .await?;
+        let truncated = streets.is_truncated();
+
+        // AWB answers typos and missing house numbers with an empty list.
+        if streets.total == 0 {
+            return Err(PortError::AddressNotFound {
+                suggestions: self.suggest_streets(street_name, building_number).await,
+                status: None,
+            });
+        }
 
-        let mut results = Vec::new();
+        let mut results = Vec::with_capacity(streets.items.len());
+        // Entries are grouped by street; consecutive house numbers share one name.
+        let mut shared_street: Option<Arc<str>> = None;
 
-        for entry in resp.data.into_iter().take(limit) {
+        for entry in streets.items {
             let street = if entry.user_street_name.is_empty() {
                 &entry.street_name
             } else {
                 &entry.user_street_name
             };
-            let house = if entry.user_building_number.is_empty() {
-                &entry.building_number
+            let mut house = if entry.user_building_number.is_empty() {
+                entry.building_number.clone()
             } else {
-                &entry.user_building_number
+                entry.user_building_number.clone()
             };
+            if !house.ends_with(entry.building_number_addition.as_str()) {
+                house.push_str(&entry.building_number_addition);
+            }
 
             // Encode street_code + house number (+ optional addition) into AddressId
             // so schedule() can reconstruct the calendar query.
-            let id = AddressId(format!(
-                "{}:{}:{}",
-                entry.street_code, entry.building_number, entry.building_number_addition
-            ));
+            let id = AddressId(
+                AwbAddressId {
+                    street_code: &entry.street_code,
+                    building_number: &entry.building_number,
+                    building_number_addition: &entry.building_number_addition,
+                }
+                .to_string(),
+            );
 
             let label = format!("{street} {house}");
+            let street = match &shared_street {
+                Some(shared) if **shared == **street => Arc::clone(shared),
+                _ => Arc::clone(shared_street.insert(Arc::from(street.as_str()))),
+            };
 
             results.push(Address {
                 id,
                 city: self.meta.id.clone(),
-                label,
-                street: street.to_owned(),
-                house_number: house.to_owned(),
+                label: label.into(),
+                street,
+                house_number: house.into(),
             });
         }
 
-        Ok(results)
+        Ok(SearchResults {
+            addresses: results,
+            truncated,
+        })
     }
 }
 
 /// Pickup schedule implementation for Cologne.
 pub struct CologneSchedulePort {
-    client: Client,
+    client: HttpClient,
+    base_url: String,
     meta: CityMeta,
+    fractions: FractionMapper,
 }
 
 impl CologneSchedulePort {
     /// Create a new schedule port bound to the given HTTP client.
     #[must_use]
-    pub fn new(client: Client) -> Self {
+    pub fn new(client: HttpClient) -> Self {
         Self {
             client,
+            base_url: BASE_URL.to_owned(),
             meta: city_meta(),
+            fractions: fraction_mapper(),
         }
     }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
 }
 
 #[async_trait]
@@ -169,73 +360,150 @@ impl SchedulePort for CologneSchedulePort {
         address_id: &AddressId,
         range: DateRange,
     ) -> Result<Vec<PickupEvent>, PortError> {
-        // AddressId format: "street_code:building_number:building_number_addition"
-        let mut id_parts = address_id.0.split(':');
-        let street_code = id_parts.next().ok_or(PortError::InvalidAddressId)?;
-        let building_number = id_parts.next().ok_or(PortError::InvalidAddressId)?;
-        let building_number_addition = id_parts.next().unwrap_or("");
-
-        // Cover at least the requested range. If the range is within a single year,
-        // only ask AWB for that year and that month span. If it crosses years,
-        // we just request full years and filter afterwards.
-        let start_year = range.start.year();
-        let end_year = range.end.year();
-
-        let (start_month, end_month) = if start_year == end_year {
-            (range.start.month(), range.end.month())
-        } else {
-            (1, 12)
-        };
-
-        let start_year_s = start_year.to_string();
-        let end_year_s = end_year.to_string();
-        let start_month_s = start_month.to_string();
-        let end_month_s = end_month.to_string();
-
-        let mut req = self.client.get(format!("{BASE_URL}/calendar")).query(&[
-            ("building_number", building_number),
-            ("street_code", street_code),
-            ("start_year", &start_year_s),
-            ("end_year", &end_year_s),
-            ("start_month", &start_month_s),
-            ("end_month", &end_month_s),
-            ("form", "json"),
-        ]);
-
-        if !building_number_addition.is_empty() {
-            req = req.query(&[("building_number_addition", building_number_addition)]);
-        }
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
 
-        let calendar = fetch_json::<CalendarResponse>(req).await?;
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let AwbAddressId {
+            street_code,
+            building_number,
+            building_number_addition,
+        } = AwbAddressId::parse(address_id)?;
+
+        // Some AWB deployments reject multi-year spans, so ask for each calendar
+        // year separately (only the months the range touches) and merge afterwards.
+        // A long range would otherwise fire one request per year at once.
+        let calendars = stream::iter(year_spans(range))
+            .map(|span| {
+                self.fetch_calendar(street_code, building_number, building_number_addition, span)
+            })
+            .buffered(MAX_CONCURRENT_YEAR_FETCHES)
+            .try_collect::<Vec<_>>()
+            .await?;
 
         let mut events = Vec::new();
+        let mut malformed = MalformedDates::default();
+        // Map and allocate each AWB type once; events share fraction and note.
+        let mut types = HashMap::<String, (Fraction, Arc<str>)>::new();
 
-        for entry in calendar.data {
-            let date = NaiveDate::from_ymd_opt(entry.year, entry.month, entry.day)
-                .ok_or_else(|| PortError::Internal("Invalid date in AWB calendar".into()))?;
+        for entry in calendars.into_iter().flat_map(|calendar| calendar.data) {
+            let Some(date) = NaiveDate::from_ymd_opt(entry.year, entry.month, entry.day) else {
+                malformed.push(format!("{}-{}-{}", entry.year, entry.month, entry.day));
+                continue;
+            };
 
-            if date < range.start || date > range.end {
+            if !range.contains(date) {
                 continue;
             }
 
-            let (fraction, note) = map_awb_type(&entry.typ);
+            let (fraction, note) = types
+                .entry(entry.typ)
+                .or_insert_with_key(|typ| {
+                    let fraction = self.fractions.map(typ);
+                    let note = awb_note(&fraction, typ).into();
+                    (fraction, note)
+                })
+                .clone();
 
             events.push(PickupEvent {
                 date,
                 fraction,
                 note: Some(note),
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
             });
         }
 
         events.sort_by_key(|event| event.date);
 
-        Ok(events)
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: malformed.into_inner(),
+        })
+    }
+
+    fn supports_partial_fetch(&self) -> bool {
+        // Calendar requests are narrowed to the months the range touches.
+        true
     }
 }
 
+impl CologneSchedulePort {
+    async fn fetch_calendar(
+        &self,
+        street_code: &str,
+        building_number: &str,
+        building_number_addition: &str,
+        span: YearSpan,
+    ) -> Result<CalendarResponse, PortError> {
+        let year = span.year.to_string();
+        let start_month = span.start_month.to_string();
+        let end_month = span.end_month.to_string();
+        let base_url = &self.base_url;
+
+        let mut req = self.client.get(format!("{base_url}/calendar")).query(&[
+            ("building_number", building_number),
+            ("street_code", street_code),
+            ("start_year", &year),
+            ("end_year", &year),
+            ("start_month", &start_month),
+            ("end_month", &end_month),
+            ("form", "json"),
+        ]);
+
+        if !building_number_addition.is_empty() {
+            req = req.query(&[("building_number_addition", building_number_addition)]);
+        }
+
+        Ok(self.client.fetch_json::<CalendarResponse>(req).await?)
+    }
+}
+
+/// Months of a single calendar year covered by a requested range.
+#[derive(Debug, Clone, Copy)]
+struct YearSpan {
+    year: i32,
+    start_month: u32,
+    end_month: u32,
+}
+
+/// Split a date range into one span per calendar year it touches.
+fn year_spans(range: DateRange) -> Vec<YearSpan> {
+    let start_year = range.start.year();
+    let end_year = range.end.year();
+
+    (start_year..=end_year)
+        .map(|year| YearSpan {
+            year,
+            start_month: if year == start_year {
+                range.start.month()
+            } else {
+                1
+            },
+            end_month: if year == end_year {
+                range.end.month()
+            } else {
+                12
+            },
+        })
+        .collect()
+}
+
+/// Mapper from AWB calendar types to [`Fraction`].
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    FractionMapper::german().with_rules(AWB_TYPES)
+}
+
 /// Build the plugin bundle for the Cologne provider.
 #[must_use]
-pub fn plugin(client: Client) -> CityPlugin {
+pub fn plugin(client: HttpClient) -> CityPlugin {
     let address_port = Arc::new(CologneAddressPort::new(client.clone()));
     let schedule_port = Arc::new(CologneSchedulePort::new(client));
 
@@ -247,40 +515,31 @@ pub fn plugin(client: Client) -> CityPlugin {
 }
 
 fn city_meta() -> CityMeta {
-    CityMeta {
-        id: CityId(String::from("cologne")),
-        name: String::from("Köln"),
-    }
+    CityMeta::new("cologne", "Köln")
+        .with_region("Nordrhein-Westfalen")
+        .with_locale("DE", "Europe/Berlin")
+        .with_area(
+            CityArea::new(Coordinates::new(50.9375, 6.9603), 15.0)
+                .with_postal_codes(["506", "507", "508", "509", "510", "511"]),
+        )
+        .with_source(
+            "AWB Abfallwirtschaftsbetriebe Köln",
+            "https://www.awbkoeln.de",
+        )
+        .with_capabilities(Capabilities {
+            house_number_required: true,
+            multi_year: true,
+            ..Capabilities::default()
+        })
 }
 
-/// Map AWB “type” strings (grey/blue/…) to the Fraction enum + a human note.
-fn map_awb_type(raw: &str) -> (Fraction, String) {
-    let type_tag = raw.to_lowercase();
-
-    match type_tag.as_str() {
-        "grey" => (Fraction::Residual, "Restabfall".to_owned()),
-
-        "blue" => (Fraction::Paper, "Papier / Pappe".to_owned()),
-
-        "wertstoff" => (
-            Fraction::Plastic,
-            "Leichtverpackungen / Wertstoffe".to_owned(),
-        ),
-
-        "brown" => (Fraction::Organic, "Bioabfall".to_owned()),
-
-        _ => (Fraction::Other(raw.to_owned()), format!("Fraktion {raw}")),
+/// Human-readable label for an AWB “type” string (grey/blue/…).
+fn awb_note(fraction: &Fraction, raw: &str) -> String {
+    match fraction {
+        Fraction::Residual => "Restabfall".to_owned(),
+        Fraction::Paper => "Papier / Pappe".to_owned(),
+        Fraction::Plastic => "Leichtverpackungen / Wertstoffe".to_owned(),
+        Fraction::Organic => "Bioabfall".to_owned(),
+        _ => format!("Fraktion {raw}"),
     }
 }
-
-// Small helper to fetch and decode JSON with status handling.
-async fn fetch_json<T: DeserializeOwned>(req: RequestBuilder) -> Result<T, PortError> {
-    req.send()
-        .await
-        .map_err(PortError::from)?
-        .error_for_status()
-        .map_err(PortError::from)?
-        .json()
-        .await
-        .map_err(PortError::from)
-}
@@ -0,0 +1,54 @@
+//! AWB calendar types must map onto known fractions.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use tonneli_provider_cologne::fraction_mapper;
+use tonneli_testkit::{fixture_labels, unexpected_other_fractions};
+
+use async_trait as _;
+use chrono as _;
+use futures as _;
+use insta as _;
+use serde as _;
+use tokio as _;
+use tonneli_core as _;
+use tonneli_http as _;
+use wiremock as _;
+
+/// Field holding the collection type in `/calendar` entries.
+const LABEL_KEY: &str = "type";
+/// Labels known from the upstream API, including ones not in any fixture.
+const KNOWN_LABELS: &[&str] = &["grey", "blue", "brown", "wertstoff"];
+/// Labels without a matching fraction that are fine as “Other”.
+const EXPECTED_OTHER: &[&str] = &[];
+/// Captured responses whose labels are checked as well, relative to the crate root.
+const FIXTURES: &[&str] = &[
+    "tests/fixtures/calendar_2025.json",
+    "tests/fixtures/calendar_2026.json",
+    "tests/fixtures/upstream/calendar.json",
+    "tests/fixtures/live/calendar.json",
+];
+
+#[test]
+fn known_vocabulary_maps_to_fractions() {
+    let mut vocabulary = KNOWN_LABELS
+        .iter()
+        .map(|label| (*label).to_owned())
+        .collect::<Vec<_>>();
+    for fixture in FIXTURES {
+        let path = format!("{}/{fixture}", env!("CARGO_MANIFEST_DIR"));
+        vocabulary.extend(fixture_labels(&path, LABEL_KEY).expect("fixture must be readable JSON"));
+    }
+
+    let unexpected = unexpected_other_fractions(
+        &fraction_mapper(),
+        vocabulary.iter().map(String::as_str),
+        EXPECTED_OTHER,
+    );
+    assert!(
+        unexpected.is_empty(),
+        "map these labels or list them in EXPECTED_OTHER: {unexpected:?}"
+    );
+}
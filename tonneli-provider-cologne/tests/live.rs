@@ -0,0 +1,121 @@
+//! Contract test against the live AWB Köln API, enabled with `TONNELI_LIVE_TESTS=1`.
+//!
+//! Looks up a known address and its calendar for the current month, records the
+//! responses into `tests/fixtures/live` and checks that the ports still parse them.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::{Datelike, Duration, Utc};
+use tonneli_core::{AddressPort, AddressSearch, DateRange, SchedulePort};
+use tonneli_provider_cologne::{AwbAddressId, BASE_URL, CologneAddressPort, CologneSchedulePort};
+use tonneli_testkit::{Recording, client, live_tests_enabled};
+
+use async_trait as _;
+use futures as _;
+use insta as _;
+use serde as _;
+use tonneli_http as _;
+use wiremock as _;
+
+const KNOWN_STREET: &str = "Domkloster";
+const KNOWN_HOUSE_NUMBER: &str = "4";
+
+#[tokio::test]
+async fn live_api_still_parses() {
+    if !live_tests_enabled() {
+        return;
+    }
+    let mut recording = Recording::new(
+        BASE_URL,
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/live"),
+    );
+
+    let streets = recording
+        .fetch(
+            "streets",
+            "/streets",
+            &[
+                ("street_name", KNOWN_STREET),
+                ("building_number", KNOWN_HOUSE_NUMBER),
+                ("building_number_addition", ""),
+                ("form", "json"),
+            ],
+        )
+        .await
+        .expect("street lookup must be recorded");
+    let street_code = streets
+        .get("data")
+        .and_then(|data| data.get(0))
+        .and_then(|street| street.get("street_code"))
+        .and_then(|code| code.as_str())
+        .expect("known address must be found")
+        .to_owned();
+
+    // One month keeps the schedule to a single calendar request.
+    let today = Utc::now().date_naive();
+    let month_start = today.with_day(1).expect("every month has a first day");
+    let month_end = (month_start + Duration::days(31))
+        .with_day(1)
+        .and_then(|next_month| next_month.pred_opt())
+        .expect("month end must exist");
+    let year = today.year().to_string();
+    let month = today.month().to_string();
+    recording
+        .fetch(
+            "calendar",
+            "/calendar",
+            &[
+                ("building_number", KNOWN_HOUSE_NUMBER),
+                ("street_code", &street_code),
+                ("start_year", &year),
+                ("end_year", &year),
+                ("start_month", &month),
+                ("end_month", &month),
+                ("form", "json"),
+            ],
+        )
+        .await
+        .expect("calendar must be recorded");
+
+    let server = recording.replay().await;
+    let addresses = CologneAddressPort::new(client())
+        .with_base_url(server.uri())
+        .search(
+            &AddressSearch::new(KNOWN_STREET, Some(KNOWN_HOUSE_NUMBER.to_owned())),
+            10,
+        )
+        .await
+        .expect("recorded streets must parse");
+    let address = addresses.first().expect("the known address must be found");
+    let parsed = AwbAddressId::parse(&address.id).expect("search must build valid ids");
+    assert_eq!(
+        parsed.street_code, street_code,
+        "the id must carry the street code"
+    );
+
+    let report = CologneSchedulePort::new(client())
+        .with_base_url(server.uri())
+        .schedule_report(
+            &address.id,
+            DateRange {
+                start: month_start,
+                end: month_end,
+            },
+        )
+        .await
+        .expect("recorded calendar must parse");
+    assert!(
+        report.malformed_dates.is_empty(),
+        "all recorded dates must parse: {:?}",
+        report.malformed_dates
+    );
+    assert!(
+        report
+            .events
+            .iter()
+            .all(|event| (month_start..=month_end).contains(&event.date)),
+        "events must stay within the requested month"
+    );
+}
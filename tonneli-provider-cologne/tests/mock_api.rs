@@ -0,0 +1,221 @@
+//! Cologne ports against canned AWB responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use std::time::{Duration, Instant};
+
+use chrono::NaiveDate;
+use tonneli_core::{
+    AddressId, AddressPort, AddressSearch, DateRange, Fraction, PortError, SchedulePort,
+};
+use tonneli_provider_cologne::{CologneAddressPort, CologneSchedulePort};
+use tonneli_testkit::{client, json};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+use async_trait as _;
+use futures as _;
+use insta as _;
+use serde as _;
+use tonneli_http as _;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+#[tokio::test]
+async fn health_check_looks_up_a_known_address() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/streets"))
+        .and(query_param("street_name", "Domkloster"))
+        .and(query_param("building_number", "4"))
+        .respond_with(json(include_str!("fixtures/streets.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = CologneAddressPort::new(client()).with_base_url(server.uri());
+    let probe = port.health_check().await;
+    assert!(
+        matches!(probe, Some(Ok(()))),
+        "the street lookup answers, got {probe:?}"
+    );
+
+    let down = CologneAddressPort::new(client()).with_base_url(format!("{}/down", server.uri()));
+    let failed = down.health_check().await;
+    assert!(
+        matches!(failed, Some(Err(_))),
+        "a failing backend is reported, got {failed:?}"
+    );
+}
+
+#[tokio::test]
+async fn search_sends_street_and_house_number() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/streets"))
+        .and(query_param("street_name", "Domkloster"))
+        .and(query_param("building_number", "4"))
+        .and(query_param("building_number_addition", ""))
+        .and(query_param("form", "json"))
+        .respond_with(json(include_str!("fixtures/streets.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = CologneAddressPort::new(client()).with_base_url(server.uri());
+    let addresses = port
+        .search(&AddressSearch::new("Domkloster", Some("4".to_owned())), 10)
+        .await
+        .expect("search against the mock must succeed");
+
+    assert_eq!(
+        addresses.len(),
+        1,
+        "one entry in the fixture: {addresses:?}"
+    );
+    let address = addresses.first().expect("one address");
+    assert_eq!(
+        address.id,
+        AddressId("1234:4:".to_owned()),
+        "id encodes street code, number and addition"
+    );
+    assert_eq!(
+        &*address.label, "Domkloster 4",
+        "label joins street and number"
+    );
+}
+
+#[tokio::test]
+async fn search_without_hits_suggests_streets() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/streets"))
+        .and(query_param("street_name", "Domklostr"))
+        .respond_with(json(include_str!("fixtures/streets_empty.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/streets"))
+        .and(query_param("street_name", "Dom"))
+        .respond_with(json(include_str!("fixtures/streets_prefix.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = CologneAddressPort::new(client()).with_base_url(server.uri());
+    let error = port
+        .search(&AddressSearch::new("Domklostr", Some("4".to_owned())), 10)
+        .await
+        .expect_err("an empty AWB answer must be reported as not found");
+
+    assert_eq!(
+        error.suggestions(),
+        ["Domkloster".to_owned(), "Dombach".to_owned()],
+        "close matches come first and names are not repeated"
+    );
+}
+
+#[tokio::test]
+async fn schedule_requests_each_year_separately() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/calendar"))
+        .and(query_param("street_code", "1234"))
+        .and(query_param("building_number", "4"))
+        .and(query_param("start_year", "2025"))
+        .and(query_param("end_year", "2025"))
+        .and(query_param("start_month", "12"))
+        .and(query_param("end_month", "12"))
+        .respond_with(json(include_str!("fixtures/calendar_2025.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/calendar"))
+        .and(query_param("start_year", "2026"))
+        .and(query_param("end_year", "2026"))
+        .and(query_param("start_month", "1"))
+        .and(query_param("end_month", "3"))
+        .respond_with(json(include_str!("fixtures/calendar_2026.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = CologneSchedulePort::new(client()).with_base_url(server.uri());
+    let range = DateRange {
+        start: date(2025, 12, 1),
+        end: date(2026, 3, 31),
+    };
+    let report = port
+        .schedule_report(&AddressId("1234:4:".to_owned()), range)
+        .await
+        .expect("schedule against the mock must succeed");
+
+    let events = report
+        .events
+        .iter()
+        .map(|event| (event.date, event.fraction.clone(), event.note.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (date(2025, 12, 15), Fraction::Residual, Some("Restabfall")),
+            (date(2026, 1, 5), Fraction::Paper, Some("Papier / Pappe")),
+        ],
+        "events from both years are merged in date order"
+    );
+    assert_eq!(
+        report.malformed_dates,
+        vec!["2026-2-31".to_owned()],
+        "impossible days must be reported"
+    );
+}
+
+#[tokio::test]
+async fn schedule_limits_concurrent_year_requests() {
+    let delay = Duration::from_millis(200);
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/calendar"))
+        .respond_with(json(include_str!("fixtures/calendar_2025.json")).set_delay(delay))
+        .expect(6)
+        .mount(&server)
+        .await;
+
+    let port = CologneSchedulePort::new(client()).with_base_url(server.uri());
+    let range = DateRange {
+        start: date(2020, 1, 1),
+        end: date(2025, 12, 31),
+    };
+    let started = Instant::now();
+    let events = port
+        .schedule(&AddressId("1234:4:".to_owned()), range)
+        .await
+        .expect("schedule against the mock must succeed");
+
+    assert!(!events.is_empty(), "the years are merged");
+    assert!(
+        started.elapsed() >= delay * 2,
+        "six years are fetched at most three at a time, took {:?}",
+        started.elapsed()
+    );
+}
+
+#[tokio::test]
+async fn schedule_rejects_incomplete_address_ids() {
+    let port = CologneSchedulePort::new(client()).with_base_url("http://127.0.0.1:9");
+    let range = DateRange {
+        start: date(2025, 1, 1),
+        end: date(2025, 12, 31),
+    };
+    let result = port.schedule(&AddressId("1234".to_owned()), range).await;
+    assert!(
+        matches!(result, Err(PortError::InvalidAddressId)),
+        "ids without a house number must be rejected: {result:?}"
+    );
+}
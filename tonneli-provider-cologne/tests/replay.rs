@@ -0,0 +1,51 @@
+//! Cologne ports against AWB responses replayed from fixture files.
+//!
+//! The files in `tests/fixtures/replay` are copies of the captured responses in
+//! `tests/fixtures/upstream`, named the way `HttpClient::with_fixtures` looks them
+//! up, so the results must match the snapshots of `golden.rs`. Run with
+//! `TONNELI_RECORD_FIXTURES=1` and network access to replace them with recordings
+//! of the live API.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use insta::{assert_json_snapshot, with_settings};
+use tonneli_core::{AddressPort, AddressSearch, DateRange, SchedulePort};
+use tonneli_http::HttpClient;
+use tonneli_provider_cologne::{CologneAddressPort, CologneSchedulePort};
+use tonneli_testkit::fixture_client;
+
+use async_trait as _;
+use futures as _;
+use serde as _;
+use wiremock as _;
+
+fn replayed() -> HttpClient {
+    fixture_client(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/replay"
+    ))
+}
+
+#[tokio::test]
+async fn replay_matches_the_golden_snapshots() {
+    let addresses = CologneAddressPort::new(replayed())
+        .search(&AddressSearch::new("Aachener Straße", Some("1")), 10)
+        .await
+        .expect("replayed street lookup must parse");
+    let address = addresses.first().expect("the replayed street is found");
+
+    let range = DateRange::this_month(NaiveDate::from_ymd_opt(2025, 3, 1).expect("valid date"));
+    let events = CologneSchedulePort::new(replayed())
+        .schedule(&address.id, range)
+        .await
+        .expect("replayed calendar must parse");
+
+    // Same snapshot files as `golden.rs`, which serves the same responses via wiremock.
+    with_settings!({ prepend_module_to_snapshot => false }, {
+        assert_json_snapshot!("golden__search_snapshot", addresses);
+        assert_json_snapshot!("golden__schedule_snapshot", events);
+    });
+}
@@ -0,0 +1,226 @@
+//! Offline demo provider with synthetic addresses and a generated bi-weekly schedule.
+//!
+//! Everything is derived from constants, so results never change between runs: useful
+//! for demos without network access, reproducible screenshots, and integration tests.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate};
+
+use tonneli_core::{
+    matching::StreetMatcher,
+    model::{Address, AddressId, Capabilities, CityId, CityMeta, DateRange, Fraction, PickupEvent},
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort},
+};
+use tonneli_http::HttpClient;
+
+/// City id under which the demo provider is registered.
+pub const DEMO_CITY_ID: &str = "demo";
+
+const STREETS: &[&str] = &[
+    "Musterstraße",
+    "Beispielweg",
+    "Demoallee",
+    "Am Testfeld",
+    "Probegasse",
+];
+const HOUSE_NUMBERS_PER_STREET: u32 = 24;
+
+/// Fractions with their pickup interval in days and offset from the street's anchor day.
+const ROTATION: &[(Fraction, i64, i64)] = &[
+    (Fraction::Residual, 14, 0),
+    (Fraction::Organic, 14, 7),
+    (Fraction::Plastic, 14, 3),
+    (Fraction::Paper, 28, 10),
+];
+
+/// Monday all generated rotations start from.
+fn epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap_or_default()
+}
+
+/// Address search over the synthetic street list.
+pub struct DemoAddressPort {
+    meta: CityMeta,
+}
+
+impl DemoAddressPort {
+    /// Create the demo address port.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { meta: city_meta() }
+    }
+}
+
+impl Default for DemoAddressPort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AddressPort for DemoAddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let matcher = StreetMatcher::new(&query.street, query.fuzzy);
+        let house_filter = query
+            .house_number
+            .as_deref()
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty());
+
+        let addresses = STREETS
+            .iter()
+            .enumerate()
+            .filter(|(_, street)| matcher.matches(street))
+            .flat_map(|(street_index, street)| {
+                let street_name = Arc::<str>::from(*street);
+                (1..=HOUSE_NUMBERS_PER_STREET).map(move |number| {
+                    let house_number = number.to_string();
+                    Address {
+                        id: AddressId(format!("{street_index}:{number}")),
+                        city: CityId(DEMO_CITY_ID.to_owned()),
+                        label: format!("{street_name} {house_number}").into(),
+                        street: Arc::clone(&street_name),
+                        house_number: house_number.into(),
+                    }
+                })
+            })
+            .filter(|address| house_filter.is_none_or(|filter| *address.house_number == *filter))
+            .take(limit)
+            .collect();
+
+        Ok(addresses)
+    }
+
+    async fn resolve(&self, address_id: &AddressId) -> Result<Address, PortError> {
+        let (street_index, number) = address_id
+            .0
+            .split_once(':')
+            .ok_or(PortError::InvalidAddressId)?;
+        let street = street_index
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| STREETS.get(index))
+            .ok_or(PortError::InvalidAddressId)?;
+        let number = number
+            .parse::<u32>()
+            .ok()
+            .filter(|number| (1..=HOUSE_NUMBERS_PER_STREET).contains(number))
+            .ok_or(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            })?;
+        Ok(Address {
+            id: address_id.clone(),
+            city: self.meta.id.clone(),
+            label: format!("{street} {number}").into(),
+            street: (*street).into(),
+            house_number: number.to_string().into(),
+        })
+    }
+}
+
+/// Schedule generator for the synthetic addresses.
+pub struct DemoSchedulePort {
+    meta: CityMeta,
+}
+
+impl DemoSchedulePort {
+    /// Create the demo schedule port.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { meta: city_meta() }
+    }
+}
+
+impl Default for DemoSchedulePort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SchedulePort for DemoSchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        // AddressId format: "street_index:house_number"
+        let street_index = address_id
+            .0
+            .split_once(':')
+            .and_then(|(street, _)| street.parse::<i64>().ok())
+            .filter(|index| usize::try_from(*index).is_ok_and(|index| index < STREETS.len()))
+            .ok_or(PortError::InvalidAddressId)?;
+
+        // Each street starts on its own weekday so neighbouring streets differ.
+        let anchor = epoch() + Duration::days(street_index);
+
+        let mut events = Vec::new();
+        for (fraction, interval, offset) in ROTATION {
+            let first = anchor + Duration::days(*offset);
+            let elapsed = (range.start - first).num_days();
+            let periods = if elapsed > 0 {
+                (elapsed + interval - 1) / interval
+            } else {
+                0
+            };
+            let mut date = first + Duration::days(periods * interval);
+            while date <= range.end {
+                events.push(PickupEvent {
+                    date,
+                    fraction: fraction.clone(),
+                    note: None,
+                    container: None,
+                    correction: None,
+                    holiday: None,
+                    predicted: false,
+                });
+                date += Duration::days(*interval);
+            }
+        }
+
+        events.sort_by_key(|event| event.date);
+        Ok(events)
+    }
+
+    fn supports_partial_fetch(&self) -> bool {
+        true
+    }
+}
+
+/// Build the plugin bundle for the demo provider.
+///
+/// The client is accepted for symmetry with the network providers and never used.
+#[must_use]
+pub fn plugin(_client: HttpClient) -> CityPlugin {
+    CityPlugin {
+        meta: city_meta(),
+        address_port: Arc::new(DemoAddressPort::new()),
+        schedule_port: Arc::new(DemoSchedulePort::new()),
+    }
+}
+
+fn city_meta() -> CityMeta {
+    CityMeta::new(DEMO_CITY_ID, "Demo City (offline)")
+        .with_locale("DE", "Europe/Berlin")
+        .with_capabilities(Capabilities {
+            multi_year: true,
+            ..Capabilities::default()
+        })
+}
@@ -0,0 +1,135 @@
+//! Reading schedule files in CSV form.
+//!
+//! The first line names the columns; `street`, `date` and `fraction` are
+//! required, `house_number` and `note` optional. Fields are separated by commas
+//! or, as spreadsheets with German settings export them, by semicolons.
+//! Double quotes enclose fields containing the separator, with `""` for a quote.
+
+use crate::{FileAddress, FilePickup};
+
+/// Separator used by the header line.
+fn separator(header: &str) -> char {
+    if header.contains(';') { ';' } else { ',' }
+}
+
+/// Split one line into its fields.
+fn fields(line: &str, separator: char) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            _ if ch == separator && !quoted => {
+                fields.push(field.trim().to_owned());
+                field.clear();
+            }
+            _ => field.push(ch),
+        }
+    }
+    if quoted {
+        return Err("unterminated quote".to_owned());
+    }
+    fields.push(field.trim().to_owned());
+    Ok(fields)
+}
+
+/// Addresses with their pickups, in the order the rows name them.
+pub(crate) fn parse(text: &str) -> Result<Vec<FileAddress>, String> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let separator = separator(header);
+    let columns = fields(header, separator)?
+        .into_iter()
+        .map(|column| column.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+    let column = |name: &str| columns.iter().position(|column| column == name);
+    let required = |name: &str| column(name).ok_or_else(|| format!("missing column `{name}`"));
+    let (street, date, fraction) = (
+        required("street")?,
+        required("date")?,
+        required("fraction")?,
+    );
+    let (house_number, note) = (column("house_number"), column("note"));
+
+    let mut addresses = Vec::<FileAddress>::new();
+    for (index, line) in lines {
+        let row = fields(line, separator).map_err(|err| format!("line {}: {err}", index + 1))?;
+        let get = |position: Option<usize>| {
+            position
+                .and_then(|position| row.get(position))
+                .map(String::as_str)
+                .unwrap_or_default()
+        };
+        let (row_street, row_house_number) = (get(Some(street)), get(house_number));
+        if row_street.is_empty() {
+            return Err(format!("line {}: empty street", index + 1));
+        }
+        let pickup = FilePickup {
+            date: get(Some(date)).to_owned(),
+            fraction: get(Some(fraction)).to_owned(),
+            note: Some(get(note).to_owned()).filter(|note| !note.is_empty()),
+        };
+        match addresses.iter_mut().find(|address| {
+            address.street == row_street && address.house_number == row_house_number
+        }) {
+            Some(address) => address.pickups.push(pickup),
+            None => addresses.push(FileAddress {
+                street: row_street.to_owned(),
+                house_number: row_house_number.to_owned(),
+                pickups: vec![pickup],
+            }),
+        }
+    }
+    Ok(addresses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_spreadsheet_exports() {
+        let text = "Street;House_Number;Date;Fraction;Note\n\
+            Hauptstraße;12;14.03.2025;Papier;\n\
+            \"Am Markt; Nord\";1;2025-03-15;Paper;\"Bitte \"\"vorne\"\" abstellen\"\n\
+            Hauptstraße;12;2025-03-21;Restmüll;\n";
+        let addresses = parse(text).expect("export is valid");
+        let summary = addresses
+            .iter()
+            .map(|address| (address.street.as_str(), address.pickups.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [("Hauptstraße", 2), ("Am Markt; Nord", 1)],
+            "rows are grouped by address in file order"
+        );
+        assert_eq!(
+            addresses
+                .get(1)
+                .and_then(|address| address.pickups.first())
+                .and_then(|pickup| pickup.note.as_deref()),
+            Some("Bitte \"vorne\" abstellen"),
+            "quoted fields keep separators and quotes"
+        );
+        assert_eq!(
+            parse("street,date\nHauptstraße,2025-03-14\n").map(|_addresses| ()),
+            Err("missing column `fraction`".to_owned()),
+            "required columns are checked"
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Provider for hand-maintained schedules in a local JSON or CSV file.
+//!
+//! Towns without a supported provider can keep their pickups in a file and
+//! still use the frontends. The file is read again for every request, so edits
+//! show up without a restart. JSON files list addresses with their pickups:
+//!
+//! ```json
+//! {
+//!   "addresses": [
+//!     {
+//!       "street": "Hauptstraße",
+//!       "house_number": "12",
+//!       "pickups": [
+//!         { "date": "2025-03-14", "fraction": "Papier" },
+//!         { "date": "2025-03-21", "fraction": "Residual", "note": "Bis 6 Uhr" }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! CSV files have one pickup per row, with the columns `street`,
+//! `house_number` (optional), `date`, `fraction` and `note` (optional) named in
+//! the first line. Dates are ISO (`2025-03-14`) or German (`14.03.2025`).
+//! Fractions are either the names of [`Fraction`]'s variants or labels as
+//! municipal calendars use them, such as "Blaue Tonne" or "Gelber Sack".
+
+mod csv;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use tonneli_core::{
+    dates::MalformedDates,
+    fraction_map::FractionMapper,
+    ics_import::CalendarImport,
+    matching::StreetMatcher,
+    model::{Address, AddressId, Capabilities, CityMeta, DateRange, Fraction, PickupEvent},
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport},
+};
+use tonneli_http::HttpClient;
+
+// Dev-dependencies only used by the tests in `tests/`.
+#[cfg(test)]
+use {chrono as _, tokio as _};
+
+#[derive(Debug, Clone)]
+/// Town whose schedule is kept in a local file.
+pub struct FileTown {
+    /// JSON or CSV file with the town's addresses and pickups.
+    pub path: PathBuf,
+    /// Metadata of the resulting city.
+    pub meta: CityMeta,
+}
+
+/// JSON form of a schedule file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScheduleFile {
+    addresses: Vec<FileAddress>,
+}
+
+/// Address of a schedule file with its pickups.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct FileAddress {
+    pub(crate) street: String,
+    #[serde(default)]
+    pub(crate) house_number: String,
+    #[serde(default)]
+    pub(crate) pickups: Vec<FilePickup>,
+}
+
+/// Pickup of a schedule file, as written by hand.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct FilePickup {
+    pub(crate) date: String,
+    pub(crate) fraction: String,
+    #[serde(default)]
+    pub(crate) note: Option<String>,
+}
+
+impl FileAddress {
+    fn id(&self) -> AddressId {
+        AddressId(format!("{}:{}", self.street, self.house_number))
+    }
+}
+
+/// Read the addresses of the schedule file at `path`.
+fn load(path: &Path) -> Result<Vec<FileAddress>, PortError> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| PortError::Internal(format!("cannot read {}: {err}", path.display())))?;
+    let addresses = if text.trim_start().starts_with('{') {
+        serde_json::from_str::<ScheduleFile>(&text)
+            .map(|file| file.addresses)
+            .map_err(|err| err.to_string())
+    } else {
+        csv::parse(&text)
+    };
+    addresses.map_err(|err| PortError::Decode(format!("{}: {err}", path.display())))
+}
+
+/// Address search in a schedule file.
+pub struct FileAddressPort {
+    town: FileTown,
+}
+
+impl FileAddressPort {
+    /// Create an address port for the given town.
+    #[must_use]
+    pub fn for_town(town: FileTown) -> Self {
+        Self { town }
+    }
+
+    fn address(&self, address: FileAddress) -> Address {
+        let label = if address.house_number.is_empty() {
+            address.street.clone()
+        } else {
+            format!("{} {}", address.street, address.house_number)
+        };
+        Address {
+            id: address.id(),
+            city: self.town.meta.id.clone(),
+            label: label.into(),
+            street: address.street.into(),
+            house_number: address.house_number.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl AddressPort for FileAddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.town.meta
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let matcher = StreetMatcher::new(&query.street, query.fuzzy);
+        let house_filter = query
+            .house_number
+            .as_deref()
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty());
+        let addresses = load(&self.town.path)?
+            .into_iter()
+            .filter(|address| matcher.matches(&address.street))
+            .filter(|address| {
+                house_filter.is_none_or(|filter| address.house_number.eq_ignore_ascii_case(filter))
+            })
+            .take(limit)
+            .map(|address| self.address(address))
+            .collect::<Vec<_>>();
+        if addresses.is_empty() {
+            return Err(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            });
+        }
+        Ok(addresses)
+    }
+
+    async fn resolve(&self, address_id: &AddressId) -> Result<Address, PortError> {
+        load(&self.town.path)?
+            .into_iter()
+            .find(|address| address.id() == *address_id)
+            .map(|address| self.address(address))
+            .ok_or(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            })
+    }
+}
+
+/// Pickup schedule from a schedule file.
+pub struct FileSchedulePort {
+    town: FileTown,
+    fractions: FractionMapper,
+}
+
+impl FileSchedulePort {
+    /// Create a schedule port for the given town.
+    #[must_use]
+    pub fn for_town(town: FileTown) -> Self {
+        Self {
+            town,
+            fractions: fraction_mapper(),
+        }
+    }
+
+    /// Variant names such as `Paper` first, then calendar labels.
+    fn fraction(&self, label: &str) -> Fraction {
+        serde_json::from_value(label.into())
+            .unwrap_or_else(|_not_a_variant| self.fractions.map(label))
+    }
+}
+
+#[async_trait]
+impl SchedulePort for FileSchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.town.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let address = load(&self.town.path)?
+            .into_iter()
+            .find(|address| address.id() == *address_id)
+            .ok_or(PortError::InvalidAddressId)?;
+
+        let mut events = Vec::new();
+        let mut malformed = MalformedDates::default();
+        for pickup in address.pickups {
+            let Some(date) = malformed.parse(&pickup.date) else {
+                continue;
+            };
+            if !range.contains(date) {
+                continue;
+            }
+            events.push(PickupEvent {
+                date,
+                fraction: self.fraction(&pickup.fraction),
+                note: pickup.note.map(Arc::from),
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            });
+        }
+        events.sort_by_key(|event| event.date);
+
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: malformed.into_inner(),
+        })
+    }
+}
+
+/// Mapper for fraction labels: the shared German vocabulary plus bin colors.
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    CalendarImport::mapper()
+}
+
+/// What schedule files support; [`plugin_for_town`] declares it for every town.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    house_number_required: false,
+    multi_year: true,
+    fraction_filter: false,
+    bulky_waste_booking: false,
+};
+
+/// Build a plugin bundle for a town kept in a schedule file.
+#[must_use]
+pub fn plugin_for_town(_client: HttpClient, mut town: FileTown) -> CityPlugin {
+    town.meta = town.meta.with_capabilities(CAPABILITIES);
+    CityPlugin {
+        meta: town.meta.clone(),
+        address_port: Arc::new(FileAddressPort::for_town(town.clone())),
+        schedule_port: Arc::new(FileSchedulePort::for_town(town)),
+    }
+}
@@ -0,0 +1,128 @@
+//! File ports against the JSON and CSV forms of the same schedule.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use tonneli_core::{
+    AddressId, AddressPort, AddressSearch, CityMeta, DateRange, Fraction, PortError, SchedulePort,
+};
+use tonneli_provider_file::{FileAddressPort, FileSchedulePort, FileTown};
+
+use async_trait as _;
+use serde as _;
+use serde_json as _;
+use tonneli_http as _;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+fn town(file: &str) -> FileTown {
+    FileTown {
+        path: [env!("CARGO_MANIFEST_DIR"), "tests", "fixtures", file]
+            .iter()
+            .collect(),
+        meta: CityMeta::new("musterdorf", "Musterdorf"),
+    }
+}
+
+#[tokio::test]
+async fn both_forms_read_the_same_schedule() {
+    for file in ["schedule.json", "schedule.csv"] {
+        let addresses = FileAddressPort::for_town(town(file))
+            .search(&AddressSearch::parse("Hauptstr 12"), 10)
+            .await
+            .expect("the address is listed");
+        let found = addresses
+            .iter()
+            .map(|address| (address.label.to_string(), address.id.0.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [("Hauptstraße 12".to_owned(), "Hauptstraße:12".to_owned())],
+            "{file}: streets are matched like provider streets"
+        );
+
+        let range = DateRange {
+            start: date(2025, 3, 1),
+            end: date(2025, 3, 31),
+        };
+        let report = FileSchedulePort::for_town(town(file))
+            .schedule_report(&AddressId("Hauptstraße:12".to_owned()), range)
+            .await
+            .expect("the schedule is read");
+        let events = report
+            .events
+            .iter()
+            .map(|event| (event.date, event.fraction.clone(), event.note.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            vec![
+                (date(2025, 3, 14), Fraction::Paper, None),
+                (date(2025, 3, 14), Fraction::Plastic, None),
+                (date(2025, 3, 21), Fraction::Residual, Some("Bis 6 Uhr")),
+            ],
+            "{file}: labels and variant names map to fractions, in date order"
+        );
+        assert_eq!(
+            report.malformed_dates,
+            ["2025-03-32"],
+            "{file}: impossible dates are reported"
+        );
+    }
+}
+
+#[tokio::test]
+async fn addresses_without_house_number() {
+    let addresses = FileAddressPort::for_town(town("schedule.csv"))
+        .search(&AddressSearch::parse("Am Dorfteich"), 10)
+        .await
+        .expect("the street is listed");
+    let labels = addresses
+        .iter()
+        .map(|address| &*address.label)
+        .collect::<Vec<_>>();
+    assert_eq!(labels, ["Am Dorfteich"], "the street alone is the label");
+
+    let missing = FileAddressPort::for_town(town("schedule.csv"))
+        .search(&AddressSearch::parse("Hauptstraße 99"), 10)
+        .await;
+    assert!(
+        matches!(missing, Err(PortError::AddressNotFound { .. })),
+        "unknown house numbers are not found, got {missing:?}"
+    );
+}
+
+#[tokio::test]
+async fn ids_resolve_to_their_address() {
+    let port = FileAddressPort::for_town(town("schedule.json"));
+    let address = port
+        .resolve(&AddressId("Hauptstraße:12".to_owned()))
+        .await
+        .expect("the id is listed");
+    assert_eq!(
+        (&*address.label, &*address.street, &*address.house_number),
+        ("Hauptstraße 12", "Hauptstraße", "12"),
+        "the address is read back from the file"
+    );
+
+    let missing = port.resolve(&AddressId("Hauptstraße:99".to_owned())).await;
+    assert!(
+        matches!(missing, Err(PortError::AddressNotFound { .. })),
+        "ids no longer in the file are not found, got {missing:?}"
+    );
+}
+
+#[tokio::test]
+async fn unreadable_files_are_reported() {
+    let result = FileAddressPort::for_town(town("missing.json"))
+        .search(&AddressSearch::parse("Hauptstraße"), 10)
+        .await;
+    assert!(
+        matches!(result, Err(PortError::Internal(_))),
+        "a missing file is a configuration error, got {result:?}"
+    );
+}
@@ -0,0 +1,267 @@
+//! Provider for per-address calendar (ICS) subscriptions.
+//!
+//! Many municipalities publish no API, only an iCalendar link per address,
+//! often as `webcal://`. This provider takes such a link, or the path of a
+//! downloaded `.ics` file, as the address: searching for it checks that it is
+//! a calendar, and the schedule is read from it on every request.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use tonneli_core::{
+    fraction_map::FractionMapper,
+    ics_import::{CalendarImport, ImportError},
+    model::{Address, AddressId, Capabilities, CityMeta, DateRange, PickupEvent},
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport},
+};
+use tonneli_http::HttpClient;
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {chrono as _, tokio as _, tonneli_testkit as _, wiremock as _};
+
+/// Id of the calendar subscription city.
+pub const CITY_ID: &str = "ics";
+
+/// Where a subscribed calendar is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IcsSource {
+    /// HTTP(S) link; `webcal://` links are fetched over HTTPS.
+    Url(String),
+    /// Local `.ics` file.
+    File(PathBuf),
+}
+
+impl IcsSource {
+    /// Read a link or file path as typed by the user or stored in an [`AddressId`].
+    ///
+    /// Returns `None` for blank input and links with other schemes.
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input.is_empty() {
+            return None;
+        }
+        let lower = input.to_ascii_lowercase();
+        if lower.starts_with("http://") || lower.starts_with("https://") {
+            return Some(Self::Url(input.to_owned()));
+        }
+        for scheme in ["webcal://", "webcals://"] {
+            if lower.starts_with(scheme) {
+                let rest = input.get(scheme.len()..)?;
+                return Some(Self::Url(format!("https://{rest}")));
+            }
+        }
+        if lower.starts_with("file://") {
+            return input
+                .get("file://".len()..)
+                .map(|path| Self::File(path.into()));
+        }
+        if input.contains("://") {
+            return None;
+        }
+        Some(Self::File(input.into()))
+    }
+
+    /// Text form, which [`IcsSource::parse`] reads back.
+    #[must_use]
+    pub fn to_id(&self) -> AddressId {
+        match self {
+            Self::Url(url) => AddressId(url.clone()),
+            Self::File(path) => AddressId(path.display().to_string()),
+        }
+    }
+}
+
+/// Reading calendars, shared by both ports.
+struct Calendars {
+    client: HttpClient,
+    fractions: FractionMapper,
+}
+
+impl Calendars {
+    async fn read(&self, source: &IcsSource) -> Result<String, PortError> {
+        match source {
+            IcsSource::Url(url) => {
+                let body = self
+                    .client
+                    .fetch_bytes(self.client.get(url.as_str()))
+                    .await?;
+                Ok(String::from_utf8_lossy(&body).into_owned())
+            }
+            IcsSource::File(path) => fs::read(path)
+                .map(|body| String::from_utf8_lossy(&body).into_owned())
+                .map_err(|err| match err.kind() {
+                    ErrorKind::NotFound => PortError::AddressNotFound {
+                        suggestions: Vec::new(),
+                        status: None,
+                    },
+                    _ => PortError::Internal(format!("cannot read {}: {err}", path.display())),
+                }),
+        }
+    }
+
+    /// The calendar at `source`, or `None` when it has no events.
+    async fn import(&self, source: &IcsSource) -> Result<Option<CalendarImport>, PortError> {
+        let ics = self.read(source).await?;
+        match CalendarImport::parse(&ics, &self.fractions) {
+            Ok(import) => Ok(Some(import)),
+            Err(ImportError::NoEvents) => Ok(None),
+            Err(err) => Err(PortError::Decode(err.to_string())),
+        }
+    }
+}
+
+/// Address "search" for calendar subscriptions: the query is the link itself.
+pub struct IcsAddressPort {
+    calendars: Calendars,
+    meta: CityMeta,
+}
+
+impl IcsAddressPort {
+    /// Create a new address port bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            calendars: Calendars {
+                client,
+                fractions: fraction_mapper(),
+            },
+            meta: city_meta(),
+        }
+    }
+
+    /// The calendar as an address, labelled with its location or name.
+    async fn address(&self, source: &IcsSource) -> Result<Address, PortError> {
+        let import = self.calendars.import(source).await?;
+
+        let id = source.to_id();
+        let label = import
+            .and_then(|import| import.location.or(import.name))
+            .unwrap_or_else(|| id.0.clone());
+        let label = Arc::<str>::from(label);
+        Ok(Address {
+            id,
+            city: self.meta.id.clone(),
+            street: Arc::clone(&label),
+            label,
+            house_number: "".into(),
+        })
+    }
+}
+
+#[async_trait]
+impl AddressPort for IcsAddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Free-form input splits a trailing number off as house number; paths may contain spaces.
+        let input = match query.house_number.as_deref() {
+            Some(rest) => format!("{} {rest}", query.street),
+            None => query.street.clone(),
+        };
+        let source = IcsSource::parse(&input).ok_or(PortError::AddressNotFound {
+            suggestions: Vec::new(),
+            status: None,
+        })?;
+        Ok(vec![self.address(&source).await?])
+    }
+
+    async fn resolve(&self, address_id: &AddressId) -> Result<Address, PortError> {
+        let source = IcsSource::parse(&address_id.0).ok_or(PortError::InvalidAddressId)?;
+        self.address(&source).await
+    }
+}
+
+/// Pickup schedule read from a subscribed calendar.
+pub struct IcsSchedulePort {
+    calendars: Calendars,
+    meta: CityMeta,
+}
+
+impl IcsSchedulePort {
+    /// Create a new schedule port bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            calendars: Calendars {
+                client,
+                fractions: fraction_mapper(),
+            },
+            meta: city_meta(),
+        }
+    }
+}
+
+#[async_trait]
+impl SchedulePort for IcsSchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let source = IcsSource::parse(&address_id.0).ok_or(PortError::InvalidAddressId)?;
+        let Some(import) = self.calendars.import(&source).await? else {
+            return Ok(ScheduleReport::default());
+        };
+        let events = import
+            .events
+            .into_iter()
+            .filter(|event| range.contains(event.date))
+            .collect();
+
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: import.malformed,
+        })
+    }
+}
+
+/// Mapper from calendar summaries to fractions: the shared German vocabulary plus bin colors.
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    CalendarImport::mapper()
+}
+
+/// Build the plugin bundle for calendar subscriptions.
+#[must_use]
+pub fn plugin(client: HttpClient) -> CityPlugin {
+    let address_port = Arc::new(IcsAddressPort::new(client.clone()));
+    let schedule_port = Arc::new(IcsSchedulePort::new(client));
+
+    CityPlugin {
+        meta: city_meta(),
+        address_port,
+        schedule_port,
+    }
+}
+
+fn city_meta() -> CityMeta {
+    CityMeta::new(CITY_ID, "Kalender-Abo (ICS)").with_capabilities(Capabilities {
+        multi_year: true,
+        ..Capabilities::default()
+    })
+}
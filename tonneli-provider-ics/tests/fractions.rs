@@ -0,0 +1,47 @@
+//! Calendar summaries must map onto known fractions.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use tonneli_provider_ics::fraction_mapper;
+use tonneli_testkit::unexpected_other_fractions;
+
+use async_trait as _;
+use chrono as _;
+use tokio as _;
+use tonneli_core as _;
+use tonneli_http as _;
+use wiremock as _;
+
+/// Summaries municipal calendars use; they choose them freely, so this is a sample.
+const KNOWN_LABELS: &[&str] = &[
+    "Restmüll",
+    "Restabfall 2-wöchentlich",
+    "Graue Tonne",
+    "Bioabfall",
+    "Braune Tonne",
+    "Altpapier",
+    "Blaue Tonne",
+    "Gelber Sack",
+    "Gelbe Tonne",
+    "Wertstofftonne",
+    "Glas",
+    "Schadstoffmobil",
+    "Sperrmüll",
+];
+/// Labels without a matching fraction that are fine as “Other”.
+const EXPECTED_OTHER: &[&str] = &[];
+
+#[test]
+fn known_vocabulary_maps_to_fractions() {
+    let unexpected = unexpected_other_fractions(
+        &fraction_mapper(),
+        KNOWN_LABELS.iter().copied(),
+        EXPECTED_OTHER,
+    );
+    assert!(
+        unexpected.is_empty(),
+        "map these labels or list them in EXPECTED_OTHER: {unexpected:?}"
+    );
+}
@@ -0,0 +1,123 @@
+//! Calendar subscriptions served by wiremock and read from disk.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use tonneli_core::{
+    AddressId, AddressPort, AddressSearch, DateRange, Fraction, PortError, SchedulePort,
+};
+use tonneli_provider_ics::{IcsAddressPort, IcsSchedulePort, IcsSource};
+use tonneli_testkit::{client, serve};
+
+use async_trait as _;
+use tonneli_http as _;
+use wiremock as _;
+
+const CALENDAR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/calendar.ics");
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+fn march() -> DateRange {
+    DateRange {
+        start: date(2025, 3, 1),
+        end: date(2025, 3, 31),
+    }
+}
+
+#[test]
+fn sources_accept_links_and_paths() {
+    assert_eq!(
+        IcsSource::parse(" webcal://example.org/abfall.ics?id=7 "),
+        Some(IcsSource::Url(
+            "https://example.org/abfall.ics?id=7".to_owned()
+        )),
+        "webcal links are fetched over HTTPS"
+    );
+    assert_eq!(
+        IcsSource::parse("file:///home/me/abfall.ics"),
+        Some(IcsSource::File(PathBuf::from("/home/me/abfall.ics"))),
+        "file links are paths"
+    );
+    assert_eq!(
+        IcsSource::parse("ftp://example.org/abfall.ics"),
+        None,
+        "other schemes are rejected"
+    );
+}
+
+#[tokio::test]
+async fn subscription_link_is_the_address() {
+    let server = serve([("/abfall/4711.ics", include_str!("fixtures/calendar.ics"))]).await;
+    let link = format!("{}/abfall/4711.ics", server.uri());
+
+    let addresses = IcsAddressPort::new(client())
+        .search(&AddressSearch::parse(&link), 10)
+        .await
+        .expect("a calendar link is found");
+    let found = addresses
+        .iter()
+        .map(|address| (address.label.to_string(), address.id.0.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [("Am Markt 3, Musterstadt".to_owned(), link.clone())],
+        "the calendar's location labels the link"
+    );
+
+    let events = IcsSchedulePort::new(client())
+        .schedule(&AddressId(link), march())
+        .await
+        .expect("the subscription is read");
+    let events = events
+        .iter()
+        .map(|event| (event.date, event.fraction.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (date(2025, 3, 3), Fraction::Residual),
+            (date(2025, 3, 5), Fraction::Paper),
+            (date(2025, 3, 10), Fraction::Plastic),
+            (date(2025, 3, 17), Fraction::Hazardous),
+        ],
+        "summaries are mapped and the range applies"
+    );
+}
+
+#[tokio::test]
+async fn local_files_work_like_links() {
+    let report = IcsSchedulePort::new(client())
+        .schedule_report(&AddressId(CALENDAR.to_owned()), march())
+        .await
+        .expect("the file is read");
+    assert_eq!(report.events.len(), 4, "all March pickups are read");
+
+    let missing = IcsAddressPort::new(client())
+        .search(&AddressSearch::parse("/nonexistent/abfall.ics"), 10)
+        .await;
+    assert!(
+        matches!(missing, Err(PortError::AddressNotFound { .. })),
+        "missing files are not found, got {missing:?}"
+    );
+}
+
+#[tokio::test]
+async fn other_documents_are_rejected() {
+    let server = serve([("/index.html", "<html>Abfallkalender</html>")]).await;
+    let result = IcsAddressPort::new(client())
+        .search(
+            &AddressSearch::parse(&format!("{}/index.html", server.uri())),
+            10,
+        )
+        .await;
+    assert!(
+        matches!(result, Err(PortError::Decode(_))),
+        "web pages are no subscription, got {result:?}"
+    );
+}
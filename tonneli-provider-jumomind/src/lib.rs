@@ -0,0 +1,393 @@
+//! Generic provider for towns on the Jumomind platform (`MyMüll` and the white-label apps).
+//!
+//! Jumomind runs one JSON API per operator, reachable under the operator's
+//! service id (`mymuell`, `zaw`, ...). Every operator serves several cities;
+//! a city is divided into areas, and the schedule is published per area.
+//! Cities without streets are a single area.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use tonneli_core::{
+    dates::MalformedDates,
+    fraction_map::FractionMapper,
+    matching::{StreetMatcher, fold},
+    model::{Address, AddressId, Capabilities, CityMeta, DateRange, Fraction, PickupEvent},
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport},
+};
+use tonneli_http::HttpClient;
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {chrono as _, tokio as _, tonneli_testkit as _, wiremock as _};
+
+/// Service id of the `MyMüll` app, which bundles most Jumomind operators.
+pub const MYMUELL: &str = "mymuell";
+
+/// Matching streets whose house numbers are listed per search.
+const MAX_STREETS: usize = 5;
+
+/// API base URL of the operator with `service_id`.
+#[must_use]
+pub fn base_url(service_id: &str) -> String {
+    format!("https://{service_id}.jumomind.com/mmapp/api.php")
+}
+
+#[derive(Debug, Clone)]
+/// City served by a Jumomind operator; the same ports work for every one of them.
+pub struct JumomindTown {
+    /// API endpoint of the operator, see [`base_url`].
+    pub base_url: String,
+    /// City name as listed by `r=cities`, used to discover its id.
+    pub city: String,
+    /// Metadata of the resulting city.
+    pub meta: CityMeta,
+}
+
+/// Parts of a Jumomind [`AddressId`]: `city_id:area_id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JumomindAddressId<'id> {
+    /// City id of the operator.
+    pub city: &'id str,
+    /// Area id the schedule is published for.
+    pub area: &'id str,
+}
+
+impl<'id> JumomindAddressId<'id> {
+    /// Split an id produced by [`JumomindAddressPort`].
+    ///
+    /// # Errors
+    ///
+    /// [`PortError::InvalidAddressId`] when the city or area id is blank.
+    pub fn parse(id: &'id AddressId) -> Result<Self, PortError> {
+        let (city, area) = id.0.split_once(':').ok_or(PortError::InvalidAddressId)?;
+        if city.trim().is_empty() || area.trim().is_empty() || area.contains(':') {
+            return Err(PortError::InvalidAddressId);
+        }
+        Ok(Self { city, area })
+    }
+}
+
+/// City as returned by `r=cities`
+#[derive(Debug, Clone, Deserialize)]
+struct City {
+    id: String,
+    name: String,
+    area_id: String,
+    #[serde(default = "has_streets_default")]
+    has_streets: bool,
+}
+
+const fn has_streets_default() -> bool {
+    true
+}
+
+/// Street as returned by `r=streets`
+#[derive(Debug, Deserialize)]
+struct Street {
+    name: String,
+    area_id: String,
+    /// `[number, area_id]` pairs; empty when the whole street is one area.
+    #[serde(rename = "houseNumbers", default)]
+    house_numbers: Vec<(String, String)>,
+}
+
+/// Day list as returned by `r=dates/0`
+#[derive(Debug, Deserialize)]
+struct Dates {
+    #[serde(rename = "_data")]
+    data: Vec<PickupResponse>,
+}
+
+/// Pickup inside [`Dates`]
+#[derive(Debug, Deserialize)]
+struct PickupResponse {
+    cal_date: String, // "YYYY-MM-DD"
+    cal_garbage_type: String,
+}
+
+/// Waste type as returned by `r=trash`
+#[derive(Debug, Deserialize)]
+struct WasteType {
+    /// Short code the pickups refer to.
+    name: String,
+    title: String,
+}
+
+/// Address search for a city on a Jumomind operator.
+pub struct JumomindAddressPort {
+    client: HttpClient,
+    town: JumomindTown,
+    city: Mutex<Option<City>>,
+}
+
+impl JumomindAddressPort {
+    /// Create an address port for the given town.
+    #[must_use]
+    pub fn for_town(client: HttpClient, town: JumomindTown) -> Self {
+        Self {
+            client,
+            town,
+            city: Mutex::new(None),
+        }
+    }
+
+    /// Cities of the operator, from `r=cities`.
+    async fn cities(&self) -> Result<Vec<City>, PortError> {
+        Ok(self
+            .client
+            .fetch_json::<Vec<City>>(
+                self.client
+                    .get(self.town.base_url.as_str())
+                    .query(&[("r", "cities")]),
+            )
+            .await?)
+    }
+
+    /// The configured city, resolved via `r=cities` once and cached.
+    async fn resolve_city(&self) -> Result<City, PortError> {
+        if let Some(city) = self.city.lock().ok().and_then(|cached| cached.clone()) {
+            return Ok(city);
+        }
+
+        let cities = self.cities().await?;
+        let wanted = fold(&self.town.city);
+        let city = cities
+            .into_iter()
+            .find(|city| fold(&city.name) == wanted)
+            .ok_or_else(|| {
+                PortError::Internal(format!("Jumomind operator has no city {}", self.town.city))
+            })?;
+
+        if let Ok(mut cached) = self.city.lock() {
+            *cached = Some(city.clone());
+        }
+        Ok(city)
+    }
+}
+
+#[async_trait]
+impl AddressPort for JumomindAddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.town.meta
+    }
+
+    /// Lists the cities of the operator.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        Some(self.cities().await.map(drop))
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let city = self.resolve_city().await?;
+        let city_id = &self.town.meta.id;
+        if !city.has_streets {
+            // One schedule for the whole city, whatever street was asked for.
+            let name = Arc::<str>::from(city.name);
+            return Ok(vec![Address {
+                id: AddressId(format!("{}:{}", city.id, city.area_id)),
+                city: city_id.clone(),
+                label: Arc::clone(&name),
+                street: name,
+                house_number: "".into(),
+            }]);
+        }
+
+        let streets = self
+            .client
+            .fetch_json::<Vec<Street>>(
+                self.client
+                    .get(self.town.base_url.as_str())
+                    .query(&[("r", "streets"), ("city_id", city.id.as_str())]),
+            )
+            .await?;
+        let matcher = StreetMatcher::new(&query.street, query.fuzzy);
+        let streets = streets
+            .into_iter()
+            .filter(|street| matcher.matches(&street.name))
+            .take(MAX_STREETS)
+            .collect::<Vec<_>>();
+        if streets.is_empty() {
+            return Err(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            });
+        }
+
+        let house_filter = query
+            .house_number
+            .as_deref()
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty());
+        let mut addresses = Vec::new();
+        for street in streets {
+            let name = Arc::<str>::from(street.name);
+            if street.house_numbers.is_empty() {
+                addresses.push(Address {
+                    id: AddressId(format!("{}:{}", city.id, street.area_id)),
+                    city: city_id.clone(),
+                    label: Arc::clone(&name),
+                    street: name,
+                    house_number: "".into(),
+                });
+                continue;
+            }
+            addresses.extend(
+                street
+                    .house_numbers
+                    .into_iter()
+                    .filter(|(number, _)| {
+                        house_filter.is_none_or(|filter| number.eq_ignore_ascii_case(filter))
+                    })
+                    .map(|(number, area_id)| Address {
+                        id: AddressId(format!("{}:{area_id}", city.id)),
+                        city: city_id.clone(),
+                        label: format!("{name} {number}").into(),
+                        street: Arc::clone(&name),
+                        house_number: number.into(),
+                    }),
+            );
+        }
+        addresses.truncate(limit);
+        Ok(addresses)
+    }
+}
+
+/// Pickup schedule for a city on a Jumomind operator.
+pub struct JumomindSchedulePort {
+    client: HttpClient,
+    town: JumomindTown,
+    fractions: FractionMapper,
+}
+
+impl JumomindSchedulePort {
+    /// Create a schedule port for the given town.
+    #[must_use]
+    pub fn for_town(client: HttpClient, town: JumomindTown) -> Self {
+        Self {
+            client,
+            town,
+            fractions: fraction_mapper(),
+        }
+    }
+}
+
+#[async_trait]
+impl SchedulePort for JumomindSchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.town.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let JumomindAddressId { city, area } = JumomindAddressId::parse(address_id)?;
+        let base_url = self.town.base_url.as_str();
+
+        let waste_types = self
+            .client
+            .fetch_json::<Vec<WasteType>>(
+                self.client
+                    .get(base_url)
+                    .query(&[("r", "trash"), ("city_id", city)]),
+            )
+            .await?;
+        // Map and allocate each waste type once; events share them.
+        let waste_types = waste_types
+            .into_iter()
+            .map(|waste_type| {
+                let fraction = self.fractions.map(&waste_type.title);
+                (
+                    waste_type.name,
+                    (Arc::<str>::from(waste_type.title), fraction),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        let days = self
+            .client
+            .fetch_json::<Vec<Dates>>(self.client.get(base_url).query(&[
+                ("r", "dates/0"),
+                ("city_id", city),
+                ("area_id", area),
+                ("ws", "3"),
+            ]))
+            .await?;
+
+        let mut events = Vec::new();
+        let mut malformed = MalformedDates::default();
+        for pickup in days.into_iter().flat_map(|dates| dates.data) {
+            let Some(date) = malformed.parse(&pickup.cal_date) else {
+                continue;
+            };
+            if !range.contains(date) {
+                continue;
+            }
+            let (note, fraction) = match waste_types.get(&pickup.cal_garbage_type) {
+                Some((title, fraction)) => (Some(Arc::clone(title)), fraction.clone()),
+                None => (None, Fraction::Other(pickup.cal_garbage_type.into())),
+            };
+            events.push(PickupEvent {
+                date,
+                fraction,
+                note,
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            });
+        }
+        events.sort_by_key(|event| event.date);
+
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: malformed.into_inner(),
+        })
+    }
+}
+
+/// Mapper from Jumomind waste type titles to [`Fraction`].
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    FractionMapper::german()
+}
+
+/// What Jumomind supports; [`plugin_for_town`] declares it for every city.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    house_number_required: false,
+    multi_year: false,
+    fraction_filter: false,
+    bulky_waste_booking: false,
+};
+
+/// Build a plugin bundle for a city on a Jumomind operator.
+#[must_use]
+pub fn plugin_for_town(client: HttpClient, mut town: JumomindTown) -> CityPlugin {
+    town.meta = town.meta.with_capabilities(CAPABILITIES);
+    let meta = town.meta.clone();
+    let address_port = Arc::new(JumomindAddressPort::for_town(client.clone(), town.clone()));
+    let schedule_port = Arc::new(JumomindSchedulePort::for_town(client, town));
+
+    CityPlugin {
+        meta,
+        address_port,
+        schedule_port,
+    }
+}
@@ -0,0 +1,218 @@
+//! Jumomind ports against canned API responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use tonneli_core::{
+    AddressId, AddressPort, AddressSearch, CityMeta, DateRange, Fraction, PortError, SchedulePort,
+};
+use tonneli_provider_jumomind::{JumomindAddressPort, JumomindSchedulePort, JumomindTown};
+use tonneli_testkit::client;
+use wiremock::matchers::{method, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use async_trait as _;
+use serde as _;
+use tonneli_http as _;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+fn town(server: &MockServer, city: &str) -> JumomindTown {
+    JumomindTown {
+        base_url: format!("{}/mmapp/api.php", server.uri()),
+        city: city.to_owned(),
+        meta: CityMeta::new(city.to_lowercase(), city),
+    }
+}
+
+fn json(body: &'static str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "application/json")
+}
+
+/// Mock server answering the city and street lists.
+async fn serve_streets() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("r", "cities"))
+        .respond_with(json(include_str!("fixtures/cities.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("r", "streets"))
+        .and(query_param("city_id", "68"))
+        .respond_with(json(include_str!("fixtures/streets.json")))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn health_check_lists_the_cities() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("r", "cities"))
+        .respond_with(json(include_str!("fixtures/cities.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let probe = JumomindAddressPort::for_town(client(), town(&server, "Musterstadt"))
+        .health_check()
+        .await;
+    assert!(
+        matches!(probe, Some(Ok(()))),
+        "the city list answers, got {probe:?}"
+    );
+
+    let down = MockServer::start().await;
+    let failed = JumomindAddressPort::for_town(client(), town(&down, "Musterstadt"))
+        .health_check()
+        .await;
+    assert!(
+        matches!(failed, Some(Err(_))),
+        "a failing backend is reported, got {failed:?}"
+    );
+}
+
+/// Labels and ids of the addresses found for `street` and `house_number`.
+async fn found(
+    port: &JumomindAddressPort,
+    street: &str,
+    house_number: Option<&str>,
+) -> Vec<(String, String)> {
+    port.search(&AddressSearch::new(street, house_number), 10)
+        .await
+        .expect("search against the mock must succeed")
+        .into_iter()
+        .map(|address| (address.label.to_string(), address.id.0))
+        .collect()
+}
+
+fn pair(label: &str, id: &str) -> (String, String) {
+    (label.to_owned(), id.to_owned())
+}
+
+#[tokio::test]
+async fn search_resolves_city_and_areas() {
+    let server = serve_streets().await;
+    let port = JumomindAddressPort::for_town(client(), town(&server, "musterstadt"));
+
+    assert_eq!(
+        found(&port, "Am Markt", Some("3A")).await,
+        [pair("Am Markt 3a", "68:512")],
+        "house numbers carry their own area"
+    );
+    assert_eq!(
+        found(&port, "Königsweg", None).await,
+        [pair("Königsweg", "68:520")],
+        "streets without house numbers use the street's area"
+    );
+    assert_eq!(
+        found(&port, "Lindenallee", Some("4")).await,
+        [pair("Lindenallee", "68:530")],
+        "a missing house number list is treated the same"
+    );
+    let missing = port
+        .search(&AddressSearch::new("Nirgendwo", Some("1")), 10)
+        .await;
+    assert!(
+        matches!(missing, Err(PortError::AddressNotFound { .. })),
+        "unknown streets are not found, got {missing:?}"
+    );
+}
+
+#[tokio::test]
+async fn cities_without_streets_are_one_address() {
+    let server = serve_streets().await;
+    let port = JumomindAddressPort::for_town(client(), town(&server, "Kleindorf"));
+
+    assert_eq!(
+        found(&port, "Dorfstraße", Some("1")).await,
+        [pair("Kleindorf", "71:7101")],
+        "the whole city shares one schedule"
+    );
+}
+
+#[tokio::test]
+async fn unknown_city_is_an_internal_error() {
+    let server = serve_streets().await;
+    let port = JumomindAddressPort::for_town(client(), town(&server, "Atlantis"));
+
+    let result = port
+        .search(&AddressSearch::new("Am Markt", None::<String>), 10)
+        .await;
+    assert!(
+        matches!(result, Err(PortError::Internal(_))),
+        "a misconfigured city is reported, got {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn schedule_maps_waste_types() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("r", "trash"))
+        .and(query_param("city_id", "68"))
+        .respond_with(json(include_str!("fixtures/trash.json")))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("r", "dates/0"))
+        .and(query_param("city_id", "68"))
+        .and(query_param("area_id", "512"))
+        .respond_with(json(include_str!("fixtures/dates.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = JumomindSchedulePort::for_town(client(), town(&server, "Musterstadt"));
+    let range = DateRange {
+        start: date(2025, 3, 1),
+        end: date(2025, 3, 31),
+    };
+    let report = port
+        .schedule_report(&AddressId("68:512".to_owned()), range)
+        .await
+        .expect("schedule against the mock must succeed");
+
+    let events = report
+        .events
+        .iter()
+        .map(|event| (event.date, event.fraction.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (date(2025, 3, 3), Fraction::Residual),
+            (date(2025, 3, 5), Fraction::Paper),
+            (date(2025, 3, 10), Fraction::Plastic),
+            (date(2025, 3, 12), Fraction::Other("XY".into())),
+            (date(2025, 3, 17), Fraction::Hazardous),
+        ],
+        "events in range are mapped by title and sorted by date"
+    );
+    assert_eq!(
+        report
+            .events
+            .first()
+            .and_then(|event| event.note.as_deref()),
+        Some("Restmüll"),
+        "the waste type title becomes the note"
+    );
+    assert_eq!(
+        report.malformed_dates,
+        ["2025-02-31"],
+        "impossible dates are reported"
+    );
+
+    let invalid = port.schedule(&AddressId("68".to_owned()), range).await;
+    assert!(
+        matches!(invalid, Err(PortError::InvalidAddressId)),
+        "ids without area are rejected, got {invalid:?}"
+    );
+}
@@ -0,0 +1,330 @@
+//! Provider implementation for the City of Luxembourg (Ville de Luxembourg).
+//!
+//! The city publishes its collection calendar per street: streets are listed
+//! with an id, and the collections of a street are served per calendar year.
+//! Fraction names come in French and German; notes use the language chosen
+//! with [`Language`], the fraction is mapped from the German name.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Datelike;
+use futures::future::try_join_all;
+use serde::Deserialize;
+
+use tonneli_core::{
+    dates::MalformedDates,
+    fraction_map::FractionMapper,
+    matching::StreetMatcher,
+    model::{
+        Address, AddressId, Capabilities, CityArea, CityMeta, Coordinates, DateRange, Fraction,
+        PickupEvent,
+    },
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport},
+};
+use tonneli_http::HttpClient;
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {tokio as _, tonneli_testkit as _, wiremock as _};
+
+/// Base URL of the public calendar API.
+pub const BASE_URL: &str = "https://www.vdl.lu/api/waste-collection";
+
+/// Id of the Luxembourg city.
+pub const CITY_ID: &str = "luxembourg";
+
+/// French names the German vocabulary does not cover.
+const FRENCH_TYPES: &[(&str, Fraction)] = &[
+    ("valorlux", Fraction::Plastic),
+    ("pmc", Fraction::Plastic),
+    ("residuel", Fraction::Residual),
+    ("menager", Fraction::Residual),
+    ("organique", Fraction::Organic),
+    ("verre", Fraction::Glass),
+    ("carton", Fraction::Paper),
+    ("encombrant", Fraction::BulkyWaste),
+    ("sapin", Fraction::ChristmasTree),
+];
+
+/// Language of the fraction names shown as notes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    /// French, the language the calendar is published in first.
+    #[default]
+    French,
+    /// German.
+    German,
+}
+
+impl Language {
+    /// Language for an ISO 639-1 code such as `fr` or `de`, ignoring case.
+    #[must_use]
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.to_ascii_lowercase().as_str() {
+            "fr" => Some(Self::French),
+            "de" => Some(Self::German),
+            _ => None,
+        }
+    }
+}
+
+/// Street as returned by `/streets`
+#[derive(Debug, Deserialize)]
+struct Street {
+    id: u32,
+    name: String,
+}
+
+/// Collection as returned by `/streets/{id}/collections`
+#[derive(Debug, Deserialize)]
+struct Collection {
+    date: String, // "YYYY-MM-DD"
+    fraction: FractionNames,
+}
+
+/// Names of the fraction inside [`Collection`]
+#[derive(Debug, Default, Deserialize)]
+struct FractionNames {
+    #[serde(default)]
+    fr: String,
+    #[serde(default)]
+    de: String,
+}
+
+impl FractionNames {
+    /// Name in `language`, or in the other one when it is missing.
+    fn in_language(&self, language: Language) -> &str {
+        let (preferred, other) = match language {
+            Language::French => (&self.fr, &self.de),
+            Language::German => (&self.de, &self.fr),
+        };
+        if preferred.trim().is_empty() {
+            other
+        } else {
+            preferred
+        }
+    }
+}
+
+/// Address search implementation for Luxembourg.
+pub struct LuxembourgAddressPort {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+}
+
+impl LuxembourgAddressPort {
+    /// Create a new address port bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_owned(),
+            meta: city_meta(),
+        }
+    }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    async fn streets(&self) -> Result<Vec<Street>, PortError> {
+        let base_url = &self.base_url;
+        Ok(self
+            .client
+            .fetch_json::<Vec<Street>>(self.client.get(format!("{base_url}/streets")))
+            .await?)
+    }
+}
+
+#[async_trait]
+impl AddressPort for LuxembourgAddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    /// Lists the streets.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        Some(self.streets().await.map(drop))
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let streets = self.streets().await?;
+
+        // The calendar is the same for every house of a street.
+        let matcher = StreetMatcher::new(&query.street, query.fuzzy);
+        let addresses = streets
+            .into_iter()
+            .filter(|street| matcher.matches(&street.name))
+            .take(limit)
+            .map(|street| {
+                let name = Arc::<str>::from(street.name);
+                Address {
+                    id: AddressId(street.id.to_string()),
+                    city: self.meta.id.clone(),
+                    label: Arc::clone(&name),
+                    street: name,
+                    house_number: "".into(),
+                }
+            })
+            .collect::<Vec<_>>();
+        if addresses.is_empty() {
+            return Err(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            });
+        }
+        Ok(addresses)
+    }
+}
+
+/// Pickup schedule implementation for Luxembourg.
+pub struct LuxembourgSchedulePort {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+    language: Language,
+    fractions: FractionMapper,
+}
+
+impl LuxembourgSchedulePort {
+    /// Create a new schedule port bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_owned(),
+            meta: city_meta(),
+            language: Language::default(),
+            fractions: fraction_mapper(),
+        }
+    }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Write notes in `language` instead of French.
+    #[must_use]
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    async fn collections(&self, street: u32, year: i32) -> Result<Vec<Collection>, PortError> {
+        let base_url = &self.base_url;
+        self.client
+            .fetch_json::<Vec<Collection>>(
+                self.client
+                    .get(format!("{base_url}/streets/{street}/collections"))
+                    .query(&[("year", year)]),
+            )
+            .await
+            .map_err(PortError::from_address_lookup)
+    }
+}
+
+#[async_trait]
+impl SchedulePort for LuxembourgSchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let street = address_id
+            .0
+            .parse::<u32>()
+            .map_err(|_not_a_number| PortError::InvalidAddressId)?;
+        let years = try_join_all(
+            (range.start.year()..=range.end.year()).map(|year| self.collections(street, year)),
+        )
+        .await?;
+
+        let mut events = Vec::new();
+        let mut malformed = MalformedDates::default();
+        for collection in years.into_iter().flatten() {
+            let Some(date) = malformed.parse(&collection.date) else {
+                continue;
+            };
+            if !range.contains(date) {
+                continue;
+            }
+            let names = &collection.fraction;
+            events.push(PickupEvent {
+                date,
+                fraction: self.fractions.map(names.in_language(Language::German)),
+                note: Some(names.in_language(self.language).into()),
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            });
+        }
+        events.sort_by_key(|event| event.date);
+
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: malformed.into_inner(),
+        })
+    }
+}
+
+/// Mapper from Luxembourg fraction names to [`Fraction`]; German names, with French as fallback.
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    FractionMapper::german().with_rules(FRENCH_TYPES)
+}
+
+/// Build the plugin bundle for the Luxembourg provider, with French notes.
+#[must_use]
+pub fn plugin(client: HttpClient) -> CityPlugin {
+    plugin_with_language(client, Language::default())
+}
+
+/// Build the plugin bundle for the Luxembourg provider, with notes in `language`.
+#[must_use]
+pub fn plugin_with_language(client: HttpClient, language: Language) -> CityPlugin {
+    let address_port = Arc::new(LuxembourgAddressPort::new(client.clone()));
+    let schedule_port = Arc::new(LuxembourgSchedulePort::new(client).with_language(language));
+
+    CityPlugin {
+        meta: city_meta(),
+        address_port,
+        schedule_port,
+    }
+}
+
+fn city_meta() -> CityMeta {
+    CityMeta::new(CITY_ID, "Luxembourg")
+        .with_locale("LU", "Europe/Luxembourg")
+        .with_area(CityArea::new(Coordinates::new(49.6116, 6.1319), 6.0))
+        .with_source("Ville de Luxembourg", "https://www.vdl.lu")
+        .with_capabilities(Capabilities {
+            multi_year: true,
+            ..Capabilities::default()
+        })
+}
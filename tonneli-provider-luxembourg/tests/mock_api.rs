@@ -0,0 +1,182 @@
+//! Luxembourg ports against canned API responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use tonneli_core::{
+    AddressId, AddressPort, AddressSearch, DateRange, Fraction, PortError, SchedulePort,
+};
+use tonneli_provider_luxembourg::{Language, LuxembourgAddressPort, LuxembourgSchedulePort};
+use tonneli_testkit::{client, json};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+use async_trait as _;
+use futures as _;
+use serde as _;
+use tonneli_http as _;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+/// Mock server answering the calendars of street 412 around the turn of the year.
+async fn serve_collections() -> MockServer {
+    let server = MockServer::start().await;
+    for (year, body) in [
+        ("2025", include_str!("fixtures/collections_2025.json")),
+        ("2026", include_str!("fixtures/collections_2026.json")),
+    ] {
+        Mock::given(method("GET"))
+            .and(path("/streets/412/collections"))
+            .and(query_param("year", year))
+            .respond_with(json(body))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+    server
+}
+
+fn winter() -> DateRange {
+    DateRange {
+        start: date(2025, 12, 15),
+        end: date(2026, 1, 31),
+    }
+}
+
+#[tokio::test]
+async fn health_check_lists_the_streets() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/streets"))
+        .respond_with(json(include_str!("fixtures/streets.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = LuxembourgAddressPort::new(client()).with_base_url(server.uri());
+    let probe = port.health_check().await;
+    assert!(
+        matches!(probe, Some(Ok(()))),
+        "the street list answers, got {probe:?}"
+    );
+
+    let down = LuxembourgAddressPort::new(client()).with_base_url(format!("{}/down", server.uri()));
+    let failed = down.health_check().await;
+    assert!(
+        matches!(failed, Some(Err(_))),
+        "a failing backend is reported, got {failed:?}"
+    );
+}
+
+#[tokio::test]
+async fn search_matches_streets() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/streets"))
+        .respond_with(json(include_str!("fixtures/streets.json")))
+        .mount(&server)
+        .await;
+    let port = LuxembourgAddressPort::new(client()).with_base_url(server.uri());
+
+    let found = port
+        .search(&AddressSearch::parse("Avenue de la"), 10)
+        .await
+        .expect("search against the mock must succeed")
+        .into_iter()
+        .map(|address| (address.label.to_string(), address.id.0))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            ("Avenue de la Liberté".to_owned(), "412".to_owned()),
+            ("Avenue de la Gare".to_owned(), "413".to_owned()),
+        ],
+        "streets are the addresses"
+    );
+    let missing = port.search(&AddressSearch::parse("Grand-Rue"), 10).await;
+    assert!(
+        matches!(missing, Err(PortError::AddressNotFound { .. })),
+        "unknown streets are not found, got {missing:?}"
+    );
+}
+
+#[tokio::test]
+async fn schedule_spans_years_with_french_notes() {
+    let server = serve_collections().await;
+    let port = LuxembourgSchedulePort::new(client()).with_base_url(server.uri());
+
+    let report = port
+        .schedule_report(&AddressId("412".to_owned()), winter())
+        .await
+        .expect("schedule against the mock must succeed");
+    let events = report
+        .events
+        .iter()
+        .map(|event| (event.date, event.fraction.clone(), event.note.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (
+                date(2025, 12, 22),
+                Fraction::Residual,
+                Some("Déchets résiduels")
+            ),
+            (
+                date(2025, 12, 29),
+                Fraction::Plastic,
+                Some("Valorlux (PMC)")
+            ),
+            (
+                date(2025, 12, 30),
+                Fraction::Paper,
+                Some("Papier et carton")
+            ),
+            (date(2026, 1, 5), Fraction::Organic, Some("Biodéchets")),
+            (date(2026, 1, 7), Fraction::BulkyWaste, Some("Encombrants")),
+        ],
+        "both years are read, mapped and cut to the range"
+    );
+    assert_eq!(
+        report.malformed_dates,
+        ["2025-12-32"],
+        "impossible dates are reported"
+    );
+}
+
+#[tokio::test]
+async fn notes_follow_the_language() {
+    let server = serve_collections().await;
+    let port = LuxembourgSchedulePort::new(client())
+        .with_base_url(server.uri())
+        .with_language(Language::German);
+
+    let notes = port
+        .schedule(&AddressId("412".to_owned()), winter())
+        .await
+        .expect("schedule against the mock must succeed")
+        .into_iter()
+        .filter_map(|event| event.note.map(|note| note.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        notes,
+        [
+            "Restmüll",
+            "Valorlux (PMC)",
+            "Papier und Karton",
+            "Bioabfall",
+            "Encombrants"
+        ],
+        "German names, French where there is no German one"
+    );
+
+    let invalid = port.schedule(&AddressId("Rue".to_owned()), winter()).await;
+    assert!(
+        matches!(invalid, Err(PortError::InvalidAddressId)),
+        "street ids are numbers, got {invalid:?}"
+    );
+}
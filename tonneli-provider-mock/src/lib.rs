@@ -0,0 +1,338 @@
+//! Deterministic in-memory provider for testing apps built on Tonneli.
+//!
+//! [`MockProvider`] serves the addresses and pickups it is given and fails on the
+//! calls its [`FailureMode`] picks, without network access or randomness:
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use tonneli_core::model::Fraction;
+//! use tonneli_provider_mock::{Failure, FailureMode, MOCK_CITY_ID, MockProvider};
+//!
+//! let day = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap_or_default();
+//! let plugin = MockProvider::new(MOCK_CITY_ID, "Mock City")
+//!     .address("Musterstraße", "1", [(day, Fraction::Paper)])
+//!     .failing(FailureMode::EveryNth {
+//!         every: 3,
+//!         failure: Failure::Timeout,
+//!     })
+//!     .plugin();
+//! assert_eq!(plugin.meta.id.0, MOCK_CITY_ID);
+//! ```
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use tonneli_core::{
+    matching::StreetMatcher,
+    model::{Address, AddressId, Capabilities, CityMeta, DateRange, Fraction, PickupEvent},
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort},
+};
+
+/// City id for mock providers that need none in particular.
+pub const MOCK_CITY_ID: &str = "mock";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Error a failing call returns.
+pub enum Failure {
+    /// [`PortError::Timeout`].
+    Timeout,
+    /// [`PortError::RateLimited`], asking to retry after a second.
+    RateLimited,
+    /// [`PortError::HttpStatus`] with the given status.
+    HttpStatus(u16),
+    /// [`PortError::ProviderUnavailable`], as during maintenance.
+    Unavailable,
+    /// [`PortError::Decode`], as for a response that does not parse.
+    Malformed,
+}
+
+impl Failure {
+    fn error(self) -> PortError {
+        match self {
+            Self::Timeout => PortError::Timeout,
+            Self::RateLimited => PortError::RateLimited {
+                retry_after: Some(Duration::from_secs(1)),
+                status: None,
+            },
+            Self::HttpStatus(status) => PortError::HttpStatus(status),
+            Self::Unavailable => PortError::ProviderUnavailable("maintenance (mock)".to_owned()),
+            Self::Malformed => {
+                PortError::Decode("expected value at line 1 column 1 (mock)".to_owned())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Which calls fail; calls to both ports are counted together, starting at one.
+pub enum FailureMode {
+    /// Every call succeeds.
+    #[default]
+    Never,
+    /// Every call fails.
+    Always(Failure),
+    /// The first `calls` calls fail, later ones succeed, as for a provider recovering.
+    First {
+        /// Number of failing calls.
+        calls: u32,
+        /// Error they fail with.
+        failure: Failure,
+    },
+    /// Every `every`-th call fails; `0` never fails.
+    EveryNth {
+        /// Distance between failing calls.
+        every: u32,
+        /// Error they fail with.
+        failure: Failure,
+    },
+}
+
+impl FailureMode {
+    fn strike(self, call: u32) -> Result<(), PortError> {
+        let failure = match self {
+            Self::Never => None,
+            Self::Always(failure) => Some(failure),
+            Self::First { calls, failure } => (call <= calls).then_some(failure),
+            Self::EveryNth { every, failure } => call
+                .checked_rem(every)
+                .is_some_and(|rest| rest == 0)
+                .then_some(failure),
+        };
+        failure.map_or(Ok(()), |failure| Err(failure.error()))
+    }
+}
+
+/// Builder for a provider serving a fixed set of addresses and pickups.
+#[derive(Debug, Clone)]
+pub struct MockProvider {
+    meta: CityMeta,
+    addresses: Vec<(Address, Vec<PickupEvent>)>,
+    failures: FailureMode,
+}
+
+impl MockProvider {
+    /// Provider for the city `id` without addresses; it serves pickups of any year.
+    #[must_use]
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self::with_meta(CityMeta::new(id, name).with_capabilities(Capabilities {
+            multi_year: true,
+            ..Capabilities::default()
+        }))
+    }
+
+    /// Provider for a city with the given details, e.g. a region or time zone.
+    #[must_use]
+    pub const fn with_meta(meta: CityMeta) -> Self {
+        Self {
+            meta,
+            addresses: Vec::new(),
+            failures: FailureMode::Never,
+        }
+    }
+
+    /// Add an address with its pickups; its id is its position, starting at `1`.
+    #[must_use]
+    pub fn address(
+        mut self,
+        street: &str,
+        house_number: &str,
+        pickups: impl IntoIterator<Item = (NaiveDate, Fraction)>,
+    ) -> Self {
+        let address = Address {
+            id: AddressId((self.addresses.len() + 1).to_string()),
+            city: self.meta.id.clone(),
+            label: format!("{street} {house_number}").into(),
+            street: street.into(),
+            house_number: house_number.into(),
+        };
+        let events = pickups
+            .into_iter()
+            .map(|(date, fraction)| PickupEvent {
+                date,
+                fraction,
+                note: None,
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            })
+            .collect();
+        self.addresses.push((address, events));
+        self
+    }
+
+    /// Fail the calls picked by `mode`.
+    #[must_use]
+    pub const fn failing(mut self, mode: FailureMode) -> Self {
+        self.failures = mode;
+        self
+    }
+
+    /// The port, to keep a handle for [`MockPort::calls`].
+    #[must_use]
+    pub fn build(self) -> Arc<MockPort> {
+        Arc::new(MockPort {
+            provider: self,
+            calls: AtomicU32::new(0),
+        })
+    }
+
+    /// Plugin bundle to register with a service.
+    #[must_use]
+    pub fn plugin(self) -> CityPlugin {
+        MockPort::plugin(&self.build())
+    }
+}
+
+/// Both ports of a [`MockProvider`].
+#[derive(Debug)]
+pub struct MockPort {
+    provider: MockProvider,
+    calls: AtomicU32,
+}
+
+impl MockPort {
+    /// Plugin bundle serving from this port.
+    #[must_use]
+    pub fn plugin(port: &Arc<Self>) -> CityPlugin {
+        CityPlugin {
+            meta: port.provider.meta.clone(),
+            address_port: Arc::<Self>::clone(port),
+            schedule_port: Arc::<Self>::clone(port),
+        }
+    }
+
+    /// Calls made to either port so far.
+    #[must_use]
+    pub fn calls(&self) -> u32 {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// Count the call and fail it if the failure mode says so.
+    fn call(&self) -> Result<(), PortError> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst).saturating_add(1);
+        self.provider.failures.strike(call)
+    }
+
+    fn entry(&self, address_id: &AddressId) -> Result<&(Address, Vec<PickupEvent>), PortError> {
+        self.provider
+            .addresses
+            .iter()
+            .find(|(address, _)| address.id == *address_id)
+            .ok_or(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            })
+    }
+}
+
+#[async_trait]
+impl AddressPort for MockPort {
+    fn city(&self) -> &CityMeta {
+        &self.provider.meta
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        self.call()?;
+        let matcher = StreetMatcher::new(&query.street, query.fuzzy);
+        let house_number = query
+            .house_number
+            .as_deref()
+            .map(str::trim)
+            .filter(|number| !number.is_empty());
+        Ok(self
+            .provider
+            .addresses
+            .iter()
+            .map(|(address, _)| address)
+            .filter(|address| !query.is_empty() && matcher.matches(&address.street))
+            .filter(|address| house_number.is_none_or(|number| *address.house_number == *number))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn resolve(&self, address_id: &AddressId) -> Result<Address, PortError> {
+        self.call()?;
+        self.entry(address_id).map(|(address, _)| address.clone())
+    }
+}
+
+#[async_trait]
+impl SchedulePort for MockPort {
+    fn city(&self) -> &CityMeta {
+        &self.provider.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        self.call()?;
+        let (_, events) = self.entry(address_id)?;
+        Ok(events
+            .iter()
+            .filter(|event| range.contains(event.date))
+            .cloned()
+            .collect())
+    }
+
+    fn supports_partial_fetch(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap_or_default()
+    }
+
+    #[test]
+    fn serves_configured_addresses_and_fails_on_schedule() {
+        let port = MockProvider::new(MOCK_CITY_ID, "Mock City")
+            .address(
+                "Musterstraße",
+                "1",
+                [(day(3), Fraction::Paper), (day(20), Fraction::Glass)],
+            )
+            .address("Beispielweg", "2", [])
+            .failing(FailureMode::EveryNth {
+                every: 3,
+                failure: Failure::Timeout,
+            })
+            .build();
+        let range = DateRange::next_days(day(1), 10);
+
+        block_on(async {
+            let found = port
+                .search(&AddressSearch::parse("Musterstr 1"), 5)
+                .await
+                .expect("first call succeeds");
+            assert_eq!(found.len(), 1, "only the matching street: {found:?}");
+            let address = found.first().expect("one address found");
+            let events = SchedulePort::schedule(&*port, &address.id, range)
+                .await
+                .expect("second call succeeds");
+            assert_eq!(events.len(), 1, "pickups outside the range are left out");
+            assert!(
+                matches!(
+                    SchedulePort::schedule(&*port, &address.id, range).await,
+                    Err(PortError::Timeout)
+                ),
+                "every third call times out"
+            );
+        });
+        assert_eq!(port.calls(), 3, "calls to both ports are counted");
+    }
+}
@@ -0,0 +1,70 @@
+//! Snapshots of the addresses and events produced from captured `RegioIT` responses.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use insta::assert_json_snapshot;
+use tonneli_core::{AddressId, AddressPort, AddressSearch, DateRange, SchedulePort};
+use tonneli_provider_regioit::{RegioItAddressPort, RegioItSchedulePort, RegioItTown};
+use tonneli_testkit::{client, serve};
+use wiremock::MockServer;
+
+use tonneli_http as _;
+
+async fn upstream() -> MockServer {
+    let routes = [
+        ("/orte", include_str!("fixtures/upstream/orte.json")),
+        (
+            "/orte/2896436/strassen",
+            include_str!("fixtures/upstream/strassen.json"),
+        ),
+        (
+            "/strassen/2896812",
+            include_str!("fixtures/upstream/strasse_2896812.json"),
+        ),
+        (
+            "/hausnummern/2896821/fraktionen",
+            include_str!("fixtures/upstream/fraktionen.json"),
+        ),
+        (
+            "/hausnummern/2896821/termine",
+            include_str!("fixtures/upstream/termine.json"),
+        ),
+    ];
+    serve(routes).await
+}
+
+fn town(server: &MockServer) -> RegioItTown {
+    RegioItTown {
+        base_url: server.uri(),
+        ..tonneli_provider_nuremberg::town()
+    }
+}
+
+#[tokio::test]
+async fn search_snapshot() {
+    let server = upstream().await;
+    let port = RegioItAddressPort::for_town(client(), town(&server));
+    let addresses = port
+        .search(&AddressSearch::new("Bucher Straße", None::<String>), 10)
+        .await
+        .expect("search against captured responses must succeed");
+    assert_json_snapshot!(addresses);
+}
+
+#[tokio::test]
+async fn schedule_snapshot() {
+    let server = upstream().await;
+    let port = RegioItSchedulePort::for_town(client(), town(&server));
+    let range = DateRange {
+        start: NaiveDate::from_ymd_opt(2025, 2, 1).expect("valid date"),
+        end: NaiveDate::from_ymd_opt(2025, 2, 28).expect("valid date"),
+    };
+    let events = port
+        .schedule(&AddressId("2896821".to_owned()), range)
+        .await
+        .expect("schedule against captured responses must succeed");
+    assert_json_snapshot!(events);
+}
@@ -0,0 +1,138 @@
+//! Contract test against the live Nuremberg `RegioIT` API, enabled with `TONNELI_LIVE_TESTS=1`.
+//!
+//! Walks a known street down to its schedule, records every response into
+//! `tests/fixtures/live` and checks that the ports still parse what was recorded.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::{Datelike, Duration, Utc};
+use tonneli_core::{AddressId, AddressPort, AddressSearch, DateRange, SchedulePort};
+use tonneli_provider_regioit::{RegioItAddressPort, RegioItSchedulePort, RegioItTown};
+use tonneli_testkit::{Recording, client, live_tests_enabled};
+
+use insta as _;
+use tonneli_http as _;
+use wiremock as _;
+
+const KNOWN_STREET: &str = "Hauptmarkt";
+
+#[tokio::test]
+async fn live_api_still_parses() {
+    if !live_tests_enabled() {
+        return;
+    }
+    let live = tonneli_provider_nuremberg::town();
+    let mut recording = Recording::new(
+        live.base_url.clone(),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/live"),
+    );
+
+    let towns = recording
+        .fetch("orte", "/orte", &[])
+        .await
+        .expect("town list must be recorded");
+    let ort_id = towns
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|town| town.get("name").and_then(|name| name.as_str()) == Some(&*live.town))
+        .and_then(|town| town.get("id"))
+        .expect("Nuremberg must be listed")
+        .to_string();
+    let year = Utc::now().year().to_string();
+    let streets = recording
+        .fetch(
+            "strassen",
+            &format!("/orte/{ort_id}/strassen"),
+            &[("jahr", &year)],
+        )
+        .await
+        .expect("street list must be recorded");
+    let street_id = streets
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|street| street.get("name").and_then(|name| name.as_str()) == Some(KNOWN_STREET))
+        .and_then(|street| street.get("id"))
+        .expect("known street must be listed")
+        .to_string();
+    let house_number_id = record_house_number(&mut recording, &street_id).await;
+
+    let server = recording.replay().await;
+    let replayed = RegioItTown {
+        base_url: server.uri(),
+        ..live
+    };
+    let addresses = RegioItAddressPort::for_town(client(), replayed.clone())
+        .search(&AddressSearch::parse(KNOWN_STREET), 10)
+        .await
+        .expect("recorded streets must parse");
+    assert!(
+        !addresses.is_empty(),
+        "the known street must yield addresses"
+    );
+
+    let today = Utc::now().date_naive();
+    let report = RegioItSchedulePort::for_town(client(), replayed)
+        .schedule_report(
+            &AddressId(house_number_id),
+            DateRange {
+                start: today,
+                end: today + Duration::days(365),
+            },
+        )
+        .await
+        .expect("recorded pickups must parse");
+    assert!(
+        !report.events.is_empty(),
+        "the coming year must have pickups"
+    );
+    assert!(
+        report.malformed_dates.is_empty(),
+        "all recorded dates must parse: {:?}",
+        report.malformed_dates
+    );
+}
+
+/// Record the first house number of a street with its fractions and pickups.
+async fn record_house_number(recording: &mut Recording, street_id: &str) -> String {
+    let street = recording
+        .fetch("strasse", &format!("/strassen/{street_id}"), &[])
+        .await
+        .expect("street must be recorded");
+    let house_number_id = street
+        .get("hausNrList")
+        .and_then(|numbers| numbers.get(0))
+        .and_then(|number| number.get("id"))
+        .expect("known street must have house numbers")
+        .to_string();
+    let fractions = recording
+        .fetch(
+            "fraktionen",
+            &format!("/hausnummern/{house_number_id}/fraktionen"),
+            &[],
+        )
+        .await
+        .expect("fractions must be recorded");
+    let fraction_ids = fractions
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|fraction| fraction.get("id").map(ToString::to_string))
+        .collect::<Vec<_>>();
+    let query = fraction_ids
+        .iter()
+        .map(|id| ("fraktion", id.as_str()))
+        .collect::<Vec<_>>();
+    recording
+        .fetch(
+            "termine",
+            &format!("/hausnummern/{house_number_id}/termine"),
+            &query,
+        )
+        .await
+        .expect("pickups must be recorded");
+    house_number_id
+}
@@ -0,0 +1,124 @@
+//! Nuremberg ports against canned `RegioIT` responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+use tonneli_core::{
+    AddressId, AddressPort, AddressSearch, DateRange, FixedClock, Fraction, SchedulePort,
+};
+use tonneli_provider_regioit::{RegioItAddressPort, RegioItSchedulePort, RegioItTown};
+use tonneli_testkit::{client, json};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+use insta as _;
+use tonneli_http as _;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+fn town(server: &MockServer) -> RegioItTown {
+    RegioItTown {
+        base_url: server.uri(),
+        ..tonneli_provider_nuremberg::town()
+    }
+}
+
+/// Clock in 2025, the year the street lists are requested for.
+fn clock() -> Arc<FixedClock> {
+    let now = NaiveDate::from_ymd_opt(2025, 6, 1)
+        .and_then(|day| day.and_hms_opt(12, 0, 0))
+        .expect("valid test time");
+    Arc::new(FixedClock::new(now))
+}
+
+#[tokio::test]
+async fn search_resolves_ort_id_once_and_prefers_exact_streets() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orte"))
+        .respond_with(json(include_str!("fixtures/orte.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/orte/42/strassen"))
+        .and(query_param("jahr", "2025"))
+        .respond_with(json(include_str!("fixtures/strassen.json")))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/strassen/3"))
+        .respond_with(json(include_str!("fixtures/strasse_3.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = RegioItAddressPort::for_town(client(), town(&server)).with_clock(clock());
+    let query = AddressSearch::new("Hauptmarkt", Some("18".to_owned()));
+    for _ in 0..2 {
+        let addresses = port
+            .search(&query, 10)
+            .await
+            .expect("search against the mock must succeed");
+        let labels = addresses
+            .iter()
+            .map(|address| &*address.label)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            ["Hauptmarkt 18"],
+            "only the exact street is fetched"
+        );
+    }
+}
+
+#[tokio::test]
+async fn schedule_maps_fractions_and_epoch_dates() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/hausnummern/318/fraktionen"))
+        .respond_with(json(include_str!("fixtures/fraktionen.json")))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/hausnummern/318/termine"))
+        .and(query_param("fraktion", "10"))
+        .and(query_param("fraktion", "11"))
+        .respond_with(json(include_str!("fixtures/termine.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = RegioItSchedulePort::for_town(client(), town(&server));
+    let range = DateRange {
+        start: date(2025, 3, 1),
+        end: date(2025, 3, 31),
+    };
+    let report = port
+        .schedule_report(&AddressId("318".to_owned()), range)
+        .await
+        .expect("schedule against the mock must succeed");
+
+    let events = report
+        .events
+        .iter()
+        .map(|event| (event.date, event.fraction.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (date(2025, 3, 10), Fraction::Plastic),
+            (date(2025, 3, 20), Fraction::Paper),
+            (date(2025, 3, 24), Fraction::Other("Fraction 99".into())),
+        ],
+        "epoch millis map to the local day and unknown ids keep their number"
+    );
+    assert!(report.malformed_dates.is_empty(), "all dates are valid");
+}
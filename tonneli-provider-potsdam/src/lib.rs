@@ -0,0 +1,307 @@
+//! Provider implementation for Potsdam using the waste calendar of the STEP
+//! (Stadtentsorgung Potsdam).
+//!
+//! Streets are searched by name, each street lists its house numbers with an
+//! id, and the collections of a house number are served for a date range.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use serde::Deserialize;
+
+use tonneli_core::{
+    dates::MalformedDates,
+    fraction_map::FractionMapper,
+    matching::StreetMatcher,
+    model::{
+        Address, AddressId, Capabilities, CityArea, CityMeta, Coordinates, DateRange, PickupEvent,
+    },
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport},
+};
+use tonneli_http::HttpClient;
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {chrono as _, tokio as _, tonneli_testkit as _, wiremock as _};
+
+/// Base URL of the public calendar API.
+pub const BASE_URL: &str = "https://www.step-potsdam.de/api/abfallkalender";
+
+/// Id of the Potsdam city.
+pub const CITY_ID: &str = "potsdam";
+
+/// Matching streets whose house numbers are looked up per search.
+const MAX_STREETS: usize = 5;
+/// Street searched by [`PotsdamAddressPort::health_check`].
+const PROBE_STREET: &str = "Friedrich-Ebert";
+
+/// Street as returned by `/strassen`
+#[derive(Debug, Deserialize)]
+struct Street {
+    id: u32,
+    name: String,
+}
+
+/// House number as returned by `/strassen/{id}/hausnummern`
+#[derive(Debug, Deserialize)]
+struct HouseNumber {
+    id: u32,
+    nummer: String,
+}
+
+/// Collection as returned by `/hausnummern/{id}/termine`
+#[derive(Debug, Deserialize)]
+struct Collection {
+    datum: String, // "YYYY-MM-DD"
+    fraktion: String,
+}
+
+/// Address search implementation for Potsdam.
+pub struct PotsdamAddressPort {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+}
+
+impl PotsdamAddressPort {
+    /// Create a new address port bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_owned(),
+            meta: city_meta(),
+        }
+    }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Streets matching `search`, from `/strassen`.
+    async fn streets(&self, search: &str) -> Result<Vec<Street>, PortError> {
+        let base_url = &self.base_url;
+        Ok(self
+            .client
+            .fetch_json::<Vec<Street>>(
+                self.client
+                    .get(format!("{base_url}/strassen"))
+                    .query(&[("suche", search)]),
+            )
+            .await?)
+    }
+
+    async fn house_numbers(&self, street: u32) -> Result<Vec<HouseNumber>, PortError> {
+        let base_url = &self.base_url;
+        Ok(self
+            .client
+            .fetch_json::<Vec<HouseNumber>>(
+                self.client
+                    .get(format!("{base_url}/strassen/{street}/hausnummern")),
+            )
+            .await?)
+    }
+}
+
+#[async_trait]
+impl AddressPort for PotsdamAddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    /// Searches the streets for a well-known one.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        Some(self.streets(PROBE_STREET).await.map(drop))
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let streets = self.streets(query.street.trim()).await?;
+
+        let matcher = StreetMatcher::new(&query.street, query.fuzzy);
+        let streets = streets
+            .into_iter()
+            .filter(|street| matcher.matches(&street.name))
+            .take(MAX_STREETS)
+            .collect::<Vec<_>>();
+        if streets.is_empty() {
+            return Err(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            });
+        }
+        let house_numbers =
+            try_join_all(streets.iter().map(|street| self.house_numbers(street.id))).await?;
+
+        let house_filter = query
+            .house_number
+            .as_deref()
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty());
+        let mut addresses = Vec::new();
+        for (street, numbers) in streets.into_iter().zip(house_numbers) {
+            let name = Arc::<str>::from(street.name);
+            addresses.extend(
+                numbers
+                    .into_iter()
+                    .filter(|number| {
+                        house_filter.is_none_or(|filter| number.nummer.eq_ignore_ascii_case(filter))
+                    })
+                    .map(|number| Address {
+                        id: AddressId(number.id.to_string()),
+                        city: self.meta.id.clone(),
+                        label: format!("{name} {}", number.nummer).into(),
+                        street: Arc::clone(&name),
+                        house_number: number.nummer.into(),
+                    }),
+            );
+        }
+        if addresses.is_empty() {
+            return Err(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            });
+        }
+        addresses.truncate(limit);
+        Ok(addresses)
+    }
+}
+
+/// Pickup schedule implementation for Potsdam.
+pub struct PotsdamSchedulePort {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+    fractions: FractionMapper,
+}
+
+impl PotsdamSchedulePort {
+    /// Create a new schedule port bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_owned(),
+            meta: city_meta(),
+            fractions: fraction_mapper(),
+        }
+    }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[async_trait]
+impl SchedulePort for PotsdamSchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let house_number = address_id
+            .0
+            .parse::<u32>()
+            .map_err(|_not_a_number| PortError::InvalidAddressId)?;
+
+        let base_url = &self.base_url;
+        let collections = self
+            .client
+            .fetch_json::<Vec<Collection>>(
+                self.client
+                    .get(format!("{base_url}/hausnummern/{house_number}/termine"))
+                    .query(&[
+                        ("von", range.start.to_string()),
+                        ("bis", range.end.to_string()),
+                    ]),
+            )
+            .await
+            .map_err(PortError::from_address_lookup)?;
+
+        let mut events = Vec::new();
+        let mut malformed = MalformedDates::default();
+        for collection in collections {
+            let Some(date) = malformed.parse(&collection.datum) else {
+                continue;
+            };
+            if !range.contains(date) {
+                continue;
+            }
+            events.push(PickupEvent {
+                date,
+                fraction: self.fractions.map(&collection.fraktion),
+                note: Some(collection.fraktion.into()),
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            });
+        }
+        events.sort_by_key(|event| event.date);
+
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: malformed.into_inner(),
+        })
+    }
+}
+
+/// Mapper from STEP fraction names to [`Fraction`](tonneli_core::model::Fraction).
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    FractionMapper::german()
+}
+
+/// Build the plugin bundle for the Potsdam provider.
+#[must_use]
+pub fn plugin(client: HttpClient) -> CityPlugin {
+    let address_port = Arc::new(PotsdamAddressPort::new(client.clone()));
+    let schedule_port = Arc::new(PotsdamSchedulePort::new(client));
+
+    CityPlugin {
+        meta: city_meta(),
+        address_port,
+        schedule_port,
+    }
+}
+
+fn city_meta() -> CityMeta {
+    CityMeta::new(CITY_ID, "Potsdam")
+        .with_region("Brandenburg")
+        .with_locale("DE", "Europe/Berlin")
+        .with_area(
+            CityArea::new(Coordinates::new(52.3906, 13.0645), 12.0)
+                .with_postal_codes(["1446", "1447", "1448"]),
+        )
+        .with_source(
+            "Stadtentsorgung Potsdam (STEP)",
+            "https://www.step-potsdam.de",
+        )
+        .with_capabilities(Capabilities {
+            multi_year: true,
+            ..Capabilities::default()
+        })
+}
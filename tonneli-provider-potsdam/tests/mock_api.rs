@@ -0,0 +1,169 @@
+//! Potsdam ports against canned STEP responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use tonneli_core::{
+    AddressId, AddressPort, AddressSearch, DateRange, Fraction, PortError, SchedulePort,
+};
+use tonneli_provider_potsdam::{PotsdamAddressPort, PotsdamSchedulePort};
+use tonneli_testkit::{client, json};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+use async_trait as _;
+use futures as _;
+use serde as _;
+use tonneli_http as _;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+#[tokio::test]
+async fn health_check_searches_a_known_street() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/strassen"))
+        .and(query_param("suche", "Friedrich-Ebert"))
+        .respond_with(json(include_str!("fixtures/strassen.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = PotsdamAddressPort::new(client()).with_base_url(server.uri());
+    let probe = port.health_check().await;
+    assert!(
+        matches!(probe, Some(Ok(()))),
+        "the street search answers, got {probe:?}"
+    );
+
+    let down = PotsdamAddressPort::new(client()).with_base_url(format!("{}/down", server.uri()));
+    let failed = down.health_check().await;
+    assert!(
+        matches!(failed, Some(Err(_))),
+        "a failing backend is reported, got {failed:?}"
+    );
+}
+
+#[tokio::test]
+async fn search_lists_house_numbers_of_matching_streets() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/strassen"))
+        .and(query_param("suche", "Friedrich-Ebert"))
+        .respond_with(json(include_str!("fixtures/strassen.json")))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/strassen/310/hausnummern"))
+        .respond_with(json(include_str!("fixtures/hausnummern_310.json")))
+        .mount(&server)
+        .await;
+
+    let port = PotsdamAddressPort::new(client()).with_base_url(server.uri());
+    let addresses = port
+        .search(&AddressSearch::new("Friedrich-Ebert", Some("12a")), 10)
+        .await
+        .expect("search against the mock must succeed");
+    let found = addresses
+        .iter()
+        .map(|address| (&*address.label, address.id.0.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [("Friedrich-Ebert-Straße 12a", "31013")],
+        "only the matching street and house number are listed"
+    );
+
+    let all = port
+        .search(&AddressSearch::new("Friedrich-Ebert", None::<String>), 10)
+        .await
+        .expect("search against the mock must succeed");
+    assert_eq!(all.len(), 3, "without a house number every house is listed");
+}
+
+#[tokio::test]
+async fn schedule_maps_fractions_within_the_range() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/hausnummern/31012/termine"))
+        .and(query_param("von", "2025-03-01"))
+        .and(query_param("bis", "2025-03-31"))
+        .respond_with(json(include_str!("fixtures/termine.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = PotsdamSchedulePort::new(client()).with_base_url(server.uri());
+    let range = DateRange {
+        start: date(2025, 3, 1),
+        end: date(2025, 3, 31),
+    };
+    let report = port
+        .schedule_report(&AddressId("31012".to_owned()), range)
+        .await
+        .expect("schedule against the mock must succeed");
+
+    let events = report
+        .events
+        .iter()
+        .map(|event| (event.date, event.fraction.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (date(2025, 3, 3), Fraction::Residual),
+            (date(2025, 3, 6), Fraction::Plastic),
+            (date(2025, 3, 11), Fraction::Paper),
+            (date(2025, 3, 14), Fraction::Organic),
+        ],
+        "collections are mapped and limited to the range"
+    );
+    assert_eq!(
+        report.malformed_dates,
+        ["2025-02-30"],
+        "impossible dates are reported"
+    );
+
+    let invalid = port
+        .schedule(&AddressId("Friedrich-Ebert-Straße".to_owned()), range)
+        .await;
+    assert!(invalid.is_err(), "non-numeric ids are rejected");
+}
+
+#[tokio::test]
+async fn only_unknown_house_numbers_are_missing_addresses() {
+    // Wiremock answers 404 for every request nothing is mounted for.
+    let server = MockServer::start().await;
+    let range = DateRange {
+        start: date(2025, 3, 1),
+        end: date(2025, 3, 31),
+    };
+
+    let missing = PotsdamSchedulePort::new(client())
+        .with_base_url(server.uri())
+        .schedule(&AddressId("99999".to_owned()), range)
+        .await;
+    assert!(
+        matches!(
+            missing,
+            Err(PortError::AddressNotFound {
+                status: Some(404),
+                ..
+            })
+        ),
+        "a 404 for the house number is an unknown address, got {missing:?}"
+    );
+
+    let moved = PotsdamAddressPort::new(client())
+        .with_base_url(server.uri())
+        .search(&AddressSearch::new("Friedrich-Ebert", None::<&str>), 10)
+        .await;
+    assert!(
+        matches!(moved, Err(PortError::HttpStatus(404))),
+        "a 404 of the street search is kept, got {moved:?}"
+    );
+}
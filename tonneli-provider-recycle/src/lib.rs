@@ -0,0 +1,483 @@
+//! Generic provider for the Belgian municipalities in the Recycle! app.
+//!
+//! Fost Plus runs one API for the municipalities of Flanders and Brussels.
+//! Every request carries an access token, which the API hands out for the
+//! secret the recycleapp.be web app sends. A town is a zip code, which the API
+//! resolves to one or more zip code ids; streets come from the Belgian address
+//! registry and are searched within those ids. Schedules are published per
+//! street and house number.
+
+use std::collections::BTreeMap;
+use std::iter;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde::de::{DeserializeOwned, IgnoredAny};
+
+use tonneli_core::{
+    dates::MalformedDates,
+    fraction_map::FractionMapper,
+    model::{Address, AddressId, Capabilities, CityMeta, DateRange, Fraction, PickupEvent},
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport},
+};
+use tonneli_http::{HttpClient, HttpError, RequestBuilder};
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {chrono as _, tokio as _, tonneli_testkit as _, wiremock as _};
+
+/// Public Recycle! API.
+pub const BASE_URL: &str = "https://api.fostplus.be/recycle-public/app/v1";
+
+/// Consumer the API expects next to the token.
+const CONSUMER: &str = "recycleapp.be";
+
+/// Matching streets listed per search.
+const MAX_STREETS: usize = 5;
+
+/// Collections requested at once; a year of pickups fits in one page.
+const PAGE_SIZE: &str = "500";
+
+/// Languages names are published in, in the order they are tried.
+const LANGUAGES: &[&str] = &["nl", "fr", "de", "en"];
+
+/// Labels of the Recycle! fractions in Dutch, French, German and English, in priority order.
+const BELGIAN_RULES: &[(&str, Fraction)] = &[
+    ("pmd", Fraction::Plastic),
+    ("pmc", Fraction::Plastic),
+    ("pmk", Fraction::Plastic),
+    ("gft", Fraction::Organic),
+    ("groente", Fraction::Organic),
+    ("tuinafval", Fraction::GardenWaste),
+    ("snoeiafval", Fraction::GardenWaste),
+    ("organi", Fraction::Organic),
+    ("bio", Fraction::Organic),
+    ("rest", Fraction::Residual),
+    ("huisvuil", Fraction::Residual),
+    ("residu", Fraction::Residual),
+    ("menager", Fraction::Residual),
+    ("papier", Fraction::Paper),
+    ("karton", Fraction::Paper),
+    ("paper", Fraction::Paper),
+    ("glas", Fraction::Glass),
+    ("verre", Fraction::Glass),
+    ("grofvuil", Fraction::BulkyWaste),
+    ("encombrant", Fraction::BulkyWaste),
+    ("kerstbom", Fraction::ChristmasTree),
+    ("sapin", Fraction::ChristmasTree),
+    ("textiel", Fraction::Textiles),
+    ("kga", Fraction::Hazardous),
+];
+
+#[derive(Debug, Clone)]
+/// Municipality in the Recycle! app; the same ports work for every one of them.
+pub struct RecycleTown {
+    /// API endpoint, usually [`BASE_URL`].
+    pub base_url: String,
+    /// Secret exchanged for access tokens, as sent by the recycleapp.be web app.
+    pub secret: String,
+    /// Postal code of the municipality, e.g. `"3000"`.
+    pub zip_code: String,
+    /// Language of street and fraction names: `nl`, `fr`, `de` or `en`.
+    pub language: String,
+    /// Metadata of the resulting city.
+    pub meta: CityMeta,
+}
+
+/// Parts of a Recycle! [`AddressId`]: `zipcode_id:street_id:house_number`.
+///
+/// Street ids are registry URLs and contain colons themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecycleAddressId<'id> {
+    /// Zip code id, e.g. `3000-24062`.
+    pub zipcode: &'id str,
+    /// Street id from the address registry.
+    pub street: &'id str,
+    /// House number; empty when none was given.
+    pub house_number: &'id str,
+}
+
+impl<'id> RecycleAddressId<'id> {
+    /// Split an id produced by [`RecycleAddressPort`].
+    ///
+    /// # Errors
+    ///
+    /// [`PortError::InvalidAddressId`] when the zip code or street id is blank.
+    pub fn parse(id: &'id AddressId) -> Result<Self, PortError> {
+        let (zipcode, rest) = id.0.split_once(':').ok_or(PortError::InvalidAddressId)?;
+        let (street, house_number) = rest.rsplit_once(':').ok_or(PortError::InvalidAddressId)?;
+        if zipcode.trim().is_empty() || street.trim().is_empty() {
+            return Err(PortError::InvalidAddressId);
+        }
+        Ok(Self {
+            zipcode,
+            street,
+            house_number,
+        })
+    }
+}
+
+/// Names by language code.
+type Names = BTreeMap<String, String>;
+
+/// Name in `language`, or in the first other language that has one.
+fn localized<'names>(names: &'names Names, language: &str) -> Option<&'names str> {
+    iter::once(language)
+        .chain(LANGUAGES.iter().copied())
+        .find_map(|code| names.get(code).filter(|name| !name.trim().is_empty()))
+        .map(String::as_str)
+}
+
+/// Answer of `/access-token`
+#[derive(Debug, Deserialize)]
+struct AccessToken {
+    #[serde(rename = "accessToken")]
+    access_token: String,
+}
+
+/// Paged list as returned by the search and collection endpoints
+#[derive(Debug, Deserialize)]
+struct Items<T> {
+    items: Vec<T>,
+}
+
+/// Zip code as returned by `/zipcodes`
+#[derive(Debug, Deserialize)]
+struct Zipcode {
+    id: String,
+    code: String,
+}
+
+/// Street as returned by `/streets`
+#[derive(Debug, Deserialize)]
+struct Street {
+    id: String,
+    names: Names,
+}
+
+/// Calendar entry as returned by `/collections`
+#[derive(Debug, Deserialize)]
+struct Collection {
+    timestamp: String,
+    /// `collection` for pickups; `event` for announcements.
+    #[serde(rename = "type")]
+    kind: String,
+    fraction: Option<CollectionFraction>,
+    exception: Option<Exception>,
+}
+
+/// Fraction inside [`Collection`]
+#[derive(Debug, Deserialize)]
+struct CollectionFraction {
+    name: Names,
+}
+
+/// Change of a [`Collection`] against the regular calendar
+#[derive(Debug, Deserialize)]
+struct Exception {
+    /// Set when the pickup moved; the replacement is listed as its own entry.
+    #[serde(rename = "replacedBy")]
+    replaced_by: Option<IgnoredAny>,
+}
+
+/// Authenticated access to the API, shared by both ports.
+struct Api {
+    client: HttpClient,
+    town: RecycleTown,
+    token: Mutex<Option<String>>,
+}
+
+impl Api {
+    fn new(client: HttpClient, town: RecycleTown) -> Self {
+        Self {
+            client,
+            town,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Cached access token, or a new one when there is none or `renew` is set.
+    async fn token(&self, renew: bool) -> Result<String, PortError> {
+        if !renew && let Some(token) = self.token.lock().ok().and_then(|cached| cached.clone()) {
+            return Ok(token);
+        }
+
+        let AccessToken { access_token } = self
+            .client
+            .fetch_json(
+                self.client
+                    .get(format!("{}/access-token", self.town.base_url))
+                    .header("x-secret", self.town.secret.as_str())
+                    .header("x-consumer", CONSUMER),
+            )
+            .await?;
+        if let Ok(mut cached) = self.token.lock() {
+            *cached = Some(access_token.clone());
+        }
+        Ok(access_token)
+    }
+
+    /// Send the request built by `request` with a token, renewing it once if it expired.
+    async fn fetch<T: DeserializeOwned>(
+        &self,
+        request: impl Fn(&HttpClient, &str) -> RequestBuilder + Send,
+    ) -> Result<T, PortError> {
+        let authorized = |token: &str| {
+            request(&self.client, &self.town.base_url)
+                .header("authorization", token)
+                .header("x-consumer", CONSUMER)
+        };
+        let token = self.token(false).await?;
+        match self.client.fetch_json(authorized(&token)).await {
+            Err(HttpError::Status(status)) if status.as_u16() == 401 => {
+                let renewed = self.token(true).await?;
+                Ok(self.client.fetch_json(authorized(&renewed)).await?)
+            }
+            result => Ok(result?),
+        }
+    }
+}
+
+/// Address search for a municipality in the Recycle! app.
+pub struct RecycleAddressPort {
+    api: Api,
+    zipcodes: Mutex<Option<Vec<String>>>,
+}
+
+impl RecycleAddressPort {
+    /// Create an address port for the given town.
+    #[must_use]
+    pub fn for_town(client: HttpClient, town: RecycleTown) -> Self {
+        Self {
+            api: Api::new(client, town),
+            zipcodes: Mutex::new(None),
+        }
+    }
+
+    /// Ids of the configured zip code, resolved via `/zipcodes` once and cached.
+    async fn resolve_zipcodes(&self) -> Result<Vec<String>, PortError> {
+        if let Some(ids) = self.zipcodes.lock().ok().and_then(|cached| cached.clone()) {
+            return Ok(ids);
+        }
+
+        let zip_code = self.api.town.zip_code.as_str();
+        let zipcodes = self
+            .api
+            .fetch::<Items<Zipcode>>(|client, base_url| {
+                client
+                    .get(format!("{base_url}/zipcodes"))
+                    .query(&[("q", zip_code)])
+            })
+            .await?;
+        let ids = zipcodes
+            .items
+            .into_iter()
+            .filter(|zipcode| zipcode.code == zip_code)
+            .map(|zipcode| zipcode.id)
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            return Err(PortError::Internal(format!(
+                "Recycle! knows no zip code {zip_code}"
+            )));
+        }
+
+        if let Ok(mut cached) = self.zipcodes.lock() {
+            *cached = Some(ids.clone());
+        }
+        Ok(ids)
+    }
+}
+
+#[async_trait]
+impl AddressPort for RecycleAddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.api.town.meta
+    }
+
+    /// Asks for a new access token, which also checks the configured secret.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        Some(self.api.token(true).await.map(drop))
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let house_number = query.house_number.as_deref().map_or("", str::trim);
+        let language = self.api.town.language.as_str();
+        let mut addresses = Vec::new();
+        for zipcode in self.resolve_zipcodes().await? {
+            // The API matches names in every language, so its results are not filtered again.
+            let streets = self
+                .api
+                .fetch::<Items<Street>>(|client, base_url| {
+                    client
+                        .post(format!("{base_url}/streets"))
+                        .query(&[("q", query.street.as_str()), ("zipcodes", &zipcode)])
+                })
+                .await?;
+            addresses.extend(
+                streets
+                    .items
+                    .into_iter()
+                    .take(MAX_STREETS)
+                    .filter_map(|street| {
+                        let name = Arc::<str>::from(localized(&street.names, language)?);
+                        let label = if house_number.is_empty() {
+                            Arc::clone(&name)
+                        } else {
+                            format!("{name} {house_number}").into()
+                        };
+                        Some(Address {
+                            id: AddressId(format!("{zipcode}:{}:{house_number}", street.id)),
+                            city: self.api.town.meta.id.clone(),
+                            label,
+                            street: name,
+                            house_number: house_number.into(),
+                        })
+                    }),
+            );
+        }
+        if addresses.is_empty() {
+            return Err(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            });
+        }
+        addresses.truncate(limit);
+        Ok(addresses)
+    }
+}
+
+/// Pickup schedule for a municipality in the Recycle! app.
+pub struct RecycleSchedulePort {
+    api: Api,
+    fractions: FractionMapper,
+}
+
+impl RecycleSchedulePort {
+    /// Create a schedule port for the given town.
+    #[must_use]
+    pub fn for_town(client: HttpClient, town: RecycleTown) -> Self {
+        Self {
+            api: Api::new(client, town),
+            fractions: fraction_mapper(),
+        }
+    }
+}
+
+#[async_trait]
+impl SchedulePort for RecycleSchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.api.town.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let RecycleAddressId {
+            zipcode,
+            street,
+            house_number,
+        } = RecycleAddressId::parse(address_id)?;
+        let (from, until) = (range.start.to_string(), range.end.to_string());
+
+        let collections = self
+            .api
+            .fetch::<Items<Collection>>(|client, base_url| {
+                let request = client.get(format!("{base_url}/collections")).query(&[
+                    ("zipcodeId", zipcode),
+                    ("streetId", street),
+                    ("fromDate", &from),
+                    ("untilDate", &until),
+                    ("size", PAGE_SIZE),
+                ]);
+                if house_number.is_empty() {
+                    request
+                } else {
+                    request.query(&[("houseNumber", house_number)])
+                }
+            })
+            .await?;
+
+        let language = self.api.town.language.as_str();
+        let mut events = Vec::new();
+        let mut malformed = MalformedDates::default();
+        for collection in collections.items {
+            if collection.kind != "collection"
+                || collection
+                    .exception
+                    .is_some_and(|exception| exception.replaced_by.is_some())
+            {
+                continue;
+            }
+            let Some(fraction) = collection.fraction else {
+                continue;
+            };
+            let Some(date) = malformed.parse(&collection.timestamp) else {
+                continue;
+            };
+            if !range.contains(date) {
+                continue;
+            }
+            let name = localized(&fraction.name, language).unwrap_or_default();
+            events.push(PickupEvent {
+                date,
+                fraction: self.fractions.map(name),
+                note: Some(name.into()),
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            });
+        }
+        events.sort_by_key(|event| event.date);
+
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: malformed.into_inner(),
+        })
+    }
+}
+
+/// Mapper from Recycle! fraction names (`PMD`, `GFT`, `Restafval`, ...) to [`Fraction`].
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    FractionMapper::empty().with_rules(BELGIAN_RULES)
+}
+
+/// What Recycle! supports; [`plugin_for_town`] declares it for every municipality.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    house_number_required: false,
+    multi_year: true,
+    fraction_filter: false,
+    bulky_waste_booking: false,
+};
+
+/// Build a plugin bundle for a municipality in the Recycle! app.
+#[must_use]
+pub fn plugin_for_town(client: HttpClient, mut town: RecycleTown) -> CityPlugin {
+    town.meta = town.meta.with_capabilities(CAPABILITIES);
+    let meta = town.meta.clone();
+    let address_port = Arc::new(RecycleAddressPort::for_town(client.clone(), town.clone()));
+    let schedule_port = Arc::new(RecycleSchedulePort::for_town(client, town));
+
+    CityPlugin {
+        meta,
+        address_port,
+        schedule_port,
+    }
+}
@@ -0,0 +1,52 @@
+//! Recycle! fraction names must map onto known fractions.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use tonneli_provider_recycle::fraction_mapper;
+use tonneli_testkit::unexpected_other_fractions;
+
+use async_trait as _;
+use chrono as _;
+use serde as _;
+use tokio as _;
+use tonneli_core as _;
+use tonneli_http as _;
+use wiremock as _;
+
+/// Fraction names the app shows, in Dutch and French.
+const KNOWN_LABELS: &[&str] = &[
+    "PMD",
+    "PMC",
+    "GFT",
+    "Déchets organiques",
+    "Groente-, fruit- en tuinafval",
+    "Restafval",
+    "Huisvuil",
+    "Déchets résiduels",
+    "Ordures ménagères",
+    "Papier-karton",
+    "Papier & karton",
+    "Papiers-cartons",
+    "Glas",
+    "Verre",
+    "Grofvuil",
+    "Encombrants",
+    "Kerstbomen",
+];
+/// Labels without a matching fraction that are fine as “Other”.
+const EXPECTED_OTHER: &[&str] = &[];
+
+#[test]
+fn known_vocabulary_maps_to_fractions() {
+    let unexpected = unexpected_other_fractions(
+        &fraction_mapper(),
+        KNOWN_LABELS.iter().copied(),
+        EXPECTED_OTHER,
+    );
+    assert!(
+        unexpected.is_empty(),
+        "map these labels or list them in EXPECTED_OTHER: {unexpected:?}"
+    );
+}
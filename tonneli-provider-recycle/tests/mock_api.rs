@@ -0,0 +1,284 @@
+//! Recycle! ports against canned API responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use tonneli_core::{
+    AddressId, AddressPort, AddressSearch, CityMeta, DateRange, Fraction, PortError, SchedulePort,
+};
+use tonneli_provider_recycle::{RecycleAddressPort, RecycleSchedulePort, RecycleTown};
+use tonneli_testkit::{client, json};
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use async_trait as _;
+use serde as _;
+use tonneli_http as _;
+
+const SECRET: &str = "web-app-secret";
+const STREET_ID: &str = "https://data.vlaanderen.be/id/straatnaam-22626";
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+fn town(server: &MockServer, language: &str) -> RecycleTown {
+    RecycleTown {
+        base_url: server.uri(),
+        secret: SECRET.to_owned(),
+        zip_code: "3000".to_owned(),
+        language: language.to_owned(),
+        meta: CityMeta::new("leuven", "Leuven"),
+    }
+}
+
+fn march() -> DateRange {
+    DateRange {
+        start: date(2025, 3, 1),
+        end: date(2025, 3, 31),
+    }
+}
+
+/// Mock server handing out `token-1` for the test secret.
+async fn serve_token() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/access-token"))
+        .and(header("x-secret", SECRET))
+        .and(header("x-consumer", "recycleapp.be"))
+        .respond_with(json(include_str!("fixtures/access_token.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn health_check_asks_for_a_token() {
+    let server = serve_token().await;
+    let probe = RecycleAddressPort::for_town(client(), town(&server, "nl"))
+        .health_check()
+        .await;
+    assert!(
+        matches!(probe, Some(Ok(()))),
+        "the token endpoint answers, got {probe:?}"
+    );
+
+    let wrong_secret = RecycleTown {
+        secret: "outdated".to_owned(),
+        ..town(&server, "nl")
+    };
+    let failed = RecycleAddressPort::for_town(client(), wrong_secret)
+        .health_check()
+        .await;
+    assert!(
+        matches!(failed, Some(Err(_))),
+        "a rejected secret is reported, got {failed:?}"
+    );
+}
+
+/// Mock server answering zip code and street searches.
+async fn serve_streets() -> MockServer {
+    let server = serve_token().await;
+    Mock::given(method("GET"))
+        .and(path("/zipcodes"))
+        .and(query_param("q", "3000"))
+        .and(header("authorization", "token-1"))
+        .respond_with(json(include_str!("fixtures/zipcodes.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/streets"))
+        .and(query_param("q", "Bondgenoten"))
+        .and(query_param("zipcodes", "3000-24062"))
+        .and(header("authorization", "token-1"))
+        .respond_with(json(include_str!("fixtures/streets.json")))
+        .mount(&server)
+        .await;
+    server
+}
+
+/// Labels and ids of the addresses found for `street` and `house_number`.
+async fn found(
+    port: &RecycleAddressPort,
+    street: &str,
+    house_number: Option<&str>,
+) -> Vec<(String, String)> {
+    port.search(&AddressSearch::new(street, house_number), 10)
+        .await
+        .expect("search against the mock must succeed")
+        .into_iter()
+        .map(|address| (address.label.to_string(), address.id.0))
+        .collect()
+}
+
+fn pair(label: &str, id: &str) -> (String, String) {
+    (label.to_owned(), id.to_owned())
+}
+
+#[tokio::test]
+async fn search_resolves_zip_code_and_streets() {
+    let server = serve_streets().await;
+    let port = RecycleAddressPort::for_town(client(), town(&server, "nl"));
+
+    assert_eq!(
+        found(&port, "Bondgenoten", Some("12")).await,
+        [
+            pair("Bondgenotenlaan 12", &format!("3000-24062:{STREET_ID}:12")),
+            pair(
+                "Bondgenotenplein 12",
+                "3000-24062:https://data.vlaanderen.be/id/straatnaam-22627:12"
+            ),
+        ],
+        "streets of the zip code carry the house number asked for"
+    );
+    assert_eq!(
+        found(&port, "Bondgenoten", None).await.first(),
+        Some(&pair(
+            "Bondgenotenlaan",
+            &format!("3000-24062:{STREET_ID}:")
+        )),
+        "the house number is optional"
+    );
+}
+
+#[tokio::test]
+async fn street_names_follow_the_language() {
+    let server = serve_streets().await;
+    let port = RecycleAddressPort::for_town(client(), town(&server, "fr"));
+
+    let labels = found(&port, "Bondgenoten", None)
+        .await
+        .into_iter()
+        .map(|(label, _)| label)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        labels,
+        ["Boulevard des Alliés", "Bondgenotenplein"],
+        "missing translations fall back to Dutch"
+    );
+}
+
+#[tokio::test]
+async fn unknown_zip_code_is_an_internal_error() {
+    let server = serve_token().await;
+    let port = RecycleAddressPort::for_town(
+        client(),
+        RecycleTown {
+            zip_code: "9999".to_owned(),
+            ..town(&server, "nl")
+        },
+    );
+    Mock::given(method("GET"))
+        .and(path("/zipcodes"))
+        .and(query_param("q", "9999"))
+        .respond_with(json(r#"{"items": []}"#))
+        .mount(&server)
+        .await;
+
+    let result = port
+        .search(&AddressSearch::new("Bondgenoten", None::<String>), 10)
+        .await;
+    assert!(
+        matches!(result, Err(PortError::Internal(_))),
+        "a misconfigured zip code is reported, got {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn schedule_maps_fractions_and_skips_moved_pickups() {
+    let server = serve_token().await;
+    Mock::given(method("GET"))
+        .and(path("/collections"))
+        .and(query_param("zipcodeId", "3000-24062"))
+        .and(query_param("streetId", STREET_ID))
+        .and(query_param("houseNumber", "12"))
+        .and(query_param("fromDate", "2025-03-01"))
+        .and(query_param("untilDate", "2025-03-31"))
+        .and(header("authorization", "token-1"))
+        .respond_with(json(include_str!("fixtures/collections.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = RecycleSchedulePort::for_town(client(), town(&server, "nl"));
+    let report = port
+        .schedule_report(&AddressId(format!("3000-24062:{STREET_ID}:12")), march())
+        .await
+        .expect("schedule against the mock must succeed");
+
+    let events = report
+        .events
+        .iter()
+        .map(|event| (event.date, event.fraction.clone(), event.note.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (date(2025, 3, 4), Fraction::Plastic, Some("PMD")),
+            (date(2025, 3, 5), Fraction::Residual, Some("Restafval")),
+            (date(2025, 3, 12), Fraction::Paper, Some("Papier-karton")),
+            (date(2025, 3, 18), Fraction::Organic, Some("GFT")),
+        ],
+        "collections are mapped, moved ones only on their new day, events skipped"
+    );
+    assert_eq!(
+        report.malformed_dates,
+        ["2025-02-30T00:00:00.000Z"],
+        "impossible dates are reported"
+    );
+
+    let invalid = port
+        .schedule(&AddressId("3000-24062".to_owned()), march())
+        .await;
+    assert!(
+        matches!(invalid, Err(PortError::InvalidAddressId)),
+        "ids without street are rejected, got {invalid:?}"
+    );
+}
+
+#[tokio::test]
+async fn expired_tokens_are_renewed() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/access-token"))
+        .respond_with(json(include_str!("fixtures/access_token.json")))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/access-token"))
+        .respond_with(json(r#"{"accessToken": "token-2"}"#))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/collections"))
+        .and(header("authorization", "token-1"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/collections"))
+        .and(header("authorization", "token-2"))
+        .respond_with(json(include_str!("fixtures/collections.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = RecycleSchedulePort::for_town(client(), town(&server, "fr"));
+    let events = port
+        .schedule(&AddressId(format!("3000-24062:{STREET_ID}:")), march())
+        .await
+        .expect("the renewed token is accepted");
+    assert_eq!(
+        events.first().and_then(|event| event.note.as_deref()),
+        Some("PMC"),
+        "fraction names follow the language"
+    );
+}
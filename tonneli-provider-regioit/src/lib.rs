@@ -0,0 +1,628 @@
+//! Generic provider for the towns on `RegioIT`'s Abfallnavi instances.
+//!
+//! `RegioIT` hosts the waste apps of many towns (Aachen, Nuremberg, Lippe,
+//! Coesfeld, ...) under `abfallnavi.de`. Each service speaks the same REST
+//! dialect and may serve several towns (orte); a [`RegioItTown`] names the
+//! service and the ort, and the same ports work for all of them. Services of a
+//! county (Landkreis) can also be offered as a whole, as a [`RegioItCounty`]
+//! whose searches name the town.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::Datelike;
+use futures::stream::{self, StreamExt};
+use serde::Deserialize;
+
+use tonneli_core::{
+    clock::{Clock, SystemClock},
+    dates::MalformedDates,
+    fraction_map::FractionMapper,
+    matching::{MatchRank, StreetMatcher, fold},
+    model::{Address, AddressId, Capabilities, CityMeta, DateRange, Fraction, PickupEvent},
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport},
+};
+use tonneli_http::HttpClient;
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {tokio as _, tonneli_testkit as _, wiremock as _};
+
+// Upper bound for concurrent /strassen/{id} requests during a search.
+const MAX_CONCURRENT_STREET_FETCHES: usize = 6;
+// Streets whose house numbers are kept between searches.
+const STREET_CACHE_CAPACITY: usize = 512;
+
+/// REST base URL of the Abfallnavi service named `service`, e.g. `"aachen"`.
+#[must_use]
+pub fn service_url(service: &str) -> String {
+    format!("https://{service}-abfallapp.regioit.de/abfall-app-{service}/rest")
+}
+
+#[derive(Debug, Clone)]
+/// Town served by a `RegioIT` instance; the same ports work for every hosted town.
+pub struct RegioItTown {
+    /// REST base URL of the `RegioIT` instance, without trailing slash.
+    pub base_url: String,
+    /// Town name as listed by `/orte`, used to discover its ort id.
+    pub town: String,
+    /// Ort id of the town when known, which saves the `/orte` lookup.
+    pub ort_id: Option<i64>,
+    /// Metadata of the resulting city.
+    pub meta: CityMeta,
+}
+
+impl RegioItTown {
+    /// Town `town` on the Abfallnavi service `service`, see [`service_url`].
+    #[must_use]
+    pub fn abfallnavi(service: &str, town: impl Into<String>, meta: CityMeta) -> Self {
+        Self {
+            base_url: service_url(service),
+            town: town.into(),
+            ort_id: None,
+            meta,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// County whose `RegioIT` instance serves all its towns; searches pick the town.
+pub struct RegioItCounty {
+    /// REST base URL of the `RegioIT` instance, without trailing slash.
+    pub base_url: String,
+    /// Metadata of the resulting city.
+    pub meta: CityMeta,
+}
+
+impl RegioItCounty {
+    /// All towns of the Abfallnavi service `service`, see [`service_url`].
+    #[must_use]
+    pub fn abfallnavi(service: &str, meta: CityMeta) -> Self {
+        Self {
+            base_url: service_url(service),
+            meta,
+        }
+    }
+}
+
+/// Town as returned by /orte
+#[derive(Debug, Clone, Deserialize)]
+struct Ort {
+    id: i64,
+    name: String,
+}
+
+/// Street as returned by /orte/{ortId}/strassen?jahr=YYYY
+#[derive(Debug, Deserialize)]
+struct Street {
+    id: i64,
+    name: String,
+    // many other fields exist, we ignore them
+}
+
+/// Detailed street (with house numbers), /strassen/{strassenId}
+#[derive(Debug, Deserialize)]
+struct StreetDetail {
+    #[serde(rename = "hausNrList")]
+    house_numbers: Vec<HouseNumber>,
+}
+
+/// House number entry inside `StreetDetail.house_numbers`
+#[derive(Debug, Deserialize)]
+struct HouseNumber {
+    id: i64,
+    #[serde(rename = "nr")]
+    number: String,
+}
+
+/// Pickup as returned by /hausnummern/{hausnummerId}/termine
+#[derive(Debug, Deserialize)]
+struct PickupResponse {
+    #[serde(rename = "datum")]
+    date: String, // "YYYY-MM-DD"
+    #[serde(rename = "bezirk")]
+    district: Option<District>,
+    // fields "jahr" and "info" exist but we don't need them
+}
+
+/// Nested district object that holds the fraction id.
+#[derive(Debug, Deserialize)]
+struct District {
+    #[serde(rename = "fraktionId")]
+    fraction_id: i64,
+}
+
+/// Fraction metadata from /hausnummern/{hausnummerId}/fraktionen
+#[derive(Debug, Deserialize)]
+struct FractionInfo {
+    id: i64,
+    name: String,
+}
+
+/// Towns of the instance an address port searches.
+enum Scope {
+    /// The configured town.
+    Town {
+        name: String,
+        ort_id: Mutex<Option<i64>>,
+    },
+    /// Every town of the instance; each search names one.
+    County { orte: Mutex<Option<Vec<Ort>>> },
+}
+
+/// Address search for a town on a `RegioIT` instance.
+pub struct RegioItAddressPort {
+    client: HttpClient,
+    base_url: String,
+    scope: Scope,
+    meta: CityMeta,
+    street_cache: Mutex<HashMap<i64, Arc<[HouseNumber]>>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl RegioItAddressPort {
+    /// Create an address port for the given town.
+    #[must_use]
+    pub fn for_town(client: HttpClient, town: RegioItTown) -> Self {
+        Self {
+            client,
+            base_url: town.base_url,
+            scope: Scope::Town {
+                name: town.town,
+                ort_id: Mutex::new(town.ort_id),
+            },
+            meta: town.meta,
+            street_cache: Mutex::new(HashMap::new()),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Create an address port for all towns of the given county.
+    #[must_use]
+    pub fn for_county(client: HttpClient, county: RegioItCounty) -> Self {
+        Self {
+            client,
+            base_url: county.base_url,
+            scope: Scope::County {
+                orte: Mutex::new(None),
+            },
+            meta: county.meta,
+            street_cache: Mutex::new(HashMap::new()),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Take the year whose street list is searched from `clock` instead of the
+    /// system time, e.g. the service's clock.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Towns of the instance, from `/orte`.
+    async fn orte(&self) -> Result<Vec<Ort>, PortError> {
+        let base_url = &self.base_url;
+        Ok(self
+            .client
+            .fetch_json::<Vec<Ort>>(self.client.get(format!("{base_url}/orte")))
+            .await?)
+    }
+
+    /// Towns of the county, fetched once and cached.
+    async fn county_orte(&self, cache: &Mutex<Option<Vec<Ort>>>) -> Result<Vec<Ort>, PortError> {
+        if let Some(orte) = cache.lock().ok().and_then(|cached| cached.clone()) {
+            return Ok(orte);
+        }
+        let orte = self.orte().await?;
+        if let Ok(mut cached) = cache.lock() {
+            *cached = Some(orte.clone());
+        }
+        Ok(orte)
+    }
+
+    /// Ort id to search in: the configured town's, resolved via `/orte` once and
+    /// cached, or that of the county town named by `query`.
+    async fn ort_id(&self, query: &AddressSearch) -> Result<i64, PortError> {
+        match &self.scope {
+            Scope::Town { name, ort_id } => {
+                if let Some(id) = ort_id.lock().ok().and_then(|cached| *cached) {
+                    return Ok(id);
+                }
+
+                let town = fold(name);
+                let id = self
+                    .orte()
+                    .await?
+                    .into_iter()
+                    .find(|ort| fold(&ort.name) == town)
+                    .map(|ort| ort.id)
+                    .ok_or_else(|| {
+                        PortError::Internal(format!("RegioIT instance has no town {name}"))
+                    })?;
+
+                if let Ok(mut cached) = ort_id.lock() {
+                    *cached = Some(id);
+                }
+                Ok(id)
+            }
+            Scope::County { orte } => {
+                let orte = self.county_orte(orte).await?;
+                let wanted = query.town.as_deref().map(fold);
+                if let Some(ort) = orte
+                    .iter()
+                    .find(|ort| wanted.as_deref() == Some(fold(&ort.name).as_str()))
+                {
+                    return Ok(ort.id);
+                }
+                Err(PortError::TownRequired {
+                    towns: orte.into_iter().map(|ort| ort.name).collect(),
+                })
+            }
+        }
+    }
+
+    /// Append addresses of `streets` matching `house_filter` until `limit` is reached.
+    async fn collect_addresses(
+        &self,
+        streets: Vec<(MatchRank, Street)>,
+        house_filter: Option<&str>,
+        limit: usize,
+        results: &mut Vec<Address>,
+    ) -> Result<(), PortError> {
+        if streets.is_empty() || results.len() >= limit {
+            return Ok(());
+        }
+
+        // Fetch street details with bounded concurrency. `buffered` yields them in
+        // street order, so results stay deterministic; dropping the stream once the
+        // limit is reached cancels the requests still in flight.
+        let mut details = stream::iter(streets)
+            .map(|(_, street)| async move {
+                let house_numbers = self.house_numbers(street.id).await?;
+                Ok::<_, PortError>((street, house_numbers))
+            })
+            .buffered(MAX_CONCURRENT_STREET_FETCHES);
+
+        while let Some(fetched) = details.next().await {
+            let (street, house_numbers) = fetched?;
+            let street_name = Arc::<str>::from(street.name);
+
+            let remaining = limit - results.len();
+
+            results.extend(
+                house_numbers
+                    .iter()
+                    .filter(|house_number| {
+                        house_filter.is_none_or(|filter| {
+                            house_number.number.to_lowercase().contains(filter)
+                        })
+                    })
+                    .take(remaining)
+                    .map(|house_number| {
+                        let id = AddressId(house_number.id.to_string());
+                        let label = format!("{street_name} {}", house_number.number);
+
+                        Address {
+                            id,
+                            city: self.meta.id.clone(),
+                            label: label.into(),
+                            street: Arc::clone(&street_name),
+                            house_number: house_number.number.as_str().into(),
+                        }
+                    }),
+            );
+
+            if results.len() == limit {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// House numbers of a street, sorted, from the cache or `/strassen/{id}`.
+    async fn house_numbers(&self, street_id: i64) -> Result<Arc<[HouseNumber]>, PortError> {
+        let cached = self
+            .street_cache
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&street_id).cloned());
+        if let Some(house_numbers) = cached {
+            return Ok(house_numbers);
+        }
+
+        let mut detail = self
+            .client
+            .fetch_json::<StreetDetail>(
+                self.client
+                    .get(format!("{}/strassen/{street_id}", self.base_url)),
+            )
+            .await?;
+        detail
+            .house_numbers
+            .sort_by(|left, right| left.number.cmp(&right.number));
+        let house_numbers = Arc::<[HouseNumber]>::from(detail.house_numbers);
+
+        if let Ok(mut cache) = self.street_cache.lock() {
+            if cache.len() >= STREET_CACHE_CAPACITY {
+                cache.clear();
+            }
+            cache.insert(street_id, Arc::clone(&house_numbers));
+        }
+        Ok(house_numbers)
+    }
+}
+
+#[async_trait]
+impl AddressPort for RegioItAddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let street_query = query.street.trim();
+        if street_query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let house_filter = query
+            .house_number
+            .as_deref()
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_lowercase);
+
+        let year = self.clock.today_in(&self.meta).year();
+        let ort_id = self.ort_id(query).await?;
+
+        let streets = self
+            .client
+            .fetch_json::<Vec<Street>>(
+                self.client
+                    .get(format!("{}/orte/{ort_id}/strassen", self.base_url))
+                    .query(&[("jahr", year)]),
+            )
+            .await?;
+
+        let matcher = StreetMatcher::new(street_query, query.fuzzy);
+        let mut candidates = streets
+            .into_iter()
+            .filter_map(|street| matcher.rank(&street.name).map(|rank| (rank, street)))
+            .collect::<Vec<_>>();
+        // Stable sort: best matches first, API order within each rank.
+        candidates.sort_by_key(|(rank, _)| *rank);
+
+        let mut results = Vec::with_capacity(limit);
+
+        // With a house number the user almost always means the exactly matching street;
+        // only fetch the others when it has no such house number.
+        let split = if house_filter.is_some() {
+            candidates
+                .iter()
+                .take_while(|(rank, _)| *rank == MatchRank::Exact)
+                .count()
+        } else {
+            0
+        };
+        let others = candidates.split_off(split);
+
+        for tier in [candidates, others] {
+            self.collect_addresses(tier, house_filter.as_deref(), limit, &mut results)
+                .await?;
+            if (house_filter.is_some() && !results.is_empty()) || results.len() == limit {
+                break;
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Lists the towns of the instance.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        Some(self.orte().await.map(drop))
+    }
+
+    async fn towns(&self) -> Result<Vec<String>, PortError> {
+        match &self.scope {
+            Scope::Town { .. } => Ok(Vec::new()),
+            Scope::County { orte } => Ok(self
+                .county_orte(orte)
+                .await?
+                .into_iter()
+                .map(|ort| ort.name)
+                .collect()),
+        }
+    }
+}
+
+/// Pickup schedule for a town on a `RegioIT` instance.
+pub struct RegioItSchedulePort {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+    fractions: FractionMapper,
+}
+
+impl RegioItSchedulePort {
+    /// Create a schedule port for the given town.
+    #[must_use]
+    pub fn for_town(client: HttpClient, town: RegioItTown) -> Self {
+        Self {
+            client,
+            base_url: town.base_url,
+            meta: town.meta,
+            fractions: fraction_mapper(),
+        }
+    }
+
+    /// Create a schedule port for all towns of the given county.
+    #[must_use]
+    pub fn for_county(client: HttpClient, county: RegioItCounty) -> Self {
+        Self {
+            client,
+            base_url: county.base_url,
+            meta: county.meta,
+            fractions: fraction_mapper(),
+        }
+    }
+}
+
+#[async_trait]
+impl SchedulePort for RegioItSchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let house_number_id = address_id
+            .0
+            .parse::<i32>()
+            .map_err(|_err| PortError::InvalidAddressId)?;
+
+        let fractions = self
+            .client
+            .fetch_json::<Vec<FractionInfo>>(self.client.get(format!(
+                "{}/hausnummern/{house_number_id}/fraktionen",
+                self.base_url
+            )))
+            .await?;
+
+        let mut fraction_ids = Vec::<i64>::new();
+        let mut fraction_names = HashMap::<i64, (Arc<str>, Fraction)>::new();
+        for fraction in fractions {
+            // Map and allocate each fraction name once; events share them.
+            let mapped = self.fractions.map(&fraction.name);
+            fraction_names.insert(fraction.id, (Arc::<str>::from(fraction.name), mapped));
+            fraction_ids.push(fraction.id);
+        }
+
+        let mut req = self.client.get(format!(
+            "{}/hausnummern/{house_number_id}/termine",
+            self.base_url
+        ));
+
+        for id in &fraction_ids {
+            req = req.query(&[("fraktion", id.to_string())]);
+        }
+
+        let pickups = self
+            .client
+            .fetch_json::<Vec<PickupResponse>>(req)
+            .await
+            .map_err(PortError::from_address_lookup)?;
+
+        let mut events = Vec::new();
+        let mut malformed = MalformedDates::default();
+
+        for pickup in pickups {
+            let Some(date) = malformed.parse(&pickup.date) else {
+                continue;
+            };
+
+            if !range.contains(date) {
+                continue;
+            }
+
+            let (note, fraction) = match pickup.district.as_ref() {
+                Some(district) => match fraction_names.get(&district.fraction_id) {
+                    Some((name, fraction)) => (Some(Arc::clone(name)), fraction.clone()),
+                    None => (
+                        None,
+                        Fraction::Other(format!("Fraction {}", district.fraction_id).into()),
+                    ),
+                },
+                None => (None, Fraction::Other("Unknown fraction".into())),
+            };
+
+            events.push(PickupEvent {
+                date,
+                fraction,
+                note,
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            });
+        }
+
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: malformed.into_inner(),
+        })
+    }
+}
+
+/// Mapper from `RegioIT` fraction names to [`Fraction`].
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    FractionMapper::german()
+}
+
+/// What `RegioIT` supports; [`plugin_for_town`] and [`plugin_for_county`] declare it.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    house_number_required: false,
+    multi_year: false,
+    fraction_filter: true,
+    bulky_waste_booking: false,
+};
+
+/// Build a plugin bundle for a town hosted on a `RegioIT` instance; searches take
+/// the current year from `clock`.
+#[must_use]
+pub fn plugin_for_town(
+    client: HttpClient,
+    mut town: RegioItTown,
+    clock: Arc<dyn Clock>,
+) -> CityPlugin {
+    town.meta = town.meta.with_capabilities(CAPABILITIES);
+    let meta = town.meta.clone();
+    let address_port =
+        Arc::new(RegioItAddressPort::for_town(client.clone(), town.clone()).with_clock(clock));
+    let schedule_port = Arc::new(RegioItSchedulePort::for_town(client, town));
+
+    CityPlugin {
+        meta,
+        address_port,
+        schedule_port,
+    }
+}
+
+/// Build a plugin bundle for a county whose towns share a `RegioIT` instance;
+/// searches take the current year from `clock`.
+#[must_use]
+pub fn plugin_for_county(
+    client: HttpClient,
+    mut county: RegioItCounty,
+    clock: Arc<dyn Clock>,
+) -> CityPlugin {
+    county.meta = county.meta.with_capabilities(CAPABILITIES);
+    let meta = county.meta.clone();
+    let address_port =
+        Arc::new(RegioItAddressPort::for_county(client.clone(), county.clone()).with_clock(clock));
+    let schedule_port = Arc::new(RegioItSchedulePort::for_county(client, county));
+
+    CityPlugin {
+        meta,
+        address_port,
+        schedule_port,
+    }
+}
@@ -0,0 +1,112 @@
+//! Generic `RegioIT` ports against canned responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use tonneli_core::{AddressPort, AddressSearch, CityMeta, PortError};
+use tonneli_provider_regioit::{RegioItAddressPort, RegioItTown, service_url};
+use tonneli_testkit::{client, serve};
+use wiremock::MockServer;
+
+use async_trait as _;
+use chrono as _;
+use futures as _;
+use serde as _;
+use tonneli_http as _;
+
+async fn upstream() -> MockServer {
+    serve([
+        ("/orte", include_str!("fixtures/orte.json")),
+        ("/orte/2/strassen", include_str!("fixtures/strassen.json")),
+        ("/orte/7/strassen", include_str!("fixtures/strassen.json")),
+        ("/strassen/10", include_str!("fixtures/strasse_10.json")),
+    ])
+    .await
+}
+
+fn town(server: &MockServer, name: &str) -> RegioItTown {
+    RegioItTown {
+        base_url: server.uri(),
+        ..RegioItTown::abfallnavi(
+            "beispiel",
+            name,
+            CityMeta::new("beispieldorf", "Beispieldorf"),
+        )
+    }
+}
+
+async fn labels(port: &RegioItAddressPort) -> Result<Vec<String>, PortError> {
+    Ok(port
+        .search(&AddressSearch::new("Hauptstraße", Some("1")), 10)
+        .await?
+        .into_iter()
+        .map(|address| address.label.to_string())
+        .collect())
+}
+
+#[test]
+fn abfallnavi_services_share_one_url_scheme() {
+    assert_eq!(
+        service_url("aachen"),
+        "https://aachen-abfallapp.regioit.de/abfall-app-aachen/rest",
+        "service name in host and path"
+    );
+}
+
+#[tokio::test]
+async fn search_discovers_the_ort() {
+    let server = upstream().await;
+    let port = RegioItAddressPort::for_town(client(), town(&server, "beispieldorf"));
+    assert_eq!(
+        labels(&port).await.expect("the town is listed"),
+        ["Hauptstraße 1"],
+        "the ort is found ignoring case"
+    );
+
+    let unknown = RegioItAddressPort::for_town(client(), town(&server, "Nirgendwo"));
+    let result = labels(&unknown).await;
+    assert!(
+        matches!(result, Err(PortError::Internal(_))),
+        "a town missing from /orte is a configuration error, got {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn known_ort_id_skips_discovery() {
+    let server = upstream().await;
+    let known = RegioItTown {
+        ort_id: Some(7),
+        ..town(&server, "Nirgendwo")
+    };
+    let port = RegioItAddressPort::for_town(client(), known);
+    assert_eq!(
+        labels(&port).await.expect("no /orte lookup is needed"),
+        ["Hauptstraße 1"],
+        "streets are listed for the configured ort id"
+    );
+}
+
+#[tokio::test]
+async fn health_check_lists_the_towns() {
+    let server = upstream().await;
+    let port = RegioItAddressPort::for_town(client(), town(&server, "beispieldorf"));
+    let probe = port.health_check().await;
+    assert!(
+        matches!(probe, Some(Ok(()))),
+        "/orte answers, got {probe:?}"
+    );
+
+    let down = RegioItAddressPort::for_town(
+        client(),
+        RegioItTown {
+            base_url: format!("{}/down", server.uri()),
+            ..town(&server, "beispieldorf")
+        },
+    );
+    let failed = down.health_check().await;
+    assert!(
+        matches!(failed, Some(Err(_))),
+        "a failing backend is reported, got {failed:?}"
+    );
+}
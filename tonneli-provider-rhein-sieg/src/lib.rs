@@ -0,0 +1,58 @@
+//! Provider implementation for the Rhein-Sieg-Kreis using the `RegioIT` waste collection API.
+//!
+//! The county's Abfallnavi instance serves all of its towns, so the provider is
+//! one city whose address searches name the town, e.g. “Siegburg, Kaiserstraße 12”.
+
+use std::sync::Arc;
+
+use tonneli_core::{
+    clock::{Clock, SystemClock},
+    fraction_map::FractionMapper,
+    model::{CityArea, CityMeta, Coordinates},
+    plugin::CityPlugin,
+};
+use tonneli_http::HttpClient;
+use tonneli_provider_regioit::{RegioItCounty, plugin_for_county};
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {chrono as _, tokio as _, tonneli_testkit as _, wiremock as _};
+
+/// Abfallnavi service of the Rhein-Sieg-Kreis, run by the RSAG.
+const SERVICE: &str = "rsag";
+
+/// The `RegioIT` county served by this provider.
+#[must_use]
+pub fn county() -> RegioItCounty {
+    RegioItCounty::abfallnavi(SERVICE, city_meta())
+}
+
+/// Mapper from Rhein-Sieg-Kreis fraction names to [`Fraction`](tonneli_core::model::Fraction).
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    tonneli_provider_regioit::fraction_mapper()
+}
+
+/// Build the plugin bundle for the Rhein-Sieg-Kreis provider.
+#[must_use]
+pub fn plugin(client: HttpClient) -> CityPlugin {
+    plugin_with_clock(client, Arc::new(SystemClock))
+}
+
+/// Build the plugin bundle with searches taking the current year from `clock`,
+/// e.g. the service's, see `ServiceBuilder::provider_with_clock`.
+#[must_use]
+pub fn plugin_with_clock(client: HttpClient, clock: Arc<dyn Clock>) -> CityPlugin {
+    plugin_for_county(client, county(), clock)
+}
+
+fn city_meta() -> CityMeta {
+    CityMeta::new("rhein-sieg", "Rhein-Sieg-Kreis")
+        .with_region("Nordrhein-Westfalen")
+        .with_locale("DE", "Europe/Berlin")
+        .with_area(
+            CityArea::new(Coordinates::new(50.7928, 7.2070), 30.0)
+                .with_postal_codes(["533", "536", "537", "538"]),
+        )
+        .with_source("RSAG", "https://www.rsag.de")
+}
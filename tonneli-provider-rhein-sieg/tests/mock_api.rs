@@ -0,0 +1,101 @@
+//! Rhein-Sieg-Kreis ports against canned `RegioIT` responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+use tonneli_core::{AddressPort, AddressSearch, FixedClock, PortError};
+use tonneli_provider_regioit::{RegioItAddressPort, RegioItCounty};
+use tonneli_testkit::{client, json};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+use tonneli_http as _;
+
+fn county(server: &MockServer) -> RegioItCounty {
+    RegioItCounty {
+        base_url: server.uri(),
+        ..tonneli_provider_rhein_sieg::county()
+    }
+}
+
+/// Mock server listing the county's towns, fetched once per port.
+async fn serve_orte() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orte"))
+        .respond_with(json(include_str!("fixtures/orte.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    server
+}
+
+/// Clock in 2025, the year the street lists are requested for.
+fn clock() -> Arc<FixedClock> {
+    let now = NaiveDate::from_ymd_opt(2025, 6, 1)
+        .and_then(|day| day.and_hms_opt(12, 0, 0))
+        .expect("valid test time");
+    Arc::new(FixedClock::new(now))
+}
+
+#[tokio::test]
+async fn search_selects_the_named_town() {
+    let server = serve_orte().await;
+    Mock::given(method("GET"))
+        .and(path("/orte/21/strassen"))
+        .and(query_param("jahr", "2025"))
+        .respond_with(json(include_str!("fixtures/strassen.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/strassen/210"))
+        .respond_with(json(include_str!("fixtures/strasse_210.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = RegioItAddressPort::for_county(client(), county(&server)).with_clock(clock());
+    let towns = port.towns().await.expect("towns against the mock");
+    assert_eq!(
+        towns,
+        ["Siegburg", "Troisdorf", "Königswinter"],
+        "the county's towns are offered for selection"
+    );
+
+    let query = AddressSearch::parse("siegburg, Kaiserstraße 12").with_town_prefix(&towns);
+    assert_eq!(
+        query.town.as_deref(),
+        Some("Siegburg"),
+        "the prefix names the town"
+    );
+    let labels = port
+        .search(&query, 10)
+        .await
+        .expect("search against the mock must succeed")
+        .into_iter()
+        .map(|address| address.label.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(labels, ["Kaiserstraße 12"], "streets come from Siegburg");
+}
+
+#[tokio::test]
+async fn search_without_town_lists_the_towns() {
+    let server = serve_orte().await;
+    let port = RegioItAddressPort::for_county(client(), county(&server)).with_clock(clock());
+
+    for query in [
+        AddressSearch::new("Kaiserstraße", Some("12")),
+        AddressSearch::new("Kaiserstraße", Some("12")).with_town(Some("Bonn")),
+    ] {
+        let result = port.search(&query, 10).await;
+        assert!(
+            matches!(&result, Err(PortError::TownRequired { towns }) if towns.len() == 3),
+            "a county search needs one of its towns, got {result:?}"
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Greater Cambridge Shared Waste Service, collecting for Cambridge and South Cambridgeshire.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use tonneli_core::{
+    model::{Capabilities, CityArea, CityMeta, Coordinates, DateRange},
+    plugin::CityPlugin,
+    ports::PortError,
+};
+use tonneli_http::HttpClient;
+
+use crate::{Council, CouncilAddress, CouncilCollection, plugin_for_council};
+
+/// Base URL of the public waste calendar API.
+pub const BASE_URL: &str = "https://servicelayer3c.azure-api.net/wastecalendar";
+
+/// Id of the Cambridge city.
+pub const CITY_ID: &str = "cambridge";
+
+/// Postcode of the Guildhall, listed by the health check.
+const PROBE_POSTCODE: &str = "CB23QJ";
+
+/// Property as returned by `/address/search`
+#[derive(Debug, Deserialize)]
+struct Property {
+    id: String,
+    #[serde(rename = "houseNumber", default)]
+    house_number: String,
+    #[serde(default)]
+    street: String,
+    #[serde(default)]
+    town: String,
+}
+
+/// Answer of `/collection/search/{uprn}`
+#[derive(Debug, Deserialize)]
+struct Collections {
+    collections: Vec<Collection>,
+}
+
+/// Collection day inside [`Collections`]
+#[derive(Debug, Deserialize)]
+struct Collection {
+    date: String,
+    #[serde(rename = "roundTypes")]
+    round_types: Vec<String>,
+}
+
+/// Readable name of a round type code.
+fn round_name(code: String) -> String {
+    match code.as_str() {
+        "DOMESTIC" => "General waste (black bin)".to_owned(),
+        "RECYCLE" => "Recycling (blue bin)".to_owned(),
+        "ORGANIC" => "Garden and food waste (green bin)".to_owned(),
+        _ => code,
+    }
+}
+
+/// Bin-day lookups of the Greater Cambridge Shared Waste Service.
+pub struct Cambridge {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+}
+
+impl Cambridge {
+    /// Create the council bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_owned(),
+            meta: CityMeta::new(CITY_ID, "Cambridge")
+                .with_region("England")
+                .with_locale("GB", "Europe/London")
+                .with_area(CityArea::new(Coordinates::new(52.2053, 0.1218), 8.0))
+                .with_source(
+                    "Greater Cambridge Shared Waste Service",
+                    "https://www.cambridge.gov.uk",
+                )
+                .with_capabilities(Capabilities {
+                    multi_year: true,
+                    ..Capabilities::default()
+                }),
+        }
+    }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[async_trait]
+impl Council for Cambridge {
+    fn meta(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn addresses(&self, postcode: &str) -> Result<Vec<CouncilAddress>, PortError> {
+        let base_url = &self.base_url;
+        let properties = self
+            .client
+            .fetch_json::<Vec<Property>>(
+                self.client
+                    .get(format!("{base_url}/address/search/"))
+                    .query(&[("postCode", postcode)]),
+            )
+            .await?;
+        Ok(properties
+            .into_iter()
+            .map(|property| {
+                let street = format!("{} {}", property.house_number, property.street);
+                let label = [street.trim(), property.town.trim()]
+                    .into_iter()
+                    .filter(|part| !part.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                CouncilAddress {
+                    uprn: property.id,
+                    label,
+                }
+            })
+            .collect())
+    }
+
+    /// Lists the properties of a known postcode.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        Some(self.addresses(PROBE_POSTCODE).await.map(drop))
+    }
+
+    async fn collections(
+        &self,
+        uprn: &str,
+        range: DateRange,
+    ) -> Result<Vec<CouncilCollection>, PortError> {
+        // Collections are counted from today; a range never has more than one per day.
+        let days = (range.end - range.start)
+            .num_days()
+            .saturating_add(1)
+            .max(1);
+        let base_url = &self.base_url;
+        let collections = self
+            .client
+            .fetch_json::<Collections>(
+                self.client
+                    .get(format!("{base_url}/collection/search/{uprn}/"))
+                    .query(&[("numberOfCollections", days)]),
+            )
+            .await
+            .map_err(PortError::from_address_lookup)?;
+        Ok(collections
+            .collections
+            .into_iter()
+            .map(|collection| CouncilCollection {
+                date: collection.date,
+                rounds: collection.round_types.into_iter().map(round_name).collect(),
+            })
+            .collect())
+    }
+}
+
+/// Build the plugin bundle for Cambridge.
+#[must_use]
+pub fn plugin(client: HttpClient) -> CityPlugin {
+    plugin_for_council(Arc::new(Cambridge::new(client)))
+}
@@ -0,0 +1,326 @@
+//! Providers for UK councils that look up bin days by postcode and UPRN.
+//!
+//! UK councils publish their rounds per property: a postcode lists the
+//! properties with their Unique Property Reference Number (UPRN), and the UPRN
+//! gives the upcoming collections. Each council has its own API; a [`Council`]
+//! implements these two lookups, and [`UkAddressPort`] and [`UkSchedulePort`]
+//! do the rest. The address search takes a postcode or, for users who know it,
+//! the UPRN itself.
+
+pub mod cambridge;
+pub mod reading;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use tonneli_core::{
+    dates::MalformedDates,
+    fraction_map::FractionMapper,
+    model::{Address, AddressId, CityMeta, DateRange, Fraction, PickupEvent},
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport},
+};
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {chrono as _, tokio as _, tonneli_testkit as _, wiremock as _};
+
+/// Longest UPRN; they have up to twelve digits.
+const MAX_UPRN_DIGITS: usize = 12;
+
+/// Round names of UK councils, in priority order.
+const ENGLISH_RULES: &[(&str, Fraction)] = &[
+    ("food", Fraction::Organic),
+    ("garden", Fraction::GardenWaste),
+    ("organic", Fraction::Organic),
+    ("compost", Fraction::Organic),
+    ("glass", Fraction::Glass),
+    ("paper", Fraction::Paper),
+    ("card", Fraction::Paper),
+    ("recycl", Fraction::Plastic),
+    ("refuse", Fraction::Residual),
+    ("rubbish", Fraction::Residual),
+    ("general", Fraction::Residual),
+    ("domestic", Fraction::Residual),
+    ("residual", Fraction::Residual),
+    ("landfill", Fraction::Residual),
+    ("bulky", Fraction::BulkyWaste),
+    ("christmas tree", Fraction::ChristmasTree),
+    ("textile", Fraction::Textiles),
+    ("electrical", Fraction::Electronics),
+    ("hazardous", Fraction::Hazardous),
+];
+
+/// Property listed for a postcode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CouncilAddress {
+    /// Unique Property Reference Number.
+    pub uprn: String,
+    /// Address as the council writes it, e.g. `"1 High Street, Reading"`.
+    pub label: String,
+}
+
+/// Collection day of a property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CouncilCollection {
+    /// Day of the collection, `YYYY-MM-DD` or any other form [`MalformedDates`] reads.
+    pub date: String,
+    /// Rounds collected that day, e.g. `"Recycling (blue bin)"`.
+    pub rounds: Vec<String>,
+}
+
+#[async_trait]
+/// Bin-day lookups of one council.
+pub trait Council: Send + Sync {
+    /// Metadata of the council's city.
+    fn meta(&self) -> &CityMeta;
+
+    /// Properties with the postcode, written without spaces in upper case (`RG11AA`).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortError`] when the council's API fails.
+    async fn addresses(&self, postcode: &str) -> Result<Vec<CouncilAddress>, PortError>;
+
+    /// Upcoming collections of the property, covering at least `range` where the API allows.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortError`] when the council's API fails.
+    async fn collections(
+        &self,
+        uprn: &str,
+        range: DateRange,
+    ) -> Result<Vec<CouncilCollection>, PortError>;
+
+    /// Probe the council's API with a cheap request; `None` when there is none.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        None
+    }
+}
+
+/// What an address search asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Lookup {
+    Postcode(String),
+    Uprn(String),
+}
+
+impl Lookup {
+    /// Read a postcode (`RG1 1AA`, `rg11aa`) or a UPRN from free-form input.
+    fn parse(input: &str) -> Option<Self> {
+        let compact = input
+            .chars()
+            .filter(|ch| !ch.is_whitespace())
+            .collect::<String>()
+            .to_ascii_uppercase();
+        if !compact.is_empty()
+            && compact.len() <= MAX_UPRN_DIGITS
+            && compact.bytes().all(|byte| byte.is_ascii_digit())
+        {
+            return Some(Self::Uprn(compact));
+        }
+        is_postcode(&compact).then_some(Self::Postcode(compact))
+    }
+}
+
+/// Whether `compact` has the shape of a UK postcode: an outward code of two to
+/// four characters starting with a letter, and an inward code of a digit and two letters.
+fn is_postcode(compact: &str) -> bool {
+    let Some(split) = compact.len().checked_sub(3) else {
+        return false;
+    };
+    let (outward, inward) = compact.split_at(split);
+    let inward = inward.as_bytes();
+    (2..=4).contains(&outward.len())
+        && outward.bytes().all(|byte| byte.is_ascii_alphanumeric())
+        && outward
+            .bytes()
+            .next()
+            .is_some_and(|byte| byte.is_ascii_alphabetic())
+        && matches!(inward, [digit, first, second]
+            if digit.is_ascii_digit() && first.is_ascii_alphabetic() && second.is_ascii_alphabetic())
+}
+
+/// Address search of a council: postcode or UPRN.
+pub struct UkAddressPort {
+    council: Arc<dyn Council>,
+}
+
+impl UkAddressPort {
+    /// Create an address port for the given council.
+    #[must_use]
+    pub fn new(council: Arc<dyn Council>) -> Self {
+        Self { council }
+    }
+}
+
+#[async_trait]
+impl AddressPort for UkAddressPort {
+    fn city(&self) -> &CityMeta {
+        self.council.meta()
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Free-form input splits the inward code of "RG1 1AA" off as house number.
+        let input = match query.house_number.as_deref() {
+            Some(rest) => format!("{} {rest}", query.street),
+            None => query.street.clone(),
+        };
+        let city = &self.council.meta().id;
+        let addresses = match Lookup::parse(&input) {
+            Some(Lookup::Postcode(postcode)) => self.council.addresses(&postcode).await?,
+            Some(Lookup::Uprn(uprn)) => vec![CouncilAddress {
+                label: format!("UPRN {uprn}"),
+                uprn,
+            }],
+            None => Vec::new(),
+        };
+        if addresses.is_empty() {
+            return Err(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            });
+        }
+        Ok(addresses
+            .into_iter()
+            .take(limit)
+            .map(|address| {
+                let label = Arc::<str>::from(address.label);
+                Address {
+                    id: AddressId(address.uprn),
+                    city: city.clone(),
+                    street: Arc::clone(&label),
+                    label,
+                    house_number: "".into(),
+                }
+            })
+            .collect())
+    }
+
+    /// Asks the council.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        self.council.health_check().await
+    }
+}
+
+/// Pickup schedule of a council.
+pub struct UkSchedulePort {
+    council: Arc<dyn Council>,
+    fractions: FractionMapper,
+}
+
+impl UkSchedulePort {
+    /// Create a schedule port for the given council.
+    #[must_use]
+    pub fn new(council: Arc<dyn Council>) -> Self {
+        Self {
+            council,
+            fractions: fraction_mapper(),
+        }
+    }
+}
+
+#[async_trait]
+impl SchedulePort for UkSchedulePort {
+    fn city(&self) -> &CityMeta {
+        self.council.meta()
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let uprn = address_id.0.trim();
+        if uprn.is_empty() || !uprn.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(PortError::InvalidAddressId);
+        }
+        let collections = self.council.collections(uprn, range).await?;
+
+        let mut events = Vec::new();
+        let mut malformed = MalformedDates::default();
+        for collection in collections {
+            let Some(date) = malformed.parse(&collection.date) else {
+                continue;
+            };
+            if !range.contains(date) {
+                continue;
+            }
+            events.extend(collection.rounds.into_iter().map(|round| PickupEvent {
+                date,
+                fraction: self.fractions.map(&round),
+                note: Some(round.into()),
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            }));
+        }
+        events.sort_by_key(|event| event.date);
+
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: malformed.into_inner(),
+        })
+    }
+}
+
+/// Mapper from UK round names to [`Fraction`].
+///
+/// Mixed dry recycling maps to [`Fraction::Plastic`], the closest match for a
+/// bin that takes packaging; rounds naming paper or glass keep their own fraction.
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    FractionMapper::empty().with_rules(ENGLISH_RULES)
+}
+
+/// Build a plugin bundle for a council.
+#[must_use]
+pub fn plugin_for_council(council: Arc<dyn Council>) -> CityPlugin {
+    CityPlugin {
+        meta: council.meta().clone(),
+        address_port: Arc::new(UkAddressPort::new(Arc::clone(&council))),
+        schedule_port: Arc::new(UkSchedulePort::new(council)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_postcodes_and_uprns() {
+        assert_eq!(
+            Lookup::parse("rg1 1aa"),
+            Some(Lookup::Postcode("RG11AA".to_owned())),
+            "postcodes are compacted and upper-cased"
+        );
+        assert_eq!(
+            Lookup::parse("EC1A 1BB"),
+            Some(Lookup::Postcode("EC1A1BB".to_owned())),
+            "outward codes may end in a letter"
+        );
+        assert_eq!(
+            Lookup::parse("310022781"),
+            Some(Lookup::Uprn("310022781".to_owned())),
+            "numbers are UPRNs"
+        );
+        for input in ["High Street", "RG1", "1234567890123", "RG1 AAA"] {
+            assert_eq!(Lookup::parse(input), None, "{input} is neither");
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Reading Borough Council.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use tonneli_core::{
+    model::{Capabilities, CityArea, CityMeta, Coordinates, DateRange},
+    plugin::CityPlugin,
+    ports::PortError,
+};
+use tonneli_http::HttpClient;
+
+use crate::{Council, CouncilAddress, CouncilCollection, plugin_for_council};
+
+/// Base URL of the council's public API.
+pub const BASE_URL: &str = "https://api.reading.gov.uk";
+
+/// Id of the Reading city.
+pub const CITY_ID: &str = "reading";
+
+/// Postcode of the Civic Offices, listed by the health check.
+const PROBE_POSTCODE: &str = "RG12LU";
+
+/// Answer of `/rbc/getaddresses/{postcode}`
+#[derive(Debug, Deserialize)]
+struct Addresses {
+    #[serde(rename = "Addresses", default)]
+    addresses: Vec<Property>,
+}
+
+/// Property inside [`Addresses`]
+#[derive(Debug, Deserialize)]
+struct Property {
+    #[serde(rename = "AccountSiteUprn")]
+    uprn: String,
+    #[serde(rename = "SiteShortAddress")]
+    short_address: String,
+}
+
+/// Answer of `/api/collections/{uprn}`
+#[derive(Debug, Deserialize)]
+struct Collections {
+    #[serde(default)]
+    collections: Vec<Collection>,
+}
+
+/// Round inside [`Collections`]
+#[derive(Debug, Deserialize)]
+struct Collection {
+    service: String,
+    date: String, // "DD/MM/YYYY HH:MM:SS"
+}
+
+/// `DD/MM/YYYY ...` as ISO date; anything else is passed on to be reported as malformed.
+fn iso_date(raw: &str) -> String {
+    let day = raw.split_whitespace().next().unwrap_or(raw);
+    match day.split('/').collect::<Vec<_>>().as_slice() {
+        [dd, mm, yyyy] => format!("{yyyy}-{mm}-{dd}"),
+        _ => raw.to_owned(),
+    }
+}
+
+/// Bin-day lookups of Reading Borough Council.
+pub struct Reading {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+}
+
+impl Reading {
+    /// Create the council bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_owned(),
+            meta: CityMeta::new(CITY_ID, "Reading")
+                .with_region("England")
+                .with_locale("GB", "Europe/London")
+                .with_area(CityArea::new(Coordinates::new(51.4543, -0.9781), 8.0))
+                .with_source("Reading Borough Council", "https://www.reading.gov.uk")
+                .with_capabilities(Capabilities {
+                    multi_year: true,
+                    ..Capabilities::default()
+                }),
+        }
+    }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[async_trait]
+impl Council for Reading {
+    fn meta(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn addresses(&self, postcode: &str) -> Result<Vec<CouncilAddress>, PortError> {
+        let base_url = &self.base_url;
+        let addresses = self
+            .client
+            .fetch_json::<Addresses>(
+                self.client
+                    .get(format!("{base_url}/rbc/getaddresses/{postcode}")),
+            )
+            .await
+            .map_err(PortError::from_address_lookup)?;
+        Ok(addresses
+            .addresses
+            .into_iter()
+            .map(|property| CouncilAddress {
+                uprn: property.uprn,
+                label: property.short_address,
+            })
+            .collect())
+    }
+
+    /// Lists the properties of a known postcode.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        Some(self.addresses(PROBE_POSTCODE).await.map(drop))
+    }
+
+    async fn collections(
+        &self,
+        uprn: &str,
+        _range: DateRange,
+    ) -> Result<Vec<CouncilCollection>, PortError> {
+        let base_url = &self.base_url;
+        let collections = self
+            .client
+            .fetch_json::<Collections>(
+                self.client
+                    .get(format!("{base_url}/api/collections/{uprn}")),
+            )
+            .await
+            .map_err(PortError::from_address_lookup)?;
+        Ok(collections
+            .collections
+            .into_iter()
+            .map(|collection| CouncilCollection {
+                date: iso_date(&collection.date),
+                rounds: vec![collection.service],
+            })
+            .collect())
+    }
+}
+
+/// Build the plugin bundle for Reading.
+#[must_use]
+pub fn plugin(client: HttpClient) -> CityPlugin {
+    plugin_for_council(Arc::new(Reading::new(client)))
+}
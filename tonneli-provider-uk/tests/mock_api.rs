@@ -0,0 +1,247 @@
+//! UK council ports against canned API responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use tonneli_core::{
+    AddressId, AddressPort, AddressSearch, DateRange, Fraction, PickupEvent, PortError,
+    SchedulePort,
+};
+use tonneli_provider_uk::cambridge::Cambridge;
+use tonneli_provider_uk::reading::Reading;
+use tonneli_provider_uk::{Council, UkAddressPort, UkSchedulePort};
+use tonneli_testkit::{client, json};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+use async_trait as _;
+use serde as _;
+use tonneli_http as _;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+fn march() -> DateRange {
+    DateRange {
+        start: date(2025, 3, 1),
+        end: date(2025, 3, 31),
+    }
+}
+
+/// Labels and ids of the addresses found for free-form `input`.
+async fn found(council: Arc<dyn Council>, input: &str) -> Vec<(String, String)> {
+    UkAddressPort::new(council)
+        .search(&AddressSearch::parse(input), 10)
+        .await
+        .expect("search against the mock must succeed")
+        .into_iter()
+        .map(|address| (address.label.to_string(), address.id.0))
+        .collect()
+}
+
+fn pair(label: &str, id: &str) -> (String, String) {
+    (label.to_owned(), id.to_owned())
+}
+
+/// Dates, fractions and notes of a schedule.
+fn summary(events: &[PickupEvent]) -> Vec<(NaiveDate, Fraction, &str)> {
+    events
+        .iter()
+        .map(|event| {
+            (
+                event.date,
+                event.fraction.clone(),
+                event.note.as_deref().unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn health_check_lists_a_known_postcode() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/address/search/"))
+        .and(query_param("postCode", "CB23QJ"))
+        .respond_with(json(include_str!("fixtures/cambridge_addresses.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rbc/getaddresses/RG12LU"))
+        .respond_with(json(include_str!("fixtures/reading_addresses.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let councils: [Arc<dyn Council>; 2] = [
+        Arc::new(Cambridge::new(client()).with_base_url(server.uri())),
+        Arc::new(Reading::new(client()).with_base_url(server.uri())),
+    ];
+    for council in councils {
+        let probe = UkAddressPort::new(council).health_check().await;
+        assert!(
+            matches!(probe, Some(Ok(()))),
+            "the postcode lookup answers, got {probe:?}"
+        );
+    }
+
+    let down = UkAddressPort::new(Arc::new(
+        Cambridge::new(client()).with_base_url(format!("{}/down", server.uri())),
+    ));
+    let failed = down.health_check().await;
+    assert!(
+        matches!(failed, Some(Err(_))),
+        "a failing backend is reported, got {failed:?}"
+    );
+}
+
+#[tokio::test]
+async fn cambridge_lists_properties_of_a_postcode() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/address/search/"))
+        .and(query_param("postCode", "CB12AD"))
+        .respond_with(json(include_str!("fixtures/cambridge_addresses.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let council = Arc::new(Cambridge::new(client()).with_base_url(server.uri()));
+
+    assert_eq!(
+        found(council, "cb1 2ad").await,
+        [
+            pair("1 Mill Road, Cambridge", "200004159750"),
+            pair("3 Mill Road, Cambridge", "200004159751"),
+            pair("The Old Mill", "200004159752"),
+        ],
+        "the postcode is normalized and every property listed"
+    );
+}
+
+#[tokio::test]
+async fn cambridge_maps_round_types() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/collection/search/200004159750/"))
+        .and(query_param("numberOfCollections", "31"))
+        .respond_with(json(include_str!("fixtures/cambridge_collections.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let port = UkSchedulePort::new(Arc::new(
+        Cambridge::new(client()).with_base_url(server.uri()),
+    ));
+
+    let events = port
+        .schedule(&AddressId("200004159750".to_owned()), march())
+        .await
+        .expect("schedule against the mock must succeed");
+    assert_eq!(
+        summary(&events),
+        vec![
+            (
+                date(2025, 3, 4),
+                Fraction::Residual,
+                "General waste (black bin)"
+            ),
+            (date(2025, 3, 11), Fraction::Plastic, "Recycling (blue bin)"),
+            (
+                date(2025, 3, 11),
+                Fraction::Organic,
+                "Garden and food waste (green bin)"
+            ),
+            (
+                date(2025, 3, 18),
+                Fraction::Residual,
+                "General waste (black bin)"
+            ),
+            (date(2025, 3, 18), Fraction::BulkyWaste, "BULKY"),
+        ],
+        "each round of a day is a pickup; codes without a name are kept"
+    );
+}
+
+#[tokio::test]
+async fn reading_reads_services_and_dates() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rbc/getaddresses/RG11AA"))
+        .respond_with(json(include_str!("fixtures/reading_addresses.json")))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/collections/310022781"))
+        .respond_with(json(include_str!("fixtures/reading_collections.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let council: Arc<dyn Council> = Arc::new(Reading::new(client()).with_base_url(server.uri()));
+
+    assert_eq!(
+        found(Arc::clone(&council), "RG1 1AA").await.first(),
+        Some(&pair("1, HIGH STREET, READING", "310022781")),
+        "properties are listed by UPRN"
+    );
+    let report = UkSchedulePort::new(council)
+        .schedule_report(&AddressId("310022781".to_owned()), march())
+        .await
+        .expect("schedule against the mock must succeed");
+    assert_eq!(
+        summary(&report.events),
+        vec![
+            (
+                date(2025, 3, 5),
+                Fraction::Residual,
+                "Domestic Waste Collection Service"
+            ),
+            (
+                date(2025, 3, 5),
+                Fraction::Organic,
+                "Food Waste Collection Service"
+            ),
+            (
+                date(2025, 3, 12),
+                Fraction::Plastic,
+                "Recycling Collection Service"
+            ),
+        ],
+        "services are mapped and sorted by date"
+    );
+    assert_eq!(
+        report.malformed_dates,
+        ["2025-02-31"],
+        "impossible dates are reported"
+    );
+}
+
+#[tokio::test]
+async fn uprns_need_no_lookup() {
+    let server = MockServer::start().await;
+    let council: Arc<dyn Council> = Arc::new(Reading::new(client()).with_base_url(server.uri()));
+
+    assert_eq!(
+        found(Arc::clone(&council), "310022781").await,
+        [pair("UPRN 310022781", "310022781")],
+        "a known UPRN is taken as it is"
+    );
+    let missing = UkAddressPort::new(Arc::clone(&council))
+        .search(&AddressSearch::parse("High Street 1"), 10)
+        .await;
+    assert!(
+        matches!(missing, Err(PortError::AddressNotFound { .. })),
+        "street names are not searched, got {missing:?}"
+    );
+    let invalid = UkSchedulePort::new(council)
+        .schedule(&AddressId("RG1 1AA".to_owned()), march())
+        .await;
+    assert!(
+        matches!(invalid, Err(PortError::InvalidAddressId)),
+        "ids are UPRNs, got {invalid:?}"
+    );
+}
@@ -0,0 +1,297 @@
+//! Provider implementation for Wuppertal using the pickup calendar of the AWG
+//! (Abfallwirtschaftsgesellschaft Wuppertal).
+//!
+//! Addresses are looked up by street and house number in one request; the
+//! pickups of an address are served per calendar year, with the container
+//! size where the AWG distinguishes them.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Datelike;
+use futures::future::try_join_all;
+use serde::Deserialize;
+
+use tonneli_core::{
+    dates::MalformedDates,
+    fraction_map::FractionMapper,
+    matching::StreetMatcher,
+    model::{
+        Address, AddressId, Capabilities, CityArea, CityMeta, Coordinates, DateRange, PickupEvent,
+    },
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport},
+};
+use tonneli_http::HttpClient;
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {insta as _, tokio as _, tonneli_testkit as _, wiremock as _};
+
+/// Base URL of the public calendar API.
+pub const BASE_URL: &str = "https://www.awg-wuppertal.de/api/abfallkalender";
+
+/// Id of the Wuppertal city.
+pub const CITY_ID: &str = "wuppertal";
+
+/// Street searched by [`WuppertalAddressPort::health_check`].
+const PROBE_STREET: &str = "Friedrich-Engels-Allee";
+
+/// Response from `/adressen`
+#[derive(Debug, Deserialize)]
+struct AddressList {
+    adressen: Vec<AwgAddress>,
+}
+
+/// Single address inside [`AddressList`]
+#[derive(Debug, Deserialize)]
+struct AwgAddress {
+    id: String,
+    strasse: String,
+    #[serde(default)]
+    hausnummer: String,
+}
+
+/// Pickup as returned by `/termine`
+#[derive(Debug, Deserialize)]
+struct Pickup {
+    datum: String, // "DD.MM.YYYY"
+    abfallart: String,
+    #[serde(default)]
+    behaelter: Option<String>,
+}
+
+/// Address search implementation for Wuppertal.
+pub struct WuppertalAddressPort {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+}
+
+impl WuppertalAddressPort {
+    /// Create a new address port bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_owned(),
+            meta: city_meta(),
+        }
+    }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Addresses of streets starting with `street`, from `/adressen`.
+    async fn addresses(&self, street: &str, house_number: &str) -> Result<AddressList, PortError> {
+        let base_url = &self.base_url;
+        Ok(self
+            .client
+            .fetch_json::<AddressList>(
+                self.client
+                    .get(format!("{base_url}/adressen"))
+                    .query(&[("strasse", street), ("hausnummer", house_number)]),
+            )
+            .await?)
+    }
+}
+
+#[async_trait]
+impl AddressPort for WuppertalAddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    /// Searches the addresses of a well-known street.
+    async fn health_check(&self) -> Option<Result<(), PortError>> {
+        Some(self.addresses(PROBE_STREET, "").await.map(drop))
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let house_number = query
+            .house_number
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or_default();
+        let found = self.addresses(query.street.trim(), house_number).await?;
+
+        // The AWG matches street prefixes; keep the streets the user meant.
+        let matcher = StreetMatcher::new(&query.street, query.fuzzy);
+        let mut shared_street: Option<Arc<str>> = None;
+        let addresses = found
+            .adressen
+            .into_iter()
+            .filter(|address| matcher.matches(&address.strasse))
+            .take(limit)
+            .map(|address| {
+                let street = match &shared_street {
+                    Some(shared) if **shared == *address.strasse => Arc::clone(shared),
+                    _ => Arc::clone(shared_street.insert(Arc::from(address.strasse.as_str()))),
+                };
+                let label = if address.hausnummer.is_empty() {
+                    address.strasse
+                } else {
+                    format!("{} {}", address.strasse, address.hausnummer)
+                };
+                Address {
+                    id: AddressId(address.id),
+                    city: self.meta.id.clone(),
+                    label: label.into(),
+                    street,
+                    house_number: address.hausnummer.into(),
+                }
+            })
+            .collect::<Vec<_>>();
+        if addresses.is_empty() {
+            return Err(PortError::AddressNotFound {
+                suggestions: Vec::new(),
+                status: None,
+            });
+        }
+        Ok(addresses)
+    }
+}
+
+/// Pickup schedule implementation for Wuppertal.
+pub struct WuppertalSchedulePort {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+    fractions: FractionMapper,
+}
+
+impl WuppertalSchedulePort {
+    /// Create a new schedule port bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_owned(),
+            meta: city_meta(),
+            fractions: fraction_mapper(),
+        }
+    }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    async fn pickups(&self, address: &str, year: i32) -> Result<Vec<Pickup>, PortError> {
+        let base_url = &self.base_url;
+        Ok(self
+            .client
+            .fetch_json::<Vec<Pickup>>(
+                self.client
+                    .get(format!("{base_url}/termine"))
+                    .query(&[("adresse", address), ("jahr", &year.to_string())]),
+            )
+            .await?)
+    }
+}
+
+#[async_trait]
+impl SchedulePort for WuppertalSchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        let address = address_id.0.trim();
+        if address.is_empty() {
+            return Err(PortError::InvalidAddressId);
+        }
+        let years = try_join_all(
+            (range.start.year()..=range.end.year()).map(|year| self.pickups(address, year)),
+        )
+        .await?;
+
+        let mut events = Vec::new();
+        let mut malformed = MalformedDates::default();
+        for pickup in years.into_iter().flatten() {
+            let Some(date) = malformed.parse(&pickup.datum) else {
+                continue;
+            };
+            if !range.contains(date) {
+                continue;
+            }
+            events.push(PickupEvent {
+                date,
+                fraction: self.fractions.map(&pickup.abfallart),
+                note: Some(pickup.abfallart.into()),
+                container: pickup
+                    .behaelter
+                    .filter(|container| !container.trim().is_empty())
+                    .map(Arc::from),
+                correction: None,
+                holiday: None,
+                predicted: false,
+            });
+        }
+        events.sort_by_key(|event| event.date);
+
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: malformed.into_inner(),
+        })
+    }
+}
+
+/// Mapper from AWG waste types to [`Fraction`](tonneli_core::model::Fraction).
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    FractionMapper::german()
+}
+
+/// Build the plugin bundle for the Wuppertal provider.
+#[must_use]
+pub fn plugin(client: HttpClient) -> CityPlugin {
+    let address_port = Arc::new(WuppertalAddressPort::new(client.clone()));
+    let schedule_port = Arc::new(WuppertalSchedulePort::new(client));
+
+    CityPlugin {
+        meta: city_meta(),
+        address_port,
+        schedule_port,
+    }
+}
+
+fn city_meta() -> CityMeta {
+    CityMeta::new(CITY_ID, "Wuppertal")
+        .with_region("Nordrhein-Westfalen")
+        .with_locale("DE", "Europe/Berlin")
+        .with_area(
+            CityArea::new(Coordinates::new(51.2562, 7.1508), 12.0)
+                .with_postal_codes(["421", "422", "423"]),
+        )
+        .with_source(
+            "AWG Abfallwirtschaftsgesellschaft Wuppertal",
+            "https://www.awg-wuppertal.de",
+        )
+        .with_capabilities(Capabilities {
+            multi_year: true,
+            ..Capabilities::default()
+        })
+}
@@ -0,0 +1,51 @@
+//! Snapshots of the addresses and events produced from captured AWG responses.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use insta::assert_json_snapshot;
+use tonneli_core::{AddressId, AddressPort, AddressSearch, DateRange, SchedulePort};
+use tonneli_provider_wuppertal::{WuppertalAddressPort, WuppertalSchedulePort};
+use tonneli_testkit::{client, serve};
+use wiremock::MockServer;
+
+use async_trait as _;
+use futures as _;
+use serde as _;
+use tonneli_http as _;
+
+async fn upstream() -> MockServer {
+    let routes = [
+        ("/adressen", include_str!("fixtures/upstream/adressen.json")),
+        ("/termine", include_str!("fixtures/upstream/termine.json")),
+    ];
+    serve(routes).await
+}
+
+#[tokio::test]
+async fn search_snapshot() {
+    let server = upstream().await;
+    let port = WuppertalAddressPort::new(client()).with_base_url(server.uri());
+    let addresses = port
+        .search(&AddressSearch::new("Neumarkt", None::<String>), 10)
+        .await
+        .expect("search against captured responses must succeed");
+    assert_json_snapshot!(addresses);
+}
+
+#[tokio::test]
+async fn schedule_snapshot() {
+    let server = upstream().await;
+    let port = WuppertalSchedulePort::new(client()).with_base_url(server.uri());
+    let range = DateRange {
+        start: NaiveDate::from_ymd_opt(2025, 3, 1).expect("valid date"),
+        end: NaiveDate::from_ymd_opt(2025, 3, 31).expect("valid date"),
+    };
+    let events = port
+        .schedule(&AddressId("2204118".to_owned()), range)
+        .await
+        .expect("schedule against captured responses must succeed");
+    assert_json_snapshot!(events);
+}
@@ -0,0 +1,98 @@
+//! Contract test against the live AWG Wuppertal API, enabled with `TONNELI_LIVE_TESTS=1`.
+//!
+//! Looks up a known address and its pickups for the current year, records the
+//! responses into `tests/fixtures/live` and checks that the ports still parse them.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::{Datelike, NaiveDate, Utc};
+use tonneli_core::{AddressPort, AddressSearch, DateRange, SchedulePort};
+use tonneli_provider_wuppertal::{BASE_URL, WuppertalAddressPort, WuppertalSchedulePort};
+use tonneli_testkit::{Recording, client, live_tests_enabled};
+
+use async_trait as _;
+use futures as _;
+use insta as _;
+use serde as _;
+use tonneli_http as _;
+use wiremock as _;
+
+const KNOWN_STREET: &str = "Neumarkt";
+const KNOWN_HOUSE_NUMBER: &str = "1";
+
+#[tokio::test]
+async fn live_api_still_parses() {
+    if !live_tests_enabled() {
+        return;
+    }
+    let mut recording = Recording::new(
+        BASE_URL,
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/live"),
+    );
+
+    let found = recording
+        .fetch(
+            "adressen",
+            "/adressen",
+            &[
+                ("strasse", KNOWN_STREET),
+                ("hausnummer", KNOWN_HOUSE_NUMBER),
+            ],
+        )
+        .await
+        .expect("address lookup must be recorded");
+    let address_id = found
+        .get("adressen")
+        .and_then(|addresses| addresses.get(0))
+        .and_then(|address| address.get("id"))
+        .and_then(|id| id.as_str())
+        .expect("known address must be found")
+        .to_owned();
+
+    // The current year keeps the schedule to a single request.
+    let year = Utc::now().year();
+    recording
+        .fetch(
+            "termine",
+            "/termine",
+            &[("adresse", &address_id), ("jahr", &year.to_string())],
+        )
+        .await
+        .expect("pickups must be recorded");
+
+    let server = recording.replay().await;
+    let addresses = WuppertalAddressPort::new(client())
+        .with_base_url(server.uri())
+        .search(
+            &AddressSearch::new(KNOWN_STREET, Some(KNOWN_HOUSE_NUMBER.to_owned())),
+            10,
+        )
+        .await
+        .expect("recorded addresses must parse");
+    let address = addresses.first().expect("the known address must be found");
+    assert_eq!(
+        address.id.0, address_id,
+        "the id must be the AWG address id"
+    );
+
+    let range = DateRange {
+        start: NaiveDate::from_ymd_opt(year, 1, 1).expect("valid date"),
+        end: NaiveDate::from_ymd_opt(year, 12, 31).expect("valid date"),
+    };
+    let report = WuppertalSchedulePort::new(client())
+        .with_base_url(server.uri())
+        .schedule_report(&address.id, range)
+        .await
+        .expect("recorded pickups must parse");
+    assert!(
+        report.malformed_dates.is_empty(),
+        "all recorded dates must parse: {:?}",
+        report.malformed_dates
+    );
+    assert!(
+        !report.events.is_empty(),
+        "a known address has pickups this year"
+    );
+}
@@ -0,0 +1,141 @@
+//! Wuppertal ports against canned AWG responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use tonneli_core::{AddressId, AddressPort, AddressSearch, DateRange, Fraction, SchedulePort};
+use tonneli_provider_wuppertal::{WuppertalAddressPort, WuppertalSchedulePort};
+use tonneli_testkit::{client, json};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+use async_trait as _;
+use futures as _;
+use insta as _;
+use serde as _;
+use tonneli_http as _;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+#[tokio::test]
+async fn health_check_searches_a_known_street() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/adressen"))
+        .and(query_param("strasse", "Friedrich-Engels-Allee"))
+        .and(query_param("hausnummer", ""))
+        .respond_with(json(include_str!("fixtures/adressen.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = WuppertalAddressPort::new(client()).with_base_url(server.uri());
+    let probe = port.health_check().await;
+    assert!(
+        matches!(probe, Some(Ok(()))),
+        "the address search answers, got {probe:?}"
+    );
+
+    let down = WuppertalAddressPort::new(client()).with_base_url(format!("{}/down", server.uri()));
+    let failed = down.health_check().await;
+    assert!(
+        matches!(failed, Some(Err(_))),
+        "a failing backend is reported, got {failed:?}"
+    );
+}
+
+#[tokio::test]
+async fn search_keeps_matching_streets() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/adressen"))
+        .and(query_param("strasse", "Friedrich-Engels-Allee"))
+        .and(query_param("hausnummer", "12"))
+        .respond_with(json(include_str!("fixtures/adressen.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = WuppertalAddressPort::new(client()).with_base_url(server.uri());
+    let addresses = port
+        .search(
+            &AddressSearch::new("Friedrich-Engels-Allee", Some("12")),
+            10,
+        )
+        .await
+        .expect("search against the mock must succeed");
+    let found = addresses
+        .iter()
+        .map(|address| (&*address.label, address.id.0.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            ("Friedrich-Engels-Allee 12", "4711"),
+            ("Friedrich-Engels-Allee 12a", "4712"),
+        ],
+        "prefix matches of other streets are dropped"
+    );
+}
+
+#[tokio::test]
+async fn schedule_spans_years_and_keeps_containers() {
+    let server = MockServer::start().await;
+    for (year, body) in [
+        ("2025", include_str!("fixtures/termine_2025.json")),
+        ("2026", include_str!("fixtures/termine_2026.json")),
+    ] {
+        Mock::given(method("GET"))
+            .and(path("/termine"))
+            .and(query_param("adresse", "4711"))
+            .and(query_param("jahr", year))
+            .respond_with(json(body))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let port = WuppertalSchedulePort::new(client()).with_base_url(server.uri());
+    let range = DateRange {
+        start: date(2025, 3, 1),
+        end: date(2026, 1, 5),
+    };
+    let report = port
+        .schedule_report(&AddressId("4711".to_owned()), range)
+        .await
+        .expect("schedule against the mock must succeed");
+
+    let events = report
+        .events
+        .iter()
+        .map(|event| {
+            (
+                event.date,
+                event.fraction.clone(),
+                event.container.as_deref(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (date(2025, 3, 4), Fraction::Residual, Some("120 l")),
+            (date(2025, 3, 4), Fraction::Residual, Some("1100 l")),
+            (date(2025, 3, 7), Fraction::Plastic, None),
+            (date(2025, 3, 12), Fraction::Paper, None),
+            (date(2025, 3, 18), Fraction::Organic, None),
+            (date(2025, 4, 1), Fraction::Residual, None),
+            (date(2026, 1, 2), Fraction::Residual, Some("120 l")),
+        ],
+        "both years are merged, blank containers dropped"
+    );
+    assert_eq!(
+        report.malformed_dates,
+        ["31.02.2025"],
+        "impossible dates are reported"
+    );
+}
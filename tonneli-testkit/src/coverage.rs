@@ -0,0 +1,70 @@
+//! Checks that a provider's fraction vocabulary maps onto known fractions.
+//!
+//! Labels that fall through to [`Fraction::Other`] render as generic “Other” rows, so
+//! new bin types should be caught here and either mapped or listed as expected.
+
+use std::fs;
+use std::io::{Error as IoError, ErrorKind};
+use std::path::Path;
+
+use serde_json::Value;
+use tonneli_core::{Fraction, FractionMapper};
+
+/// Labels from `vocabulary` that `mapper` turns into [`Fraction::Other`] although they
+/// are not listed in `expected_other`, in vocabulary order without repetitions.
+#[must_use]
+pub fn unexpected_other_fractions<'label>(
+    mapper: &FractionMapper,
+    vocabulary: impl IntoIterator<Item = &'label str>,
+    expected_other: &[&str],
+) -> Vec<&'label str> {
+    let mut unexpected = Vec::new();
+    for label in vocabulary {
+        if matches!(mapper.map(label), Fraction::Other(_))
+            && !expected_other.contains(&label)
+            && !unexpected.contains(&label)
+        {
+            unexpected.push(label);
+        }
+    }
+    unexpected
+}
+
+/// String values of every `key` field anywhere in a JSON fixture.
+///
+/// A missing file yields no labels, so optional fixtures such as recorded live
+/// responses can be included unconditionally.
+///
+/// # Errors
+///
+/// If the file exists but cannot be read or is not JSON.
+pub fn fixture_labels(path: impl AsRef<Path>, key: &str) -> Result<Vec<String>, IoError> {
+    let body = match fs::read(path) {
+        Ok(body) => body,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let json = serde_json::from_slice::<Value>(&body)?;
+    let mut labels = Vec::new();
+    collect_labels(&json, key, &mut labels);
+    Ok(labels)
+}
+
+fn collect_labels(json: &Value, key: &str, labels: &mut Vec<String>) {
+    match json {
+        Value::Array(items) => {
+            for item in items {
+                collect_labels(item, key, labels);
+            }
+        }
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                match value {
+                    Value::String(label) if name == key => labels.push(label.clone()),
+                    _ => collect_labels(value, key, labels),
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,17 @@
+//! Test helpers shared by the provider crates: canned responses served by wiremock,
+//! live contract tests that record upstream responses, replay of recorded responses
+//! and fraction coverage checks.
+
+/// Fraction vocabulary coverage of provider mappers.
+pub mod coverage;
+/// Live API contract tests and response recording.
+pub mod live;
+/// Canned responses served by a local wiremock server.
+pub mod mock;
+/// Replaying recorded live responses.
+pub mod replay;
+
+pub use coverage::*;
+pub use live::*;
+pub use mock::*;
+pub use replay::*;
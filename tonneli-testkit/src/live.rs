@@ -0,0 +1,114 @@
+//! Contract tests against the real upstream APIs.
+//!
+//! Live tests return early unless [`LIVE_TESTS_ENV`] is set to `1`. When enabled they
+//! fetch each response once through a [`Recording`], which saves it as a fixture and
+//! replays it to the provider ports through wiremock, so the recorded fixtures are
+//! exactly what the ports were checked against.
+
+use std::env;
+use std::fs;
+use std::io::Error as IoError;
+use std::path::PathBuf;
+
+use serde_json::{Error as JsonError, Value};
+use thiserror::Error;
+use tonneli_http::{HttpClient, HttpError};
+use wiremock::MockServer;
+
+use crate::mock::{client, serve};
+
+/// Environment variable enabling live tests.
+pub const LIVE_TESTS_ENV: &str = "TONNELI_LIVE_TESTS";
+
+/// Whether live tests were requested via [`LIVE_TESTS_ENV`].
+#[must_use]
+pub fn live_tests_enabled() -> bool {
+    env::var(LIVE_TESTS_ENV).is_ok_and(|value| value == "1")
+}
+
+/// Reasons a live response could not be recorded; each one fails the contract test.
+#[derive(Debug, Error)]
+pub enum RecordError {
+    /// The live request failed.
+    #[error("request to {url} failed: {source}")]
+    Http {
+        /// Requested URL.
+        url: String,
+        /// Underlying HTTP error.
+        source: HttpError,
+    },
+    /// The live response is not JSON.
+    #[error("response of {url} is not JSON: {source}")]
+    Json {
+        /// Requested URL.
+        url: String,
+        /// Underlying parse error.
+        source: JsonError,
+    },
+    /// The fixture could not be written.
+    #[error("writing fixture failed: {0}")]
+    Io(#[from] IoError),
+}
+
+/// Responses fetched from a live API, saved as fixtures and replayable through wiremock.
+pub struct Recording {
+    client: HttpClient,
+    base_url: String,
+    fixtures: PathBuf,
+    routes: Vec<(String, Vec<u8>)>,
+}
+
+impl Recording {
+    /// Record responses of the API at `base_url` into the `fixtures` directory.
+    #[must_use]
+    pub fn new(base_url: impl Into<String>, fixtures: impl Into<PathBuf>) -> Self {
+        Self {
+            client: client(),
+            base_url: base_url.into(),
+            fixtures: fixtures.into(),
+            routes: Vec::new(),
+        }
+    }
+
+    /// Fetch `path` from the live API and save the body as `<name>.json`.
+    ///
+    /// Returns the parsed body so tests can pick ids for follow-up requests.
+    ///
+    /// # Errors
+    ///
+    /// If the request fails, the body is not JSON or the fixture cannot be written.
+    pub async fn fetch(
+        &mut self,
+        name: &str,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Value, RecordError> {
+        let url = format!("{}{path}", self.base_url);
+        let body = self
+            .client
+            .fetch_bytes(self.client.get(&url).query(query))
+            .await
+            .map_err(|source| RecordError::Http {
+                url: url.clone(),
+                source,
+            })?;
+        let value =
+            serde_json::from_slice(&body).map_err(|source| RecordError::Json { url, source })?;
+
+        fs::create_dir_all(&self.fixtures)?;
+        fs::write(self.fixtures.join(format!("{name}.json")), &body)?;
+
+        self.routes.push((path.to_owned(), body));
+        Ok(value)
+    }
+
+    /// Serve everything fetched so far from a local mock server.
+    pub async fn replay(&self) -> MockServer {
+        serve(
+            self.routes
+                .iter()
+                .map(|(path, body)| (path.as_str(), body.clone())),
+        )
+        .await
+    }
+}
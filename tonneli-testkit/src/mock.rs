@@ -0,0 +1,40 @@
+//! Canned responses served by a local wiremock server.
+
+use tonneli_http::{HttpClient, HttpConfig};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// HTTP client with the default configuration, as providers get it from the service.
+///
+/// # Panics
+///
+/// If the default configuration cannot build a client.
+#[must_use]
+pub fn client() -> HttpClient {
+    HttpClient::new(&HttpConfig::default()).expect("default config must build a client")
+}
+
+/// `200 OK` response with a JSON body.
+#[must_use]
+pub fn json(body: impl Into<Vec<u8>>) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "application/json")
+}
+
+/// Start a mock server answering `GET` requests for each path with its JSON body.
+///
+/// Query parameters are not matched; use one route per path.
+pub async fn serve<P, B>(routes: impl IntoIterator<Item = (P, B)>) -> MockServer
+where
+    P: AsRef<str>,
+    B: Into<Vec<u8>>,
+{
+    let server = MockServer::start().await;
+    for (route, body) in routes {
+        Mock::given(method("GET"))
+            .and(path(route.as_ref()))
+            .respond_with(json(body))
+            .mount(&server)
+            .await;
+    }
+    server
+}
@@ -0,0 +1,19 @@
+//! Provider tests against responses recorded from the live APIs.
+//!
+//! [`fixture_client`] answers every request from a fixture directory, so tests run
+//! the real provider code against realistic data without network access. Run them
+//! with [`RECORD_FIXTURES_ENV`] set to `1` to refresh the fixtures from the live API.
+
+use std::path::PathBuf;
+
+pub use tonneli_http::RECORD_FIXTURES_ENV;
+use tonneli_http::{Fixtures, HttpClient};
+
+use crate::mock::client;
+
+/// Client replaying the responses recorded in `dir`, or recording them there when
+/// [`RECORD_FIXTURES_ENV`] is `1`.
+#[must_use]
+pub fn fixture_client(dir: impl Into<PathBuf>) -> HttpClient {
+    client().with_fixtures(Fixtures::from_env(dir))
+}
@@ -1,25 +1,45 @@
 use std::sync::Arc;
 
-use chrono::{Duration, Local};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use tonneli_config::Config;
 use tonneli_core::{
-    model::{Address, CityId, DateRange, PickupEvent},
-    service::TonneliService,
+    clock::Clock,
+    favorites::Favorite,
+    model::{Address, CityId, CityMeta, DateRange, PickupEvent},
+    reminder::ReminderSettings,
 };
+use tonneli_i18n::Localizer;
+
+use crate::checklist::Checklist;
+use crate::logging::LogBuffer;
+use crate::settings::SettingsRow;
+
+/// How long `z` holds back the reminders of a pickup.
+pub(crate) const SNOOZE_HOURS: i64 = 2;
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum Screen {
     CitySelect,
     AddressSearch,
     ScheduleView,
+    Settings,
+    Log,
 }
 
+/// View state of the TUI; the service lives in the event loop, so this can be built without one.
 pub(crate) struct App {
-    pub service: Arc<TonneliService>,
+    pub clock: Arc<dyn Clock>,
+    /// Texts in the user's language; English until the caller sets it.
+    pub l10n: Localizer,
+    /// Date the screens are drawn for, see [`App::tick`].
+    pub today: NaiveDate,
 
     pub screen: Screen,
     pub cities: Vec<(CityId, String)>,
     pub city_list_index: usize,
     pub selected_city: Option<CityId>,
+    /// Details of the city whose schedule is shown, for its time zone.
+    pub city_meta: Option<CityMeta>,
 
     pub address_input: String,
     pub address_results: Vec<Address>,
@@ -27,36 +47,84 @@ pub(crate) struct App {
     pub selected_address: Option<Address>,
 
     pub pickups: Vec<PickupEvent>,
+    /// Publisher of the shown schedule, credited below it.
+    pub source: Option<String>,
+    pub pickup_list_index: usize,
+    pub checklist: Checklist,
+
+    pub config: Config,
+    pub reminders: ReminderSettings,
+    pub settings_rows: Vec<SettingsRow>,
+    pub settings_index: usize,
+
+    pub logs: LogBuffer,
+    /// Lines scrolled back from the end of the log.
+    pub log_scroll: usize,
+    /// Screen to return to when leaving the log.
+    pub log_return: Screen,
 
     pub is_loading: bool,
     pub error_message: Option<String>,
 }
 
 impl App {
-    pub(crate) fn new(service: Arc<TonneliService>) -> Self {
-        let cities = service.cities();
+    pub(crate) fn new(
+        clock: Arc<dyn Clock>,
+        cities: Vec<(CityId, String)>,
+        checklist: Checklist,
+        config: Config,
+        reminders: ReminderSettings,
+    ) -> Self {
+        let city_list_index = default_city_index(&cities, &config);
         Self {
-            service,
+            today: clock.today(),
+            clock,
+            l10n: Localizer::default(),
             screen: Screen::CitySelect,
             cities,
-            city_list_index: 0,
+            city_list_index,
             selected_city: None,
+            city_meta: None,
             address_input: String::new(),
             address_results: Vec::new(),
             address_list_index: 0,
             selected_address: None,
             pickups: Vec::new(),
+            source: None,
+            pickup_list_index: 0,
+            checklist,
+            config,
+            reminders,
+            settings_rows: SettingsRow::all(),
+            settings_index: 0,
+            logs: LogBuffer::default(),
+            log_scroll: 0,
+            log_return: Screen::CitySelect,
             is_loading: false,
             error_message: None,
         }
     }
 
-    pub(crate) fn current_range() -> DateRange {
-        let today = Local::now().date_naive();
-        DateRange {
-            start: today,
-            end: today + Duration::days(60),
-        }
+    /// Catch up with the clock, so "today" stays current when the TUI is left open past midnight.
+    ///
+    /// Midnight is the one of the shown city's time zone, where it is known.
+    pub(crate) fn tick(&mut self) {
+        self.today = self
+            .city_meta
+            .as_ref()
+            .map_or_else(|| self.clock.today(), |meta| self.clock.today_in(meta));
+    }
+
+    /// Current time in the shown city's time zone, where it is known; snoozes and
+    /// reminders are meant in it.
+    pub(crate) fn now(&self) -> NaiveDateTime {
+        self.city_meta
+            .as_ref()
+            .map_or_else(|| self.clock.now(), |meta| self.clock.now_in(meta))
+    }
+
+    pub(crate) fn current_range(&self) -> DateRange {
+        DateRange::next_days(self.today, self.config.frontends().tui.range_days)
     }
 
     pub(crate) fn select_current_city(&mut self) {
@@ -66,10 +134,100 @@ impl App {
         }
     }
 
+    /// Open the address search of the configured default city; `false` when it is not registered.
+    pub(crate) fn select_default_city(&mut self) -> bool {
+        let Some(index) = self
+            .config
+            .frontends()
+            .tui
+            .default_city
+            .as_deref()
+            .and_then(|default| self.cities.iter().position(|(id, _)| id.0 == default))
+        else {
+            return false;
+        };
+        self.city_list_index = index;
+        self.select_current_city();
+        true
+    }
+
     pub(crate) fn select_current_address(&mut self) -> Option<Address> {
         let addr = self.address_results.get(self.address_list_index).cloned()?;
         self.selected_address = Some(addr.clone());
         self.screen = Screen::ScheduleView;
         Some(addr)
     }
+
+    /// Show the saved address as the only search result, ready for its schedule to load.
+    ///
+    /// Returns `false` when the favorite's city is no longer registered.
+    pub(crate) fn open_favorite(&mut self, favorite: Favorite) -> bool {
+        let Some(index) = self.cities.iter().position(|(id, _)| *id == favorite.city) else {
+            return false;
+        };
+        self.city_list_index = index;
+        self.selected_city = Some(favorite.city);
+        self.address_input = favorite.address.label.to_string();
+        self.address_results = vec![favorite.address];
+        self.address_list_index = 0;
+        self.screen = Screen::AddressSearch;
+        true
+    }
+
+    /// Show `pickups`, sorted by date as the service returns them.
+    pub(crate) fn set_pickups(&mut self, pickups: Vec<PickupEvent>) {
+        self.pickups = pickups;
+        self.pickup_list_index = 0;
+    }
+
+    /// Toggle the checkmark of the highlighted pickup and persist the checklist.
+    pub(crate) async fn toggle_current_pickup(&mut self) -> anyhow::Result<()> {
+        let Some(address) = &self.selected_address else {
+            return Ok(());
+        };
+        let Some(pickup) = self.pickups.get(self.pickup_list_index) else {
+            return Ok(());
+        };
+        self.checklist.toggle(address, pickup).await?;
+        Ok(())
+    }
+
+    /// Snooze reminders of the highlighted pickup for [`SNOOZE_HOURS`].
+    pub(crate) async fn snooze_current_pickup(&mut self) -> anyhow::Result<()> {
+        let Some(address) = &self.selected_address else {
+            return Ok(());
+        };
+        let Some(pickup) = self.pickups.get(self.pickup_list_index) else {
+            return Ok(());
+        };
+        let until = self.now() + Duration::hours(SNOOZE_HOURS);
+        self.checklist.snooze(address, pickup, until).await
+    }
+
+    /// Show the log screen, or leave it again.
+    pub(crate) fn toggle_log(&mut self) {
+        if matches!(self.screen, Screen::Log) {
+            self.screen = self.log_return;
+        } else {
+            self.log_return = self.screen;
+            self.log_scroll = 0;
+            self.screen = Screen::Log;
+        }
+    }
+
+    /// Persist the reminder settings to the shared config file.
+    pub(crate) fn save_reminders(&mut self) -> anyhow::Result<()> {
+        Ok(self.config.save_reminders(&self.reminders)?)
+    }
+}
+
+/// Position of the configured default city, or the first city.
+fn default_city_index(cities: &[(CityId, String)], config: &Config) -> usize {
+    config
+        .frontends()
+        .tui
+        .default_city
+        .as_deref()
+        .and_then(|default| cities.iter().position(|(id, _)| id.0 == default))
+        .unwrap_or(0)
 }
@@ -0,0 +1,105 @@
+//! Persistent "bin put out" checklist, backed by the shared reminder states.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use tonneli_core::acknowledge::{ReminderState, ReminderStates};
+use tonneli_core::model::{Address, PickupEvent};
+use tonneli_core::storage::{
+    EncryptedStorage, MemoryStorage, SqliteStorage, StorageKey, StoragePort,
+};
+
+const FILE_NAME: &str = "tonneli.sqlite3";
+/// Kept apart so switching encryption on does not meet unreadable plain entries.
+const ENCRYPTED_FILE_NAME: &str = "tonneli-encrypted.sqlite3";
+
+/// Pickups the user marked as handled or snoozed.
+///
+/// States live in the same storage notification channels read, so a pickup
+/// checked off here is not reminded of elsewhere.
+pub(crate) struct Checklist {
+    storage: Arc<dyn StoragePort>,
+    states: ReminderStates,
+}
+
+impl Default for Checklist {
+    /// Checklist that is forgotten on exit.
+    fn default() -> Self {
+        Self {
+            storage: Arc::new(MemoryStorage::default()),
+            states: ReminderStates::default(),
+        }
+    }
+}
+
+/// Open the storage shared by the checklist and the favorites in
+/// `$XDG_DATA_HOME/tonneli/tonneli.sqlite3`.
+///
+/// With a `key_file` the entries are encrypted and kept in
+/// `tonneli-encrypted.sqlite3` instead. Without a data directory everything is
+/// kept in memory only.
+pub(crate) fn open_storage(key_file: Option<&Path>) -> Result<Arc<dyn StoragePort>> {
+    let Some(dir) = dirs::data_dir().map(|dir| dir.join("tonneli")) else {
+        return Ok(Arc::new(MemoryStorage::default()));
+    };
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(key_file.map_or(FILE_NAME, |_| ENCRYPTED_FILE_NAME));
+    let database =
+        SqliteStorage::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+    Ok(match key_file {
+        Some(key_file) => {
+            let key = StorageKey::load_or_create(key_file)?;
+            Arc::new(EncryptedStorage::new(database, &key))
+        }
+        None => Arc::new(database),
+    })
+}
+
+impl Checklist {
+    /// Read the checklist kept in `storage`, see [`open_storage`].
+    pub(crate) async fn load(storage: Arc<dyn StoragePort>) -> Result<Self> {
+        let states = ReminderStates::load(storage.as_ref())
+            .await
+            .context("Invalid checklist")?;
+        Ok(Self { storage, states })
+    }
+
+    /// What the user did about the pickup at the address, if anything.
+    pub(crate) fn state(&self, address: &Address, pickup: &PickupEvent) -> Option<ReminderState> {
+        self.states.get(address, pickup)
+    }
+
+    /// Flip the handled state of a pickup and persist it, returning the new state.
+    ///
+    /// Checking off a snoozed pickup acknowledges it.
+    pub(crate) async fn toggle(&mut self, address: &Address, pickup: &PickupEvent) -> Result<bool> {
+        let done = !self.states.is_acknowledged(address, pickup);
+        let state = done.then_some(ReminderState::Acknowledged);
+        self.states
+            .set(self.storage.as_ref(), address, pickup, state)
+            .await?;
+        Ok(done)
+    }
+
+    /// Hold back reminders of a pickup until `until` and persist that.
+    pub(crate) async fn snooze(
+        &mut self,
+        address: &Address,
+        pickup: &PickupEvent,
+        until: NaiveDateTime,
+    ) -> Result<()> {
+        let state = ReminderState::Snoozed { until };
+        Ok(self
+            .states
+            .set(self.storage.as_ref(), address, pickup, Some(state))
+            .await?)
+    }
+
+    /// Drop entries of pickups before `today`.
+    pub(crate) async fn prune(&mut self, today: NaiveDate) -> Result<()> {
+        Ok(self.states.prune(self.storage.as_ref(), today).await?)
+    }
+}
@@ -10,6 +10,14 @@ pub(crate) enum Action {
     SearchAddresses,
     /// Run `service.schedule_for`(...) for the currently selected address
     LoadScheduleForCurrentAddress,
+    /// Toggle and persist the checkmark of the highlighted pickup
+    TogglePickupDone,
+    /// Snooze and persist the reminders of the highlighted pickup
+    SnoozePickup,
+    /// Save the shown address as home favorite, or forget it again
+    ToggleFavorite,
+    /// Write the reminder settings back to the config file
+    SaveSettings,
 }
 
 pub(crate) fn handle_key_event(key: KeyEvent, app: &mut App) -> Action {
@@ -22,44 +30,42 @@ pub(crate) fn handle_key_event(key: KeyEvent, app: &mut App) -> Action {
     if key.code == Char('q') && key.modifiers.is_empty() {
         return Action::Quit;
     }
+    if key.code == Char('l') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        app.toggle_log();
+        return Action::None;
+    }
 
     let mut action = Action::None;
 
     match app.screen {
         Screen::CitySelect => match key.code {
-            Up | Char('k') => {
-                if app.city_list_index > 0 {
-                    app.city_list_index -= 1;
-                }
+            Up | Char('k') if app.city_list_index > 0 => {
+                app.city_list_index -= 1;
             }
-            Down | Char('j') => {
-                if app.city_list_index + 1 < app.cities.len() {
-                    app.city_list_index += 1;
-                }
+            Down | Char('j') if app.city_list_index + 1 < app.cities.len() => {
+                app.city_list_index += 1;
             }
             Enter | Char(' ') => {
                 app.select_current_city();
             }
+            Char('s') => {
+                app.screen = Screen::Settings;
+            }
             _ => {}
         },
 
         Screen::AddressSearch => match key.code {
-            Up => {
-                if app.address_list_index > 0 {
-                    app.address_list_index -= 1;
-                }
+            Up if app.address_list_index > 0 => {
+                app.address_list_index -= 1;
             }
-            Down => {
-                if app.address_list_index + 1 < app.address_results.len() {
-                    app.address_list_index += 1;
-                }
+            Down if app.address_list_index + 1 < app.address_results.len() => {
+                app.address_list_index += 1;
             }
-            Char(character) => {
+            Char(character)
                 if !key.modifiers.contains(KeyModifiers::CONTROL)
-                    && !key.modifiers.contains(KeyModifiers::ALT)
-                {
-                    app.address_input.push(character);
-                }
+                    && !key.modifiers.contains(KeyModifiers::ALT) =>
+            {
+                app.address_input.push(character);
             }
             Backspace => {
                 app.address_input.pop();
@@ -78,12 +84,72 @@ pub(crate) fn handle_key_event(key: KeyEvent, app: &mut App) -> Action {
             _ => {}
         },
 
-        Screen::ScheduleView => match key.code {
-            Left | Esc | Char('b') => {
-                app.screen = Screen::AddressSearch;
+        Screen::ScheduleView => action = handle_schedule_key(key.code, app),
+
+        Screen::Settings => match key.code {
+            Up | Char('k') if app.settings_index > 0 => {
+                app.settings_index -= 1;
+            }
+            Down | Char('j') if app.settings_index + 1 < app.settings_rows.len() => {
+                app.settings_index += 1;
+            }
+            Enter | Char(' ') => {
+                if let Some(row) = app.settings_rows.get(app.settings_index) {
+                    row.activate(&mut app.reminders);
+                    action = Action::SaveSettings;
+                }
+            }
+            Left | Right | Char('h' | 'l') => {
+                if let Some(row) = app.settings_rows.get(app.settings_index) {
+                    row.adjust(&mut app.reminders, matches!(key.code, Right | Char('l')));
+                    action = Action::SaveSettings;
+                }
+            }
+            Esc | Char('b') => {
+                app.screen = Screen::CitySelect;
             }
             _ => {}
         },
+
+        Screen::Log => handle_log_key(key.code, app),
     }
     action
 }
+
+fn handle_schedule_key(code: KeyCode, app: &mut App) -> Action {
+    use KeyCode::{Char, Down, Esc, Left, Up};
+
+    match code {
+        Up | Char('k') if app.pickup_list_index > 0 => {
+            app.pickup_list_index -= 1;
+        }
+        Down | Char('j') if app.pickup_list_index + 1 < app.pickups.len() => {
+            app.pickup_list_index += 1;
+        }
+        Char(' ') => return Action::TogglePickupDone,
+        Char('z') => return Action::SnoozePickup,
+        Char('f') => return Action::ToggleFavorite,
+        Left | Esc | Char('b') => {
+            app.screen = Screen::AddressSearch;
+        }
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_log_key(code: KeyCode, app: &mut App) {
+    use KeyCode::{Char, Down, Esc, Up};
+
+    match code {
+        Up | Char('k') if app.log_scroll + 1 < app.logs.len() => {
+            app.log_scroll += 1;
+        }
+        Down | Char('j') => {
+            app.log_scroll = app.log_scroll.saturating_sub(1);
+        }
+        Esc | Char('b') => {
+            app.toggle_log();
+        }
+        _ => {}
+    }
+}
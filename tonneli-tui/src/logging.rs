@@ -0,0 +1,203 @@
+//! Log file at `$XDG_STATE_HOME/tonneli/log`, since the alternate screen hides stderr.
+//!
+//! Every line is also kept in a small in-memory buffer for the log screen.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, SecondsFormat};
+use tonneli_core::{AuditEntry, ErrorContext, ErrorReporter, PortError, RequestAuditor};
+use tracing::level_filters::LevelFilter;
+use tracing::subscriber::set_global_default;
+use tracing::warn;
+
+/// Lines kept for the log screen.
+pub(crate) const LOG_VIEW_LINES: usize = 500;
+/// Log files larger than this are started over instead of appended to.
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Most recent log lines, shared between the subscriber and the UI.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    path: Option<PathBuf>,
+}
+
+impl LogBuffer {
+    /// File the lines are written to, if one could be opened.
+    pub(crate) fn path(&self) -> Option<&PathBuf> {
+        self.path.as_ref()
+    }
+
+    /// Number of buffered lines.
+    pub(crate) fn len(&self) -> usize {
+        self.lines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Copy of the buffered lines, oldest first.
+    pub(crate) fn lines(&self) -> Vec<String> {
+        self.lines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn push(&self, line: &str) {
+        let mut lines = self.lines.lock().unwrap_or_else(PoisonError::into_inner);
+        if lines.len() == LOG_VIEW_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line.to_owned());
+    }
+}
+
+/// Writes every provider error the service sees to the log.
+pub(crate) struct LogReporter;
+
+impl ErrorReporter for LogReporter {
+    fn report(&self, error: &PortError, context: &ErrorContext) {
+        warn!(
+            city = %context.city.0,
+            operation = %context.operation,
+            query = context.query.as_deref().unwrap_or_default(),
+            error = ?error,
+            "provider call failed"
+        );
+    }
+}
+
+/// Appends one line per provider request to `audit.log` next to the log file.
+pub(crate) struct AuditLog {
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    /// Open the audit log; without a state directory there is nowhere to write it.
+    pub(crate) fn open() -> Result<Option<Self>> {
+        let Some(path) = state_dir().map(|dir| dir.join("audit.log")) else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            file: Mutex::new(open(&path)?),
+        }))
+    }
+}
+
+impl RequestAuditor for AuditLog {
+    fn record(&self, entry: &AuditEntry) {
+        let time = DateTime::<Local>::from(entry.time).to_rfc3339_opts(SecondsFormat::Secs, false);
+        let query = entry
+            .query
+            .as_deref()
+            .map(|query| format!("?{query}"))
+            .unwrap_or_default();
+        let status = entry
+            .status
+            .map_or_else(|| "-".to_owned(), |status| status.to_string());
+        let line = format!(
+            "{time} {} {}{}{query} {status}\n",
+            entry.method, entry.host, entry.path
+        );
+        let written = self
+            .file
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write_all(line.as_bytes());
+        if let Err(err) = written {
+            warn!(%err, "writing the audit log failed");
+        }
+    }
+}
+
+/// Writer handed to the subscriber for each event.
+#[derive(Clone)]
+struct LogSink {
+    file: Option<Arc<Mutex<File>>>,
+    buffer: LogBuffer,
+}
+
+impl Write for LogSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(file) = &self.file {
+            file.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .write_all(buf)?;
+        }
+        for line in String::from_utf8_lossy(buf).lines() {
+            self.buffer.push(line);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &self.file {
+            Some(file) => file.lock().unwrap_or_else(PoisonError::into_inner).flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Start logging `info` and above (`debug` with `verbose`) to the log file.
+///
+/// Without a state directory, lines only go to the in-memory buffer.
+pub(crate) fn init(verbose: bool) -> Result<LogBuffer> {
+    let path = state_dir().map(|dir| dir.join("log"));
+    let file = match &path {
+        Some(path) => Some(Arc::new(Mutex::new(open(path)?))),
+        None => None,
+    };
+
+    let buffer = LogBuffer {
+        lines: Arc::default(),
+        path,
+    };
+    let sink = LogSink {
+        file,
+        buffer: buffer.clone(),
+    };
+    let level = if verbose {
+        LevelFilter::DEBUG
+    } else {
+        LevelFilter::INFO
+    };
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_ansi(false)
+        .with_target(verbose)
+        .with_writer(move || sink.clone())
+        .finish();
+    set_global_default(subscriber).context("Logging is already set up")?;
+
+    Ok(buffer)
+}
+
+/// `tonneli` in the platform state directory, or the local data directory.
+fn state_dir() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("tonneli"))
+}
+
+fn open(path: &PathBuf) -> Result<File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Cannot create log directory {}", dir.display()))?;
+    }
+    let too_large = fs::metadata(path).is_ok_and(|meta| meta.len() > MAX_LOG_BYTES);
+    OpenOptions::new()
+        .create(true)
+        .append(!too_large)
+        .write(true)
+        .truncate(too_large)
+        .open(path)
+        .with_context(|| format!("Cannot open log file {}", path.display()))
+}
@@ -1,56 +1,151 @@
 //! Terminal UI for tonneli that lets users search addresses and view pickup schedules.
 
 mod app;
+mod checklist;
 mod input;
+mod logging;
+mod settings;
 mod ui;
 
-use std::{io, sync::Arc, time::Duration as StdDuration};
+use std::{env, io, sync::Arc, time::Duration as StdDuration};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event as CEvent},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
-use reqwest::Client;
-use tonneli_core::{AddressSearch, plugin::PluginRegistry, service::TonneliService};
+use tonneli_config::{ConfigLoader, ProviderSettings};
+use tonneli_core::{
+    AddressSearch, ChaosConfig, CityMeta, FavoritesStore, PortError,
+    service::{ServiceBuilder, TonneliService},
+};
+use tonneli_i18n::Localizer;
 use tonneli_provider_aachen as aachen;
+use tonneli_provider_abfallio::{self as abfallio, AbfallIoTown};
+use tonneli_provider_bremen as bremen;
 use tonneli_provider_cologne as cologne;
+use tonneli_provider_file::{self as file, FileTown};
+use tonneli_provider_ics as ics;
+use tonneli_provider_jumomind::{self as jumomind, JumomindTown};
+use tonneli_provider_luxembourg::{self as luxembourg, Language as LuxembourgLanguage};
 use tonneli_provider_nuremberg as nuremberg;
+use tonneli_provider_potsdam as potsdam;
+use tonneli_provider_recycle::{self as recycle, RecycleTown};
+use tonneli_provider_regioit::{self as regioit, RegioItTown};
+use tonneli_provider_rhein_sieg as rhein_sieg;
+use tonneli_provider_uk::{cambridge, reading};
+use tonneli_provider_wuppertal as wuppertal;
+use tracing::{error, info, warn};
 
 use crate::app::App;
+use crate::checklist::Checklist;
 use crate::input::Action;
 
+const SEARCH_LIMIT: usize = 50;
+
+/// Country and time zone of the German towns the generic providers serve.
+const GERMANY: &str = "DE";
+const BERLIN: &str = "Europe/Berlin";
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // HTTP + service setup
-    let client = Client::builder().user_agent("tonneli/0.1").build()?;
+    let (loader, args) = ConfigLoader::new()
+        .env_vars(env::vars())?
+        .args(env::args().skip(1))?;
+    let verbose = args.iter().any(|arg| arg == "--verbose" || arg == "-v");
+    let logs = logging::init(verbose)?;
+    info!(version = env!("CARGO_PKG_VERSION"), verbose, "starting");
 
-    let plugins = vec![
-        aachen::plugin(client.clone()),
-        cologne::plugin(client.clone()),
-        nuremberg::plugin(client.clone()),
-    ];
-    let registry = Arc::new(PluginRegistry::new(plugins));
-    let service = Arc::new(TonneliService::new(registry));
+    let config = loader
+        .load()
+        .inspect_err(|err| error!(%err, "invalid configuration"))?;
+
+    // HTTP + service setup
+    let mut builder = config
+        .http()
+        .apply(TonneliService::builder())
+        .provider_with_clock(aachen::plugin_with_clock)
+        .provider(bremen::plugin)
+        .provider(cambridge::plugin)
+        .provider(cologne::plugin)
+        .provider(ics::plugin)
+        .provider(|client| luxembourg::plugin_with_language(client, luxembourg_language()))
+        .provider_with_clock(nuremberg::plugin_with_clock)
+        .provider(potsdam::plugin)
+        .provider(reading::plugin)
+        .provider_with_clock(rhein_sieg::plugin_with_clock)
+        .provider(wuppertal::plugin)
+        .household(config.household().clone())
+        .overrides(config.overrides().clone())
+        .error_reporter(logging::LogReporter);
+    builder = configured_towns(builder, config.providers());
+    builder = config.providers().apply(builder);
+    #[cfg(feature = "demo")]
+    {
+        builder = builder.provider(tonneli_provider_demo::plugin);
+    }
+    builder = config.privacy().apply(builder);
+    if config.privacy().audit_log
+        && let Some(audit) = logging::AuditLog::open()?
+    {
+        info!(detail = ?config.privacy().audit_detail(), "recording provider requests");
+        builder = builder.request_auditor(audit, config.privacy().audit_detail());
+    }
+    if let Some(chaos) = ChaosConfig::from_env()? {
+        warn!(?chaos, "injecting provider failures");
+        builder = builder.chaos(chaos);
+    }
+    // Favorites share the checklist's storage, and with it its encryption.
+    let storage = checklist::open_storage(config.privacy().key_file.as_deref())?;
+    let favorites = FavoritesStore::open(Arc::clone(&storage))
+        .await
+        .context("Failed to open favorites")?;
+    if let Some(path) = FavoritesStore::legacy_path() {
+        let imported = favorites
+            .import_file(&path)
+            .await
+            .with_context(|| format!("Failed to import favorites from {}", path.display()))?;
+        if imported > 0 {
+            info!(imported, "favorites moved into the storage");
+        }
+    }
+    let service = builder
+        .build()
+        .inspect_err(|err| error!(%err, "setup failed"))?
+        .with_favorites(favorites);
+    info!(cities = service.cities().len(), "providers registered");
+    for id in config.providers().enabled.iter().flatten() {
+        if !service.cities().iter().any(|(city, _)| city.0 == *id) {
+            warn!(%id, "enabled provider does not exist");
+        }
+    }
 
     // App state
-    let app = App::new(service);
+    let mut checklist = Checklist::load(storage).await?;
+    checklist.prune(service.clock().today()).await?;
+    let reminders = config.reminders().clone();
+    let mut app = App::new(
+        service.clock(),
+        service.cities(),
+        checklist,
+        config,
+        reminders,
+    );
+    app.logs = logs;
+    app.l10n = Localizer::from_env();
+    info!(language = ?app.l10n.language(), "texts localized");
 
     // Terminal init
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(
-        stdout,
-        EnterAlternateScreen,
-        EnableMouseCapture
-    )?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Run event loop
-    let res = run(&mut terminal, app).await;
+    let res = run(&mut terminal, &service, app).await;
 
     // Restore terminal
     disable_raw_mode()?;
@@ -61,11 +156,126 @@ async fn main() -> Result<()> {
     )?;
     terminal.show_cursor()?;
 
+    if let Err(err) = &res {
+        error!(%err, "exiting after error");
+    }
     res
 }
 
-async fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, mut app: App) -> Result<()> {
+type Term = Terminal<CrosstermBackend<io::Stdout>>;
+
+/// Register the towns configured for the generic providers.
+fn configured_towns(mut builder: ServiceBuilder, providers: &ProviderSettings) -> ServiceBuilder {
+    for town in &providers.regioit_towns {
+        let base_url = town
+            .base_url
+            .clone()
+            .or_else(|| town.service.as_deref().map(regioit::service_url))
+            .unwrap_or_default();
+        let town = RegioItTown {
+            meta: CityMeta::new(
+                &town.id,
+                town.name.clone().unwrap_or_else(|| town.town.clone()),
+            )
+            .with_locale(GERMANY, BERLIN)
+            .with_source("RegioIT Abfallnavi", &base_url),
+            town: town.town.clone(),
+            ort_id: town.ort_id,
+            base_url,
+        };
+        builder = builder.provider_with_clock(move |client, clock| {
+            regioit::plugin_for_town(client, town, clock)
+        });
+    }
+    for town in &providers.abfallio_towns {
+        let town = AbfallIoTown {
+            meta: CityMeta::new(&town.id, &town.name)
+                .with_locale(GERMANY, BERLIN)
+                .with_source("AbfallPlus (abfall.io)", "https://www.abfallplus.de"),
+            key: town.key.clone(),
+            kommune: town.kommune.clone(),
+            base_url: town
+                .base_url
+                .clone()
+                .unwrap_or_else(|| abfallio::BASE_URL.to_owned()),
+        };
+        builder = builder.provider(move |client| abfallio::plugin_for_town(client, town));
+    }
+    for town in &providers.jumomind_towns {
+        let base_url = town
+            .base_url
+            .clone()
+            .unwrap_or_else(|| jumomind::base_url(&town.service_id));
+        let town = JumomindTown {
+            meta: CityMeta::new(
+                &town.id,
+                town.name.clone().unwrap_or_else(|| town.city.clone()),
+            )
+            .with_locale(GERMANY, BERLIN)
+            .with_source("Jumomind", &base_url),
+            city: town.city.clone(),
+            base_url,
+        };
+        builder = builder.provider(move |client| jumomind::plugin_for_town(client, town));
+    }
+    for town in &providers.recycle_towns {
+        let town = RecycleTown {
+            meta: CityMeta::new(&town.id, &town.name)
+                .with_locale("BE", "Europe/Brussels")
+                .with_source("Recycle! (Fost Plus)", "https://www.recycleapp.be"),
+            secret: town.secret.clone(),
+            zip_code: town.zip_code.clone(),
+            language: town.language.clone().unwrap_or_else(|| "nl".to_owned()),
+            base_url: town
+                .base_url
+                .clone()
+                .unwrap_or_else(|| recycle::BASE_URL.to_owned()),
+        };
+        builder = builder.provider(move |client| recycle::plugin_for_town(client, town));
+    }
+    for town in &providers.file_towns {
+        let town = FileTown {
+            meta: CityMeta::new(&town.id, &town.name),
+            path: town.path.clone(),
+        };
+        builder = builder.provider(move |client| file::plugin_for_town(client, town));
+    }
+    builder
+}
+
+/// Language of Luxembourg's notes: the first of French and German the environment asks for.
+fn luxembourg_language() -> LuxembourgLanguage {
+    tonneli_i18n::requested_from_env(|name| env::var(name).ok())
+        .iter()
+        .find_map(|requested| LuxembourgLanguage::from_code(requested.language.as_str()))
+        .unwrap_or_default()
+}
+
+async fn run(terminal: &mut Term, service: &TonneliService, mut app: App) -> Result<()> {
+    // Start on the schedule of the first saved address instead of searching it again,
+    // or on the configured default address.
+    let favorite = service.favorites().into_iter().next();
+    if let Some(favorite) = favorite {
+        let label = favorite.label.clone();
+        if app.open_favorite(favorite) {
+            info!(%label, "opening favorite");
+            load_schedule(terminal, service, &mut app).await?;
+        } else {
+            warn!(%label, "favorite of an unavailable city");
+        }
+    } else if let Some(address) = app.config.frontends().tui.default_address.clone()
+        && app.select_default_city()
+    {
+        app.address_input = address;
+        search_addresses(terminal, service, &mut app).await?;
+        if !app.address_results.is_empty() {
+            load_schedule(terminal, service, &mut app).await?;
+        }
+    }
+
     loop {
+        app.tick();
+
         // Draw current UI
         terminal.draw(|frame| ui::draw(frame, &app))?;
 
@@ -79,69 +289,37 @@ async fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, mut app: App
                 Action::Quit => break,
                 Action::None => {}
                 Action::SearchAddresses => {
-                    // Needs a city & non-empty query
-                    let query_text = app.address_input.trim();
-                    if query_text.is_empty() {
-                        app.error_message = Some(
-                            "Type a street (optionally add a house number), then press Enter"
-                                .into(),
-                        );
-                        continue;
+                    search_addresses(terminal, service, &mut app).await?;
+                }
+                Action::LoadScheduleForCurrentAddress => {
+                    load_schedule(terminal, service, &mut app).await?;
+                }
+                Action::TogglePickupDone => {
+                    if let Err(err) = app.toggle_current_pickup().await {
+                        error!(%err, "saving checklist failed");
+                        app.error_message = Some(app.l10n.text_with(
+                            "error-save-checklist",
+                            &[("error", err.to_string().into())],
+                        ));
                     }
-
-                    let Some(city) = app.selected_city.clone() else {
-                        app.error_message = Some("Select a city first".into());
-                        continue;
-                    };
-
-                    let query = parse_search_input(query_text);
-
-                    app.is_loading = true;
-                    app.error_message = None;
-                    terminal.draw(|frame| ui::draw(frame, &app))?;
-
-                    let res = app.service.search_addresses(city, query, 50).await;
-
-                    app.is_loading = false;
-                    match res {
-                        Ok(addresses) => {
-                            app.address_results = addresses;
-                            app.address_list_index = 0;
-                            app.selected_address = None;
-                        }
-                        Err(err) => {
-                            app.error_message = Some(format!("Search failed: {err}"));
-                        }
+                }
+                Action::SnoozePickup => {
+                    if let Err(err) = app.snooze_current_pickup().await {
+                        error!(%err, "saving checklist failed");
+                        app.error_message = Some(app.l10n.text_with(
+                            "error-save-checklist",
+                            &[("error", err.to_string().into())],
+                        ));
                     }
                 }
-                Action::LoadScheduleForCurrentAddress => {
-                    let Some(city) = app.selected_city.clone() else {
-                        app.error_message = Some("Select a city first".into());
-                        continue;
-                    };
-
-                    let Some(addr) = app.select_current_address() else {
-                        app.error_message =
-                            Some("No address selected (search and pick one first)".into());
-                        continue;
-                    };
-
-                    app.is_loading = true;
-                    app.error_message = None;
-                    terminal.draw(|frame| ui::draw(frame, &app))?;
-
-                    let range = App::current_range();
-                    let res = app.service.schedule_for(city, &addr.id, range).await;
-
-                    app.is_loading = false;
-                    match res {
-                        Ok(pickups) => {
-                            app.pickups = pickups;
-                        }
-                        Err(err) => {
-                            app.pickups.clear();
-                            app.error_message = Some(format!("Failed to load schedule: {err}"));
-                        }
+                Action::ToggleFavorite => toggle_favorite(service, &mut app).await,
+                Action::SaveSettings => {
+                    if let Err(err) = app.save_reminders() {
+                        error!(%err, "saving settings failed");
+                        app.error_message = Some(app.l10n.text_with(
+                            "error-save-settings",
+                            &[("error", err.to_string().into())],
+                        ));
                     }
                 }
             }
@@ -151,23 +329,210 @@ async fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, mut app: App
     Ok(())
 }
 
-fn parse_search_input(input: &str) -> AddressSearch {
-    let parts: Vec<&str> = input.split_whitespace().collect();
-    if parts.is_empty() {
-        return AddressSearch::new("", None::<String>);
+async fn search_addresses(
+    terminal: &mut Term,
+    service: &TonneliService,
+    app: &mut App,
+) -> Result<()> {
+    // Needs a city & non-empty query
+    let query_text = app.address_input.trim();
+    if query_text.is_empty() {
+        app.error_message = Some(app.l10n.text("error-empty-query"));
+        return Ok(());
     }
 
-    let Some((last, street_parts)) = parts.split_last() else {
-        return AddressSearch::new("", None::<String>);
+    let Some(city) = app.selected_city.clone() else {
+        app.error_message = Some(app.l10n.text("error-no-city"));
+        return Ok(());
+    };
+
+    // Counties take the town as a prefix: "Siegburg, Kaiserstraße 12".
+    let towns = service.towns(city.clone()).await.unwrap_or_default();
+    let query = AddressSearch::parse(query_text).with_town_prefix(&towns);
+    // Ask for the house number up front instead of showing an empty result list.
+    if query.house_number.is_none()
+        && let Some(meta) = service.city_meta(&city)
+        && meta.capabilities.house_number_required
+    {
+        app.error_message = Some(
+            app.l10n
+                .text_with("error-house-number-required", &[("city", meta.name.into())]),
+        );
+        return Ok(());
+    }
+    info!(city = %city.0, town = ?query.town, street = %query.street, "searching addresses");
+
+    app.is_loading = true;
+    app.error_message = None;
+    terminal.draw(|frame| ui::draw(frame, app))?;
+
+    let res = service
+        .search_addresses_with_status(city, query, SEARCH_LIMIT)
+        .await;
+
+    app.is_loading = false;
+    match res {
+        Ok(results) => {
+            info!(
+                found = results.addresses.len(),
+                truncated = results.truncated,
+                "search finished"
+            );
+            if results.truncated {
+                app.error_message = Some(
+                    app.l10n
+                        .text_with("search-truncated", &[("limit", SEARCH_LIMIT.into())]),
+                );
+            }
+            app.address_results = results.addresses;
+            app.address_list_index = 0;
+            app.selected_address = None;
+        }
+        Err(err) => {
+            let reason = describe_error(&app.l10n, &err);
+            app.error_message = Some(
+                app.l10n
+                    .text_with("error-search", &[("reason", reason.into())]),
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn load_schedule(terminal: &mut Term, service: &TonneliService, app: &mut App) -> Result<()> {
+    let Some(city) = app.selected_city.clone() else {
+        app.error_message = Some(app.l10n.text("error-no-city"));
+        return Ok(());
+    };
+
+    let Some(addr) = app.select_current_address() else {
+        app.error_message = Some(app.l10n.text("error-no-address"));
+        return Ok(());
     };
 
-    let has_number = last.chars().any(|ch| ch.is_ascii_digit()) && !street_parts.is_empty();
+    app.is_loading = true;
+    app.error_message = None;
+    terminal.draw(|frame| ui::draw(frame, app))?;
 
-    if has_number {
-        let street = street_parts.join(" ");
-        let house_number = last.to_owned();
-        AddressSearch::new(street, Some(house_number))
+    app.city_meta = service.city_meta(&city);
+    app.tick();
+    let range = app.current_range();
+    // Address ids usually contain the house number.
+    let address = if app.config.privacy().minimize_data {
+        addr.street.to_string()
     } else {
-        AddressSearch::new(parts.join(" "), None::<String>)
+        addr.id.0.clone()
+    };
+    info!(city = %city.0, %address, %range.start, %range.end, "loading schedule");
+    app.source = service.city_meta(&city).and_then(|meta| meta.attribution());
+    let res = service.validated_schedule_for(city, &addr.id, range).await;
+
+    app.is_loading = false;
+    match res {
+        Ok(validated) => {
+            info!(pickups = validated.events.len(), "schedule loaded");
+            for warning in &validated.warnings {
+                warn!(%warning, "dropped provider entry");
+            }
+            if !validated.warnings.is_empty() {
+                let dropped = validated
+                    .warnings
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                app.error_message = Some(
+                    app.l10n
+                        .text_with("error-suspicious-data", &[("dropped", dropped.into())]),
+                );
+            }
+            app.set_pickups(validated.events);
+        }
+        Err(err) => {
+            app.set_pickups(Vec::new());
+            let reason = describe_error(&app.l10n, &err);
+            app.error_message = Some(
+                app.l10n
+                    .text_with("error-schedule", &[("reason", reason.into())]),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Save the shown address as home favorite, or forget it when it is saved already.
+async fn toggle_favorite(service: &TonneliService, app: &mut App) {
+    let Some(address) = app.selected_address.clone() else {
+        app.error_message = Some(app.l10n.text("error-no-address"));
+        return;
+    };
+    let saved = service
+        .favorites()
+        .into_iter()
+        .find(|favorite| favorite.city == address.city && favorite.address.id == address.id);
+    let result = if let Some(favorite) = saved {
+        service
+            .remove_favorite(&favorite.label)
+            .await
+            .map(|_removed| {
+                app.l10n.text_with(
+                    "favorite-removed",
+                    &[("address", address.label.to_string().into())],
+                )
+            })
+    } else {
+        let label = app.l10n.text("favorite-home");
+        service.add_favorite(label, address).await.map(|favorite| {
+            app.l10n.text_with(
+                "favorite-saved",
+                &[
+                    ("address", favorite.address.label.to_string().into()),
+                    ("label", favorite.label.into()),
+                ],
+            )
+        })
+    };
+    app.error_message = Some(match result {
+        Ok(message) => message,
+        Err(err) => {
+            error!(%err, "saving favorites failed");
+            app.l10n
+                .text_with("error-save-favorite", &[("error", err.to_string().into())])
+        }
+    });
+}
+
+fn describe_error(l10n: &Localizer, err: &PortError) -> String {
+    match err.kind() {
+        PortError::Timeout => l10n.text("reason-timeout"),
+        PortError::RateLimited {
+            retry_after: Some(delay),
+            ..
+        } => l10n.text_with(
+            "reason-rate-limited-retry",
+            &[("seconds", delay.as_secs().max(1).into())],
+        ),
+        PortError::RateLimited {
+            retry_after: None, ..
+        } => l10n.text("reason-rate-limited"),
+        PortError::DeadlineExceeded { .. } => l10n.text("reason-too-slow"),
+        PortError::ProviderUnavailable(message) => l10n.text_with(
+            "reason-unavailable",
+            &[("message", message.as_str().into())],
+        ),
+        PortError::AddressNotFound { suggestions, .. } if !suggestions.is_empty() => l10n
+            .text_with(
+                "reason-not-found-suggestions",
+                &[("suggestions", suggestions.join(", ").into())],
+            ),
+        PortError::AddressNotFound { .. } => l10n.text("reason-not-found"),
+        PortError::TownRequired { towns } => l10n.text_with(
+            "reason-town-required",
+            &[
+                ("example", towns.first().map_or("", String::as_str).into()),
+                ("towns", towns.join(", ").into()),
+            ],
+        ),
+        other => other.to_string(),
     }
 }
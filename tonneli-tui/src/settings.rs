@@ -0,0 +1,200 @@
+//! Rows of the reminder settings screen and how keys change them.
+
+use chrono::{Duration, NaiveTime};
+use tonneli_core::{
+    model::Fraction,
+    reminder::{NotificationChannel, QuietHours, ReminderSettings},
+};
+use tonneli_i18n::Localizer;
+
+const MAX_LEAD_TIME_HOURS: u32 = 72;
+const QUIET_HOURS_STEP_MINUTES: i64 = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SettingsRow {
+    Enabled,
+    Fraction(Fraction),
+    LeadTime,
+    QuietHours,
+    QuietStart,
+    QuietEnd,
+    Channel,
+}
+
+impl SettingsRow {
+    pub(crate) fn all() -> Vec<Self> {
+        vec![
+            Self::Enabled,
+            Self::Fraction(Fraction::Residual),
+            Self::Fraction(Fraction::Organic),
+            Self::Fraction(Fraction::Paper),
+            Self::Fraction(Fraction::Plastic),
+            Self::Fraction(Fraction::Glass),
+            Self::Fraction(Fraction::Metal),
+            Self::Fraction(Fraction::StreetCleaning),
+            Self::Fraction(Fraction::BulkyWaste),
+            Self::Fraction(Fraction::GardenWaste),
+            Self::Fraction(Fraction::Hazardous),
+            Self::Fraction(Fraction::ChristmasTree),
+            Self::Fraction(Fraction::Textiles),
+            Self::Fraction(Fraction::Electronics),
+            Self::Fraction(Fraction::Other("".into())),
+            Self::LeadTime,
+            Self::QuietHours,
+            Self::QuietStart,
+            Self::QuietEnd,
+            Self::Channel,
+        ]
+    }
+
+    pub(crate) fn label(&self, settings: &ReminderSettings, l10n: &Localizer) -> String {
+        match self {
+            Self::Enabled => format!(
+                "{} {}",
+                checkbox(settings.enabled),
+                l10n.text("settings-enabled")
+            ),
+            Self::Fraction(fraction) => format!(
+                "{}   {}",
+                checkbox(settings.fractions.get(fraction)),
+                l10n.text(fraction_message(fraction))
+            ),
+            Self::LeadTime => l10n.text_with(
+                "settings-lead-time",
+                &[("hours", settings.lead_time_hours.into())],
+            ),
+            Self::QuietHours => format!(
+                "{} {}",
+                checkbox(settings.quiet_hours.is_some()),
+                l10n.text("settings-quiet-hours")
+            ),
+            Self::QuietStart => format!(
+                "    {}",
+                l10n.text_with(
+                    "settings-quiet-from",
+                    &[(
+                        "time",
+                        quiet_time(settings.quiet_hours.map(|quiet| quiet.start)).into()
+                    )],
+                )
+            ),
+            Self::QuietEnd => format!(
+                "    {}",
+                l10n.text_with(
+                    "settings-quiet-until",
+                    &[(
+                        "time",
+                        quiet_time(settings.quiet_hours.map(|quiet| quiet.end)).into()
+                    )],
+                )
+            ),
+            Self::Channel => l10n.text_with(
+                "settings-channel",
+                &[(
+                    "channel",
+                    l10n.text(channel_message(settings.channel)).into(),
+                )],
+            ),
+        }
+    }
+
+    /// Space/Enter: flip switches, cycle the channel.
+    pub(crate) fn activate(&self, settings: &mut ReminderSettings) {
+        match self {
+            Self::Enabled => settings.enabled = !settings.enabled,
+            Self::Fraction(fraction) => {
+                let toggle = settings.fractions.get_mut(fraction);
+                *toggle = !*toggle;
+            }
+            Self::QuietHours => {
+                settings.quiet_hours = match settings.quiet_hours {
+                    Some(_) => None,
+                    None => Some(QuietHours::default()),
+                };
+            }
+            Self::LeadTime | Self::QuietStart | Self::QuietEnd | Self::Channel => {
+                self.adjust(settings, true);
+            }
+        }
+    }
+
+    /// Left/right: step numeric values, cycle the channel, flip switches.
+    pub(crate) fn adjust(&self, settings: &mut ReminderSettings, forward: bool) {
+        match self {
+            Self::Enabled | Self::Fraction(_) | Self::QuietHours => self.activate(settings),
+            Self::LeadTime => {
+                settings.lead_time_hours = if forward {
+                    (settings.lead_time_hours + 1).min(MAX_LEAD_TIME_HOURS)
+                } else {
+                    settings.lead_time_hours.saturating_sub(1)
+                };
+            }
+            Self::QuietStart => {
+                if let Some(quiet) = settings.quiet_hours.as_mut() {
+                    quiet.start = step_time(quiet.start, forward);
+                }
+            }
+            Self::QuietEnd => {
+                if let Some(quiet) = settings.quiet_hours.as_mut() {
+                    quiet.end = step_time(quiet.end, forward);
+                }
+            }
+            Self::Channel => {
+                let channels = NotificationChannel::ALL;
+                let current = channels
+                    .iter()
+                    .position(|channel| *channel == settings.channel)
+                    .unwrap_or(0);
+                let next = if forward {
+                    current + 1
+                } else {
+                    current + channels.len() - 1
+                };
+                if let Some(channel) = channels.get(next % channels.len()) {
+                    settings.channel = *channel;
+                }
+            }
+        }
+    }
+}
+
+fn checkbox(checked: bool) -> &'static str {
+    if checked { "[x]" } else { "[ ]" }
+}
+
+/// Message id of a fraction's name; all other fractions share one row.
+pub(crate) fn fraction_message(fraction: &Fraction) -> &'static str {
+    match fraction {
+        Fraction::Residual => "fraction-residual",
+        Fraction::Organic => "fraction-organic",
+        Fraction::Paper => "fraction-paper",
+        Fraction::Plastic => "fraction-plastic",
+        Fraction::Glass => "fraction-glass",
+        Fraction::Metal => "fraction-metal",
+        Fraction::StreetCleaning => "fraction-street-cleaning",
+        Fraction::BulkyWaste => "fraction-bulky-waste",
+        Fraction::GardenWaste => "fraction-garden-waste",
+        Fraction::Hazardous => "fraction-hazardous",
+        Fraction::ChristmasTree => "fraction-christmas-tree",
+        Fraction::Textiles => "fraction-textiles",
+        Fraction::Electronics => "fraction-electronics",
+        Fraction::Other(_) => "fraction-other",
+    }
+}
+
+fn channel_message(channel: NotificationChannel) -> &'static str {
+    match channel {
+        NotificationChannel::Desktop => "channel-desktop",
+        NotificationChannel::Email => "channel-email",
+        NotificationChannel::Webhook => "channel-webhook",
+    }
+}
+
+fn quiet_time(time: Option<NaiveTime>) -> String {
+    time.map_or_else(|| "–".to_owned(), |time| time.format("%H:%M").to_string())
+}
+
+fn step_time(time: NaiveTime, forward: bool) -> NaiveTime {
+    let step = Duration::minutes(QUIET_HOURS_STEP_MINUTES);
+    if forward { time + step } else { time - step }
+}
@@ -1,13 +1,19 @@
-use chrono::Local;
+use chrono::{Datelike, NaiveDate, Weekday};
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Table, Wrap},
+    widgets::{
+        Block, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Table, TableState, Wrap,
+    },
 };
-use tonneli_core::model::Fraction;
+use tonneli_core::acknowledge::ReminderState;
+use tonneli_core::model::{Correction, Fraction};
+use tonneli_i18n::Localizer;
 
 use crate::app::{App, Screen};
+use crate::settings::fraction_message;
 
 pub(crate) fn draw(frame: &mut Frame<'_>, app: &App) {
+    let l10n = &app.l10n;
     let area = frame.area();
 
     // Outer layout: title, main content, status line
@@ -27,8 +33,11 @@ pub(crate) fn draw(frame: &mut Frame<'_>, app: &App) {
     };
 
     // Title / header
-    let header = Paragraph::new("tonneli – waste collection schedules")
-        .block(Block::default().borders(Borders::ALL).title("Tonneli"));
+    let header = Paragraph::new(l10n.text("app-subtitle")).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(l10n.text("app-title")),
+    );
     frame.render_widget(header, *header_area);
 
     // Main screen
@@ -36,23 +45,25 @@ pub(crate) fn draw(frame: &mut Frame<'_>, app: &App) {
         Screen::CitySelect => draw_city_select(frame, app, *content_area),
         Screen::AddressSearch => draw_address_search(frame, app, *content_area),
         Screen::ScheduleView => draw_schedule_view(frame, app, *content_area),
+        Screen::Settings => draw_settings(frame, app, *content_area),
+        Screen::Log => draw_log(frame, app, *content_area),
     }
 
     // Status bar
-    let nav_hint = match app.screen {
-        Screen::CitySelect => "↑/↓ move · Enter/Space select city · q/Ctrl-C quit",
-        Screen::AddressSearch => {
-            "Type to edit · Enter search · Tab/→ open schedule · Left/Esc back · q/Ctrl-C quit"
-        }
-        Screen::ScheduleView => "Esc/←/b back to results · q/Ctrl-C quit",
-    };
+    let nav_hint = l10n.text(match app.screen {
+        Screen::CitySelect => "hint-city-select",
+        Screen::AddressSearch => "hint-address-search",
+        Screen::ScheduleView => "hint-schedule",
+        Screen::Settings => "hint-settings",
+        Screen::Log => "hint-log",
+    });
 
     let status_text = if app.is_loading {
-        format!("Loading… · {nav_hint}")
+        format!("{} · {nav_hint}", l10n.text("status-loading"))
     } else if let Some(msg) = &app.error_message {
         format!("{msg} · {nav_hint}")
     } else {
-        nav_hint.to_owned()
+        nav_hint
     };
 
     let status_style = if app.error_message.is_some() {
@@ -63,8 +74,12 @@ pub(crate) fn draw(frame: &mut Frame<'_>, app: &App) {
         Style::default()
     };
 
-    let status = Paragraph::new(status_text.to_owned())
-        .block(Block::default().borders(Borders::ALL).title("Status"))
+    let status = Paragraph::new(status_text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(l10n.text("status-title")),
+        )
         .style(status_style)
         .wrap(Wrap { trim: true });
 
@@ -90,7 +105,7 @@ fn draw_city_select(frame: &mut Frame<'_>, app: &App, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Select city (↑/↓, Enter)"),
+                .title(app.l10n.text("city-select-title")),
         )
         .highlight_style(
             Style::default()
@@ -119,29 +134,30 @@ fn draw_address_search(frame: &mut Frame<'_>, app: &App, area: Rect) {
         return;
     };
 
+    let l10n = &app.l10n;
     let city_name = app
         .cities
         .get(app.city_list_index)
-        .map_or("<no city>", |(_, name)| name.as_str());
+        .map_or_else(|| l10n.text("search-no-city"), |(_, name)| name.clone());
 
     let input = Paragraph::new(app.address_input.as_str())
-        .block(Block::default().borders(Borders::ALL).title(format!(
-            "Search in {city_name} (street + optional house number, Enter)"
-        )))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(l10n.text_with("search-title", &[("city", city_name.into())])),
+        )
         .wrap(Wrap { trim: true });
 
     frame.render_widget(input, *input_area);
 
     let items = if app.address_results.is_empty() {
-        vec![ListItem::new(
-            "No results yet. Try typing a street plus house number.",
-        )]
+        vec![ListItem::new(l10n.text("search-empty"))]
     } else {
         app.address_results
             .iter()
             .map(|addr| {
                 // Use label if available; it’s usually nice and human-readable
-                ListItem::new(addr.label.clone())
+                ListItem::new(&*addr.label)
             })
             .collect()
     };
@@ -150,7 +166,7 @@ fn draw_address_search(frame: &mut Frame<'_>, app: &App, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Addresses (↑/↓, Tab/→ to open schedule)"),
+                .title(l10n.text("search-results-title")),
         )
         .highlight_style(
             Style::default()
@@ -166,43 +182,84 @@ fn draw_address_search(frame: &mut Frame<'_>, app: &App, area: Rect) {
 }
 
 fn draw_schedule_view(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let l10n = &app.l10n;
     let city_name = app
         .cities
         .get(app.city_list_index)
-        .map_or("<city>", |(_, name)| name.as_str());
-
-    let address_label = app
-        .selected_address
-        .as_ref()
-        .map_or("<address>", |address| address.label.as_str());
-
-    let title = format!("Schedule for {address_label} in {city_name} (Esc/←/b to go back)");
+        .map_or_else(|| l10n.text("schedule-no-city"), |(_, name)| name.clone());
+
+    let address_label = app.selected_address.as_ref().map_or_else(
+        || l10n.text("schedule-no-address"),
+        |address| address.label.to_string(),
+    );
+
+    let title = l10n.text_with(
+        "schedule-title",
+        &[
+            ("address", address_label.into()),
+            ("city", city_name.into()),
+        ],
+    );
+
+    let mut block = Block::default().borders(Borders::ALL).title(title);
+    if let Some(source) = &app.source {
+        block = block
+            .title_bottom(l10n.text_with("schedule-source", &[("source", source.as_str().into())]));
+    }
 
     if app.is_loading {
-        let paragraph = Paragraph::new("Loading schedule…")
-            .block(Block::default().borders(Borders::ALL).title(title))
+        let paragraph = Paragraph::new(l10n.text("schedule-loading"))
+            .block(block)
             .wrap(Wrap { trim: true });
         frame.render_widget(paragraph, area);
         return;
     }
 
     if app.pickups.is_empty() {
-        let paragraph = Paragraph::new("No upcoming pickups in the current range.")
-            .block(Block::default().borders(Borders::ALL).title(title))
+        let paragraph = Paragraph::new(l10n.text("schedule-empty"))
+            .block(block)
             .wrap(Wrap { trim: true });
         frame.render_widget(paragraph, area);
         return;
     }
 
-    let today = Local::now().date_naive();
-    let mut pickups = app.pickups.clone();
-    pickups.sort_by_key(|pickup| pickup.date);
-
-    let rows = pickups.into_iter().map(|pickup| {
+    let today = app.today;
+    let now = app.now();
+    let headers = [
+        "schedule-column-out",
+        "schedule-column-date",
+        "schedule-column-day",
+        "schedule-column-in",
+        "schedule-column-fraction",
+    ]
+    .map(|id| l10n.text(id));
+    let relatives = app
+        .pickups
+        .iter()
+        .map(|pickup| relative_day_label(l10n, pickup.date, today))
+        .collect::<Vec<_>>();
+    // Translations may need wider columns than the English defaults.
+    let [out_header, _, _, in_header, _] = &headers;
+    let out_width = text_width([out_header]).max(3);
+    let relative_width = text_width(relatives.iter().chain([in_header])).max(10);
+
+    // The service returns pickups sorted by date, so rows render straight from them.
+    let rows = app.pickups.iter().zip(relatives).map(|(pickup, relative)| {
+        let state = app
+            .selected_address
+            .as_ref()
+            .and_then(|address| app.checklist.state(address, pickup));
+        let mark = match state {
+            Some(ReminderState::Acknowledged) => "[x]",
+            Some(snoozed) if snoozed.silences(now) => "[z]",
+            _ => "[ ]",
+        };
         let date = pickup.date.format("%d.%m.%Y").to_string();
-        let weekday = pickup.date.format("%a").to_string();
-        let relative = relative_day_label(pickup.date, today);
-        let label = fraction_label(&pickup.fraction, pickup.note.as_deref());
+        let weekday = l10n.text(weekday_message(pickup.date.weekday()));
+        let mut label = fraction_label(l10n, &pickup.fraction, pickup.note.as_deref());
+        if let Some(correction) = pickup.correction {
+            label = format!("{label} [{}]", correction_label(l10n, correction));
+        }
 
         let mut style = Style::default().fg(fraction_color(&pickup.fraction));
         if pickup.date <= today {
@@ -210,6 +267,7 @@ fn draw_schedule_view(frame: &mut Frame<'_>, app: &App, area: Rect) {
         }
 
         Row::new(vec![
+            Cell::from(mark),
             Cell::from(date),
             Cell::from(weekday),
             Cell::from(relative),
@@ -219,37 +277,97 @@ fn draw_schedule_view(frame: &mut Frame<'_>, app: &App, area: Rect) {
     });
 
     let column_widths = [
+        Constraint::Length(out_width),
         Constraint::Length(12),
         Constraint::Length(8),
-        Constraint::Length(10),
+        Constraint::Length(relative_width),
         Constraint::Min(20),
     ];
 
     let table = Table::new(rows, column_widths)
-        .header(
-            Row::new(vec!["Date", "Day", "In", "Fraction"])
-                .style(Style::default().add_modifier(Modifier::BOLD)),
+        .header(Row::new(headers).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(block)
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    let mut state = TableState::default();
+    state.select(Some(app.pickup_list_index));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_settings(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let items = app
+        .settings_rows
+        .iter()
+        .map(|row| ListItem::new(row.label(&app.reminders, &app.l10n)))
+        .collect::<Vec<ListItem<'_>>>();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(app.l10n.text("settings-title")),
         )
-        .block(Block::default().borders(Borders::ALL).title(title))
-        .column_spacing(1);
+        .highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut state = ListState::default();
+    state.select(Some(app.settings_index));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_log(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let lines = app.logs.lines();
+    let l10n = &app.l10n;
+    let title = match app.logs.path() {
+        Some(path) => l10n.text_with("log-title", &[("path", path.display().to_string().into())]),
+        None => l10n.text("log-title-memory"),
+    };
+
+    // Newest lines at the bottom; scrolling moves the window towards older ones.
+    let visible = usize::from(area.height.saturating_sub(2));
+    let end = lines.len().saturating_sub(app.log_scroll);
+    let start = end.saturating_sub(visible);
+    let text = lines
+        .get(start..end)
+        .unwrap_or_default()
+        .iter()
+        .map(|line| Line::raw(line.as_str()))
+        .collect::<Vec<_>>();
 
-    frame.render_widget(table, area);
+    let paragraph = if text.is_empty() {
+        Paragraph::new(l10n.text("log-empty")).style(Style::default().fg(Color::DarkGray))
+    } else {
+        Paragraph::new(text)
+    };
+    frame.render_widget(
+        paragraph.block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
 }
 
-fn fraction_label(fraction: &Fraction, note: Option<&str>) -> String {
+fn fraction_label(l10n: &Localizer, fraction: &Fraction, note: Option<&str>) -> String {
     let base = match fraction {
-        Fraction::Residual => "Residual waste",
-        Fraction::Organic => "Organic",
-        Fraction::Paper => "Paper",
-        Fraction::Plastic => "Plastics / packaging",
-        Fraction::Glass => "Glass",
-        Fraction::Metal => "Metal",
-        Fraction::Other(name) => name.as_str(),
+        Fraction::Other(name) => name.to_string(),
+        known => l10n.text(fraction_message(known)),
     };
 
     match note {
         Some(note) if !note.is_empty() => format!("{base} ({note})"),
-        _ => base.to_owned(),
+        _ => base,
+    }
+}
+
+fn correction_label(l10n: &Localizer, correction: Correction) -> String {
+    match correction {
+        Correction::Added => l10n.text("correction-added"),
+        Correction::Moved { from } => l10n.text_with(
+            "correction-moved",
+            &[("date", from.format("%d.%m.").to_string().into())],
+        ),
     }
 }
 
@@ -261,17 +379,289 @@ fn fraction_color(fraction: &Fraction) -> Color {
         Fraction::Plastic => Color::Yellow,
         Fraction::Glass => Color::Cyan,
         Fraction::Metal => Color::LightBlue,
+        Fraction::StreetCleaning => Color::White,
+        Fraction::BulkyWaste => Color::LightMagenta,
+        Fraction::GardenWaste => Color::LightGreen,
+        Fraction::Hazardous => Color::Red,
+        Fraction::ChristmasTree => Color::LightRed,
+        Fraction::Textiles => Color::LightCyan,
+        Fraction::Electronics => Color::LightYellow,
         Fraction::Other(_) => Color::Magenta,
     }
 }
 
-fn relative_day_label(date: chrono::NaiveDate, today: chrono::NaiveDate) -> String {
+fn relative_day_label(l10n: &Localizer, date: NaiveDate, today: NaiveDate) -> String {
     let delta = (date - today).num_days();
-    match delta {
-        0 => "today".to_owned(),
-        1 => "tomorrow".to_owned(),
-        days if days > 1 => format!("in {days} days"),
-        -1 => "yesterday".to_owned(),
-        days => format!("{} days ago", days.abs()),
+    if delta >= 0 {
+        l10n.text_with("day-relative-future", &[("days", delta.into())])
+    } else {
+        l10n.text_with("day-relative-past", &[("days", delta.abs().into())])
+    }
+}
+
+/// Columns needed for the widest of `texts`.
+fn text_width(texts: impl IntoIterator<Item = impl AsRef<str>>) -> u16 {
+    let widest = texts
+        .into_iter()
+        .map(|text| text.as_ref().chars().count())
+        .max()
+        .unwrap_or_default();
+    u16::try_from(widest).unwrap_or(u16::MAX)
+}
+
+fn weekday_message(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "weekday-mon",
+        Weekday::Tue => "weekday-tue",
+        Weekday::Wed => "weekday-wed",
+        Weekday::Thu => "weekday-thu",
+        Weekday::Fri => "weekday-fri",
+        Weekday::Sat => "weekday-sat",
+        Weekday::Sun => "weekday-sun",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Duration;
+    use insta::assert_snapshot;
+    use ratatui::{Terminal, backend::TestBackend};
+    use tokio::runtime::Builder;
+    use tonneli_config::Config;
+    use tonneli_core::clock::FixedClock;
+    use tonneli_core::model::{Address, AddressId, CityId, PickupEvent};
+    use tonneli_core::reminder::ReminderSettings;
+
+    use super::*;
+    use crate::checklist::Checklist;
+
+    const SIZES: [(u16, u16); 3] = [(80, 24), (50, 16), (120, 32)];
+
+    fn day(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).expect("valid test date")
+    }
+
+    fn app() -> App {
+        let cities = ["Aachen", "Köln", "Nürnberg"]
+            .into_iter()
+            .map(|name| (CityId(name.to_lowercase()), name.to_owned()))
+            .collect();
+        let clock = FixedClock::new(day(3, 10).and_hms_opt(8, 0, 0).expect("valid test time"));
+        App::new(
+            Arc::new(clock),
+            cities,
+            Checklist::default(),
+            Config::default(),
+            ReminderSettings::default(),
+        )
+    }
+
+    fn address(street: &str, number: &str) -> Address {
+        Address {
+            id: AddressId(format!("{street}:{number}")),
+            city: CityId("cologne".to_owned()),
+            label: format!("{street} {number}").into(),
+            street: Arc::from(street),
+            house_number: Arc::from(number),
+        }
+    }
+
+    fn pickup(date: NaiveDate, fraction: Fraction, note: Option<&str>) -> PickupEvent {
+        PickupEvent {
+            date,
+            fraction,
+            note: note.map(Arc::from),
+            container: None,
+            correction: None,
+            holiday: None,
+            predicted: false,
+        }
+    }
+
+    fn search_app() -> App {
+        let mut app = app();
+        app.city_list_index = 1;
+        app.select_current_city();
+        app.address_input = "Aachener Str 1".to_owned();
+        app.address_results = vec![
+            address("Aachener Straße", "1"),
+            address("Aachener Straße", "1a"),
+            address("Aachener Straße", "1b"),
+        ];
+        app.address_list_index = 1;
+        app
+    }
+
+    fn schedule_app() -> App {
+        let mut app = search_app();
+        app.select_current_address();
+        let pickups = vec![
+            pickup(day(3, 10), Fraction::Residual, Some("Restabfall")),
+            pickup(day(3, 11), Fraction::Organic, None),
+            pickup(day(3, 14), Fraction::Plastic, Some("Leichtverpackungen")),
+            pickup(day(3, 19), Fraction::Paper, Some("Papier / Pappe")),
+            pickup(day(3, 21), Fraction::Other("Sperrmüll".into()), None),
+            PickupEvent {
+                correction: Some(Correction::Moved { from: day(3, 24) }),
+                ..pickup(day(3, 25), Fraction::Glass, None)
+            },
+        ];
+        if let (Some(address), Some(first), Some(second)) =
+            (&app.selected_address, pickups.first(), pickups.get(1))
+        {
+            let snoozed_until = app.now() + Duration::hours(1);
+            let runtime = Builder::new_current_thread()
+                .build()
+                .expect("test runtime starts");
+            runtime.block_on(async {
+                app.checklist
+                    .toggle(address, first)
+                    .await
+                    .expect("memory checklist accepts states");
+                app.checklist
+                    .snooze(address, second, snoozed_until)
+                    .await
+                    .expect("memory checklist accepts states");
+            });
+        }
+        app.set_pickups(pickups);
+        app.pickup_list_index = 2;
+        app
+    }
+
+    fn render(app: &App, (width, height): (u16, u16)) -> TestBackend {
+        let mut terminal =
+            Terminal::new(TestBackend::new(width, height)).expect("test backend never fails");
+        terminal
+            .draw(|frame| draw(frame, app))
+            .expect("test backend never fails");
+        terminal.backend().clone()
+    }
+
+    #[test]
+    fn city_select() {
+        let app = app();
+        for size in SIZES {
+            assert_snapshot!(
+                format!("city_select_{}x{}", size.0, size.1),
+                render(&app, size)
+            );
+        }
+    }
+
+    #[test]
+    fn search_results() {
+        let app = search_app();
+        for size in SIZES {
+            assert_snapshot!(
+                format!("search_results_{}x{}", size.0, size.1),
+                render(&app, size)
+            );
+        }
+    }
+
+    #[test]
+    fn schedule_table() {
+        let app = schedule_app();
+        for size in SIZES {
+            assert_snapshot!(
+                format!("schedule_table_{}x{}", size.0, size.1),
+                render(&app, size)
+            );
+        }
+    }
+
+    #[test]
+    fn schedule_table_in_german() {
+        let mut app = schedule_app();
+        app.l10n = Localizer::new(&["de-DE".parse().expect("valid language")]);
+        assert_snapshot!(render(&app, (80, 24)));
+    }
+
+    #[test]
+    fn schedule_credits_the_source() {
+        let mut app = schedule_app();
+        app.source =
+            Some("AWB Abfallwirtschaftsbetriebe Köln (https://www.awbkoeln.de)".to_owned());
+        assert_snapshot!(render(&app, (80, 24)));
+    }
+
+    #[test]
+    fn search_error() {
+        let mut app = search_app();
+        app.address_results.clear();
+        app.error_message = Some(
+            "Search failed: no matching address, did you mean Aachener Straße, Achterstraße?"
+                .to_owned(),
+        );
+        assert_snapshot!(render(&app, (80, 24)));
+    }
+
+    #[test]
+    fn schedule_loading() {
+        let mut app = schedule_app();
+        app.is_loading = true;
+        assert_snapshot!(render(&app, (80, 24)));
+    }
+
+    #[test]
+    fn schedule_error() {
+        let mut app = schedule_app();
+        app.set_pickups(Vec::new());
+        app.error_message = Some(
+            "Failed to load schedule: the city's server did not respond in time, try again later"
+                .to_owned(),
+        );
+        assert_snapshot!(render(&app, (80, 24)));
+    }
+
+    #[test]
+    fn log_view() {
+        let mut app = app();
+        app.toggle_log();
+        for index in 0..30 {
+            app.logs.push(&format!(
+                "2025-03-10T08:00:{index:02}Z  INFO searching addresses city=cologne"
+            ));
+        }
+        app.log_scroll = 2;
+        assert_snapshot!(render(&app, (80, 24)));
+    }
+
+    #[test]
+    fn today_rolls_over_into_the_new_year() {
+        let eve = day(12, 31);
+        let clock = Arc::new(FixedClock::new(
+            eve.and_hms_opt(23, 59, 0).expect("valid test time"),
+        ));
+        let mut app = app();
+        app.clock = Arc::<FixedClock>::clone(&clock);
+        app.tick();
+        assert_eq!(
+            relative_day_label(&app.l10n, eve + Duration::days(1), app.today),
+            "tomorrow",
+            "New Year's Day is tomorrow on the eve"
+        );
+
+        clock.advance(Duration::minutes(1));
+        app.tick();
+        assert_eq!(app.today, eve + Duration::days(1), "midnight moves today");
+        assert_eq!(
+            relative_day_label(&app.l10n, eve, app.today),
+            "yesterday",
+            "the eve is yesterday after midnight"
+        );
+        assert_eq!(
+            relative_day_label(&app.l10n, day(12, 1), app.today),
+            "31 days ago",
+            "labels count across the year boundary"
+        );
+        assert_eq!(
+            app.current_range().end.to_string(),
+            "2026-03-02",
+            "the default range reaches into the new year"
+        );
     }
 }
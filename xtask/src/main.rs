@@ -0,0 +1,22 @@
+//! Repository maintenance tasks, run with `cargo xtask <task>`.
+
+mod new_provider;
+mod schema;
+
+use std::env;
+
+use anyhow::{Result, bail};
+
+const USAGE: &str = "usage:
+  cargo xtask new-provider <city name> --backend <custom|regioit> [--id <city id>]
+  cargo xtask schema [--out <dir>]";
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("new-provider") => new_provider::run(args),
+        Some("schema") => schema::run(args),
+        Some(task) => bail!("unknown task `{task}`\n{USAGE}"),
+        None => bail!(USAGE),
+    }
+}
@@ -0,0 +1,408 @@
+//! `cargo xtask new-provider`: scaffold a provider crate and register it in the workspace.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+use crate::USAGE;
+
+/// Template files per backend, as (path inside the new crate, contents).
+const CUSTOM_TEMPLATES: &[(&str, &str)] = &[
+    ("Cargo.toml", include_str!("../templates/custom/Cargo.toml")),
+    ("src/lib.rs", include_str!("../templates/custom/lib.rs")),
+    (
+        "tests/mock_api.rs",
+        include_str!("../templates/custom/tests/mock_api.rs"),
+    ),
+    (
+        "tests/fractions.rs",
+        include_str!("../templates/custom/tests/fractions.rs"),
+    ),
+    (
+        "tests/fixtures/addresses.json",
+        include_str!("../templates/custom/tests/fixtures/addresses.json"),
+    ),
+    (
+        "tests/fixtures/pickups.json",
+        include_str!("../templates/custom/tests/fixtures/pickups.json"),
+    ),
+];
+const REGIOIT_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "Cargo.toml",
+        include_str!("../templates/regioit/Cargo.toml"),
+    ),
+    ("src/lib.rs", include_str!("../templates/regioit/lib.rs")),
+    (
+        "tests/mock_api.rs",
+        include_str!("../templates/regioit/tests/mock_api.rs"),
+    ),
+    (
+        "tests/fixtures/orte.json",
+        include_str!("../templates/regioit/tests/fixtures/orte.json"),
+    ),
+    (
+        "tests/fixtures/strassen.json",
+        include_str!("../templates/regioit/tests/fixtures/strassen.json"),
+    ),
+    (
+        "tests/fixtures/strasse_10.json",
+        include_str!("../templates/regioit/tests/fixtures/strasse_10.json"),
+    ),
+];
+
+/// Marker in the TUI's `main.rs` before which new providers are registered.
+const TUI_REGISTRATION_MARKER: &str = "    #[cfg(feature = \"demo\")]\n";
+
+#[derive(Debug, Clone, Copy)]
+enum Backend {
+    /// Placeholder REST client to adapt to the city's API.
+    Custom,
+    /// Thin wrapper around the shared `RegioIT` ports.
+    RegioIt,
+}
+
+impl Backend {
+    fn parse(raw: &str) -> Result<Self> {
+        match raw {
+            "custom" => Ok(Self::Custom),
+            "regioit" => Ok(Self::RegioIt),
+            other => bail!("unknown backend `{other}`, expected `custom` or `regioit`"),
+        }
+    }
+
+    fn templates(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Custom => CUSTOM_TEMPLATES,
+            Self::RegioIt => REGIOIT_TEMPLATES,
+        }
+    }
+}
+
+/// Names derived from the city name that the templates refer to.
+#[derive(Debug)]
+struct Names {
+    /// Display name, e.g. `Bad Homburg`.
+    city_name: String,
+    /// City id, crate suffix and feature name, e.g. `bad-homburg`.
+    slug: String,
+    /// Rust identifier of the crate, e.g. `tonneli_provider_bad_homburg`.
+    crate_ident: String,
+    /// Prefix of the port types, e.g. `BadHomburg`.
+    type_prefix: String,
+}
+
+impl Names {
+    fn new(city_name: String, slug: Option<String>) -> Result<Self> {
+        let slug = slug.unwrap_or_else(|| slugify(&city_name));
+        let valid = slug.starts_with(|ch: char| ch.is_ascii_lowercase())
+            && slug
+                .chars()
+                .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-')
+            && !slug.ends_with('-');
+        if !valid {
+            bail!("city id `{slug}` must be lowercase ASCII letters, digits and dashes");
+        }
+
+        let type_prefix = slug
+            .split('-')
+            .map(|part| {
+                let mut chars = part.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase())
+                    .into_iter()
+                    .chain(chars)
+                    .collect::<String>()
+            })
+            .collect();
+
+        Ok(Self {
+            crate_ident: format!("tonneli_provider_{}", slug.replace('-', "_")),
+            city_name,
+            slug,
+            type_prefix,
+        })
+    }
+
+    fn crate_name(&self) -> String {
+        format!("tonneli-provider-{}", self.slug)
+    }
+
+    fn render(&self, template: &str) -> String {
+        template
+            .replace("{{city_name}}", &self.city_name)
+            .replace("{{slug}}", &self.slug)
+            .replace("{{crate_ident}}", &self.crate_ident)
+            .replace("{{type_prefix}}", &self.type_prefix)
+    }
+}
+
+/// Entry point for `cargo xtask new-provider <city name> --backend <backend> [--id <id>]`.
+pub(crate) fn run(mut args: impl Iterator<Item = String>) -> Result<()> {
+    let mut city_name = None;
+    let mut backend = None;
+    let mut slug = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--backend" => backend = Some(Backend::parse(&args.next().context(USAGE)?)?),
+            "--id" => slug = Some(args.next().context(USAGE)?),
+            flag if flag.starts_with("--") => bail!("unknown option `{flag}`\n{USAGE}"),
+            _ if city_name.is_none() => city_name = Some(arg),
+            _ => bail!("unexpected argument `{arg}`\n{USAGE}"),
+        }
+    }
+    let (Some(city_name), Some(backend)) = (city_name, backend) else {
+        bail!(USAGE);
+    };
+
+    let names = Names::new(city_name, slug)?;
+    let root = workspace_root()?;
+    let crate_dir = root.join(names.crate_name());
+    if crate_dir.exists() {
+        bail!("{} already exists", crate_dir.display());
+    }
+
+    write_crate(&crate_dir, backend, &names)?;
+    register_in_workspace(&root, &names)?;
+    register_in_tui(&root, &names)?;
+
+    let formatted = Command::new("cargo")
+        .args(["fmt", "--package", &names.crate_name()])
+        .current_dir(&root)
+        .status()
+        .is_ok_and(|status| status.success());
+
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "Created {}.", crate_dir.display())?;
+    if !formatted {
+        writeln!(stdout, "`cargo fmt` failed; format the new crate manually.")?;
+    }
+    writeln!(
+        stdout,
+        "Next: adapt the endpoints in src/lib.rs and the fixtures in tests/, then run\n  \
+         cargo test -p {crate_name}\n  cargo run --bin tonneli-tui --features {slug}",
+        crate_name = names.crate_name(),
+        slug = names.slug,
+    )?;
+    Ok(())
+}
+
+/// Render the templates of `backend` into `crate_dir`.
+fn write_crate(crate_dir: &Path, backend: Backend, names: &Names) -> Result<()> {
+    for (relative, template) in backend.templates() {
+        let path = crate_dir.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::write(&path, names.render(template))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+pub(crate) fn workspace_root() -> Result<PathBuf> {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .map(Path::to_path_buf)
+        .context("xtask must live inside the workspace")
+}
+
+/// Add the crate to the workspace members and dependencies.
+fn register_in_workspace(root: &Path, names: &Names) -> Result<()> {
+    let crate_name = names.crate_name();
+    edit(&root.join("Cargo.toml"), |manifest| {
+        let manifest = insert_sorted(
+            manifest,
+            "    \"tonneli-provider-",
+            &format!("    \"{crate_name}\","),
+        )?;
+        insert_sorted(
+            &manifest,
+            "tonneli-provider-",
+            &format!("{crate_name} = {{ path = \"{crate_name}\", version = \"0.1.0\" }}"),
+        )
+    })
+}
+
+/// Add the crate to the TUI as an optional dependency behind a feature named after the city.
+fn register_in_tui(root: &Path, names: &Names) -> Result<()> {
+    let crate_name = names.crate_name();
+    let slug = &names.slug;
+    edit(&root.join("tonneli-tui/Cargo.toml"), |manifest| {
+        let mut manifest = insert_sorted(
+            manifest,
+            "tonneli-provider-",
+            &format!("{crate_name} = {{ workspace = true, optional = true }}"),
+        )?;
+        let features = manifest
+            .find("[features]\n")
+            .context("tonneli-tui/Cargo.toml has no [features] section")?;
+        let section_end = manifest
+            .get(features..)
+            .and_then(|section| section.find("\n\n"))
+            .map_or(manifest.len(), |offset| features + offset + 1);
+        manifest.insert_str(section_end, &format!("{slug} = [\"dep:{crate_name}\"]\n"));
+        Ok(manifest)
+    })?;
+
+    edit(&root.join("tonneli-tui/src/main.rs"), |main| {
+        let marker = main
+            .find(TUI_REGISTRATION_MARKER)
+            .context("provider registration marker not found in tonneli-tui/src/main.rs")?;
+        let mut main = main.to_owned();
+        main.insert_str(
+            marker,
+            &format!(
+                "    #[cfg(feature = \"{slug}\")]\n    {{\n        builder = builder.provider({}::plugin);\n    }}\n",
+                names.crate_ident
+            ),
+        );
+        Ok(main)
+    })
+}
+
+fn edit(path: &Path, change: impl FnOnce(&str) -> Result<String>) -> Result<()> {
+    let original =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let changed =
+        change(&original).with_context(|| format!("Failed to update {}", path.display()))?;
+    fs::write(path, changed).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Insert `line` among the consecutive lines starting with `prefix`, keeping them sorted.
+fn insert_sorted(text: &str, prefix: &str, line: &str) -> Result<String> {
+    let mut lines = text.lines().collect::<Vec<_>>();
+    let matching = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, existing)| existing.starts_with(prefix))
+        .map(|(index, existing)| (index, *existing))
+        .collect::<Vec<_>>();
+    let Some((last, _)) = matching.last() else {
+        bail!(
+            "no line starting with `{}` to insert next to",
+            prefix.trim()
+        );
+    };
+    let position = matching
+        .iter()
+        .find(|(_, existing)| *existing > line)
+        .map_or(last + 1, |(index, _)| *index);
+
+    lines.insert(position, line);
+    let mut joined = lines.join("\n");
+    if text.ends_with('\n') {
+        joined.push('\n');
+    }
+    Ok(joined)
+}
+
+/// City id derived from a display name, e.g. `Bad Homburg` → `bad-homburg`.
+fn slugify(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for ch in name.chars().flat_map(char::to_lowercase) {
+        match ch {
+            'ä' => slug.push_str("ae"),
+            'ö' => slug.push_str("oe"),
+            'ü' => slug.push_str("ue"),
+            'ß' => slug.push_str("ss"),
+            _ if ch.is_ascii_alphanumeric() => slug.push(ch),
+            _ if !slug.is_empty() && !slug.ends_with('-') => slug.push('-'),
+            _ => {}
+        }
+    }
+    slug.trim_end_matches('-').to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    /// Scratch workspace, next to the build output, holding one generated crate per
+    /// backend; path dependencies point back at the real workspace.
+    fn scratch_workspace(root: &Path, members: &[String]) -> Result<PathBuf> {
+        let scratch = root.join("target/xtask-scaffold");
+        fs::create_dir_all(&scratch)?;
+        let manifest = fs::read_to_string(root.join("Cargo.toml"))?;
+        let members_start = manifest.find("members = [").context("no members")?;
+        let members_end = manifest
+            .get(members_start..)
+            .and_then(|rest| rest.find("]\n"))
+            .map(|offset| members_start + offset + 2)
+            .context("unterminated members")?;
+        let listed = members
+            .iter()
+            .map(|member| format!("\"{member}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let manifest = format!(
+            "{}members = [{listed}]\n{}",
+            manifest.get(..members_start).unwrap_or_default(),
+            manifest.get(members_end..).unwrap_or_default(),
+        )
+        .replace("path = \"", &format!("path = \"{}/", root.display()));
+        fs::write(scratch.join("Cargo.toml"), manifest)?;
+        fs::copy(root.join("Cargo.lock"), scratch.join("Cargo.lock"))?;
+        Ok(scratch)
+    }
+
+    /// Run `cargo <args>` in the scratch workspace with its own target directory, as
+    /// the outer build holds the lock on the workspace's.
+    fn cargo(scratch: &Path, args: &[&str]) -> Result<bool> {
+        Ok(
+            Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+                .args(args)
+                .env("CARGO_TARGET_DIR", scratch.join("target"))
+                .current_dir(scratch)
+                .status()?
+                .success(),
+        )
+    }
+
+    /// Generate a crate per backend into the scratch workspace.
+    fn generate() -> Result<PathBuf> {
+        let root = workspace_root()?;
+        let backends = [
+            ("Scaffold Custom", Backend::Custom),
+            ("Scaffold Regio", Backend::RegioIt),
+        ];
+        let mut generated = Vec::new();
+        for (city_name, backend) in backends {
+            generated.push((Names::new(city_name.to_owned(), None)?, backend));
+        }
+        let members = generated
+            .iter()
+            .map(|(names, _)| names.crate_name())
+            .collect::<Vec<_>>();
+        let scratch = scratch_workspace(&root, &members)?;
+        for (names, backend) in &generated {
+            write_crate(&scratch.join(names.crate_name()), *backend, names)?;
+        }
+        Ok(scratch)
+    }
+
+    #[test]
+    fn generated_crates_build_and_pass_their_tests() {
+        let scratch = generate().expect("templates render into the scratch workspace");
+
+        assert!(
+            cargo(
+                &scratch,
+                &["clippy", "--all-targets", "--", "-D", "warnings"]
+            )
+            .expect("cargo clippy runs"),
+            "generated crates must be clippy-clean"
+        );
+        assert!(
+            cargo(&scratch, &["test"]).expect("cargo test runs"),
+            "tests of the generated crates must pass"
+        );
+    }
+}
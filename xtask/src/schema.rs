@@ -0,0 +1,37 @@
+//! `cargo xtask schema`: write the JSON Schemas of the serialized models.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use tonneli_core::schema::schemas;
+
+use crate::USAGE;
+use crate::new_provider::workspace_root;
+
+/// Entry point for `cargo xtask schema [--out <dir>]`; defaults to `target/schema`.
+pub(crate) fn run(mut args: impl Iterator<Item = String>) -> Result<()> {
+    let mut out = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out = Some(PathBuf::from(args.next().context(USAGE)?)),
+            _ => bail!("unexpected argument `{arg}`\n{USAGE}"),
+        }
+    }
+    let out = match out {
+        Some(dir) => dir,
+        None => workspace_root()?.join("target").join("schema"),
+    };
+    fs::create_dir_all(&out).with_context(|| format!("Failed to create {}", out.display()))?;
+
+    let mut stdout = io::stdout().lock();
+    for (name, schema) in schemas() {
+        let path = out.join(format!("{name}.schema.json"));
+        let mut json = serde_json::to_string_pretty(&schema)?;
+        json.push('\n');
+        fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+        writeln!(stdout, "Wrote {}", path.display())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,224 @@
+//! Provider implementation for {{city_name}}.
+//!
+//! Generated by `cargo xtask new-provider`; the endpoints and response types below are
+//! placeholders to be replaced with the ones of the city's API.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use tonneli_core::{
+    dates::MalformedDates,
+    fraction_map::FractionMapper,
+    matching::StreetMatcher,
+    model::{Address, AddressId, CityMeta, DateRange, Fraction, PickupEvent},
+    plugin::CityPlugin,
+    ports::{AddressPort, AddressSearch, PortError, SchedulePort, ScheduleReport},
+};
+use tonneli_http::HttpClient;
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {chrono as _, tokio as _, tonneli_testkit as _, wiremock as _};
+
+const BASE_URL: &str = "https://{{slug}}.example/api";
+
+/// Provider labels on top of the shared German vocabulary.
+const FRACTION_RULES: &[(&str, Fraction)] = &[];
+
+/// Address as returned by /addresses?street=...
+#[derive(Debug, Deserialize)]
+struct AddressEntry {
+    id: String,
+    street: String,
+    house_number: String,
+}
+
+/// Pickup as returned by /addresses/{id}/pickups
+#[derive(Debug, Deserialize)]
+struct PickupEntry {
+    date: String,
+    fraction: String,
+}
+
+/// Address search implementation for {{city_name}}.
+pub struct {{type_prefix}}AddressPort {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+}
+
+impl {{type_prefix}}AddressPort {
+    /// Create a new address port bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_owned(),
+            meta: city_meta(),
+        }
+    }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[async_trait]
+impl AddressPort for {{type_prefix}}AddressPort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn search(&self, query: &AddressSearch, limit: usize) -> Result<Vec<Address>, PortError> {
+        if limit == 0 || query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let base_url = &self.base_url;
+        let entries = self
+            .client
+            .fetch_json::<Vec<AddressEntry>>(
+                self.client
+                    .get(format!("{base_url}/addresses"))
+                    .query(&[("street", query.street.trim())]),
+            )
+            .await?;
+
+        let matcher = StreetMatcher::new(&query.street, query.fuzzy);
+        let house_filter = query
+            .house_number
+            .as_deref()
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty());
+
+        Ok(entries
+            .into_iter()
+            .filter(|entry| matcher.matches(&entry.street))
+            .filter(|entry| house_filter.is_none_or(|filter| entry.house_number == filter))
+            .take(limit)
+            .map(|entry| Address {
+                id: AddressId(entry.id),
+                city: self.meta.id.clone(),
+                label: format!("{} {}", entry.street, entry.house_number).into(),
+                street: entry.street.into(),
+                house_number: entry.house_number.into(),
+            })
+            .collect())
+    }
+}
+
+/// Pickup schedule implementation for {{city_name}}.
+pub struct {{type_prefix}}SchedulePort {
+    client: HttpClient,
+    base_url: String,
+    meta: CityMeta,
+    fractions: FractionMapper,
+}
+
+impl {{type_prefix}}SchedulePort {
+    /// Create a new schedule port bound to the given HTTP client.
+    #[must_use]
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_owned(),
+            meta: city_meta(),
+            fractions: fraction_mapper(),
+        }
+    }
+
+    /// Send requests to `base_url` instead of the public API, e.g. a mock server.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[async_trait]
+impl SchedulePort for {{type_prefix}}SchedulePort {
+    fn city(&self) -> &CityMeta {
+        &self.meta
+    }
+
+    async fn schedule(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<Vec<PickupEvent>, PortError> {
+        Ok(self.schedule_report(address_id, range).await?.events)
+    }
+
+    async fn schedule_report(
+        &self,
+        address_id: &AddressId,
+        range: DateRange,
+    ) -> Result<ScheduleReport, PortError> {
+        if address_id.0.trim().is_empty() {
+            return Err(PortError::InvalidAddressId);
+        }
+
+        let base_url = &self.base_url;
+        let pickups = self
+            .client
+            .fetch_json::<Vec<PickupEntry>>(
+                self.client
+                    .get(format!("{base_url}/addresses/{}/pickups", address_id.0)),
+            )
+            .await?;
+
+        let mut events = Vec::new();
+        let mut malformed = MalformedDates::default();
+        for pickup in pickups {
+            let Some(date) = malformed.parse(&pickup.date) else {
+                continue;
+            };
+            if date < range.start || date > range.end {
+                continue;
+            }
+            events.push(PickupEvent {
+                date,
+                fraction: self.fractions.map(&pickup.fraction),
+                note: Some(Arc::from(pickup.fraction)),
+                container: None,
+                correction: None,
+                holiday: None,
+                predicted: false,
+            });
+        }
+        events.sort_by_key(|event| event.date);
+
+        Ok(ScheduleReport {
+            events,
+            malformed_dates: malformed.into_inner(),
+        })
+    }
+}
+
+/// Mapper from {{city_name}} fraction labels to [`Fraction`].
+#[must_use]
+pub fn fraction_mapper() -> FractionMapper {
+    FractionMapper::german().with_rules(FRACTION_RULES)
+}
+
+/// Build the plugin bundle for the {{city_name}} provider.
+#[must_use]
+pub fn plugin(client: HttpClient) -> CityPlugin {
+    let address_port = Arc::new({{type_prefix}}AddressPort::new(client.clone()));
+    let schedule_port = Arc::new({{type_prefix}}SchedulePort::new(client));
+
+    CityPlugin {
+        meta: city_meta(),
+        address_port,
+        schedule_port,
+    }
+}
+
+fn city_meta() -> CityMeta {
+    CityMeta::new("{{slug}}", "{{city_name}}")
+}
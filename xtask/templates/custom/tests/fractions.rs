@@ -0,0 +1,47 @@
+//! {{city_name}} fraction labels must map onto known fractions.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use {{crate_ident}}::fraction_mapper;
+use tonneli_testkit::{fixture_labels, unexpected_other_fractions};
+
+use async_trait as _;
+use chrono as _;
+use serde as _;
+use tokio as _;
+use tonneli_core as _;
+use tonneli_http as _;
+use wiremock as _;
+
+/// Field holding the fraction label in the pickups response.
+const LABEL_KEY: &str = "fraction";
+/// Labels known from the upstream API, including ones not in any fixture.
+const KNOWN_LABELS: &[&str] = &[];
+/// Labels without a matching fraction that are fine as “Other”.
+const EXPECTED_OTHER: &[&str] = &[];
+/// Captured responses whose labels are checked as well, relative to the crate root.
+const FIXTURES: &[&str] = &["tests/fixtures/pickups.json"];
+
+#[test]
+fn known_vocabulary_maps_to_fractions() {
+    let mut vocabulary = KNOWN_LABELS
+        .iter()
+        .map(|label| (*label).to_owned())
+        .collect::<Vec<_>>();
+    for fixture in FIXTURES {
+        let path = format!("{}/{fixture}", env!("CARGO_MANIFEST_DIR"));
+        vocabulary.extend(fixture_labels(&path, LABEL_KEY).expect("fixture must be readable JSON"));
+    }
+
+    let unexpected = unexpected_other_fractions(
+        &fraction_mapper(),
+        vocabulary.iter().map(String::as_str),
+        EXPECTED_OTHER,
+    );
+    assert!(
+        unexpected.is_empty(),
+        "map these labels or list them in EXPECTED_OTHER: {unexpected:?}"
+    );
+}
@@ -0,0 +1,84 @@
+//! {{city_name}} ports against canned responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use chrono::NaiveDate;
+use tonneli_core::{AddressId, AddressPort, AddressSearch, DateRange, Fraction, SchedulePort};
+use {{crate_ident}}::{ {{type_prefix}}AddressPort, {{type_prefix}}SchedulePort};
+use wiremock::matchers::{method, path, query_param};
+use tonneli_testkit::{client, json};
+use wiremock::{Mock, MockServer};
+
+use async_trait as _;
+use serde as _;
+use tonneli_http as _;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid test date")
+}
+
+#[tokio::test]
+async fn search_filters_house_numbers() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/addresses"))
+        .and(query_param("street", "Hauptstraße"))
+        .respond_with(json(include_str!("fixtures/addresses.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = {{type_prefix}}AddressPort::new(client()).with_base_url(server.uri());
+    let addresses = port
+        .search(&AddressSearch::new("Hauptstraße", Some("2")), 10)
+        .await
+        .expect("search against the mock must succeed");
+
+    let labels = addresses
+        .iter()
+        .map(|address| &*address.label)
+        .collect::<Vec<_>>();
+    assert_eq!(labels, ["Hauptstraße 2"], "only house number 2 matches");
+}
+
+#[tokio::test]
+async fn schedule_maps_fractions() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/addresses/a-2/pickups"))
+        .respond_with(json(include_str!("fixtures/pickups.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let port = {{type_prefix}}SchedulePort::new(client()).with_base_url(server.uri());
+    let range = DateRange {
+        start: date(2025, 1, 1),
+        end: date(2025, 12, 31),
+    };
+    let report = port
+        .schedule_report(&AddressId("a-2".to_owned()), range)
+        .await
+        .expect("schedule against the mock must succeed");
+
+    let events = report
+        .events
+        .iter()
+        .map(|event| (event.date, event.fraction.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (date(2025, 3, 10), Fraction::Residual),
+            (date(2025, 3, 12), Fraction::Paper),
+        ],
+        "events are mapped and sorted by date"
+    );
+    assert_eq!(
+        report.malformed_dates,
+        vec!["soon".to_owned()],
+        "unparseable dates must be reported"
+    );
+}
@@ -0,0 +1,42 @@
+//! Provider implementation for {{city_name}} using the `RegioIT` waste collection API.
+//!
+//! Generated by `cargo xtask new-provider`; check `SERVICE` and `TOWN` against the
+//! city's abfall app.
+
+use std::sync::Arc;
+
+use tonneli_core::{
+    clock::{Clock, SystemClock},
+    model::CityMeta,
+    plugin::CityPlugin,
+};
+use tonneli_http::HttpClient;
+use tonneli_provider_regioit::{RegioItTown, plugin_for_town};
+
+// Dev-dependencies only used by the wiremock tests in `tests/`.
+#[cfg(test)]
+use {tokio as _, tonneli_testkit as _, wiremock as _};
+
+/// Abfallnavi service hosting the town.
+const SERVICE: &str = "{{slug}}";
+/// Town name as listed by the instance's `/orte`.
+const TOWN: &str = "{{city_name}}";
+
+/// The `RegioIT` town served by this provider.
+#[must_use]
+pub fn town() -> RegioItTown {
+    RegioItTown::abfallnavi(SERVICE, TOWN, CityMeta::new("{{slug}}", "{{city_name}}"))
+}
+
+/// Build the plugin bundle for the {{city_name}} provider.
+#[must_use]
+pub fn plugin(client: HttpClient) -> CityPlugin {
+    plugin_with_clock(client, Arc::new(SystemClock))
+}
+
+/// Build the plugin bundle with searches taking the current year from `clock`,
+/// e.g. the service's, see `ServiceBuilder::provider_with_clock`.
+#[must_use]
+pub fn plugin_with_clock(client: HttpClient, clock: Arc<dyn Clock>) -> CityPlugin {
+    plugin_for_town(client, town(), clock)
+}
@@ -0,0 +1,38 @@
+//! {{city_name}} ports against canned `RegioIT` responses served by wiremock.
+#![expect(
+    clippy::tests_outside_test_module,
+    reason = "integration tests are a test crate of their own"
+)]
+
+use tonneli_core::{AddressPort, AddressSearch};
+use tonneli_provider_regioit::{RegioItAddressPort, RegioItTown};
+use tonneli_testkit::{client, serve};
+
+use tonneli_http as _;
+use wiremock as _;
+
+#[tokio::test]
+async fn search_finds_the_town() {
+    let server = serve([
+        ("/orte", include_str!("fixtures/orte.json")),
+        ("/orte/1/strassen", include_str!("fixtures/strassen.json")),
+        ("/strassen/10", include_str!("fixtures/strasse_10.json")),
+    ])
+    .await;
+
+    let town = RegioItTown {
+        base_url: server.uri(),
+        ..{{crate_ident}}::town()
+    };
+    let port = RegioItAddressPort::for_town(client(), town);
+    let addresses = port
+        .search(&AddressSearch::new("Hauptstraße", Some("1")), 10)
+        .await
+        .expect("search against the mock must succeed");
+
+    let labels = addresses
+        .iter()
+        .map(|address| &*address.label)
+        .collect::<Vec<_>>();
+    assert_eq!(labels, ["Hauptstraße 1"], "the configured town must be found");
+}